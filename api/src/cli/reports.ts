@@ -0,0 +1,104 @@
+import { parseArgs } from 'node:util';
+import { normalizeStructTag } from '@mysten/sui/utils';
+import { connectMongoDB, disconnectMongoDB } from '@/db/mongodb.ts';
+import {
+    createCommitmentsRepository,
+    createPoolsRepository,
+    createRelayerJobsRepository,
+} from '@/repositories/index.ts';
+import { buildReserveReport, signReserveReport } from '@/services/reserves.ts';
+import { formatRevenue, summarizeRevenue, type RevenueFormat } from '@/services/revenue.ts';
+import { keypair, nodeClient } from '@/services/sui.ts';
+
+const USAGE = [
+    'Usage: bun run reports revenue --from YYYY-MM-DD --to YYYY-MM-DD [--format csv|json]',
+    '       bun run reports reserve --checkpoint <n> [--coin-type <type>]',
+].join('\n');
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+const parseDay = (value: string | undefined, flag: string): Date => {
+    const date = new Date(`${value}T00:00:00.000Z`);
+    if (!value || !/^\d{4}-\d{2}-\d{2}$/.test(value) || Number.isNaN(date.getTime())) {
+        throw new Error(`${flag} must be a date in YYYY-MM-DD format`);
+    }
+    return date;
+};
+
+const revenue = async (args: string[]): Promise<void> => {
+    const { values } = parseArgs({
+        args,
+        options: {
+            from: { type: 'string' },
+            to: { type: 'string' },
+            format: { type: 'string', default: 'csv' },
+        },
+    });
+
+    const from = parseDay(values.from, '--from');
+    // `--to` is inclusive: the whole day is reported
+    const to = new Date(parseDay(values.to, '--to').getTime() + DAY_MS);
+    if (from >= to) throw new Error('--from must not be after --to');
+
+    const format = values.format as RevenueFormat;
+    if (format !== 'csv' && format !== 'json') throw new Error('--format must be csv or json');
+
+    const db = await connectMongoDB();
+    try {
+        const jobs = await createRelayerJobsRepository(db).findBetween(from, to);
+        console.log(formatRevenue(summarizeRevenue(jobs), format));
+    } finally {
+        await disconnectMongoDB();
+    }
+};
+
+/**
+ * Proof of reserve: indexed deposits minus withdrawals of each pool against its
+ * on-chain balance at `--checkpoint`, signed with the operator key.
+ */
+const reserve = async (args: string[]): Promise<void> => {
+    const { values } = parseArgs({
+        args,
+        options: {
+            checkpoint: { type: 'string' },
+            'coin-type': { type: 'string' },
+        },
+    });
+
+    const checkpoint = Number(values.checkpoint);
+    if (!values.checkpoint || !Number.isSafeInteger(checkpoint) || checkpoint < 0) {
+        throw new Error('--checkpoint must be a checkpoint sequence number');
+    }
+
+    const db = await connectMongoDB();
+    try {
+        const report = await buildReserveReport({
+            client: nodeClient,
+            pools: createPoolsRepository(db),
+            commitments: createCommitmentsRepository(db),
+            checkpoint,
+            coinType: values['coin-type'] && normalizeStructTag(values['coin-type']),
+        });
+        console.log(JSON.stringify(await signReserveReport(report, keypair), null, 2));
+    } finally {
+        await disconnectMongoDB();
+    }
+};
+
+const reports: Record<string, (args: string[]) => Promise<void>> = { revenue, reserve };
+
+const main = async () => {
+    const [command, ...args] = process.argv.slice(2);
+
+    try {
+        const report = command ? reports[command] : undefined;
+        if (!report) throw new Error(`Unknown report '${command ?? ''}'`);
+        await report(args);
+    } catch (error) {
+        console.error(error instanceof Error ? error.message : String(error));
+        console.error(USAGE);
+        process.exit(1);
+    }
+};
+
+await main();
@@ -0,0 +1,106 @@
+import { env } from '@/config/env.ts';
+import { connectMongoDB, findMissingIndexes } from '@/db/mongodb.ts';
+import { connectRedis } from '@/db/redis.ts';
+import { keypair, nodeClient } from '@/services/sui.ts';
+
+export type DoctorCheck = {
+    name: string;
+    passed: boolean;
+    /** What was verified, or what went wrong and how to fix it */
+    detail: string;
+};
+
+export type DoctorReport = {
+    passed: boolean;
+    checks: DoctorCheck[];
+};
+
+const errorMessage = (error: unknown): string =>
+    error instanceof Error ? error.message : String(error);
+
+const check = async (
+    name: string,
+    run: () => Promise<string>,
+    hint: string
+): Promise<DoctorCheck> => {
+    try {
+        return { name, passed: true, detail: await run() };
+    } catch (error) {
+        return { name, passed: false, detail: `${errorMessage(error)}. ${hint}` };
+    }
+};
+
+/**
+ * Validates the configuration against the services the API depends on. The
+ * environment itself is already validated by `env.ts`, including the relayer
+ * key encoding. MongoDB and Redis connections opened here are reused by the
+ * server.
+ */
+export const runDoctor = async (): Promise<DoctorReport> => {
+    const mongodb = await check(
+        'MongoDB reachable',
+        async () => {
+            await connectMongoDB();
+            return 'connected';
+        },
+        'Check MONGODB_URI and that the server accepts connections'
+    );
+    const indexes = mongodb.passed
+        ? [
+              await check(
+                  'MongoDB indexes',
+                  async () => {
+                      const missing = await findMissingIndexes(await connectMongoDB());
+                      if (missing.length > 0) throw new Error(`missing ${missing.join(', ')}`);
+                      return 'all present';
+                  },
+                  'The MongoDB user needs the createIndex privilege'
+              ),
+          ]
+        : [];
+
+    const checks: DoctorCheck[] = [
+        {
+            name: 'Relayer key',
+            passed: true,
+            detail: keypair.toSuiAddress(),
+        },
+        {
+            name: 'API key',
+            passed: env.NODE_ENV !== 'production' || Boolean(env.API_KEY),
+            detail: env.API_KEY
+                ? 'set'
+                : env.NODE_ENV === 'production'
+                  ? 'API_KEY is not set, so protected routes reject every request. Set API_KEY'
+                  : 'not set, protected routes are open outside production',
+        },
+        await check(
+            'Sui RPC reachable',
+            async () => `chain ${await nodeClient.getChainIdentifier()}`,
+            'Check SHINAMI_RPC_KEY and outbound HTTPS access'
+        ),
+        mongodb,
+        ...indexes,
+        await check(
+            'Redis reachable',
+            async () => `${await connectRedis().ping()}`,
+            'Check REDIS_URL and that the server accepts connections'
+        ),
+    ];
+
+    return { passed: checks.every(({ passed }) => passed), checks };
+};
+
+export const formatDoctorReport = ({ passed, checks }: DoctorReport): string => {
+    const lines = checks.map(
+        ({ name, passed, detail }) => `[${passed ? 'PASS' : 'FAIL'}] ${name}: ${detail}`
+    );
+    const failed = checks.filter((c) => !c.passed).length;
+
+    return [
+        ...lines,
+        passed
+            ? `All ${checks.length} checks passed`
+            : `${failed} of ${checks.length} checks failed`,
+    ].join('\n');
+};
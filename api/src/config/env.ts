@@ -1,5 +1,18 @@
+import { hostname } from 'node:os';
+import { Ed25519Keypair } from '@mysten/sui/keypairs/ed25519';
 import { z } from 'zod';
 
+const isEd25519PrivateKey = (value: string): boolean => {
+    try {
+        Ed25519Keypair.fromSecretKey(value);
+        return true;
+    } catch {
+        return false;
+    }
+};
+
+const redactionModeSchema = z.enum(['keep', 'hash', 'truncate', 'drop']);
+
 const baseSchema = z.object({
     NODE_ENV: z.enum(['development', 'production', 'test']).default('development'),
     PORT: z.coerce.number().default(3000),
@@ -8,8 +21,29 @@ const baseSchema = z.object({
     REDIS_URL: z.string().default('redis://localhost:6379'),
     CORS_ORIGIN: z.string().optional(),
     API_KEY: z.string().optional(),
-    SUI_PRIVATE_KEY: z.string(),
+    SUI_PRIVATE_KEY: z.string().refine(isEd25519PrivateKey, {
+        message: 'SUI_PRIVATE_KEY must be an Ed25519 key in suiprivkey format (sui keytool export)',
+    }),
     SHINAMI_RPC_KEY: z.string(),
+    WEBHOOK_POLL_INTERVAL_MS: z.coerce.number().int().positive().default(5000),
+    WEBHOOK_TIMEOUT_MS: z.coerce.number().int().positive().default(5000),
+    WEBHOOK_MAX_ATTEMPTS: z.coerce.number().int().positive().default(5),
+    WATCH_STREAM_POLL_INTERVAL_MS: z.coerce.number().int().positive().default(2000),
+    WATCH_STREAM_MAX_PER_WATCH: z.coerce.number().int().positive().default(3),
+    WATCH_CREATE_LIMIT_PER_HOUR: z.coerce.number().int().positive().default(20),
+    INSTANCE_ID: z.string().default(() => `${hostname()}:${process.pid}`),
+    REPLAY_LEASE_TTL_MS: z.coerce.number().int().positive().default(60_000),
+    IDEMPOTENCY_CLAIM_TTL_MS: z.coerce.number().int().positive().default(60_000),
+    PROOF_REPLAY_WINDOW_MS: z.coerce.number().int().positive().default(600_000),
+    DEAD_LETTERS_TTL_DAYS: z.coerce.number().int().nonnegative().default(30),
+    WATCH_NOTIFICATIONS_TTL_DAYS: z.coerce.number().int().nonnegative().default(7),
+    IDEMPOTENCY_KEYS_TTL_DAYS: z.coerce.number().int().nonnegative().default(7),
+    COMPACTION_INTERVAL_MS: z.coerce.number().int().nonnegative().default(0),
+    SHUTDOWN_GRACE_MS: z.coerce.number().int().positive().default(10_000),
+    LOG_REDACT_IP: redactionModeSchema.default('hash'),
+    LOG_REDACT_NULLIFIER: redactionModeSchema.default('truncate'),
+    LOG_REDACT_ADDRESS: redactionModeSchema.default('truncate'),
+    LOG_REDACTION_SALT: z.string().optional(),
 });
 
 const envSchema = baseSchema.refine((data) => data.NODE_ENV !== 'production' || data.CORS_ORIGIN, {
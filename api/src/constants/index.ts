@@ -4,6 +4,9 @@ export const PAGINATION = {
     MIN_PAGE: 1,
 } as const;
 
+/** Latest roots the contract accepts proofs against (`ROOT_HISTORY_SIZE` in merkle_tree.move). */
+export const ROOT_HISTORY_SIZE = 100;
+
 export const REDIS_KEYS = {
     MERKLE_TREE_PREFIX: 'merkle_tree:',
     MERKLE_LAST_INDEX_PREFIX: 'merkle_last_index:',
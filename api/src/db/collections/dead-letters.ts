@@ -0,0 +1,24 @@
+export const DEAD_LETTERS_COLLECTION = 'dead_letters';
+
+export type DeadLetterSource = 'indexer' | 'relayer';
+
+/**
+ * Permanently failed work kept for replay. Indexer entries hold a document
+ * rejected by `collection`; relayer entries hold the transaction bytes under
+ * `payload.tx_bytes`. `lease_owner` is the instance replaying the entry until
+ * `lease_expires_at`; an expired lease can be taken over by another instance.
+ */
+export type DeadLetterDocument = {
+    _id: string;
+    source: DeadLetterSource;
+    collection: string | null;
+    payload: Record<string, unknown>;
+    error: string;
+    attempts: number;
+    created_at: Date;
+    last_attempt_at: Date;
+    replayed_at?: Date;
+    replay_digest?: string;
+    lease_owner?: string;
+    lease_expires_at?: Date;
+};
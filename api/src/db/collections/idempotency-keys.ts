@@ -0,0 +1,16 @@
+export const IDEMPOTENCY_KEYS_COLLECTION = 'idempotency_keys';
+
+/**
+ * Claim on a relayer submission's `Idempotency-Key`. `digest` is null while
+ * the claiming request executes the transaction; retries with the same key
+ * get it back instead of sponsoring the transaction again. `tx_hash` is the
+ * SHA-256 of the claiming request's transaction bytes, so a key cannot be
+ * reused for a different transaction.
+ */
+export type IdempotencyKeyDocument = {
+    _id: string;
+    tx_hash: string;
+    digest: string | null;
+    claimed_at: Date;
+    completed_at?: Date;
+};
@@ -0,0 +1,13 @@
+export const LEAF_COUNTS_COLLECTION = 'leaf_counts';
+
+/**
+ * Leaves in a pool's tree after a checkpoint that appended commitments to it,
+ * written by the indexer. `_id` is `{checkpoint}:{coin_type}`.
+ */
+export type LeafCountDocument = {
+    _id: string;
+    coin_type: string;
+    checkpoint: number;
+    checkpoint_timestamp_ms: number;
+    leaf_count: number;
+};
@@ -0,0 +1,12 @@
+export const NULLIFIERS_COLLECTION = 'nullifiers_spent';
+
+/** `NullifierSpent` event written by the indexer; `nullifier` is little-endian hex. */
+export type NullifierDocument = {
+    _id: string;
+    digest: string;
+    sender: string;
+    checkpoint: number;
+    checkpoint_timestamp_ms: number;
+    coin_type: string;
+    nullifier: string;
+};
@@ -0,0 +1,15 @@
+export const POOL_ROOTS_COLLECTION = 'pool_roots';
+
+/**
+ * Root of a pool's Merkle tree once its `seq`-th commitment pair is appended,
+ * which is when the contract pushes a root to its history. `_id` is
+ * `{coin_type}:{seq}`.
+ */
+export type PoolRootDocument = {
+    _id: string;
+    coin_type: string;
+    seq: number;
+    root: string;
+    leaf_count: number;
+    checkpoint: number;
+};
@@ -0,0 +1,15 @@
+export const RELAYER_JOBS_COLLECTION = 'relayer_jobs';
+
+/**
+ * Accounting of one sponsored transaction. Amounts are decimal strings in the
+ * smallest unit: `fee` in `coin_type`, `gas_spent` in MIST. `net_margin` is
+ * only known when the pool coin is SUI.
+ */
+export type RelayerJobDocument = {
+    _id: string;
+    coin_type: string | null;
+    fee: string;
+    gas_spent: string;
+    net_margin: string | null;
+    created_at: Date;
+};
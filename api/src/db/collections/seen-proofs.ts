@@ -0,0 +1,10 @@
+export const SEEN_PROOFS_COLLECTION = 'seen_proofs';
+
+/**
+ * Hash of the proof points of a relayer submission, kept for the replay window
+ * so a copy of a pending proof is not sponsored a second time.
+ */
+export type SeenProofDocument = {
+    _id: string;
+    expires_at: Date;
+};
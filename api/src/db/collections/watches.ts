@@ -0,0 +1,33 @@
+export const WATCHES_COLLECTION = 'watches';
+
+export const WATCH_NOTIFICATIONS_COLLECTION = 'watch_notifications';
+
+export type WatchKind = 'nullifier';
+
+export type WatchDocument = {
+    _id: string;
+    kind: WatchKind;
+    value: string;
+    webhook_url: string | null;
+    /** HMAC key for this watch's webhook bodies, returned to the caller once on creation */
+    webhook_secret: string | null;
+    created_at: Date;
+};
+
+export type WatchNotificationDocument = {
+    _id: string;
+    watch_id: string;
+    kind: WatchKind;
+    value: string;
+    event_digest: string;
+    digest: string;
+    coin_type: string;
+    checkpoint: number;
+    checkpoint_timestamp_ms: number;
+    webhook_url: string | null;
+    delivered: boolean;
+    delivery_attempts?: number;
+    last_attempt_at?: Date;
+    delivered_at?: Date;
+    created_at: Date;
+};
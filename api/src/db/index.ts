@@ -1,3 +1,11 @@
 export { connectMongoDB, disconnectMongoDB, getDb } from './mongodb.ts';
 
+export type { RetentionPolicy } from './retention.ts';
+export {
+    RETENTION,
+    compactTransientCollections,
+    ensureTtlIndexes,
+    startCompactionScheduler,
+} from './retention.ts';
+
 export { connectRedis, disconnectRedis, getRedis } from './redis.ts';
@@ -1,36 +1,93 @@
-import { MongoClient, type Db } from 'mongodb';
+import { MongoClient, type Db, type IndexDescription } from 'mongodb';
 import invariant from 'tiny-invariant';
 import { env } from '@/config/env.ts';
 import { logger } from '@/utils/logger.ts';
 import {
     ACCOUNTS_COLLECTION,
     COMMITMENTS_COLLECTION,
+    DEAD_LETTERS_COLLECTION,
+    LEAF_COUNTS_COLLECTION,
+    POOL_ROOTS_COLLECTION,
     POOLS_COLLECTION,
+    RELAYER_JOBS_COLLECTION,
+    SEEN_PROOFS_COLLECTION,
+    WATCHES_COLLECTION,
+    WATCH_NOTIFICATIONS_COLLECTION,
 } from './collections/index.ts';
+import { ensureTtlIndexes } from './retention.ts';
+
+const DUPLICATE_KEY_ERROR_CODE = 11000;
 
 let client: MongoClient | null = null;
 
 let db: Db | null = null;
 
+const INDEXES: Record<string, IndexDescription[]> = {
+    [ACCOUNTS_COLLECTION]: [
+        { key: { hashed_secret: 1 } },
+        { key: { account_object_id: 1 } },
+        { key: { hidden: 1 } },
+    ],
+    [COMMITMENTS_COLLECTION]: [
+        { key: { coin_type: 1, index: 1 }, name: 'coin_type_index_idx' },
+        { key: { coin_type: 1, checkpoint_timestamp_ms: 1 }, name: 'coin_type_timestamp_idx' },
+    ],
+    [DEAD_LETTERS_COLLECTION]: [
+        { key: { source: 1, created_at: -1 }, name: 'source_created_at_idx' },
+    ],
+    [LEAF_COUNTS_COLLECTION]: [
+        { key: { coin_type: 1, checkpoint: 1 }, name: 'coin_type_checkpoint_idx' },
+    ],
+    [POOL_ROOTS_COLLECTION]: [{ key: { coin_type: 1, seq: 1 }, name: 'coin_type_seq_idx' }],
+    [POOLS_COLLECTION]: [
+        { key: { coin_type: 1 }, name: 'coin_type_idx' },
+        { key: { checkpoint: -1 } },
+    ],
+    [RELAYER_JOBS_COLLECTION]: [{ key: { created_at: 1 }, name: 'created_at_idx' }],
+    [SEEN_PROOFS_COLLECTION]: [
+        { key: { expires_at: 1 }, name: 'expires_at_ttl_idx', expireAfterSeconds: 0 },
+    ],
+    [WATCHES_COLLECTION]: [{ key: { kind: 1, value: 1 }, name: 'kind_value_idx' }],
+    [WATCH_NOTIFICATIONS_COLLECTION]: [
+        { key: { watch_id: 1, checkpoint: 1 }, name: 'watch_id_checkpoint_idx' },
+        { key: { delivered: 1, created_at: 1 } },
+    ],
+};
+
+export const isDuplicateKeyError = (error: unknown): boolean =>
+    typeof error === 'object' &&
+    error !== null &&
+    'code' in error &&
+    error.code === DUPLICATE_KEY_ERROR_CODE;
+
 const ensureIndexes = async (database: Db): Promise<void> => {
-    await Promise.all([
-        database
-            .collection(ACCOUNTS_COLLECTION)
-            .createIndexes([
-                { key: { hashed_secret: 1 } },
-                { key: { account_object_id: 1 } },
-                { key: { hidden: 1 } },
-            ]),
-        database
-            .collection(COMMITMENTS_COLLECTION)
-            .createIndexes([{ key: { coin_type: 1, index: 1 }, name: 'coin_type_index_idx' }]),
-        database
-            .collection(POOLS_COLLECTION)
-            .createIndexes([
-                { key: { coin_type: 1 }, name: 'coin_type_idx' },
-                { key: { checkpoint: -1 } },
-            ]),
-    ]);
+    await Promise.all(
+        Object.entries(INDEXES).map(([name, indexes]) =>
+            database.collection(name).createIndexes(indexes)
+        )
+    );
+};
+
+const sameKey = (a: IndexDescription['key'], b: unknown): boolean =>
+    JSON.stringify(a) === JSON.stringify(b);
+
+/** Returns `collection.{key}` for every index `ensureIndexes` creates that the database lacks. */
+export const findMissingIndexes = async (database: Db): Promise<string[]> => {
+    const missing = await Promise.all(
+        Object.entries(INDEXES).map(async ([name, indexes]) => {
+            const existing = await database
+                .collection(name)
+                .listIndexes()
+                .toArray()
+                .catch(() => []);
+
+            return indexes
+                .filter(({ key }) => !existing.some((index) => sameKey(key, index.key)))
+                .map(({ key }) => `${name}.${JSON.stringify(key)}`);
+        })
+    );
+
+    return missing.flat();
 };
 
 export const connectMongoDB = async (): Promise<Db> => {
@@ -41,6 +98,7 @@ export const connectMongoDB = async (): Promise<Db> => {
     db = client.db();
 
     await ensureIndexes(db);
+    await ensureTtlIndexes(db);
 
     logger.info('Connected to MongoDB');
     return db;
@@ -0,0 +1,120 @@
+import type { Db } from 'mongodb';
+import { env } from '@/config/env.ts';
+import { logger } from '@/utils/logger.ts';
+import {
+    DEAD_LETTERS_COLLECTION,
+    IDEMPOTENCY_KEYS_COLLECTION,
+    WATCH_NOTIFICATIONS_COLLECTION,
+} from './collections/index.ts';
+
+const SECONDS_PER_DAY = 86_400;
+
+/**
+ * Expiry of an operationally transient collection. Documents expire `ttlDays`
+ * after the date in `field`; documents without it are kept, so only finished
+ * work expires. A `ttlDays` of 0 keeps everything.
+ */
+export type RetentionPolicy = {
+    collection: string;
+    field: string;
+    ttlDays: number;
+};
+
+export const RETENTION: RetentionPolicy[] = [
+    {
+        collection: DEAD_LETTERS_COLLECTION,
+        field: 'replayed_at',
+        ttlDays: env.DEAD_LETTERS_TTL_DAYS,
+    },
+    {
+        collection: WATCH_NOTIFICATIONS_COLLECTION,
+        field: 'delivered_at',
+        ttlDays: env.WATCH_NOTIFICATIONS_TTL_DAYS,
+    },
+    {
+        collection: IDEMPOTENCY_KEYS_COLLECTION,
+        field: 'completed_at',
+        ttlDays: env.IDEMPOTENCY_KEYS_TTL_DAYS,
+    },
+];
+
+const ttlIndexName = (field: string): string => `${field}_ttl_idx`;
+
+/**
+ * Creates, updates or drops the TTL index of every policy. An existing index
+ * whose expiry differs is changed in place with `collMod`, since
+ * `createIndex` rejects option changes.
+ */
+export const ensureTtlIndexes = async (database: Db): Promise<void> => {
+    await Promise.all(
+        RETENTION.map(async ({ collection, field, ttlDays }) => {
+            const name = ttlIndexName(field);
+            const existing = await database
+                .collection(collection)
+                .listIndexes()
+                .toArray()
+                .catch(() => []);
+            const current = existing.find((index) => index.name === name);
+
+            if (ttlDays === 0) {
+                if (current) await database.collection(collection).dropIndex(name);
+                return;
+            }
+
+            const expireAfterSeconds = ttlDays * SECONDS_PER_DAY;
+            if (!current) {
+                await database
+                    .collection(collection)
+                    .createIndex({ [field]: 1 }, { name, expireAfterSeconds });
+            } else if (current.expireAfterSeconds !== expireAfterSeconds) {
+                await database.command({
+                    collMod: collection,
+                    index: { name, expireAfterSeconds },
+                });
+            }
+        })
+    );
+};
+
+/**
+ * Reclaims the disk space expired documents leave behind. `compact` is run one
+ * collection at a time; failures (e.g. on managed clusters that disallow it)
+ * are logged and skipped.
+ */
+export const compactTransientCollections = async (database: Db): Promise<string[]> => {
+    const compacted: string[] = [];
+
+    for (const { collection, ttlDays } of RETENTION) {
+        if (ttlDays === 0) continue;
+
+        try {
+            await database.command({ compact: collection });
+            compacted.push(collection);
+        } catch (error) {
+            logger.warn({ error, collection }, 'Compaction failed');
+        }
+    }
+
+    return compacted;
+};
+
+export const startCompactionScheduler = (database: Db): (() => void) => {
+    if (env.COMPACTION_INTERVAL_MS === 0) return () => {};
+
+    let running = false;
+
+    const timer = setInterval(() => {
+        if (running) return;
+        running = true;
+
+        compactTransientCollections(database)
+            .then((compacted) => {
+                if (compacted.length > 0) logger.info({ compacted }, 'Compacted collections');
+            })
+            .finally(() => {
+                running = false;
+            });
+    }, env.COMPACTION_INTERVAL_MS);
+
+    return () => clearInterval(timer);
+};
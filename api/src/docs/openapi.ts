@@ -19,11 +19,14 @@ export const openApiSpec: OpenAPIV3.Document = {
     tags: [
         { name: 'Health', description: 'Health check endpoints' },
         { name: 'Accounts', description: 'Vortex account management' },
+        { name: 'Admin', description: 'Operator tooling' },
         { name: 'Pools', description: 'Privacy pool queries' },
         { name: 'Commitments', description: 'Commitment queries' },
         { name: 'Merkle', description: 'Merkle tree operations' },
+        { name: 'Nullifiers', description: 'Spent nullifier queries' },
         { name: 'Relayer', description: 'Relayer information' },
         { name: 'Transactions', description: 'Sponsored transaction execution' },
+        { name: 'Watches', description: 'Nullifier watch list and notifications' },
     ],
     paths: {
         '/api/health': {
@@ -61,8 +64,9 @@ export const openApiSpec: OpenAPIV3.Document = {
                         name: 'hashed_secret',
                         in: 'query',
                         required: true,
-                        description: 'Poseidon hash of the secret (decimal string)',
-                        schema: { type: 'string', pattern: '^[0-9]+$' },
+                        description:
+                            'Poseidon hash of the secret (decimal, 0x-hex or [b0, b1, ...] byte array)',
+                        schema: { type: 'string' },
                     },
                     {
                         name: 'exclude_hidden',
@@ -182,6 +186,156 @@ export const openApiSpec: OpenAPIV3.Document = {
                 },
             },
         },
+        '/api/v1/admin/dlq': {
+            get: {
+                tags: ['Admin'],
+                summary: 'List dead letters',
+                description:
+                    'Indexer documents and relayer transactions that failed permanently, newest first',
+                security: [{ ApiKeyAuth: [] }],
+                parameters: [
+                    {
+                        name: 'page',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, default: 1 },
+                    },
+                    {
+                        name: 'limit',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, maximum: 100, default: 20 },
+                    },
+                    {
+                        name: 'source',
+                        in: 'query',
+                        schema: { type: 'string', enum: ['indexer', 'relayer'] },
+                    },
+                    {
+                        name: 'replayed',
+                        in: 'query',
+                        schema: { type: 'boolean' },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Paginated list of dead letters',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/DeadLettersResponse' },
+                            },
+                        },
+                    },
+                    '401': {
+                        description: 'Missing or invalid API key',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/admin/dlq/{id}': {
+            get: {
+                tags: ['Admin'],
+                summary: 'Get a dead letter',
+                security: [{ ApiKeyAuth: [] }],
+                parameters: [
+                    {
+                        name: 'id',
+                        in: 'path',
+                        required: true,
+                        schema: { type: 'string' },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Dead letter',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/DeadLetterResponse' },
+                            },
+                        },
+                    },
+                    '401': {
+                        description: 'Missing or invalid API key',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '404': {
+                        description: 'Dead letter not found',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/admin/dlq/replay/{id}': {
+            post: {
+                tags: ['Admin'],
+                summary: 'Replay a dead letter',
+                description:
+                    'Re-inserts an indexer document into its collection, or re-sponsors and executes a relayer transaction. Failed replays increment `attempts` and keep the entry. The replaying instance holds a lease on the entry, renewed while it runs, so concurrent replays from other instances are rejected; the lease of a crashed instance expires after `REPLAY_LEASE_TTL_MS` and can be taken over.',
+                security: [{ ApiKeyAuth: [] }],
+                parameters: [
+                    {
+                        name: 'id',
+                        in: 'path',
+                        required: true,
+                        schema: { type: 'string' },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Dead letter replayed',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/DeadLetterResponse' },
+                            },
+                        },
+                    },
+                    '401': {
+                        description: 'Missing or invalid API key',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '404': {
+                        description: 'Dead letter not found',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '409': {
+                        description:
+                            'Dead letter was already replayed, or is being replayed by another instance',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '500': {
+                        description: 'Replay failed',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
         '/api/v1/pools': {
             get: {
                 tags: ['Pools'],
@@ -200,7 +354,7 @@ export const openApiSpec: OpenAPIV3.Document = {
                     {
                         name: 'coin_type',
                         in: 'query',
-                        description: 'Filter by coin type (e.g., 0x2::sui::SUI)',
+                        description: 'Filter by coin type, short or long-form address (e.g., 0x2::sui::SUI)',
                         schema: { type: 'string' },
                     },
                 ],
@@ -216,6 +370,149 @@ export const openApiSpec: OpenAPIV3.Document = {
                 },
             },
         },
+        '/api/v1/pools/{coin_type}/roots': {
+            get: {
+                tags: ['Pools'],
+                summary: 'Get the Merkle root change feed of a pool',
+                description:
+                    'Roots of the pool tree in order, one per appended commitment pair, as the contract records them. `seq` is the number of pairs, so `leafCount` is `2 * seq`. A root is accepted by the contract while `latestSeq - seq < acceptanceWindow`; poll with `since_seq` set to the last seen `seq` to invalidate cached proofs whose root ages out. Roots from before the API first built the tree of a pool, other than the latest `acceptanceWindow`, are not available.',
+                parameters: [
+                    {
+                        name: 'coin_type',
+                        in: 'path',
+                        required: true,
+                        description: 'URL encoded coin type, short or long-form address (e.g., 0x2%3A%3Asui%3A%3ASUI)',
+                        schema: { type: 'string' },
+                    },
+                    {
+                        name: 'since_seq',
+                        in: 'query',
+                        description: 'Return roots with a greater seq',
+                        schema: { type: 'integer', minimum: 0, default: 0 },
+                    },
+                    {
+                        name: 'limit',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, maximum: 1000, default: 20 },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Roots after since_seq, oldest first',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/PoolRootsResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid coin type or query',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/pools/{coin_type}/leaf-counts': {
+            get: {
+                tags: ['Pools'],
+                summary: 'Get the anonymity set size of a pool over time',
+                description:
+                    'Leaves in the pool tree after each checkpoint that appended commitments to it, oldest first. The anonymity set of a withdrawal is the leaf count at the time it is made, so wallets can compare `latestLeafCount` against a threshold before withdrawing. Page with `since_checkpoint` set to the last seen checkpoint while `hasNext` is true.',
+                parameters: [
+                    {
+                        name: 'coin_type',
+                        in: 'path',
+                        required: true,
+                        description: 'URL encoded coin type, short or long-form address (e.g., 0x2%3A%3Asui%3A%3ASUI)',
+                        schema: { type: 'string' },
+                    },
+                    {
+                        name: 'since_checkpoint',
+                        in: 'query',
+                        description: 'Return leaf counts of later checkpoints',
+                        schema: { type: 'integer', minimum: 0, default: 0 },
+                    },
+                    {
+                        name: 'limit',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, maximum: 1000, default: 20 },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Leaf counts after since_checkpoint, oldest first',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/PoolLeafCountsResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid coin type or query',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/pools/{coin_type}/anonymity': {
+            get: {
+                tags: ['Pools'],
+                summary: 'Get anonymity advice for a planned withdrawal',
+                description:
+                    "Effective anonymity set of withdrawing a note in leaf `bucket` (the note's leaf index divided by 256, rounded down): the commitments appended to the pool after the bucket, each counting in proportion to its age up to one day, since a withdrawal right after a deposit is easy to pair with it by timing. Counting from the end of the bucket makes the set at most 255 commitments smaller than the note's own. The score is `moderate` from an effective set of 20 and `strong` from 100. Weaker scores come with a `wait` suggestion when waiting up to a week, at the pool's commitment rate over the last week, would make it strong.\n\nPrivacy: the request tells the API operator, and anyone observing it, that the caller owns a note in that bucket and is about to withdraw it. A request made shortly before a withdrawal narrows the withdrawn note down to the 256 leaves of the bucket. Query it well ahead of withdrawing, through a different network path than the withdrawal, or compute equivalent advice locally from `GET /api/v1/pools/{coin_type}/leaf-counts`, which reveals nothing about the note.",
+                parameters: [
+                    {
+                        name: 'coin_type',
+                        in: 'path',
+                        required: true,
+                        description: 'URL encoded coin type, short or long-form address (e.g., 0x2%3A%3Asui%3A%3ASUI)',
+                        schema: { type: 'string' },
+                    },
+                    {
+                        name: 'bucket',
+                        in: 'query',
+                        required: true,
+                        description:
+                            'Leaf index of the note to withdraw divided by 256, rounded down',
+                        schema: { type: 'integer', minimum: 0 },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Anonymity set and suggestions',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/PoolAnonymityResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid coin type or query',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '404': {
+                        description: 'No commitment in this bucket of the pool',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
         '/api/v1/commitments': {
             get: {
                 tags: ['Commitments'],
@@ -225,7 +522,7 @@ export const openApiSpec: OpenAPIV3.Document = {
                         name: 'coin_type',
                         in: 'query',
                         required: true,
-                        description: 'Coin type (e.g., 0x2::sui::SUI)',
+                        description: 'Coin type, short or long-form address (e.g., 0x2::sui::SUI)',
                         schema: { type: 'string', pattern: '^0x[a-fA-F0-9]+::\\w+::\\w+$' },
                     },
                     {
@@ -245,53 +542,289 @@ export const openApiSpec: OpenAPIV3.Document = {
                             default: 'gte',
                         },
                     },
-                    {
-                        name: 'page',
-                        in: 'query',
-                        schema: { type: 'integer', minimum: 1, default: 1 },
-                    },
-                    {
-                        name: 'limit',
-                        in: 'query',
-                        schema: { type: 'integer', minimum: 1, maximum: 500, default: 100 },
+                    {
+                        name: 'page',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, default: 1 },
+                    },
+                    {
+                        name: 'limit',
+                        in: 'query',
+                        schema: { type: 'integer', minimum: 1, maximum: 500, default: 100 },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Paginated list of commitments',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/CommitmentsResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/commitments/balance-inputs': {
+            get: {
+                tags: ['Commitments'],
+                summary: 'Get encrypted outputs of all pools',
+                description:
+                    'Returns, for every pool, the commitments and encrypted outputs past the given cursor, so a wallet can scan all its pools in one loop. All pools are returned whether or not a cursor is given, so the request does not reveal which pools the wallet uses.',
+                parameters: [
+                    {
+                        name: 'cursors',
+                        in: 'query',
+                        description:
+                            'Comma-separated <coin_type>:<index> pairs, the first index to return per pool (default 0)',
+                        schema: { type: 'string', example: '0x2::sui::SUI:128' },
+                    },
+                    {
+                        name: 'limit',
+                        in: 'query',
+                        description: 'Maximum items per pool',
+                        schema: { type: 'integer', minimum: 1, maximum: 1000, default: 20 },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Encrypted outputs per pool',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/BalanceInputsResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid cursors',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/nullifiers/spent': {
+            get: {
+                tags: ['Nullifiers'],
+                summary: 'Get spent nullifiers',
+                description:
+                    'Returns those of the given nullifiers already spent in the pool, so a wallet can drop inputs another device spent before proving.',
+                parameters: [
+                    {
+                        name: 'coin_type',
+                        in: 'query',
+                        required: true,
+                        description: 'Coin type, short or long-form address (e.g., 0x2::sui::SUI)',
+                        schema: { type: 'string', pattern: '^0x[a-fA-F0-9]+::\\w+::\\w+$' },
+                    },
+                    {
+                        name: 'nullifiers',
+                        in: 'query',
+                        required: true,
+                        description:
+                            'Comma-separated nullifiers, at most 16, as decimal or 64 hex digits of the little-endian bytes',
+                        schema: { type: 'string' },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'Spent nullifiers, in decimal',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/SpentNullifiersResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid coin type or nullifiers',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/merkle/path': {
+            post: {
+                tags: ['Merkle'],
+                summary: 'Get Merkle path for a commitment',
+                description: 'Returns the Merkle path and root for proving ownership of a UTXO',
+                requestBody: {
+                    required: true,
+                    content: {
+                        'application/json': {
+                            schema: { $ref: '#/components/schemas/MerklePathRequest' },
+                        },
+                    },
+                },
+                responses: {
+                    '200': {
+                        description: 'Merkle path',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/MerklePathResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid request',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/relayer': {
+            get: {
+                tags: ['Relayer'],
+                summary: 'Get relayer address',
+                description: 'Returns the Sui public address of the relayer',
+                responses: {
+                    '200': {
+                        description: 'Relayer address',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/RelayerResponse' },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        '/api/v1/transactions': {
+            post: {
+                tags: ['Transactions'],
+                summary: 'Execute a sponsored transaction',
+                description:
+                    'Takes transaction bytes from client, rebuilds, sponsors with Shinami, and executes. With an `Idempotency-Key` (the SDK derives it from the nullifier pair), a retry of a submission that executed returns the original digest with status 200 and `Idempotent-Replayed: true`; a failed submission releases its key so it can be retried. The key is bound to the SHA-256 of the first `txBytes` claiming it, and reusing it for different bytes is rejected with 422. A proof already submitted within the replay window is rejected with 422, so a copy of a pending proof cannot be sponsored twice.',
+                security: [{ ApiKeyAuth: [] }],
+                parameters: [
+                    {
+                        name: 'Idempotency-Key',
+                        in: 'header',
+                        required: false,
+                        schema: { type: 'string', pattern: '^[A-Za-z0-9_-]{16,128}$' },
+                    },
+                ],
+                requestBody: {
+                    required: true,
+                    content: {
+                        'application/json': {
+                            schema: { $ref: '#/components/schemas/ExecuteTransactionRequest' },
+                        },
+                    },
+                },
+                responses: {
+                    '200': {
+                        description:
+                            'Retry of an executed submission with the same Idempotency-Key',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/TransactionResponse' },
+                            },
+                        },
+                    },
+                    '201': {
+                        description: 'Transaction executed',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/TransactionResponse' },
+                            },
+                        },
+                    },
+                    '400': {
+                        description: 'Invalid request',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '401': {
+                        description: 'Missing or invalid API key',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '409': {
+                        description: 'A submission with the same Idempotency-Key is executing',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '422': {
+                        description:
+                            'The proof was already submitted within the replay window, or the Idempotency-Key was used for different transaction bytes',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
                     },
-                ],
-                responses: {
-                    '200': {
-                        description: 'Paginated list of commitments',
+                    '500': {
+                        description: 'Transaction failed',
                         content: {
                             'application/json': {
-                                schema: { $ref: '#/components/schemas/CommitmentsResponse' },
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
                             },
                         },
                     },
                 },
             },
         },
-        '/api/v1/merkle/path': {
+        '/api/v1/watches': {
             post: {
-                tags: ['Merkle'],
-                summary: 'Get Merkle path for a commitment',
-                description: 'Returns the Merkle path and root for proving ownership of a UTXO',
+                tags: ['Watches'],
+                summary: 'Watch a nullifier',
+                description:
+                    'Registers a nullifier to watch. When the indexer sees it spent, a notification is recorded, streamed to SSE subscribers and POSTed to the optional webhook. The webhook host must resolve to public addresses only, and redirects are not followed. Webhook bodies are signed with HMAC-SHA256 in the x-vortex-signature header, keyed by the webhookSecret returned once in this response. The returned id is the only handle to the watch; keep it private. Creation is rate limited per client.',
                 requestBody: {
                     required: true,
                     content: {
                         'application/json': {
-                            schema: { $ref: '#/components/schemas/MerklePathRequest' },
+                            schema: { $ref: '#/components/schemas/CreateWatchRequest' },
                         },
                     },
                 },
                 responses: {
-                    '200': {
-                        description: 'Merkle path',
+                    '201': {
+                        description: 'Watch created',
                         content: {
                             'application/json': {
-                                schema: { $ref: '#/components/schemas/MerklePathResponse' },
+                                schema: { $ref: '#/components/schemas/CreateWatchResponse' },
                             },
                         },
                     },
                     '400': {
-                        description: 'Invalid request',
+                        description: 'Invalid request or non-public webhook host',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '429': {
+                        description: 'Too many watches created by this client',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '500': {
+                        description: 'Server error',
                         content: {
                             'application/json': {
                                 schema: { $ref: '#/components/schemas/ErrorResponse' },
@@ -301,65 +834,106 @@ export const openApiSpec: OpenAPIV3.Document = {
                 },
             },
         },
-        '/api/v1/relayer': {
+        '/api/v1/watches/{id}': {
             get: {
-                tags: ['Relayer'],
-                summary: 'Get relayer address',
-                description: 'Returns the Sui public address of the relayer',
+                tags: ['Watches'],
+                summary: 'Get a watch and its notifications',
+                parameters: [
+                    {
+                        name: 'id',
+                        in: 'path',
+                        required: true,
+                        schema: { type: 'string', format: 'uuid' },
+                    },
+                ],
                 responses: {
                     '200': {
-                        description: 'Relayer address',
+                        description: 'Watch with notifications',
                         content: {
                             'application/json': {
-                                schema: { $ref: '#/components/schemas/RelayerResponse' },
+                                schema: { $ref: '#/components/schemas/WatchResponse' },
                             },
                         },
                     },
-                },
-            },
-        },
-        '/api/v1/transactions': {
-            post: {
-                tags: ['Transactions'],
-                summary: 'Execute a sponsored transaction',
-                description:
-                    'Takes transaction bytes from client, rebuilds, sponsors with Shinami, and executes',
-                security: [{ ApiKeyAuth: [] }],
-                requestBody: {
-                    required: true,
-                    content: {
-                        'application/json': {
-                            schema: { $ref: '#/components/schemas/ExecuteTransactionRequest' },
+                    '404': {
+                        description: 'Watch not found',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
+                        },
+                    },
+                    '500': {
+                        description: 'Server error',
+                        content: {
+                            'application/json': {
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
+                            },
                         },
                     },
                 },
+            },
+            delete: {
+                tags: ['Watches'],
+                summary: 'Delete a watch',
+                parameters: [
+                    {
+                        name: 'id',
+                        in: 'path',
+                        required: true,
+                        schema: { type: 'string', format: 'uuid' },
+                    },
+                ],
                 responses: {
-                    '201': {
-                        description: 'Transaction executed',
+                    '200': { description: 'Watch deleted' },
+                    '404': {
+                        description: 'Watch not found',
                         content: {
                             'application/json': {
-                                schema: { $ref: '#/components/schemas/TransactionResponse' },
+                                schema: { $ref: '#/components/schemas/ErrorResponse' },
                             },
                         },
                     },
-                    '400': {
-                        description: 'Invalid request',
+                    '500': {
+                        description: 'Server error',
                         content: {
                             'application/json': {
                                 schema: { $ref: '#/components/schemas/ErrorResponse' },
                             },
                         },
                     },
-                    '401': {
-                        description: 'Missing or invalid API key',
+                },
+            },
+        },
+        '/api/v1/watches/{id}/events': {
+            get: {
+                tags: ['Watches'],
+                summary: 'Stream watch notifications',
+                description:
+                    'Server-sent events stream. Emits a `notification` event for every match, including matches recorded before the stream was opened. Reconnecting with Last-Event-ID resumes after that notification. The number of concurrent streams per watch is capped.',
+                parameters: [
+                    {
+                        name: 'id',
+                        in: 'path',
+                        required: true,
+                        schema: { type: 'string', format: 'uuid' },
+                    },
+                ],
+                responses: {
+                    '200': {
+                        description: 'SSE stream of WatchNotification objects',
+                        content: { 'text/event-stream': { schema: { type: 'string' } } },
+                    },
+                    '404': {
+                        description: 'Watch not found',
                         content: {
                             'application/json': {
                                 schema: { $ref: '#/components/schemas/ErrorResponse' },
                             },
                         },
                     },
-                    '500': {
-                        description: 'Transaction failed',
+                    '429': {
+                        description: 'Too many open streams for this watch',
                         content: {
                             'application/json': {
                                 schema: { $ref: '#/components/schemas/ErrorResponse' },
@@ -376,7 +950,7 @@ export const openApiSpec: OpenAPIV3.Document = {
                 type: 'apiKey',
                 in: 'header',
                 name: 'x-api-key',
-                description: 'API key required for transaction execution',
+                description: 'API key required for transaction execution and admin endpoints',
             },
         },
         schemas: {
@@ -426,7 +1000,8 @@ export const openApiSpec: OpenAPIV3.Document = {
                     },
                     hashedSecret: {
                         type: 'string',
-                        description: 'Poseidon hash of secret (decimal string)',
+                        description:
+                            'Poseidon hash of secret (decimal, 0x-hex or [b0, b1, ...] byte array)',
                         example: '12345678901234567890',
                     },
                 },
@@ -530,6 +1105,123 @@ export const openApiSpec: OpenAPIV3.Document = {
                     },
                 },
             },
+            PoolRoot: {
+                type: 'object',
+                properties: {
+                    seq: { type: 'integer' },
+                    root: { type: 'string', description: 'Decimal root' },
+                    leafCount: { type: 'integer' },
+                    checkpoint: { type: 'integer', description: 'Checkpoint of the pair' },
+                },
+            },
+            PoolRootsResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            items: {
+                                type: 'array',
+                                items: { $ref: '#/components/schemas/PoolRoot' },
+                            },
+                            latestSeq: { type: 'integer' },
+                            acceptanceWindow: { type: 'integer', example: 100 },
+                            hasNext: { type: 'boolean' },
+                        },
+                    },
+                },
+            },
+            PoolLeafCount: {
+                type: 'object',
+                properties: {
+                    checkpoint: { type: 'integer' },
+                    checkpointTimestampMs: { type: 'integer' },
+                    leafCount: { type: 'integer' },
+                },
+            },
+            PoolLeafCountsResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            items: {
+                                type: 'array',
+                                items: { $ref: '#/components/schemas/PoolLeafCount' },
+                            },
+                            latestLeafCount: { type: 'integer' },
+                            hasNext: { type: 'boolean' },
+                        },
+                    },
+                },
+            },
+            PoolAnonymityResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            commitmentsSince: { type: 'integer' },
+                            effectiveSetSize: { type: 'integer' },
+                            score: { type: 'string', enum: ['weak', 'moderate', 'strong'] },
+                            suggestions: {
+                                type: 'array',
+                                items: {
+                                    type: 'object',
+                                    properties: {
+                                        type: { type: 'string', enum: ['wait'] },
+                                        hours: { type: 'integer' },
+                                        projectedSetSize: { type: 'integer' },
+                                    },
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+            DeadLetter: {
+                type: 'object',
+                properties: {
+                    id: { type: 'string' },
+                    source: { type: 'string', enum: ['indexer', 'relayer'] },
+                    collection: { type: 'string', nullable: true },
+                    payload: { type: 'object' },
+                    error: { type: 'string' },
+                    attempts: { type: 'integer' },
+                    createdAt: { type: 'string', format: 'date-time' },
+                    lastAttemptAt: { type: 'string', format: 'date-time' },
+                    replayedAt: { type: 'string', format: 'date-time', nullable: true },
+                    replayDigest: { type: 'string', nullable: true },
+                    leaseOwner: { type: 'string', nullable: true },
+                    leaseExpiresAt: { type: 'string', format: 'date-time', nullable: true },
+                },
+            },
+            DeadLetterResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: { $ref: '#/components/schemas/DeadLetter' },
+                },
+            },
+            DeadLettersResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            items: {
+                                type: 'array',
+                                items: { $ref: '#/components/schemas/DeadLetter' },
+                            },
+                            pagination: { $ref: '#/components/schemas/Pagination' },
+                        },
+                    },
+                },
+            },
             Commitment: {
                 type: 'object',
                 properties: {
@@ -560,6 +1252,57 @@ export const openApiSpec: OpenAPIV3.Document = {
                     },
                 },
             },
+            SpentNullifiersResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            spent: { type: 'array', items: { type: 'string' } },
+                        },
+                    },
+                },
+            },
+            BalanceInputsResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            pools: {
+                                type: 'array',
+                                items: {
+                                    type: 'object',
+                                    properties: {
+                                        coinType: { type: 'string' },
+                                        items: {
+                                            type: 'array',
+                                            items: {
+                                                type: 'object',
+                                                properties: {
+                                                    index: { type: 'integer' },
+                                                    commitment: { type: 'string' },
+                                                    encryptedOutput: {
+                                                        type: 'array',
+                                                        items: { type: 'integer' },
+                                                    },
+                                                },
+                                            },
+                                        },
+                                        nextIndex: {
+                                            type: 'integer',
+                                            description: 'Cursor to send on the next request',
+                                        },
+                                        hasNext: { type: 'boolean' },
+                                    },
+                                },
+                            },
+                        },
+                    },
+                },
+            },
             MerklePathRequest: {
                 type: 'object',
                 properties: {
@@ -575,15 +1318,15 @@ export const openApiSpec: OpenAPIV3.Document = {
                     },
                     amount: {
                         type: 'string',
-                        description: 'UTXO amount (decimal string)',
+                        description: 'UTXO amount (decimal, 0x-hex or byte array)',
                     },
                     public_key: {
                         type: 'string',
-                        description: 'Public key (decimal string)',
+                        description: 'Public key (decimal, 0x-hex or byte array)',
                     },
                     blinding: {
                         type: 'string',
-                        description: 'Blinding factor (decimal string)',
+                        description: 'Blinding factor (decimal, 0x-hex or byte array)',
                     },
                     vortex_pool: {
                         type: 'string',
@@ -653,6 +1396,78 @@ export const openApiSpec: OpenAPIV3.Document = {
                     },
                 },
             },
+            WatchNotification: {
+                type: 'object',
+                properties: {
+                    id: { type: 'string' },
+                    watchId: { type: 'string', format: 'uuid' },
+                    kind: { type: 'string', enum: ['nullifier'] },
+                    nullifier: { type: 'string', description: 'Decimal nullifier' },
+                    digest: { type: 'string', description: 'Spending transaction digest' },
+                    coinType: { type: 'string' },
+                    checkpoint: { type: 'number' },
+                    checkpointTimestampMs: { type: 'number' },
+                    delivered: { type: 'boolean', description: 'Webhook delivered' },
+                },
+            },
+            CreateWatchRequest: {
+                type: 'object',
+                properties: {
+                    nullifier: {
+                        type: 'string',
+                        description:
+                            'Nullifier to watch, as a decimal or as the 64 hex digits of its little-endian bytes (0x optional, any case)',
+                    },
+                    webhookUrl: {
+                        type: 'string',
+                        format: 'uri',
+                        description: 'Optional HTTPS endpoint notified when the nullifier is spent',
+                    },
+                },
+                required: ['nullifier'],
+            },
+            CreateWatchResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            id: { type: 'string', format: 'uuid' },
+                            kind: { type: 'string', enum: ['nullifier'] },
+                            nullifier: { type: 'string' },
+                            webhookUrl: { type: 'string', nullable: true },
+                            webhookSecret: {
+                                type: 'string',
+                                nullable: true,
+                                description:
+                                    'HMAC-SHA256 key for this watch\'s webhook signatures. Only returned here; null without a webhook',
+                            },
+                            createdAt: { type: 'string', format: 'date-time' },
+                        },
+                    },
+                },
+            },
+            WatchResponse: {
+                type: 'object',
+                properties: {
+                    success: { type: 'boolean', example: true },
+                    data: {
+                        type: 'object',
+                        properties: {
+                            id: { type: 'string', format: 'uuid' },
+                            kind: { type: 'string', enum: ['nullifier'] },
+                            nullifier: { type: 'string' },
+                            webhookUrl: { type: 'string', nullable: true },
+                            createdAt: { type: 'string', format: 'date-time' },
+                            notifications: {
+                                type: 'array',
+                                items: { $ref: '#/components/schemas/WatchNotification' },
+                            },
+                        },
+                    },
+                },
+            },
         },
     },
 };
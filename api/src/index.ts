@@ -1,24 +1,29 @@
 import { Hono } from 'hono';
-import { logger as honoLogger } from 'hono/logger';
 import { Scalar } from '@scalar/hono-api-reference';
+import { formatDoctorReport, runDoctor } from '@/config/doctor.ts';
 import { env } from '@/config/env.ts';
-import { connectMongoDB, disconnectMongoDB } from '@/db/mongodb.ts';
+import { connectMongoDB, disconnectMongoDB, getDb } from '@/db/mongodb.ts';
 import { connectRedis, disconnectRedis } from '@/db/redis.ts';
+import { startCompactionScheduler } from '@/db/retention.ts';
 import { openApiSpec } from '@/docs/openapi.ts';
 import {
     corsMiddleware,
     databaseMiddleware,
     errorHandler,
     rateLimitMiddleware,
+    requestLogMiddleware,
 } from '@/middleware/index.ts';
+import { createWatchesRepository } from '@/repositories/index.ts';
 import { routes } from '@/routes/index.ts';
+import { createWatchesService, startWebhookDispatcher } from '@/services/index.ts';
 import type { AppBindings } from '@/types/index.ts';
 import { logger } from '@/utils/logger.ts';
+import { createRuntime, installShutdownHandlers } from '@/utils/runtime.ts';
 
 const createApp = () => {
     const app = new Hono<AppBindings>();
 
-    app.use(honoLogger());
+    app.use(requestLogMiddleware);
     app.use(corsMiddleware);
     app.use(databaseMiddleware);
     app.use(rateLimitMiddleware);
@@ -54,9 +59,29 @@ const createApp = () => {
     return app;
 };
 
+const runtime = createRuntime(env.SHUTDOWN_GRACE_MS);
+
 const main = async () => {
+    const report = await runDoctor();
+    console.log(formatDoctorReport(report));
+
+    if (process.argv.includes('--doctor')) process.exit(report.passed ? 0 : 1);
+    if (!report.passed) {
+        logger.error('Startup checks failed, see the checklist above');
+        process.exit(1);
+    }
+
     await connectMongoDB();
+    runtime.onShutdown('mongodb', disconnectMongoDB);
     connectRedis();
+    runtime.onShutdown('redis', disconnectRedis);
+
+    runtime.onShutdown(
+        'webhook-dispatcher',
+        startWebhookDispatcher(createWatchesService(createWatchesRepository(getDb())))
+    );
+
+    runtime.onShutdown('compaction-scheduler', startCompactionScheduler(getDb()));
 
     const app = createApp();
 
@@ -69,20 +94,6 @@ const main = async () => {
     };
 };
 
-const shutdown = async () => {
-    logger.info('Shutting down...');
-    await disconnectMongoDB();
-    await disconnectRedis();
-    process.exit(0);
-};
-
-const handleShutdown = () => {
-    shutdown().catch((err: unknown) => {
-        logger.error({ err }, 'Shutdown error');
-    });
-};
-
-process.on('SIGINT', handleShutdown);
-process.on('SIGTERM', handleShutdown);
+installShutdownHandlers(runtime);
 
 export default await main();
@@ -15,8 +15,8 @@ const createCorsMiddleware = (): MiddlewareHandler<AppBindings> => {
     return cors({
         origin,
         allowMethods: ['GET', 'POST', 'OPTIONS'],
-        allowHeaders: ['Content-Type', 'Authorization', 'x-api-key'],
-        exposeHeaders: ['Content-Length'],
+        allowHeaders: ['Content-Type', 'Authorization', 'x-api-key', 'Idempotency-Key'],
+        exposeHeaders: ['Content-Length', 'Idempotent-Replayed'],
         maxAge: 86400,
         credentials: origin !== '*',
     });
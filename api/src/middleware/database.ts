@@ -6,12 +6,23 @@ import {
     createPoolsRepository,
     createAccountsRepository,
     createCommitmentsRepository,
+    createDeadLettersRepository,
+    createIdempotencyKeysRepository,
+    createLeafCountsRepository,
+    createNullifiersRepository,
+    createPoolRootsRepository,
+    createRelayerJobsRepository,
+    createSeenProofsRepository,
+    createWatchesRepository,
 } from '@/repositories/index.ts';
 import { createAccountsService } from '@/services/accounts.ts';
+import { createAnonymityService } from '@/services/anonymity.ts';
+import { createDeadLettersService } from '@/services/dead-letters.ts';
 import { createHealthService } from '@/services/health.ts';
 import { createMerkleService } from '@/services/merkle.ts';
 import { createRelayerService } from '@/services/relayer.ts';
 import { createTransactionsService } from '@/services/transactions.ts';
+import { createWatchesService } from '@/services/watches.ts';
 import { keypair } from '@/services/sui.ts';
 
 export const databaseMiddleware: MiddlewareHandler<AppBindings> = async (c, next) => {
@@ -21,15 +32,33 @@ export const databaseMiddleware: MiddlewareHandler<AppBindings> = async (c, next
     const pools = createPoolsRepository(db);
     const accounts = createAccountsRepository(db);
     const commitments = createCommitmentsRepository(db);
+    const deadLetters = createDeadLettersRepository(db);
+    const relayerJobs = createRelayerJobsRepository(db);
+    const watches = createWatchesRepository(db);
 
     c.set('pools', pools);
     c.set('accounts', accounts);
     c.set('commitments', commitments);
+    c.set('deadLetters', deadLetters);
+    c.set('leafCounts', createLeafCountsRepository(db));
+    c.set('nullifiers', createNullifiersRepository(db));
+    c.set('watches', watches);
     c.set('accountsService', createAccountsService(accounts));
+    c.set('anonymityService', createAnonymityService(commitments));
+    c.set('deadLettersService', createDeadLettersService(db, deadLetters));
     c.set('healthService', createHealthService(db, redis));
-    c.set('merkleService', createMerkleService(redis, commitments));
+    c.set('merkleService', createMerkleService(redis, commitments, createPoolRootsRepository(db)));
     c.set('relayerService', createRelayerService(keypair));
-    c.set('transactionsService', createTransactionsService());
+    c.set(
+        'transactionsService',
+        createTransactionsService(
+            deadLetters,
+            relayerJobs,
+            createIdempotencyKeysRepository(db),
+            createSeenProofsRepository(db)
+        )
+    );
+    c.set('watchesService', createWatchesService(watches));
 
     await next();
 };
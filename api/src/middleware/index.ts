@@ -3,3 +3,4 @@ export { corsMiddleware } from './cors.ts';
 export { databaseMiddleware } from './database.ts';
 export { errorHandler } from './error.ts';
 export { createRateLimiter, rateLimitMiddleware } from './rate-limit.ts';
+export { requestLogMiddleware } from './request-log.ts';
@@ -22,7 +22,7 @@ const DEFAULT_CONFIG: RateLimitConfig = {
     keyPrefix: 'rl',
 };
 
-const getClientIp = (c: {
+export const getClientIp = (c: {
     req: { header: (name: string) => string | undefined };
 }): string | null => {
     const ip =
@@ -0,0 +1,21 @@
+import type { MiddlewareHandler } from 'hono';
+import type { AppBindings } from '@/types/index.ts';
+import { logger } from '@/utils/logger.ts';
+import { getClientIp } from './rate-limit.ts';
+
+export const requestLogMiddleware: MiddlewareHandler<AppBindings> = async (c, next) => {
+    const start = performance.now();
+
+    await next();
+
+    logger.info(
+        {
+            method: c.req.method,
+            path: c.req.path,
+            status: c.res.status,
+            ms: Math.round(performance.now() - start),
+            ip: getClientIp(c),
+        },
+        'Request'
+    );
+};
@@ -14,6 +14,15 @@ export type CommitmentsRepository = {
     }) => Promise<CommitmentDocument[]>;
     count: (filter: CommitmentFilter) => Promise<number>;
     findFromIndex: (coinType: string, fromIndex: number) => Promise<CommitmentDocument[]>;
+    coinTypes: () => Promise<string[]>;
+    digestsUpTo: (coinType: string, checkpoint: number) => Promise<string[]>;
+    /** Checkpoint timestamps of the commitments from `fromIndex` appended since `sinceMs`. */
+    timestampsFromIndex: (
+        coinType: string,
+        fromIndex: number,
+        sinceMs: number
+    ) => Promise<number[]>;
+    countSince: (coinType: string, sinceMs: number) => Promise<number>;
 };
 
 export const createCommitmentsRepository = (db: Db): CommitmentsRepository => {
@@ -30,5 +39,33 @@ export const createCommitmentsRepository = (db: Db): CommitmentsRepository => {
                 .find({ coin_type: coinType, index: { $gte: fromIndex } })
                 .sort({ index: 1 })
                 .toArray(),
+
+        coinTypes: async () => collection.distinct('coin_type'),
+
+        digestsUpTo: async (coinType, checkpoint) =>
+            collection.distinct('digest', {
+                coin_type: coinType,
+                checkpoint: { $lte: checkpoint },
+            }),
+
+        timestampsFromIndex: async (coinType, fromIndex, sinceMs) => {
+            const docs = await collection
+                .find(
+                    {
+                        coin_type: coinType,
+                        index: { $gte: fromIndex },
+                        checkpoint_timestamp_ms: { $gte: sinceMs },
+                    },
+                    { projection: { _id: 0, checkpoint_timestamp_ms: 1 } }
+                )
+                .toArray();
+            return docs.map((doc) => doc.checkpoint_timestamp_ms);
+        },
+
+        countSince: async (coinType, sinceMs) =>
+            collection.countDocuments({
+                coin_type: coinType,
+                checkpoint_timestamp_ms: { $gte: sinceMs },
+            }),
     };
 };
@@ -0,0 +1,113 @@
+import type { Db } from 'mongodb';
+import {
+    DEAD_LETTERS_COLLECTION,
+    type DeadLetterDocument,
+    type DeadLetterSource,
+} from '@/db/collections/index.ts';
+
+export type DeadLetterFilter = {
+    source?: DeadLetterSource;
+    replayed?: boolean;
+};
+
+export type DeadLettersRepository = {
+    find: (params: {
+        filter: DeadLetterFilter;
+        skip: number;
+        limit: number;
+    }) => Promise<DeadLetterDocument[]>;
+    count: (filter: DeadLetterFilter) => Promise<number>;
+    findById: (id: string) => Promise<DeadLetterDocument | null>;
+    insert: (doc: DeadLetterDocument) => Promise<void>;
+    recordAttempt: (id: string, error: string) => Promise<void>;
+    markReplayed: (id: string, digest?: string) => Promise<void>;
+    acquireLease: (id: string, owner: string, ttlMs: number) => Promise<boolean>;
+    renewLease: (id: string, owner: string, ttlMs: number) => Promise<boolean>;
+    releaseLease: (id: string, owner: string) => Promise<void>;
+};
+
+const toQuery = ({ source, replayed }: DeadLetterFilter) => ({
+    ...(source ? { source } : {}),
+    ...(replayed === undefined ? {} : { replayed_at: { $exists: replayed } }),
+});
+
+export const createDeadLettersRepository = (db: Db): DeadLettersRepository => {
+    const collection = db.collection<DeadLetterDocument>(DEAD_LETTERS_COLLECTION);
+
+    return {
+        find: async ({ filter, skip, limit }) =>
+            collection
+                .find(toQuery(filter))
+                .sort({ created_at: -1 })
+                .skip(skip)
+                .limit(limit)
+                .toArray(),
+
+        count: async (filter) => collection.countDocuments(toQuery(filter)),
+
+        findById: async (id) => collection.findOne({ _id: id }),
+
+        insert: async (doc) => {
+            await collection.insertOne(doc);
+        },
+
+        recordAttempt: async (id, error) => {
+            await collection.updateOne(
+                { _id: id },
+                { $set: { error, last_attempt_at: new Date() }, $inc: { attempts: 1 } }
+            );
+        },
+
+        markReplayed: async (id, digest) => {
+            const now = new Date();
+            await collection.updateOne(
+                { _id: id },
+                {
+                    $set: {
+                        replayed_at: now,
+                        last_attempt_at: now,
+                        ...(digest ? { replay_digest: digest } : {}),
+                    },
+                    $unset: { lease_owner: '', lease_expires_at: '' },
+                    $inc: { attempts: 1 },
+                }
+            );
+        },
+
+        acquireLease: async (id, owner, ttlMs) => {
+            const now = new Date();
+            const result = await collection.updateOne(
+                {
+                    _id: id,
+                    replayed_at: { $exists: false },
+                    $or: [
+                        { lease_expires_at: { $exists: false } },
+                        { lease_expires_at: { $lte: now } },
+                    ],
+                },
+                {
+                    $set: {
+                        lease_owner: owner,
+                        lease_expires_at: new Date(now.getTime() + ttlMs),
+                    },
+                }
+            );
+            return result.modifiedCount === 1;
+        },
+
+        renewLease: async (id, owner, ttlMs) => {
+            const result = await collection.updateOne(
+                { _id: id, lease_owner: owner },
+                { $set: { lease_expires_at: new Date(Date.now() + ttlMs) } }
+            );
+            return result.matchedCount === 1;
+        },
+
+        releaseLease: async (id, owner) => {
+            await collection.updateOne(
+                { _id: id, lease_owner: owner },
+                { $unset: { lease_owner: '', lease_expires_at: '' } }
+            );
+        },
+    };
+};
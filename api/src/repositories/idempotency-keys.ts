@@ -0,0 +1,65 @@
+import type { Db } from 'mongodb';
+import { isDuplicateKeyError } from '@/db/mongodb.ts';
+import {
+    IDEMPOTENCY_KEYS_COLLECTION,
+    type IdempotencyKeyDocument,
+} from '@/db/collections/index.ts';
+
+export type IdempotencyKeysRepository = {
+    /**
+     * Claims `key` for the transaction hashing to `txHash`. Returns null when
+     * the claim is taken, otherwise the document of the request holding it. A
+     * pending claim for the same transaction made before `staleBefore` belongs
+     * to a request that died and is taken over.
+     */
+    claim: (
+        key: string,
+        txHash: string,
+        staleBefore: Date
+    ) => Promise<IdempotencyKeyDocument | null>;
+    complete: (key: string, digest: string) => Promise<void>;
+    release: (key: string) => Promise<void>;
+};
+
+export const createIdempotencyKeysRepository = (db: Db): IdempotencyKeysRepository => {
+    const collection = db.collection<IdempotencyKeyDocument>(IDEMPOTENCY_KEYS_COLLECTION);
+
+    const claim: IdempotencyKeysRepository['claim'] = async (key, txHash, staleBefore) => {
+        const now = new Date();
+        try {
+            await collection.insertOne({
+                _id: key,
+                tx_hash: txHash,
+                digest: null,
+                claimed_at: now,
+            });
+            return null;
+        } catch (error) {
+            if (!isDuplicateKeyError(error)) throw error;
+        }
+
+        const takeover = await collection.updateOne(
+            { _id: key, tx_hash: txHash, digest: null, claimed_at: { $lte: staleBefore } },
+            { $set: { claimed_at: now } }
+        );
+        if (takeover.modifiedCount === 1) return null;
+
+        // Released between the insert and the read: claim it again
+        return (await collection.findOne({ _id: key })) ?? claim(key, txHash, staleBefore);
+    };
+
+    return {
+        claim,
+
+        complete: async (key, digest) => {
+            await collection.updateOne(
+                { _id: key },
+                { $set: { digest, completed_at: new Date() } }
+            );
+        },
+
+        release: async (key) => {
+            await collection.deleteOne({ _id: key, digest: null });
+        },
+    };
+};
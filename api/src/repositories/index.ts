@@ -1,3 +1,11 @@
 export * from './accounts.ts';
 export * from './commitments.ts';
+export * from './dead-letters.ts';
+export * from './idempotency-keys.ts';
+export * from './leaf-counts.ts';
+export * from './nullifiers.ts';
+export * from './pool-roots.ts';
 export * from './pools.ts';
+export * from './relayer-jobs.ts';
+export * from './seen-proofs.ts';
+export * from './watches.ts';
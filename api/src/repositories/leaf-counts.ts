@@ -0,0 +1,27 @@
+import type { Db } from 'mongodb';
+import { LEAF_COUNTS_COLLECTION, type LeafCountDocument } from '@/db/collections/index.ts';
+
+export type LeafCountsRepository = {
+    findSince: (
+        coinType: string,
+        sinceCheckpoint: number,
+        limit: number
+    ) => Promise<LeafCountDocument[]>;
+    latest: (coinType: string) => Promise<LeafCountDocument | null>;
+};
+
+export const createLeafCountsRepository = (db: Db): LeafCountsRepository => {
+    const collection = db.collection<LeafCountDocument>(LEAF_COUNTS_COLLECTION);
+
+    return {
+        findSince: async (coinType, sinceCheckpoint, limit) =>
+            collection
+                .find({ coin_type: coinType, checkpoint: { $gt: sinceCheckpoint } })
+                .sort({ checkpoint: 1 })
+                .limit(limit)
+                .toArray(),
+
+        latest: async (coinType) =>
+            collection.findOne({ coin_type: coinType }, { sort: { checkpoint: -1 } }),
+    };
+};
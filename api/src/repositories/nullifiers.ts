@@ -0,0 +1,19 @@
+import type { Db } from 'mongodb';
+import { NULLIFIERS_COLLECTION, type NullifierDocument } from '@/db/collections/index.ts';
+
+export type NullifiersRepository = {
+    /** Those of `nullifiers` spent in the `coinType` pool. */
+    findSpent: (coinType: string, nullifiers: string[]) => Promise<string[]>;
+};
+
+export const createNullifiersRepository = (db: Db): NullifiersRepository => {
+    const collection = db.collection<NullifierDocument>(NULLIFIERS_COLLECTION);
+
+    return {
+        findSpent: async (coinType, nullifiers) =>
+            collection.distinct('nullifier', {
+                coin_type: coinType,
+                nullifier: { $in: nullifiers },
+            }),
+    };
+};
@@ -0,0 +1,35 @@
+import type { Db } from 'mongodb';
+import { POOL_ROOTS_COLLECTION, type PoolRootDocument } from '@/db/collections/index.ts';
+
+export type PoolRootsRepository = {
+    insertMany: (docs: PoolRootDocument[]) => Promise<void>;
+    findSince: (coinType: string, sinceSeq: number, limit: number) => Promise<PoolRootDocument[]>;
+};
+
+export const createPoolRootsRepository = (db: Db): PoolRootsRepository => {
+    const collection = db.collection<PoolRootDocument>(POOL_ROOTS_COLLECTION);
+
+    return {
+        // Upserts, since instances sharing the database record the same roots
+        insertMany: async (docs) => {
+            if (docs.length === 0) return;
+            await collection.bulkWrite(
+                docs.map((doc) => ({
+                    updateOne: {
+                        filter: { _id: doc._id },
+                        update: { $setOnInsert: doc },
+                        upsert: true,
+                    },
+                })),
+                { ordered: false }
+            );
+        },
+
+        findSince: async (coinType, sinceSeq, limit) =>
+            collection
+                .find({ coin_type: coinType, seq: { $gt: sinceSeq } })
+                .sort({ seq: 1 })
+                .limit(limit)
+                .toArray(),
+    };
+};
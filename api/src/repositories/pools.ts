@@ -8,6 +8,7 @@ export type PoolFilter = {
 export type PoolsRepository = {
     find: (params: { filter: PoolFilter; skip: number; limit: number }) => Promise<PoolDocument[]>;
     count: (filter: PoolFilter) => Promise<number>;
+    createdUpTo: (checkpoint: number, filter: PoolFilter) => Promise<PoolDocument[]>;
 };
 
 export const createPoolsRepository = (db: Db): PoolsRepository => {
@@ -18,5 +19,11 @@ export const createPoolsRepository = (db: Db): PoolsRepository => {
             collection.find(filter).sort({ checkpoint: -1 }).skip(skip).limit(limit).toArray(),
 
         count: async (filter) => collection.countDocuments(filter),
+
+        createdUpTo: async (checkpoint, filter) =>
+            collection
+                .find({ ...filter, checkpoint: { $lte: checkpoint } })
+                .sort({ checkpoint: 1 })
+                .toArray(),
     };
 };
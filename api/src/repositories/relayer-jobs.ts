@@ -0,0 +1,23 @@
+import type { Db } from 'mongodb';
+import { RELAYER_JOBS_COLLECTION, type RelayerJobDocument } from '@/db/collections/index.ts';
+
+export type RelayerJobsRepository = {
+    insert: (doc: RelayerJobDocument) => Promise<void>;
+    findBetween: (from: Date, to: Date) => Promise<RelayerJobDocument[]>;
+};
+
+export const createRelayerJobsRepository = (db: Db): RelayerJobsRepository => {
+    const collection = db.collection<RelayerJobDocument>(RELAYER_JOBS_COLLECTION);
+
+    return {
+        insert: async (doc) => {
+            await collection.insertOne(doc);
+        },
+
+        findBetween: async (from, to) =>
+            collection
+                .find({ created_at: { $gte: from, $lt: to } })
+                .sort({ created_at: 1 })
+                .toArray(),
+    };
+};
@@ -0,0 +1,38 @@
+import type { Db } from 'mongodb';
+import { isDuplicateKeyError } from '@/db/mongodb.ts';
+import { SEEN_PROOFS_COLLECTION, type SeenProofDocument } from '@/db/collections/index.ts';
+
+export type SeenProofsRepository = {
+    /**
+     * Records `hash` as seen until `expiresAt`. Returns false when it was
+     * already seen and its window has not expired.
+     */
+    record: (hash: string, expiresAt: Date) => Promise<boolean>;
+    forget: (hash: string) => Promise<void>;
+};
+
+export const createSeenProofsRepository = (db: Db): SeenProofsRepository => {
+    const collection = db.collection<SeenProofDocument>(SEEN_PROOFS_COLLECTION);
+
+    return {
+        record: async (hash, expiresAt) => {
+            try {
+                await collection.insertOne({ _id: hash, expires_at: expiresAt });
+                return true;
+            } catch (error) {
+                if (!isDuplicateKeyError(error)) throw error;
+            }
+
+            // The TTL monitor only runs once a minute, so expired entries may linger
+            const takeover = await collection.updateOne(
+                { _id: hash, expires_at: { $lte: new Date() } },
+                { $set: { expires_at: expiresAt } }
+            );
+            return takeover.modifiedCount === 1;
+        },
+
+        forget: async (hash) => {
+            await collection.deleteOne({ _id: hash });
+        },
+    };
+};
@@ -0,0 +1,102 @@
+import type { Db } from 'mongodb';
+import {
+    WATCHES_COLLECTION,
+    WATCH_NOTIFICATIONS_COLLECTION,
+    type WatchDocument,
+    type WatchNotificationDocument,
+} from '@/db/collections/index.ts';
+
+/** Position of the last notification sent on a stream, in `(checkpoint, _id)` order */
+export type WatchNotificationCursor = {
+    checkpoint: number;
+    id: string;
+};
+
+export type WatchesRepository = {
+    findById: (id: string) => Promise<WatchDocument | null>;
+    findByIds: (ids: string[]) => Promise<WatchDocument[]>;
+    insert: (doc: WatchDocument) => Promise<void>;
+    delete: (id: string) => Promise<boolean>;
+    findNotifications: (watchId: string) => Promise<WatchNotificationDocument[]>;
+    findNotificationById: (id: string) => Promise<WatchNotificationDocument | null>;
+    findNotificationsAfter: (
+        watchId: string,
+        after: WatchNotificationCursor | null
+    ) => Promise<WatchNotificationDocument[]>;
+    findUndelivered: (params: {
+        maxAttempts: number;
+        limit: number;
+    }) => Promise<WatchNotificationDocument[]>;
+    recordAttempt: (id: string, delivered: boolean) => Promise<void>;
+};
+
+export const createWatchesRepository = (db: Db): WatchesRepository => {
+    const watches = db.collection<WatchDocument>(WATCHES_COLLECTION);
+    const notifications = db.collection<WatchNotificationDocument>(WATCH_NOTIFICATIONS_COLLECTION);
+
+    return {
+        findById: async (id) => watches.findOne({ _id: id }),
+
+        findByIds: async (ids) => watches.find({ _id: { $in: ids } }).toArray(),
+
+        insert: async (doc) => {
+            await watches.insertOne(doc);
+        },
+
+        delete: async (id) => {
+            const [result] = await Promise.all([
+                watches.deleteOne({ _id: id }),
+                notifications.deleteMany({ watch_id: id }),
+            ]);
+            return result.deletedCount > 0;
+        },
+
+        findNotifications: async (watchId) =>
+            notifications.find({ watch_id: watchId }).sort({ checkpoint: 1 }).toArray(),
+
+        findNotificationById: async (id) => notifications.findOne({ _id: id }),
+
+        findNotificationsAfter: async (watchId, after) =>
+            notifications
+                .find(
+                    after
+                        ? {
+                              watch_id: watchId,
+                              $or: [
+                                  { checkpoint: { $gt: after.checkpoint } },
+                                  { checkpoint: after.checkpoint, _id: { $gt: after.id } },
+                              ],
+                          }
+                        : { watch_id: watchId }
+                )
+                .sort({ checkpoint: 1, _id: 1 })
+                .toArray(),
+
+        findUndelivered: async ({ maxAttempts, limit }) =>
+            notifications
+                .find({
+                    delivered: false,
+                    webhook_url: { $ne: null },
+                    $or: [
+                        { delivery_attempts: { $exists: false } },
+                        { delivery_attempts: { $lt: maxAttempts } },
+                    ],
+                })
+                .sort({ created_at: 1 })
+                .limit(limit)
+                .toArray(),
+
+        recordAttempt: async (id, delivered) => {
+            const now = new Date();
+            await notifications.updateOne(
+                { _id: id },
+                {
+                    $set: delivered
+                        ? { delivered: true, delivered_at: now, last_attempt_at: now }
+                        : { last_attempt_at: now },
+                    $inc: { delivery_attempts: 1 },
+                }
+            );
+        },
+    };
+};
@@ -0,0 +1,73 @@
+import type { Context } from 'hono';
+import type { AppBindings } from '@/types/index.ts';
+import { buildPaginatedResponse } from '@/types/index.ts';
+import { validateParams, validateQuery } from '@/utils/validation.ts';
+import { withErrorHandler } from '@/utils/handler.ts';
+import { deadLetterParamsSchema, deadLettersQuerySchema } from './schema.ts';
+import { toDeadLetter } from './mappers.ts';
+
+const deadLetterNotFound = (c: Context<AppBindings>) =>
+    c.json({ success: false, error: 'Dead letter not found' }, 404);
+
+const getDeadLettersHandler = async (c: Context<AppBindings>) => {
+    const validation = validateQuery(c, deadLettersQuerySchema);
+    if (!validation.success) return validation.response;
+
+    const deadLetters = c.get('deadLetters');
+    const { page, limit, source, replayed } = validation.data;
+    const skip = (page - 1) * limit;
+    const filter = { source, replayed };
+
+    const [docs, total] = await Promise.all([
+        deadLetters.find({ filter, skip, limit }),
+        deadLetters.count(filter),
+    ]);
+
+    const data = buildPaginatedResponse(docs, toDeadLetter, { page, limit, total });
+
+    return c.json({ success: true, data });
+};
+
+const getDeadLetterHandler = async (c: Context<AppBindings>) => {
+    const validation = validateParams(c, deadLetterParamsSchema);
+    if (!validation.success) return validation.response;
+
+    const deadLetter = await c.get('deadLetters').findById(validation.data.id);
+    if (!deadLetter) return deadLetterNotFound(c);
+
+    return c.json({ success: true, data: toDeadLetter(deadLetter) });
+};
+
+const replayDeadLetterHandler = async (c: Context<AppBindings>) => {
+    const validation = validateParams(c, deadLetterParamsSchema);
+    if (!validation.success) return validation.response;
+
+    const result = await c.get('deadLettersService').replay(validation.data.id);
+
+    switch (result.status) {
+        case 'not_found':
+            return deadLetterNotFound(c);
+        case 'already_replayed':
+            return c.json({ success: false, error: 'Dead letter was already replayed' }, 409);
+        case 'leased':
+            return c.json(
+                { success: false, error: 'Dead letter is being replayed by another instance' },
+                409
+            );
+        case 'replayed':
+            return c.json({
+                success: true,
+                data: { ...toDeadLetter(result.deadLetter), digest: result.digest },
+            });
+    }
+};
+
+export const getDeadLetters = withErrorHandler(
+    getDeadLettersHandler,
+    'Failed to fetch dead letters'
+);
+export const getDeadLetter = withErrorHandler(getDeadLetterHandler, 'Failed to fetch dead letter');
+export const replayDeadLetter = withErrorHandler(
+    replayDeadLetterHandler,
+    'Failed to replay dead letter'
+);
@@ -0,0 +1,10 @@
+import { Hono } from 'hono';
+import type { AppBindings } from '@/types/index.ts';
+import { apiKeyMiddleware } from '@/middleware/index.ts';
+import { getDeadLetter, getDeadLetters, replayDeadLetter } from './handlers.ts';
+
+export const adminRoutes = new Hono<AppBindings>()
+    .use('*', apiKeyMiddleware)
+    .get('/dlq', getDeadLetters)
+    .get('/dlq/:id', getDeadLetter)
+    .post('/dlq/replay/:id', replayDeadLetter);
@@ -0,0 +1,17 @@
+import type { DeadLetterDocument } from '@/db/collections/index.ts';
+import type { DeadLetter } from './types.ts';
+
+export const toDeadLetter = (doc: DeadLetterDocument): DeadLetter => ({
+    id: doc._id,
+    source: doc.source,
+    collection: doc.collection,
+    payload: doc.payload,
+    error: doc.error,
+    attempts: doc.attempts,
+    createdAt: doc.created_at.toISOString(),
+    lastAttemptAt: doc.last_attempt_at.toISOString(),
+    replayedAt: doc.replayed_at?.toISOString() ?? null,
+    replayDigest: doc.replay_digest ?? null,
+    leaseOwner: doc.lease_owner ?? null,
+    leaseExpiresAt: doc.lease_expires_at?.toISOString() ?? null,
+});
@@ -0,0 +1,24 @@
+import { z } from 'zod';
+import { PAGINATION } from '@/constants/index.ts';
+
+export const deadLettersQuerySchema = z.object({
+    page: z.coerce.number().int().min(PAGINATION.MIN_PAGE).default(PAGINATION.MIN_PAGE),
+    limit: z.coerce
+        .number()
+        .int()
+        .min(1)
+        .max(PAGINATION.MAX_LIMIT)
+        .default(PAGINATION.DEFAULT_LIMIT),
+    source: z.enum(['indexer', 'relayer']).optional(),
+    replayed: z
+        .enum(['true', 'false'])
+        .transform((value) => value === 'true')
+        .optional(),
+});
+
+// Indexer entries are keyed by `<collection>:<document id>`, relayer entries by UUID
+export const deadLetterParamsSchema = z.object({
+    id: z.string().min(1),
+});
+
+export type DeadLettersQuery = z.infer<typeof deadLettersQuerySchema>;
@@ -0,0 +1,14 @@
+export type DeadLetter = {
+    id: string;
+    source: 'indexer' | 'relayer';
+    collection: string | null;
+    payload: Record<string, unknown>;
+    error: string;
+    attempts: number;
+    createdAt: string;
+    lastAttemptAt: string;
+    replayedAt: string | null;
+    replayDigest: string | null;
+    leaseOwner: string | null;
+    leaseExpiresAt: string | null;
+};
@@ -1,9 +1,11 @@
+import { normalizeStructTag } from '@mysten/sui/utils';
 import type { Context } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
 import { validateQuery } from '@/utils/validation.ts';
 import { withErrorHandler } from '@/utils/handler.ts';
-import { getCommitmentsQuerySchema } from './schema.ts';
-import { toCommitment } from './mappers.ts';
+import { getBalanceInputsQuerySchema, getCommitmentsQuerySchema } from './schema.ts';
+import { toBalanceInput, toCommitment } from './mappers.ts';
+import type { PoolBalanceInputs } from './types.ts';
 
 const getCommitmentsHandler = async (c: Context<AppBindings>) => {
     const validation = validateQuery(c, getCommitmentsQuerySchema);
@@ -24,3 +26,43 @@ export const getCommitments = withErrorHandler(
     getCommitmentsHandler,
     'Failed to fetch commitments'
 );
+
+/**
+ * Encrypted outputs of every pool past the caller's cursors, for wallets that
+ * scan all their pools in one sync loop. Every pool is returned whether or not
+ * it has a cursor, so the request does not reveal which pools the wallet uses.
+ */
+const getBalanceInputsHandler = async (c: Context<AppBindings>) => {
+    const validation = validateQuery(c, getBalanceInputsQuerySchema);
+    if (!validation.success) return validation.response;
+
+    const commitments = c.get('commitments');
+    const { cursors, limit } = validation.data;
+    const coinTypes = await commitments.coinTypes();
+
+    const pools = await Promise.all(
+        coinTypes.map(async (coinType): Promise<PoolBalanceInputs> => {
+            const normalized = normalizeStructTag(coinType);
+            const fromIndex = cursors.get(normalized) ?? 0;
+            const filter = { coin_type: coinType, index: { $gte: fromIndex } };
+
+            const docs = await commitments.find({ filter, skip: 0, limit: limit + 1 });
+            const hasNext = docs.length > limit;
+            const items = (hasNext ? docs.slice(0, limit) : docs).map(toBalanceInput);
+
+            return {
+                coinType: normalized,
+                items,
+                nextIndex: (items.at(-1)?.index ?? fromIndex - 1) + 1,
+                hasNext,
+            };
+        })
+    );
+
+    return c.json({ success: true, data: { pools } });
+};
+
+export const getBalanceInputs = withErrorHandler(
+    getBalanceInputsHandler,
+    'Failed to fetch balance inputs'
+);
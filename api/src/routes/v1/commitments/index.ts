@@ -1,5 +1,7 @@
 import { Hono } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
-import { getCommitments } from './handlers.ts';
+import { getBalanceInputs, getCommitments } from './handlers.ts';
 
-export const commitmentsRoutes = new Hono<AppBindings>().get('/', getCommitments);
+export const commitmentsRoutes = new Hono<AppBindings>()
+    .get('/', getCommitments)
+    .get('/balance-inputs', getBalanceInputs);
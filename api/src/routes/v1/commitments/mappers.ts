@@ -1,7 +1,7 @@
 import { normalizeStructTag } from '@mysten/sui/utils';
 
 import { hexToDecimal } from '@/utils/hex.ts';
-import type { CommitmentDocument, Commitment } from './types.ts';
+import type { BalanceInput, CommitmentDocument, Commitment } from './types.ts';
 
 export const toCommitment = (doc: CommitmentDocument): Commitment => ({
     id: doc._id,
@@ -14,3 +14,9 @@ export const toCommitment = (doc: CommitmentDocument): Commitment => ({
     commitment: hexToDecimal(doc.commitment),
     encryptedOutput: doc.encrypted_output,
 });
+
+export const toBalanceInput = (doc: CommitmentDocument): BalanceInput => ({
+    index: doc.index,
+    commitment: hexToDecimal(doc.commitment),
+    encryptedOutput: doc.encrypted_output,
+});
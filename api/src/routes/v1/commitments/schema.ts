@@ -1,3 +1,4 @@
+import { normalizeStructTag } from '@mysten/sui/utils';
 import { z } from 'zod';
 import { PAGINATION } from '@/constants/index.ts';
 import { coinTypeSchema, indexSchema } from '@/utils/schemas.ts';
@@ -22,3 +23,32 @@ export const getCommitmentsQuerySchema = z
         mongoOp: `$${data.op}` as const,
         limit: data.limit,
     }));
+
+const CURSOR_PATTERN = /^(0x[a-fA-F0-9]+::\w+::\w+):(\d+)$/;
+
+export const getBalanceInputsQuerySchema = z.object({
+    cursors: z
+        .string()
+        .optional()
+        .transform((value, ctx) => {
+            const cursors = new Map<string, number>();
+            for (const entry of value ? value.split(',') : []) {
+                const match = CURSOR_PATTERN.exec(entry.trim());
+                if (!match?.[1] || !match[2]) {
+                    ctx.addIssue({
+                        code: z.ZodIssueCode.custom,
+                        message: `Expected <coin_type>:<index>, got ${entry}`,
+                    });
+                    return z.NEVER;
+                }
+                cursors.set(normalizeStructTag(match[1]), Number(match[2]));
+            }
+            return cursors;
+        }),
+    limit: z.coerce
+        .number()
+        .int()
+        .min(1)
+        .max(PAGINATION.MAX_LIMIT)
+        .default(PAGINATION.DEFAULT_LIMIT),
+});
@@ -11,3 +11,16 @@ export type Commitment = {
     commitment: string;
     encryptedOutput: number[];
 };
+
+export type BalanceInput = {
+    index: number;
+    commitment: string;
+    encryptedOutput: number[];
+};
+
+export type PoolBalanceInputs = {
+    coinType: string;
+    items: BalanceInput[];
+    nextIndex: number;
+    hasNext: boolean;
+};
@@ -1,16 +1,22 @@
 import { Hono } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
 import { accountsRoutes } from './accounts/index.ts';
+import { adminRoutes } from './admin/index.ts';
 import { commitmentsRoutes } from './commitments/index.ts';
 import { merkleRoutes } from './merkle/index.ts';
+import { nullifiersRoutes } from './nullifiers/index.ts';
 import { poolsRoutes } from './pools/index.ts';
 import { relayerRoutes } from './relayer/index.ts';
 import { transactionsRoutes } from './transactions/index.ts';
+import { watchesRoutes } from './watches/index.ts';
 
 export const v1Routes = new Hono<AppBindings>()
     .route('/accounts', accountsRoutes)
+    .route('/admin', adminRoutes)
     .route('/commitments', commitmentsRoutes)
     .route('/merkle', merkleRoutes)
+    .route('/nullifiers', nullifiersRoutes)
     .route('/pools', poolsRoutes)
     .route('/relayer', relayerRoutes)
-    .route('/transactions', transactionsRoutes);
+    .route('/transactions', transactionsRoutes)
+    .route('/watches', watchesRoutes);
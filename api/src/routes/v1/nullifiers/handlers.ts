@@ -0,0 +1,25 @@
+import type { Context } from 'hono';
+import type { AppBindings } from '@/types/index.ts';
+import { hexToDecimal } from '@/utils/hex.ts';
+import { validateQuery } from '@/utils/validation.ts';
+import { withErrorHandler } from '@/utils/handler.ts';
+import { getSpentNullifiersQuerySchema } from './schema.ts';
+
+/**
+ * Which of a wallet's candidate inputs are already spent, so it can drop them
+ * before spending minutes proving a transaction the contract would reject.
+ */
+const getSpentNullifiersHandler = async (c: Context<AppBindings>) => {
+    const validation = validateQuery(c, getSpentNullifiersQuerySchema);
+    if (!validation.success) return validation.response;
+
+    const { coinType, nullifiers } = validation.data;
+    const spent = await c.get('nullifiers').findSpent(coinType, nullifiers);
+
+    return c.json({ success: true, data: { spent: spent.map(hexToDecimal) } });
+};
+
+export const getSpentNullifiers = withErrorHandler(
+    getSpentNullifiersHandler,
+    'Failed to fetch spent nullifiers'
+);
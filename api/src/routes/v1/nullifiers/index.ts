@@ -0,0 +1,5 @@
+import { Hono } from 'hono';
+import type { AppBindings } from '@/types/index.ts';
+import { getSpentNullifiers } from './handlers.ts';
+
+export const nullifiersRoutes = new Hono<AppBindings>().get('/spent', getSpentNullifiers);
@@ -0,0 +1,18 @@
+import { z } from 'zod';
+import { coinTypeSchema, u256Schema } from '@/utils/schemas.ts';
+
+/** Enough for every input of a few transactions built at once. */
+export const MAX_NULLIFIERS = 16;
+
+export const getSpentNullifiersQuerySchema = z
+    .object({
+        coin_type: coinTypeSchema,
+        nullifiers: z
+            .string()
+            .transform((value) => value.split(',').map((nullifier) => nullifier.trim()))
+            .pipe(z.array(u256Schema).min(1).max(MAX_NULLIFIERS)),
+    })
+    .transform((data) => ({
+        coinType: data.coin_type,
+        nullifiers: data.nullifiers,
+    }));
@@ -1,10 +1,18 @@
 import type { Context } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
 import { buildPaginatedResponse } from '@/types/index.ts';
-import { validateQuery } from '@/utils/validation.ts';
+import { ROOT_HISTORY_SIZE } from '@/constants/index.ts';
+import { validateParams, validateQuery } from '@/utils/validation.ts';
 import { withErrorHandler } from '@/utils/handler.ts';
-import { poolsQuerySchema } from './schema.ts';
-import { toPool } from './mappers.ts';
+import {
+    poolAnonymityQuerySchema,
+    poolLeafCountsQuerySchema,
+    poolRootsParamsSchema,
+    poolRootsQuerySchema,
+    poolsQuerySchema,
+} from './schema.ts';
+import { toPool, toPoolAnonymity, toPoolLeafCount, toPoolRoot } from './mappers.ts';
+import type { PoolLeafCountsFeed, PoolRootsFeed } from './types.ts';
 
 const getPoolsHandler = async (c: Context<AppBindings>) => {
     const validation = validateQuery(c, poolsQuerySchema);
@@ -25,4 +33,77 @@ const getPoolsHandler = async (c: Context<AppBindings>) => {
     return c.json({ success: true, data });
 };
 
+const getPoolRootsHandler = async (c: Context<AppBindings>) => {
+    const params = validateParams(c, poolRootsParamsSchema);
+    if (!params.success) return params.response;
+
+    const query = validateQuery(c, poolRootsQuerySchema);
+    if (!query.success) return query.response;
+
+    const { since_seq, limit } = query.data;
+    const { roots, latestSeq } = await c.get('merkleService').getRoots({
+        coinType: params.data.coin_type,
+        sinceSeq: since_seq,
+        limit,
+    });
+
+    const data: PoolRootsFeed = {
+        items: roots.map(toPoolRoot),
+        latestSeq,
+        acceptanceWindow: ROOT_HISTORY_SIZE,
+        hasNext: (roots.at(-1)?.seq ?? latestSeq) < latestSeq,
+    };
+
+    return c.json({ success: true, data });
+};
+
+const getPoolLeafCountsHandler = async (c: Context<AppBindings>) => {
+    const params = validateParams(c, poolRootsParamsSchema);
+    if (!params.success) return params.response;
+
+    const query = validateQuery(c, poolLeafCountsQuerySchema);
+    if (!query.success) return query.response;
+
+    const { since_checkpoint, limit } = query.data;
+    const leafCounts = c.get('leafCounts');
+    const [counts, latest] = await Promise.all([
+        leafCounts.findSince(params.data.coin_type, since_checkpoint, limit),
+        leafCounts.latest(params.data.coin_type),
+    ]);
+
+    const latestCheckpoint = latest?.checkpoint ?? 0;
+    const data: PoolLeafCountsFeed = {
+        items: counts.map(toPoolLeafCount),
+        latestLeafCount: latest?.leaf_count ?? 0,
+        hasNext: (counts.at(-1)?.checkpoint ?? latestCheckpoint) < latestCheckpoint,
+    };
+
+    return c.json({ success: true, data });
+};
+
+const getPoolAnonymityHandler = async (c: Context<AppBindings>) => {
+    const params = validateParams(c, poolRootsParamsSchema);
+    if (!params.success) return params.response;
+
+    const query = validateQuery(c, poolAnonymityQuerySchema);
+    if (!query.success) return query.response;
+
+    const advice = await c.get('anonymityService').advise({
+        coinType: params.data.coin_type,
+        bucket: query.data.bucket,
+    });
+    if (!advice) return c.json({ success: false, error: 'Bucket not found' }, 404);
+
+    return c.json({ success: true, data: toPoolAnonymity(advice) });
+};
+
 export const getPools = withErrorHandler(getPoolsHandler, 'Failed to fetch pools');
+export const getPoolRoots = withErrorHandler(getPoolRootsHandler, 'Failed to fetch pool roots');
+export const getPoolLeafCounts = withErrorHandler(
+    getPoolLeafCountsHandler,
+    'Failed to fetch pool leaf counts'
+);
+export const getPoolAnonymity = withErrorHandler(
+    getPoolAnonymityHandler,
+    'Failed to compute pool anonymity'
+);
@@ -1,5 +1,9 @@
 import { Hono } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
-import { getPools } from './handlers.ts';
+import { getPoolAnonymity, getPoolLeafCounts, getPoolRoots, getPools } from './handlers.ts';
 
-export const poolsRoutes = new Hono<AppBindings>().get('/', getPools);
+export const poolsRoutes = new Hono<AppBindings>()
+    .get('/', getPools)
+    .get('/:coin_type/roots', getPoolRoots)
+    .get('/:coin_type/leaf-counts', getPoolLeafCounts)
+    .get('/:coin_type/anonymity', getPoolAnonymity);
@@ -1,7 +1,12 @@
 import { normalizeSuiObjectId, normalizeStructTag } from '@mysten/sui/utils';
 
-import type { PoolDocument } from '@/db/collections/index.ts';
-import type { Pool } from './types.ts';
+import type {
+    LeafCountDocument,
+    PoolDocument,
+    PoolRootDocument,
+} from '@/db/collections/index.ts';
+import type { AnonymityAdvice } from '@/services/index.ts';
+import type { Pool, PoolAnonymity, PoolLeafCount, PoolRoot } from './types.ts';
 
 export const toPool = (doc: PoolDocument): Pool => ({
     id: doc._id,
@@ -12,3 +17,23 @@ export const toPool = (doc: PoolDocument): Pool => ({
     objectId: normalizeSuiObjectId(doc.pool_address),
     coinType: normalizeStructTag(doc.coin_type),
 });
+
+export const toPoolRoot = (doc: PoolRootDocument): PoolRoot => ({
+    seq: doc.seq,
+    root: doc.root,
+    leafCount: doc.leaf_count,
+    checkpoint: doc.checkpoint,
+});
+
+export const toPoolLeafCount = (doc: LeafCountDocument): PoolLeafCount => ({
+    checkpoint: doc.checkpoint,
+    checkpointTimestampMs: doc.checkpoint_timestamp_ms,
+    leafCount: doc.leaf_count,
+});
+
+export const toPoolAnonymity = (advice: AnonymityAdvice): PoolAnonymity => ({
+    commitmentsSince: advice.commitmentsSince,
+    effectiveSetSize: advice.effectiveSetSize,
+    score: advice.score,
+    suggestions: advice.suggestions,
+});
@@ -1,5 +1,6 @@
 import { z } from 'zod';
 import { PAGINATION } from '@/constants/index.ts';
+import { coinTypeSchema, indexSchema } from '@/utils/schemas.ts';
 
 export const poolsQuerySchema = z.object({
     page: z.coerce.number().int().min(PAGINATION.MIN_PAGE).default(PAGINATION.MIN_PAGE),
@@ -9,7 +10,35 @@ export const poolsQuerySchema = z.object({
         .min(1)
         .max(PAGINATION.MAX_LIMIT)
         .default(PAGINATION.DEFAULT_LIMIT),
-    coin_type: z.string().optional(),
+    coin_type: coinTypeSchema.optional(),
 });
 
 export type PoolsQuery = z.infer<typeof poolsQuerySchema>;
+
+export const poolRootsParamsSchema = z.object({
+    coin_type: coinTypeSchema,
+});
+
+export const poolRootsQuerySchema = z.object({
+    since_seq: z.coerce.number().int().min(0).default(0),
+    limit: z.coerce
+        .number()
+        .int()
+        .min(1)
+        .max(PAGINATION.MAX_LIMIT)
+        .default(PAGINATION.DEFAULT_LIMIT),
+});
+
+export const poolLeafCountsQuerySchema = z.object({
+    since_checkpoint: z.coerce.number().int().min(0).default(0),
+    limit: z.coerce
+        .number()
+        .int()
+        .min(1)
+        .max(PAGINATION.MAX_LIMIT)
+        .default(PAGINATION.DEFAULT_LIMIT),
+});
+
+export const poolAnonymityQuerySchema = z.object({
+    bucket: indexSchema,
+});
@@ -1,3 +1,5 @@
+import type { AnonymityScore } from '@/services/index.ts';
+
 export type Pool = {
     id: string;
     digest: string;
@@ -7,3 +9,43 @@ export type Pool = {
     objectId: string;
     coinType: string;
 };
+
+export type PoolRoot = {
+    seq: number;
+    root: string;
+    leafCount: number;
+    checkpoint: number;
+};
+
+/**
+ * A root is accepted by the contract while `latestSeq - seq` is below
+ * `acceptanceWindow`.
+ */
+export type PoolRootsFeed = {
+    items: PoolRoot[];
+    latestSeq: number;
+    acceptanceWindow: number;
+    hasNext: boolean;
+};
+
+export type PoolLeafCount = {
+    checkpoint: number;
+    checkpointTimestampMs: number;
+    leafCount: number;
+};
+
+/** Anonymity set size of a pool over time; `latestLeafCount` is its current size. */
+export type PoolLeafCountsFeed = {
+    items: PoolLeafCount[];
+    latestLeafCount: number;
+    hasNext: boolean;
+};
+
+export type PoolAnonymitySuggestion = { type: 'wait'; hours: number; projectedSetSize: number };
+
+export type PoolAnonymity = {
+    commitmentsSince: number;
+    effectiveSetSize: number;
+    score: AnonymityScore;
+    suggestions: PoolAnonymitySuggestion[];
+};
@@ -1,17 +1,47 @@
 import type { Context } from 'hono';
 import type { AppBindings } from '@/types/index.ts';
-import { validateBody } from '@/utils/validation.ts';
+import { validateBody, validateHeaders } from '@/utils/validation.ts';
 import { withErrorHandler } from '@/utils/handler.ts';
-import { executeTransactionSchema } from './schema.ts';
+import { executeTransactionHeadersSchema, executeTransactionSchema } from './schema.ts';
 
 const executeTransactionHandler = async (c: Context<AppBindings>) => {
+    const headers = validateHeaders(c, executeTransactionHeadersSchema);
+    if (!headers.success) return headers.response;
+
     const validation = await validateBody(c, executeTransactionSchema);
     if (!validation.success) return validation.response;
 
     const transactionsService = c.get('transactionsService');
-    const digest = await transactionsService.execute(validation.data.txBytes);
+    const result = await transactionsService.execute(
+        validation.data.txBytes,
+        headers.data['idempotency-key']
+    );
 
-    return c.json({ success: true, data: { digest } }, 201);
+    switch (result.status) {
+        case 'in_progress':
+            return c.json(
+                { success: false, error: 'A request with this idempotency key is in progress' },
+                409
+            );
+        case 'key_mismatch':
+            return c.json(
+                {
+                    success: false,
+                    error: 'This idempotency key was used for a different transaction',
+                },
+                422
+            );
+        case 'proof_replayed':
+            return c.json(
+                { success: false, error: 'This proof was already submitted to the relayer' },
+                422
+            );
+        case 'replayed':
+            c.header('Idempotent-Replayed', 'true');
+            return c.json({ success: true, data: { digest: result.digest } });
+        case 'executed':
+            return c.json({ success: true, data: { digest: result.digest } }, 201);
+    }
 };
 
 export const executeTransaction = withErrorHandler(
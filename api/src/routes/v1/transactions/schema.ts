@@ -3,3 +3,7 @@ import { z } from 'zod';
 export const executeTransactionSchema = z.object({
     txBytes: z.string().min(1),
 });
+
+export const executeTransactionHeadersSchema = z.object({
+    'idempotency-key': z.string().regex(/^[A-Za-z0-9_-]{16,128}$/).optional(),
+});
@@ -0,0 +1,127 @@
+import type { Context } from 'hono';
+import { streamSSE } from 'hono/streaming';
+import { env } from '@/config/env.ts';
+import type { AppBindings } from '@/types/index.ts';
+import { validateBody, validateParams } from '@/utils/validation.ts';
+import { withErrorHandler } from '@/utils/handler.ts';
+import { isPublicWebhookUrl } from '@/utils/webhook-host.ts';
+import { createWatchSchema, watchParamsSchema } from './schema.ts';
+import { toWatch, toWatchNotification } from './mappers.ts';
+
+const openStreams = new Map<string, number>();
+
+const watchNotFound = (c: Context<AppBindings>) =>
+    c.json({ success: false, error: 'Watch not found' }, 404);
+
+const createWatchHandler = async (c: Context<AppBindings>) => {
+    const validation = await validateBody(c, createWatchSchema);
+    if (!validation.success) return validation.response;
+
+    const { webhookUrl } = validation.data;
+    if (webhookUrl && !(await isPublicWebhookUrl(webhookUrl))) {
+        return c.json(
+            {
+                success: false,
+                error: { webhookUrl: ['Webhook host must resolve to public addresses only'] },
+            },
+            400
+        );
+    }
+
+    const watchesService = c.get('watchesService');
+    const watch = await watchesService.create(validation.data);
+
+    return c.json(
+        { success: true, data: { ...toWatch(watch), webhookSecret: watch.webhook_secret } },
+        201
+    );
+};
+
+const getWatchHandler = async (c: Context<AppBindings>) => {
+    const validation = validateParams(c, watchParamsSchema);
+    if (!validation.success) return validation.response;
+
+    const watchesService = c.get('watchesService');
+    const { id } = validation.data;
+    const watch = await watchesService.findById(id);
+    if (!watch) return watchNotFound(c);
+
+    const notifications = await watchesService.findNotifications(id);
+
+    return c.json({
+        success: true,
+        data: { ...toWatch(watch), notifications: notifications.map(toWatchNotification) },
+    });
+};
+
+const deleteWatchHandler = async (c: Context<AppBindings>) => {
+    const validation = validateParams(c, watchParamsSchema);
+    if (!validation.success) return validation.response;
+
+    const watchesService = c.get('watchesService');
+    const deleted = await watchesService.delete(validation.data.id);
+    if (!deleted) return watchNotFound(c);
+
+    return c.json({ success: true, data: { deleted } });
+};
+
+const streamWatchHandler = async (c: Context<AppBindings>) => {
+    const validation = validateParams(c, watchParamsSchema);
+    if (!validation.success) return validation.response;
+
+    const watchesService = c.get('watchesService');
+    const { id } = validation.data;
+    const watch = await watchesService.findById(id);
+    if (!watch) return watchNotFound(c);
+
+    const open = openStreams.get(id) ?? 0;
+    if (open >= env.WATCH_STREAM_MAX_PER_WATCH) {
+        return c.json({ success: false, error: 'Too many open streams for this watch' }, 429);
+    }
+
+    const lastEventId = c.req.header('last-event-id');
+    const lastSent = lastEventId ? await watchesService.findNotificationById(lastEventId) : null;
+    let cursor =
+        lastSent?.watch_id === id ? { checkpoint: lastSent.checkpoint, id: lastSent._id } : null;
+
+    openStreams.set(id, open + 1);
+    const release = () => {
+        const remaining = (openStreams.get(id) ?? 1) - 1;
+        if (remaining > 0) openStreams.set(id, remaining);
+        else openStreams.delete(id);
+    };
+
+    return streamSSE(c, async (stream) => {
+        let closed = false;
+        stream.onAbort(() => {
+            closed = true;
+        });
+
+        try {
+            while (!closed) {
+                const notifications = await watchesService.findNotificationsAfter(id, cursor);
+
+                for (const notification of notifications) {
+                    await stream.writeSSE({
+                        id: notification._id,
+                        event: 'notification',
+                        data: JSON.stringify(toWatchNotification(notification)),
+                    });
+                    cursor = { checkpoint: notification.checkpoint, id: notification._id };
+                }
+
+                await stream.sleep(env.WATCH_STREAM_POLL_INTERVAL_MS);
+            }
+        } finally {
+            release();
+        }
+    });
+};
+
+export const createWatch = withErrorHandler(createWatchHandler, 'Failed to create watch');
+
+export const getWatch = withErrorHandler(getWatchHandler, 'Failed to fetch watch');
+
+export const deleteWatch = withErrorHandler(deleteWatchHandler, 'Failed to delete watch');
+
+export const streamWatch = withErrorHandler(streamWatchHandler, 'Failed to stream watch');
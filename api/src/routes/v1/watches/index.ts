@@ -0,0 +1,19 @@
+import { Hono } from 'hono';
+import { env } from '@/config/env.ts';
+import { createRateLimiter } from '@/middleware/index.ts';
+import type { AppBindings } from '@/types/index.ts';
+import { createWatch, deleteWatch, getWatch, streamWatch } from './handlers.ts';
+
+const WATCH_CREATE_WINDOW_SECONDS = 3600;
+
+const watchCreateLimiter = createRateLimiter({
+    points: env.WATCH_CREATE_LIMIT_PER_HOUR,
+    duration: WATCH_CREATE_WINDOW_SECONDS,
+    keyPrefix: 'rl:watches',
+});
+
+export const watchesRoutes = new Hono<AppBindings>()
+    .post('/', watchCreateLimiter, createWatch)
+    .get('/:id', getWatch)
+    .delete('/:id', deleteWatch)
+    .get('/:id/events', streamWatch);
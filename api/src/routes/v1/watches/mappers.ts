@@ -0,0 +1,25 @@
+import { normalizeStructTag } from '@mysten/sui/utils';
+
+import type { WatchDocument, WatchNotificationDocument } from '@/db/collections/index.ts';
+import { hexToDecimal } from '@/utils/hex.ts';
+import type { Watch, WatchNotification } from './types.ts';
+
+export const toWatch = (doc: WatchDocument): Watch => ({
+    id: doc._id,
+    kind: doc.kind,
+    nullifier: hexToDecimal(doc.value),
+    webhookUrl: doc.webhook_url,
+    createdAt: doc.created_at,
+});
+
+export const toWatchNotification = (doc: WatchNotificationDocument): WatchNotification => ({
+    id: doc._id,
+    watchId: doc.watch_id,
+    kind: doc.kind,
+    nullifier: hexToDecimal(doc.value),
+    digest: doc.digest,
+    coinType: normalizeStructTag(doc.coin_type),
+    checkpoint: doc.checkpoint,
+    checkpointTimestampMs: doc.checkpoint_timestamp_ms,
+    delivered: doc.delivered,
+});
@@ -0,0 +1,11 @@
+import { z } from 'zod';
+import { u256Schema } from '@/utils/schemas.ts';
+
+export const createWatchSchema = z.object({
+    nullifier: u256Schema,
+    webhookUrl: z.string().url().startsWith('https://').optional(),
+});
+
+export const watchParamsSchema = z.object({
+    id: z.string().uuid(),
+});
@@ -0,0 +1,21 @@
+import type { WatchKind } from '@/db/collections/index.ts';
+
+export type Watch = {
+    id: string;
+    kind: WatchKind;
+    nullifier: string;
+    webhookUrl: string | null;
+    createdAt: Date;
+};
+
+export type WatchNotification = {
+    id: string;
+    watchId: string;
+    kind: WatchKind;
+    nullifier: string;
+    digest: string;
+    coinType: string;
+    checkpoint: number;
+    checkpointTimestampMs: number;
+    delivered: boolean;
+};
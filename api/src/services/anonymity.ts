@@ -0,0 +1,123 @@
+import type { CommitmentsRepository } from '@/repositories/index.ts';
+
+export type AnonymityScore = 'weak' | 'moderate' | 'strong';
+
+export type AnonymitySuggestion = { type: 'wait'; hours: number; projectedSetSize: number };
+
+export type AnonymityAdvice = {
+    commitmentsSince: number;
+    effectiveSetSize: number;
+    score: AnonymityScore;
+    suggestions: AnonymitySuggestion[];
+};
+
+export type AdviseParams = {
+    coinType: string;
+    /** Leaf index of the note to withdraw divided by `ANONYMITY_BUCKET_SIZE` */
+    bucket: number;
+};
+
+export type AnonymityService = {
+    /** Returns null when the pool has no commitment in `bucket`. */
+    advise: (params: AdviseParams) => Promise<AnonymityAdvice | null>;
+};
+
+/**
+ * Leaves per bucket of the anonymity query. Callers send the bucket of their
+ * note instead of its index, so the request only narrows the note down to one
+ * of this many leaves.
+ */
+export const ANONYMITY_BUCKET_SIZE = 256;
+
+const HOUR_MS = 60 * 60 * 1000;
+
+/**
+ * Age at which a commitment counts fully towards the anonymity set. Younger
+ * ones count in proportion to their age, since a withdrawal shortly after a
+ * deposit is easy to pair with it by timing alone.
+ */
+const MATURITY_MS = 24 * HOUR_MS;
+
+/** Period the pool's recent commitment rate is measured over. */
+const RATE_WINDOW_MS = 7 * 24 * HOUR_MS;
+
+const MAX_WAIT_HOURS = 7 * 24;
+
+const MODERATE_SET_SIZE = 20;
+
+const STRONG_SET_SIZE = 100;
+
+type Forecast = {
+    /** Commitments since the note that already count fully */
+    matured: number;
+    pendingAgesMs: number[];
+    ratePerMs: number;
+};
+
+const weight = (ageMs: number): number => Math.min(1, Math.max(0, ageMs) / MATURITY_MS);
+
+/**
+ * Effective set size after waiting `waitMs`: pending commitments keep
+ * maturing and new ones arrive at the recent rate, each weighted by its age.
+ */
+const effectiveSetSize = (forecast: Forecast, waitMs: number): number => {
+    const pending = forecast.pendingAgesMs.reduce((sum, age) => sum + weight(age + waitMs), 0);
+    const arriving =
+        waitMs <= MATURITY_MS
+            ? (forecast.ratePerMs * waitMs ** 2) / (2 * MATURITY_MS)
+            : forecast.ratePerMs * (waitMs - MATURITY_MS / 2);
+
+    return forecast.matured + pending + arriving;
+};
+
+const toAnonymityScore = (size: number): AnonymityScore => {
+    if (size >= STRONG_SET_SIZE) return 'strong';
+    if (size >= MODERATE_SET_SIZE) return 'moderate';
+    return 'weak';
+};
+
+const waitSuggestion = (forecast: Forecast): AnonymitySuggestion | null => {
+    for (let hours = 1; hours <= MAX_WAIT_HOURS; hours++) {
+        const projected = effectiveSetSize(forecast, hours * HOUR_MS);
+        if (projected >= STRONG_SET_SIZE) {
+            return { type: 'wait', hours, projectedSetSize: Math.floor(projected) };
+        }
+    }
+    return null;
+};
+
+export const createAnonymityService = (commitments: CommitmentsRepository): AnonymityService => ({
+    advise: async ({ coinType, bucket }) => {
+        const now = Date.now();
+        const bucketStart = bucket * ANONYMITY_BUCKET_SIZE;
+        const bucketEnd = bucketStart + ANONYMITY_BUCKET_SIZE;
+        const fromBucket = await commitments.count({
+            coin_type: coinType,
+            index: { $gte: bucketStart },
+        });
+        if (fromBucket === 0) return null;
+
+        // Only commitments after the whole bucket surely follow the note
+        const [commitmentsSince, pendingTimestamps, recent] = await Promise.all([
+            commitments.count({ coin_type: coinType, index: { $gte: bucketEnd } }),
+            commitments.timestampsFromIndex(coinType, bucketEnd, now - MATURITY_MS),
+            commitments.countSince(coinType, now - RATE_WINDOW_MS),
+        ]);
+
+        const forecast: Forecast = {
+            matured: commitmentsSince - pendingTimestamps.length,
+            pendingAgesMs: pendingTimestamps.map((timestamp) => now - timestamp),
+            ratePerMs: recent / RATE_WINDOW_MS,
+        };
+        const size = effectiveSetSize(forecast, 0);
+
+        const suggestion = size < STRONG_SET_SIZE ? waitSuggestion(forecast) : null;
+
+        return {
+            commitmentsSince,
+            effectiveSetSize: Math.floor(size),
+            score: toAnonymityScore(size),
+            suggestions: suggestion ? [suggestion] : [],
+        };
+    },
+});
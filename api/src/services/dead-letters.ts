@@ -0,0 +1,108 @@
+import { randomUUID } from 'node:crypto';
+import type { Db } from 'mongodb';
+import { env } from '@/config/env.ts';
+import type { DeadLetterDocument } from '@/db/collections/index.ts';
+import { isDuplicateKeyError } from '@/db/mongodb.ts';
+import type { DeadLettersRepository } from '@/repositories/index.ts';
+import { sponsorAndExecuteTransaction } from '@/services/sui.ts';
+import { buildTransaction } from '@/services/transactions.ts';
+import { logger } from '@/utils/logger.ts';
+
+const LEASE_RENEWALS_PER_TTL = 3;
+
+export type ReplayResult =
+    | { status: 'not_found' }
+    | { status: 'already_replayed'; deadLetter: DeadLetterDocument }
+    | { status: 'leased'; deadLetter: DeadLetterDocument }
+    | { status: 'replayed'; deadLetter: DeadLetterDocument; digest: string | null };
+
+export type DeadLettersService = {
+    replay: (id: string) => Promise<ReplayResult>;
+};
+
+const replayIndexer = async (db: Db, deadLetter: DeadLetterDocument): Promise<null> => {
+    if (!deadLetter.collection) throw new Error('Dead letter has no target collection');
+
+    try {
+        await db.collection(deadLetter.collection).insertOne(deadLetter.payload);
+    } catch (error) {
+        // The indexer or an earlier replay already wrote the document
+        if (!isDuplicateKeyError(error)) throw error;
+    }
+
+    return null;
+};
+
+const replayRelayer = async (deadLetter: DeadLetterDocument): Promise<string> => {
+    const txBytes = deadLetter.payload.tx_bytes;
+    if (typeof txBytes !== 'string') throw new Error('Dead letter has no transaction bytes');
+
+    const result = await sponsorAndExecuteTransaction(await buildTransaction(txBytes));
+    return result.digest;
+};
+
+/**
+ * Keeps this instance's lease on a dead letter alive while it is replayed, so
+ * a slow submission is not taken over. Returns a function stopping renewal.
+ */
+const keepLeaseAlive = (
+    repository: DeadLettersRepository,
+    id: string,
+    owner: string
+): (() => void) => {
+    const timer = setInterval(() => {
+        repository
+            .renewLease(id, owner, env.REPLAY_LEASE_TTL_MS)
+            .then((renewed) => {
+                if (!renewed) logger.warn({ id }, 'Lost dead letter lease during replay');
+            })
+            .catch((error: unknown) => {
+                logger.error({ error, id }, 'Failed to renew dead letter lease');
+            });
+    }, env.REPLAY_LEASE_TTL_MS / LEASE_RENEWALS_PER_TTL);
+
+    return () => clearInterval(timer);
+};
+
+export const createDeadLettersService = (
+    db: Db,
+    repository: DeadLettersRepository
+): DeadLettersService => ({
+    replay: async (id) => {
+        const deadLetter = await repository.findById(id);
+        if (!deadLetter) return { status: 'not_found' };
+        if (deadLetter.replayed_at) return { status: 'already_replayed', deadLetter };
+
+        // Instances share the dead letter queue; only the lease holder submits
+        const owner = `${env.INSTANCE_ID}:${randomUUID()}`;
+        const leased = await repository.acquireLease(id, owner, env.REPLAY_LEASE_TTL_MS);
+        if (!leased) {
+            const current = (await repository.findById(id)) ?? deadLetter;
+            return current.replayed_at
+                ? { status: 'already_replayed', deadLetter: current }
+                : { status: 'leased', deadLetter: current };
+        }
+
+        const stopRenewal = keepLeaseAlive(repository, id, owner);
+        try {
+            const digest =
+                deadLetter.source === 'relayer'
+                    ? await replayRelayer(deadLetter)
+                    : await replayIndexer(db, deadLetter);
+
+            await repository.markReplayed(id, digest ?? undefined);
+            const replayed = (await repository.findById(id)) ?? deadLetter;
+
+            return { status: 'replayed', deadLetter: replayed, digest };
+        } catch (error) {
+            await repository.recordAttempt(
+                id,
+                error instanceof Error ? error.message : String(error)
+            );
+            await repository.releaseLease(id, owner);
+            throw error;
+        } finally {
+            stopRenewal();
+        }
+    },
+});
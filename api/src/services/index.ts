@@ -1,16 +1,47 @@
 export type { AccountsService, CreateAccountParams } from './accounts.ts';
 export { createAccountsService } from './accounts.ts';
 
+export type {
+    AnonymityAdvice,
+    AnonymityScore,
+    AnonymityService,
+    AnonymitySuggestion,
+} from './anonymity.ts';
+export { createAnonymityService } from './anonymity.ts';
+
+export type { DeadLettersService, ReplayResult } from './dead-letters.ts';
+export { createDeadLettersService } from './dead-letters.ts';
+
 export type { HealthService, HealthStatus, HealthCheckResult } from './health.ts';
 export { createHealthService } from './health.ts';
 
-export type { MerkleService, MerklePath, MerklePathResponse, UtxoData } from './merkle.ts';
+export type {
+    MerkleService,
+    MerklePath,
+    MerklePathResponse,
+    RootsFeed,
+    UtxoData,
+} from './merkle.ts';
 export { createMerkleService } from './merkle.ts';
 
 export type { TransactionsService } from './transactions.ts';
-export { createTransactionsService } from './transactions.ts';
+export { buildTransaction, createTransactionsService } from './transactions.ts';
+
+export type { PoolReserve, ReserveReport, SignedReserveReport } from './reserves.ts';
+export {
+    buildReserveReport,
+    poolBalanceAt,
+    proofPublicValues,
+    signReserveReport,
+} from './reserves.ts';
+
+export type { RevenueFormat, RevenueRow } from './revenue.ts';
+export { formatRevenue, summarizeRevenue, toRelayerJob } from './revenue.ts';
 
 export type { RelayerService } from './relayer.ts';
 export { createRelayerService } from './relayer.ts';
 
 export { nodeClient, gasClient, keypair, sponsorAndExecuteTransaction } from './sui.ts';
+
+export type { WatchesService, CreateWatchParams } from './watches.ts';
+export { createWatchesService, startWebhookDispatcher, signWebhookPayload } from './watches.ts';
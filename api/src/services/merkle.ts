@@ -9,8 +9,9 @@ import {
     type MerkleTree,
 } from '@interest-protocol/vortex-sdk';
 import type { SerializedTreeState } from 'fixed-merkle-tree';
-import { REDIS_KEYS } from '@/constants/index.ts';
-import type { CommitmentsRepository } from '@/repositories/index.ts';
+import { REDIS_KEYS, ROOT_HISTORY_SIZE } from '@/constants/index.ts';
+import type { PoolRootDocument } from '@/db/collections/index.ts';
+import type { CommitmentsRepository, PoolRootsRepository } from '@/repositories/index.ts';
 import { hexToDecimal } from '@/utils/hex.ts';
 
 export type MerklePath = [string, string][];
@@ -27,12 +28,19 @@ export type UtxoData = {
     vortexPool: string;
 };
 
+export type RootsFeed = {
+    roots: PoolRootDocument[];
+    latestSeq: number;
+};
+
 export type MerkleService = {
     getMerklePath: (params: {
         coinType: string;
         index: number;
         utxo: UtxoData;
     }) => Promise<MerklePathResponse>;
+    /** Roots after `sinceSeq`, oldest first, brought up to date with the indexed commitments. */
+    getRoots: (params: { coinType: string; sinceSeq: number; limit: number }) => Promise<RootsFeed>;
 };
 
 const getTreeKey = (coinType: string): string => `${REDIS_KEYS.MERKLE_TREE_PREFIX}${coinType}`;
@@ -69,9 +77,16 @@ const cacheTree = async (
     await redis.set(getLastIndexKey(coinType), lastIndex.toString());
 };
 
+/** Number of leading elements a first build inserts without recording roots. */
+const unrecordedPrefix = (count: number): number => {
+    const prefix = Math.max(0, count - 2 * ROOT_HISTORY_SIZE);
+    return prefix - (prefix % 2);
+};
+
 const getOrBuildMerkleTree = async (
     redis: Redis,
     commitmentsRepo: CommitmentsRepository,
+    poolRoots: PoolRootsRepository,
     coinType: string
 ): Promise<MerkleTree> => {
     const cachedTree = await getCachedTree(redis, coinType);
@@ -89,10 +104,32 @@ const getOrBuildMerkleTree = async (
     const elements = commitments.map((c) => hexToDecimal(c.commitment));
     const tree = cachedTree ?? buildMerkleTree([]);
 
-    if (elements.length > 0) {
-        tree.bulkInsert(elements);
+    // Roots older than the contract's history are no longer accepted, so a
+    // first build only records the recent ones
+    const prefix = cachedTree ? 0 : unrecordedPrefix(elements.length);
+    if (prefix > 0) {
+        tree.bulkInsert(elements.slice(0, prefix));
     }
 
+    const roots: PoolRootDocument[] = [];
+    commitments.slice(prefix).forEach((commitment, i) => {
+        tree.insert(elements[prefix + i] as string);
+
+        const leafCount = tree.elements.length;
+        if (leafCount % 2 !== 0) return;
+
+        const seq = leafCount / 2;
+        roots.push({
+            _id: `${coinType}:${String(seq)}`,
+            coin_type: coinType,
+            seq,
+            root: tree.root.toString(),
+            leaf_count: leafCount,
+            checkpoint: commitment.checkpoint,
+        });
+    });
+    await poolRoots.insertMany(roots);
+
     const newLastIndex = commitments.at(-1)?.index ?? lastIndex;
     await cacheTree(redis, coinType, tree, newLastIndex);
 
@@ -101,10 +138,11 @@ const getOrBuildMerkleTree = async (
 
 export const createMerkleService = (
     redis: Redis,
-    commitmentsRepo: CommitmentsRepository
+    commitmentsRepo: CommitmentsRepository,
+    poolRoots: PoolRootsRepository
 ): MerkleService => ({
     getMerklePath: async ({ coinType, index, utxo }) => {
-        const tree = await getOrBuildMerkleTree(redis, commitmentsRepo, coinType);
+        const tree = await getOrBuildMerkleTree(redis, commitmentsRepo, poolRoots, coinType);
         const zeroPath: MerklePath = Array(MERKLE_TREE_HEIGHT)
             .fill(null)
             .map(() => [ZERO_VALUE.toString(), ZERO_VALUE.toString()]);
@@ -147,4 +185,11 @@ export const createMerkleService = (
             root: tree.root.toString(),
         };
     },
+
+    getRoots: async ({ coinType, sinceSeq, limit }) => {
+        const tree = await getOrBuildMerkleTree(redis, commitmentsRepo, poolRoots, coinType);
+        const roots = await poolRoots.findSince(coinType, sinceSeq, limit);
+
+        return { roots, latestSeq: Math.floor(tree.elements.length / 2) };
+    },
 });
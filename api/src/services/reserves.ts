@@ -0,0 +1,207 @@
+import type {
+    SuiArgument,
+    SuiClient,
+    SuiObjectData,
+    SuiTransactionBlockResponse,
+} from '@mysten/sui/client';
+import type { Ed25519Keypair } from '@mysten/sui/keypairs/ed25519';
+import { normalizeSuiAddress } from '@mysten/sui/utils';
+import type { CommitmentsRepository, PoolsRepository } from '@/repositories/index.ts';
+import { BN254_FIELD_MODULUS } from '@/utils/field.ts';
+
+export type PoolReserve = {
+    poolAddress: string;
+    coinType: string;
+    transactions: number;
+    deposits: string;
+    withdrawals: string;
+    netDeposits: string;
+    onChainBalance: string;
+    difference: string;
+};
+
+export type ReserveReport = {
+    checkpoint: number;
+    generatedAt: string;
+    pools: PoolReserve[];
+};
+
+/**
+ * A report with the operator's Sui personal message signature over
+ * `JSON.stringify(report)`; check it with `verifyPersonalMessageSignature`.
+ */
+export type SignedReserveReport = {
+    report: ReserveReport;
+    signer: string;
+    signature: string;
+};
+
+export const PROOF_MODULE = 'vortex_proof';
+export const PROOF_CONSTRUCTOR = 'new';
+const PROOF_VORTEX_ARGUMENT = 0;
+const PROOF_PUBLIC_VALUE_ARGUMENT = 3;
+
+/** Most transactions `sui_multiGetTransactionBlocks` returns per call. */
+const MULTI_GET_LIMIT = 50;
+
+/** Proof public values above half of the field are withdrawals. */
+const toSignedAmount = (value: bigint): bigint =>
+    value > BN254_FIELD_MODULUS / 2n ? value - BN254_FIELD_MODULUS : value;
+
+/**
+ * Signed public values of the `vortex_proof::new` calls for `pool` in a
+ * transaction: positive for deposits, negative for withdrawals including the
+ * relayer fee, as the pool balance moves.
+ */
+export const proofPublicValues = (tx: SuiTransactionBlockResponse, pool: string): bigint[] => {
+    const kind = tx.transaction?.data.transaction;
+    if (kind?.kind !== 'ProgrammableTransaction') return [];
+
+    const pure = (argument: SuiArgument | undefined): string | null => {
+        if (typeof argument !== 'object' || !('Input' in argument)) return null;
+        const input = kind.inputs[argument.Input];
+        return input?.type === 'pure' ? String(input.value) : null;
+    };
+
+    return kind.transactions.flatMap((command) => {
+        if (!('MoveCall' in command)) return [];
+        const call = command.MoveCall;
+        if (call.module !== PROOF_MODULE || call.function !== PROOF_CONSTRUCTOR) return [];
+
+        const vortex = pure(call.arguments?.[PROOF_VORTEX_ARGUMENT]);
+        const value = pure(call.arguments?.[PROOF_PUBLIC_VALUE_ARGUMENT]);
+        if (vortex === null || value === null) return [];
+        if (normalizeSuiAddress(vortex) !== normalizeSuiAddress(pool)) return [];
+
+        return [toSignedAmount(BigInt(value))];
+    });
+};
+
+const fetchTransactions = async (
+    client: SuiClient,
+    digests: string[]
+): Promise<SuiTransactionBlockResponse[]> => {
+    const transactions: SuiTransactionBlockResponse[] = [];
+    for (let i = 0; i < digests.length; i += MULTI_GET_LIMIT) {
+        transactions.push(
+            ...(await client.multiGetTransactionBlocks({
+                digests: digests.slice(i, i + MULTI_GET_LIMIT),
+                options: { showInput: true },
+            }))
+        );
+    }
+    return transactions;
+};
+
+const balanceOf = (object: SuiObjectData): bigint => {
+    const content = object.content;
+    if (content?.dataType !== 'moveObject') {
+        throw new Error(`Pool ${object.objectId} has no Move content`);
+    }
+    const fields = content.fields as Record<string, unknown>;
+    return BigInt(String(fields.balance ?? 0));
+};
+
+/**
+ * Balance of `pool` as of `checkpoint`. Walks back from the latest version
+ * through the transactions that mutated it, so the fullnode must still serve
+ * those past versions.
+ */
+export const poolBalanceAt = async (
+    client: SuiClient,
+    pool: string,
+    checkpoint: number
+): Promise<bigint> => {
+    const options = { showContent: true, showPreviousTransaction: true };
+    let object = (await client.getObject({ id: pool, options })).data;
+
+    while (object) {
+        const digest = object.previousTransaction;
+        if (!digest) throw new Error(`Pool ${pool} has no previous transaction`);
+
+        const tx = await client.getTransactionBlock({
+            digest,
+            options: { showObjectChanges: true },
+        });
+        if (Number(tx.checkpoint) <= checkpoint) return balanceOf(object);
+
+        const change = tx.objectChanges?.find(
+            (change) =>
+                change.type === 'mutated' &&
+                normalizeSuiAddress(change.objectId) === normalizeSuiAddress(pool)
+        );
+        if (change?.type !== 'mutated') return 0n;
+
+        const past = await client.tryGetPastObject({
+            id: pool,
+            version: Number(change.previousVersion),
+            options,
+        });
+        if (past.status !== 'VersionFound') {
+            throw new Error(
+                `Version ${change.previousVersion} of pool ${pool} is not available: ${past.status}`
+            );
+        }
+        object = past.details;
+    }
+
+    throw new Error(`Pool ${pool} not found`);
+};
+
+export const buildReserveReport = async (params: {
+    client: SuiClient;
+    pools: PoolsRepository;
+    commitments: CommitmentsRepository;
+    checkpoint: number;
+    coinType?: string;
+}): Promise<ReserveReport> => {
+    const { client, checkpoint, coinType } = params;
+    const pools = await params.pools.createdUpTo(
+        checkpoint,
+        coinType ? { coin_type: coinType } : {}
+    );
+
+    const reserves: PoolReserve[] = [];
+    for (const pool of pools) {
+        const digests = await params.commitments.digestsUpTo(pool.coin_type, checkpoint);
+        const transactions = await fetchTransactions(client, digests);
+
+        let matched = 0;
+        let deposits = 0n;
+        let withdrawals = 0n;
+        for (const tx of transactions) {
+            const values = proofPublicValues(tx, pool.pool_address);
+            if (values.length > 0) matched += 1;
+            for (const value of values) {
+                if (value > 0n) deposits += value;
+                else withdrawals -= value;
+            }
+        }
+
+        const netDeposits = deposits - withdrawals;
+        const onChainBalance = await poolBalanceAt(client, pool.pool_address, checkpoint);
+
+        reserves.push({
+            poolAddress: pool.pool_address,
+            coinType: pool.coin_type,
+            transactions: matched,
+            deposits: deposits.toString(),
+            withdrawals: withdrawals.toString(),
+            netDeposits: netDeposits.toString(),
+            onChainBalance: onChainBalance.toString(),
+            difference: (onChainBalance - netDeposits).toString(),
+        });
+    }
+
+    return { checkpoint, generatedAt: new Date().toISOString(), pools: reserves };
+};
+
+export const signReserveReport = async (
+    report: ReserveReport,
+    keypair: Ed25519Keypair
+): Promise<SignedReserveReport> => {
+    const message = new TextEncoder().encode(JSON.stringify(report));
+    const { signature } = await keypair.signPersonalMessage(message);
+
+    return { report, signer: keypair.toSuiAddress(), signature };
+};
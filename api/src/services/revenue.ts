@@ -0,0 +1,114 @@
+import type { SuiTransactionBlockResponse } from '@mysten/sui/client';
+import { normalizeStructTag, SUI_TYPE_ARG } from '@mysten/sui/utils';
+import type { RelayerJobDocument } from '@/db/collections/index.ts';
+
+export type RevenueRow = {
+    day: string;
+    coinType: string;
+    jobs: number;
+    fee: string;
+    gasSpent: string;
+    netMargin: string | null;
+};
+
+export type RevenueFormat = 'csv' | 'json';
+
+type Bucket = {
+    day: string;
+    coinType: string;
+    jobs: number;
+    fee: bigint;
+    gasSpent: bigint;
+    netMargin: bigint | null;
+};
+
+const SUI_COIN_TYPE = normalizeStructTag(SUI_TYPE_ARG);
+
+const UNKNOWN_COIN_TYPE = 'unknown';
+
+/**
+ * Derives the accounting for an executed relayer job. The relayer is the
+ * transaction sender, so the fee it collects shows up as a positive balance
+ * change on its address in the pool coin.
+ */
+export const toRelayerJob = (
+    result: SuiTransactionBlockResponse,
+    relayer: string
+): RelayerJobDocument => {
+    const received = (result.balanceChanges ?? []).find(
+        (change) =>
+            typeof change.owner === 'object' &&
+            'AddressOwner' in change.owner &&
+            change.owner.AddressOwner === relayer &&
+            BigInt(change.amount) > 0n
+    );
+
+    const gasUsed = result.effects?.gasUsed;
+    const gasSpent = gasUsed
+        ? BigInt(gasUsed.computationCost) +
+          BigInt(gasUsed.storageCost) -
+          BigInt(gasUsed.storageRebate)
+        : 0n;
+
+    const coinType = received ? normalizeStructTag(received.coinType) : null;
+    const fee = received ? BigInt(received.amount) : 0n;
+
+    return {
+        _id: result.digest,
+        coin_type: coinType,
+        fee: fee.toString(),
+        gas_spent: gasSpent.toString(),
+        net_margin: coinType === SUI_COIN_TYPE ? (fee - gasSpent).toString() : null,
+        created_at: new Date(Number(result.timestampMs ?? Date.now())),
+    };
+};
+
+/** Aggregates jobs per UTC day and pool coin type, in chronological order. */
+export const summarizeRevenue = (jobs: RelayerJobDocument[]): RevenueRow[] => {
+    const buckets = new Map<string, Bucket>();
+
+    for (const job of jobs) {
+        const day = job.created_at.toISOString().slice(0, 10);
+        const coinType = job.coin_type ?? UNKNOWN_COIN_TYPE;
+        const key = `${day}|${coinType}`;
+        const bucket = buckets.get(key) ?? {
+            day,
+            coinType,
+            jobs: 0,
+            fee: 0n,
+            gasSpent: 0n,
+            netMargin: 0n,
+        };
+
+        bucket.jobs += 1;
+        bucket.fee += BigInt(job.fee);
+        bucket.gasSpent += BigInt(job.gas_spent);
+        bucket.netMargin =
+            bucket.netMargin !== null && job.net_margin !== null
+                ? bucket.netMargin + BigInt(job.net_margin)
+                : null;
+        buckets.set(key, bucket);
+    }
+
+    return [...buckets.values()]
+        .sort((a, b) => a.day.localeCompare(b.day) || a.coinType.localeCompare(b.coinType))
+        .map((bucket) => ({
+            day: bucket.day,
+            coinType: bucket.coinType,
+            jobs: bucket.jobs,
+            fee: bucket.fee.toString(),
+            gasSpent: bucket.gasSpent.toString(),
+            netMargin: bucket.netMargin?.toString() ?? null,
+        }));
+};
+
+const CSV_HEADER = 'day,coin_type,jobs,fee,gas_spent,net_margin';
+
+export const formatRevenue = (rows: RevenueRow[], format: RevenueFormat): string => {
+    if (format === 'json') return JSON.stringify(rows, null, 2);
+
+    const lines = rows.map((row) =>
+        [row.day, row.coinType, row.jobs, row.fee, row.gasSpent, row.netMargin ?? ''].join(',')
+    );
+    return [CSV_HEADER, ...lines].join('\n');
+};
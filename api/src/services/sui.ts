@@ -22,11 +22,19 @@ export const sponsorAndExecuteTransaction = async (tx: Transaction) => {
     const result = await nodeClient.executeTransactionBlock({
         transactionBlock: txBytes,
         signature: [senderSignature, sponsorSignature],
-        options: { showEffects: true, showEvents: true, showObjectChanges: true },
+        options: {
+            showBalanceChanges: true,
+            showEffects: true,
+            showEvents: true,
+            showObjectChanges: true,
+        },
     });
 
     if (result.effects?.status.status !== 'success') {
-        logger.error({ result }, 'Transaction failed');
+        logger.error(
+            { digest: result.digest, status: result.effects?.status },
+            'Transaction failed'
+        );
         throw new Error(`Transaction failed: ${result.effects?.status.error ?? 'unknown error'}`);
     }
 
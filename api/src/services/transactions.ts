@@ -1,22 +1,166 @@
+import { createHash, randomUUID } from 'node:crypto';
+import type { SuiTransactionBlockResponse } from '@mysten/sui/client';
 import { Transaction } from '@mysten/sui/transactions';
 import { fromHex } from '@mysten/sui/utils';
+import { env } from '@/config/env.ts';
+import type {
+    DeadLettersRepository,
+    IdempotencyKeysRepository,
+    RelayerJobsRepository,
+    SeenProofsRepository,
+} from '@/repositories/index.ts';
+import { PROOF_CONSTRUCTOR, PROOF_MODULE } from '@/services/reserves.ts';
+import { toRelayerJob } from '@/services/revenue.ts';
 import { keypair, sponsorAndExecuteTransaction } from '@/services/sui.ts';
+import { logger } from '@/utils/logger.ts';
 import { validateTransactionCommands, type TransactionJson } from '@/utils/validate-commands.ts';
 
+export type ExecuteResult =
+    | { status: 'executed'; digest: string }
+    | { status: 'replayed'; digest: string }
+    | { status: 'in_progress' }
+    | { status: 'key_mismatch' }
+    | { status: 'proof_replayed' };
+
 export type TransactionsService = {
-    execute: (txBytes: string) => Promise<string>;
+    /**
+     * Sponsors and executes `txBytes`. With an `idempotencyKey`, a retry of a
+     * submission that executed returns its digest instead of executing again,
+     * and reusing the key for different transaction bytes is rejected. A proof
+     * already submitted within the replay window is rejected.
+     */
+    execute: (txBytes: string, idempotencyKey?: string) => Promise<ExecuteResult>;
+};
+
+/** Rebuilds client transaction bytes and rejects commands the relayer does not sponsor. */
+export const buildTransaction = async (txBytes: string): Promise<Transaction> => {
+    const rebuiltTransaction = Transaction.from(fromHex(txBytes));
+
+    const transactionJson = JSON.parse(await rebuiltTransaction.toJSON()) as TransactionJson;
+    validateTransactionCommands(transactionJson.commands);
+
+    rebuiltTransaction.setSender(keypair.toSuiAddress());
+
+    return rebuiltTransaction;
+};
+
+const PROOF_POINTS_ARGUMENT = 1;
+
+/** SHA-256 of the proof points passed to each `vortex_proof::new` call. */
+export const proofHashes = (transaction: Transaction): string[] => {
+    const { commands, inputs } = transaction.getData();
+
+    return commands.flatMap((command) => {
+        if (command.$kind !== 'MoveCall') return [];
+        const call = command.MoveCall;
+        if (call.module !== PROOF_MODULE || call.function !== PROOF_CONSTRUCTOR) return [];
+
+        const argument = call.arguments[PROOF_POINTS_ARGUMENT];
+        if (argument?.$kind !== 'Input') return [];
+        const input = inputs[argument.Input];
+        if (input?.$kind !== 'Pure') return [];
+
+        return [createHash('sha256').update(input.Pure.bytes).digest('hex')];
+    });
 };
 
-export const createTransactionsService = (): TransactionsService => ({
-    execute: async (txBytes) => {
-        const rebuiltTransaction = Transaction.from(fromHex(txBytes));
+export const createTransactionsService = (
+    deadLetters: DeadLettersRepository,
+    relayerJobs: RelayerJobsRepository,
+    idempotencyKeys: IdempotencyKeysRepository,
+    seenProofs: SeenProofsRepository
+): TransactionsService => {
+    // Failed submissions may be retried with the same key and proof
+    const release = async (idempotencyKey: string | undefined, proofs: string[]) => {
+        if (idempotencyKey) {
+            await idempotencyKeys
+                .release(idempotencyKey)
+                .catch((keyError: unknown) =>
+                    logger.error({ error: keyError }, 'Failed to release idempotency key')
+                );
+        }
+        await Promise.all(
+            proofs.map((hash) =>
+                seenProofs
+                    .forget(hash)
+                    .catch((proofError: unknown) =>
+                        logger.error({ error: proofError, hash }, 'Failed to forget proof')
+                    )
+            )
+        );
+    };
+
+    return {
+        execute: async (txBytes, idempotencyKey) => {
+            const transaction = await buildTransaction(txBytes);
+
+            if (idempotencyKey) {
+                const staleBefore = new Date(Date.now() - env.IDEMPOTENCY_CLAIM_TTL_MS);
+                const txHash = createHash('sha256').update(fromHex(txBytes)).digest('hex');
+                const holder = await idempotencyKeys.claim(idempotencyKey, txHash, staleBefore);
+                if (holder && holder.tx_hash !== txHash) return { status: 'key_mismatch' };
+                if (holder?.digest) return { status: 'replayed', digest: holder.digest };
+                if (holder) return { status: 'in_progress' };
+            }
 
-        const transactionJson = JSON.parse(await rebuiltTransaction.toJSON()) as TransactionJson;
-        validateTransactionCommands(transactionJson.commands);
+            const expiresAt = new Date(Date.now() + env.PROOF_REPLAY_WINDOW_MS);
+            const proofs: string[] = [];
+            for (const hash of proofHashes(transaction)) {
+                if (!(await seenProofs.record(hash, expiresAt))) {
+                    logger.warn({ hash }, 'Rejected replayed proof');
+                    await release(idempotencyKey, proofs);
+                    return { status: 'proof_replayed' };
+                }
+                proofs.push(hash);
+            }
 
-        rebuiltTransaction.setSender(keypair.toSuiAddress());
+            let result: SuiTransactionBlockResponse;
+            try {
+                result = await sponsorAndExecuteTransaction(transaction);
+            } catch (error) {
+                await release(idempotencyKey, proofs);
 
-        const result = await sponsorAndExecuteTransaction(rebuiltTransaction);
-        return result.digest;
-    },
-});
+                const now = new Date();
+                const id = randomUUID();
+                await deadLetters
+                    .insert({
+                        _id: id,
+                        source: 'relayer',
+                        collection: null,
+                        payload: { tx_bytes: txBytes },
+                        error: error instanceof Error ? error.message : String(error),
+                        attempts: 1,
+                        created_at: now,
+                        last_attempt_at: now,
+                    })
+                    .catch((dlqError: unknown) =>
+                        logger.error({ error: dlqError, id }, 'Failed to record dead letter')
+                    );
+                throw error;
+            }
+
+            if (idempotencyKey) {
+                await idempotencyKeys
+                    .complete(idempotencyKey, result.digest)
+                    .catch((keyError: unknown) =>
+                        logger.error(
+                            { error: keyError, digest: result.digest },
+                            'Failed to complete idempotency key'
+                        )
+                    );
+            }
+
+            const job = toRelayerJob(result, keypair.toSuiAddress());
+            await relayerJobs
+                .insert(job)
+                .catch((jobError: unknown) =>
+                    logger.error(
+                        { error: jobError, digest: job._id },
+                        'Failed to record relayer job'
+                    )
+                );
+
+            return { status: 'executed', digest: job._id };
+        },
+    };
+};
@@ -0,0 +1,146 @@
+import { createHmac, randomBytes, randomUUID } from 'node:crypto';
+import { env } from '@/config/env.ts';
+import type { WatchDocument, WatchNotificationDocument } from '@/db/collections/index.ts';
+import type { WatchNotificationCursor, WatchesRepository } from '@/repositories/index.ts';
+import { hexToDecimal } from '@/utils/hex.ts';
+import { logger } from '@/utils/logger.ts';
+import { isPublicWebhookUrl } from '@/utils/webhook-host.ts';
+
+export type CreateWatchParams = {
+    /** Normalized by `u256Schema` to the indexer's stored hex form */
+    nullifier: string;
+    webhookUrl?: string | undefined;
+};
+
+export type WatchesService = {
+    create: (params: CreateWatchParams) => Promise<WatchDocument>;
+    findById: (id: string) => Promise<WatchDocument | null>;
+    delete: (id: string) => Promise<boolean>;
+    findNotifications: (watchId: string) => Promise<WatchNotificationDocument[]>;
+    findNotificationById: (id: string) => Promise<WatchNotificationDocument | null>;
+    findNotificationsAfter: (
+        watchId: string,
+        after: WatchNotificationCursor | null
+    ) => Promise<WatchNotificationDocument[]>;
+    deliverPending: () => Promise<number>;
+};
+
+const DELIVERY_BATCH_SIZE = 100;
+const WEBHOOK_SECRET_BYTES = 32;
+
+export const signWebhookPayload = (body: string, secret: string): string =>
+    createHmac('sha256', secret).update(body).digest('hex');
+
+const postWebhook = async (url: string, secret: string, body: string) => {
+    if (!(await isPublicWebhookUrl(url))) {
+        logger.warn('Webhook host no longer resolves to a public address');
+        return false;
+    }
+
+    const response = await fetch(url, {
+        method: 'POST',
+        headers: {
+            'content-type': 'application/json',
+            'x-vortex-signature': signWebhookPayload(body, secret),
+        },
+        body,
+        redirect: 'error',
+        signal: AbortSignal.timeout(env.WEBHOOK_TIMEOUT_MS),
+    });
+
+    return response.ok;
+};
+
+const toWebhookPayload = (notification: WatchNotificationDocument) => ({
+    id: notification._id,
+    watchId: notification.watch_id,
+    kind: notification.kind,
+    nullifier: hexToDecimal(notification.value),
+    digest: notification.digest,
+    coinType: notification.coin_type,
+    checkpoint: notification.checkpoint,
+    checkpointTimestampMs: notification.checkpoint_timestamp_ms,
+});
+
+export const createWatchesService = (repository: WatchesRepository): WatchesService => ({
+    create: async ({ nullifier, webhookUrl }) => {
+        const doc: WatchDocument = {
+            _id: randomUUID(),
+            kind: 'nullifier',
+            value: nullifier,
+            webhook_url: webhookUrl ?? null,
+            webhook_secret: webhookUrl ? randomBytes(WEBHOOK_SECRET_BYTES).toString('hex') : null,
+            created_at: new Date(),
+        };
+
+        await repository.insert(doc);
+
+        return doc;
+    },
+
+    findById: (id) => repository.findById(id),
+
+    delete: (id) => repository.delete(id),
+
+    findNotifications: (watchId) => repository.findNotifications(watchId),
+
+    findNotificationById: (id) => repository.findNotificationById(id),
+
+    findNotificationsAfter: (watchId, after) => repository.findNotificationsAfter(watchId, after),
+
+    deliverPending: async () => {
+        const pending = await repository.findUndelivered({
+            maxAttempts: env.WEBHOOK_MAX_ATTEMPTS,
+            limit: DELIVERY_BATCH_SIZE,
+        });
+
+        const watches = await repository.findByIds([...new Set(pending.map((n) => n.watch_id))]);
+        const secrets = new Map(watches.map((watch) => [watch._id, watch.webhook_secret]));
+
+        const results = await Promise.all(
+            pending.map(async (notification) => {
+                const secret = secrets.get(notification.watch_id);
+                const body = JSON.stringify(toWebhookPayload(notification));
+                const delivered =
+                    secret && notification.webhook_url
+                        ? await postWebhook(notification.webhook_url, secret, body).catch(
+                              (error: unknown) => {
+                                  logger.warn(
+                                      { error, id: notification._id },
+                                      'Webhook delivery failed'
+                                  );
+                                  return false;
+                              }
+                          )
+                        : false;
+                await repository.recordAttempt(notification._id, delivered);
+                return delivered;
+            })
+        );
+
+        return results.filter(Boolean).length;
+    },
+});
+
+export const startWebhookDispatcher = (service: WatchesService): (() => void) => {
+    let running = false;
+
+    const timer = setInterval(() => {
+        if (running) return;
+        running = true;
+
+        service
+            .deliverPending()
+            .then((delivered) => {
+                if (delivered > 0) logger.info({ delivered }, 'Delivered watch notifications');
+            })
+            .catch((error: unknown) => {
+                logger.error({ error }, 'Webhook dispatcher error');
+            })
+            .finally(() => {
+                running = false;
+            });
+    }, env.WEBHOOK_POLL_INTERVAL_MS);
+
+    return () => clearInterval(timer);
+};
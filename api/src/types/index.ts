@@ -3,13 +3,20 @@ import type {
     PoolsRepository,
     AccountsRepository,
     CommitmentsRepository,
+    DeadLettersRepository,
+    LeafCountsRepository,
+    NullifiersRepository,
+    WatchesRepository,
 } from '@/repositories/index.ts';
 import type {
     AccountsService,
+    AnonymityService,
+    DeadLettersService,
     HealthService,
     MerkleService,
     RelayerService,
     TransactionsService,
+    WatchesService,
 } from '@/services/index.ts';
 
 export type AppBindings = {
@@ -17,11 +24,18 @@ export type AppBindings = {
         pools: PoolsRepository;
         accounts: AccountsRepository;
         commitments: CommitmentsRepository;
+        deadLetters: DeadLettersRepository;
+        leafCounts: LeafCountsRepository;
+        nullifiers: NullifiersRepository;
+        watches: WatchesRepository;
         accountsService: AccountsService;
+        anonymityService: AnonymityService;
+        deadLettersService: DeadLettersService;
         healthService: HealthService;
         merkleService: MerkleService;
         relayerService: RelayerService;
         transactionsService: TransactionsService;
+        watchesService: WatchesService;
     };
 };
 
@@ -0,0 +1,38 @@
+/** Scalar field of BN254, which commitments, nullifiers and hashes live in. */
+export const BN254_FIELD_MODULUS =
+    21888242871839275222246405745257275088548364400416034343698204186575808495617n;
+
+const FIELD_BYTES = 32;
+const MAX_BYTE = 255;
+const DECIMAL = /^[0-9]+$/;
+const HEX = /^0x[0-9a-f]+$/i;
+const BYTE_ARRAY = /^\[([0-9,\s]+)\]$/;
+
+const parseBytes = (list: string): bigint | null => {
+    const bytes = list.split(',').map((byte) => byte.trim());
+    if (bytes.length > FIELD_BYTES || bytes.some((byte) => !DECIMAL.test(byte))) {
+        return null;
+    }
+    let value = 0n;
+    for (const byte of bytes) {
+        const parsed = Number(byte);
+        if (parsed > MAX_BYTE) return null;
+        value = (value << 8n) | BigInt(parsed);
+    }
+    return value;
+};
+
+// Same forms as `parse_field` in the circuit crate: decimal, 0x-prefixed
+// big-endian hex or a [b0, b1, ...] big-endian byte array. Returns the
+// decimal value responses use, or null when it is not below the modulus.
+export const parseField = (value: string): string | null => {
+    const trimmed = value.trim();
+    const bytes = BYTE_ARRAY.exec(trimmed);
+    let parsed: bigint | null = null;
+    if (bytes) {
+        parsed = parseBytes(bytes[1]!);
+    } else if (DECIMAL.test(trimmed) || HEX.test(trimmed)) {
+        parsed = BigInt(trimmed);
+    }
+    return parsed !== null && parsed < BN254_FIELD_MODULUS ? parsed.toString() : null;
+};
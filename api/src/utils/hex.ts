@@ -3,3 +3,23 @@ export const hexToDecimal = (hex: string): string => {
     const reversed = Buffer.from(cleanHex, 'hex').reverse();
     return BigInt('0x' + reversed.toString('hex')).toString();
 };
+
+export const decimalToHex = (decimal: string): string => {
+    const bytes = Buffer.from(BigInt(decimal).toString(16).padStart(64, '0'), 'hex').reverse();
+    return '0x' + bytes.toString('hex');
+};
+
+const HEX_U256 = /^(?:0x)?([0-9a-f]{64})$/i;
+const DECIMAL = /^[0-9]+$/;
+
+// Same policy as `HexU256` in vortex-schema: 64 hex digits of the
+// little-endian bytes (0x optional, any case) or the decimal value.
+// Unprefixed digit-only strings are decimal.
+export const normalizeU256 = (value: string): string | null => {
+    const trimmed = value.trim();
+    if (DECIMAL.test(trimmed)) {
+        return BigInt(trimmed) < 2n ** 256n ? decimalToHex(trimmed) : null;
+    }
+    const match = HEX_U256.exec(trimmed);
+    return match ? '0x' + match[1]!.toLowerCase() : null;
+};
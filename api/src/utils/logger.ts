@@ -1,5 +1,15 @@
 import pino from 'pino';
 import { env } from '@/config/env.ts';
+import { createRedactor } from './redaction.ts';
+
+const redactor = createRedactor(
+    {
+        ip: env.LOG_REDACT_IP,
+        nullifier: env.LOG_REDACT_NULLIFIER,
+        address: env.LOG_REDACT_ADDRESS,
+    },
+    env.LOG_REDACTION_SALT
+);
 
 const baseConfig = {
     level: env.NODE_ENV === 'production' ? 'info' : 'debug',
@@ -7,6 +17,7 @@ const baseConfig = {
         level: (label: string) => ({ level: label }),
     },
     timestamp: pino.stdTimeFunctions.isoTime,
+    redact: { paths: redactor.paths, censor: redactor.censor },
 };
 
 export const logger =
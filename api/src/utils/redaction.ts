@@ -0,0 +1,104 @@
+import { createHmac, randomBytes } from 'node:crypto';
+
+export type RedactionMode = 'keep' | 'hash' | 'truncate' | 'drop';
+
+export type RedactedField = 'ip' | 'nullifier' | 'address';
+
+/**
+ * How each kind of identifying value is written to the logs:
+ * - `keep` logs it as is
+ * - `hash` logs a keyed SHA-256 prefix, so one client or value can still be
+ *   followed across lines without being recoverable
+ * - `truncate` keeps only the first and last few characters
+ * - `drop` replaces it with a fixed marker
+ */
+export type RedactionPolicy = Record<RedactedField, RedactionMode>;
+
+export type Redactor = {
+    paths: string[];
+    censor: (value: unknown, path: string[]) => unknown;
+};
+
+const REDACTED = '[redacted]';
+const HASH_PREFIX_LENGTH = 16;
+const TRUNCATE_HEAD = 6;
+const TRUNCATE_TAIL = 4;
+
+/**
+ * Log keys holding each kind of value, one level deep at most. `path` keys are
+ * request paths and are handled segment by segment.
+ */
+const FIELD_KEYS: Record<RedactedField, string[]> = {
+    ip: ['ip', 'clientIp'],
+    nullifier: ['nullifier', 'nullifiers', 'commitment', 'commitments', 'hashedSecret'],
+    address: ['address', 'recipient', 'sender', 'owner'],
+};
+
+const PATH_KEY = 'path';
+
+const IDENTIFIER_SEGMENT =
+    /^(0x[0-9a-fA-F]{40,}|[0-9]{20,}|[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12})$/;
+
+const fieldOfKey = (key: string | undefined): RedactedField | null => {
+    if (key === undefined) return null;
+    const entry = Object.entries(FIELD_KEYS).find(([, keys]) => keys.includes(key));
+    return entry ? (entry[0] as RedactedField) : null;
+};
+
+export const createRedactor = (policy: RedactionPolicy, salt?: string): Redactor => {
+    const key = salt ?? randomBytes(32).toString('hex');
+
+    const redactValue = (value: string, mode: RedactionMode): string => {
+        switch (mode) {
+            case 'keep':
+                return value;
+            case 'hash':
+                return createHmac('sha256', key)
+                    .update(value)
+                    .digest('hex')
+                    .slice(0, HASH_PREFIX_LENGTH);
+            case 'truncate':
+                return value.length <= TRUNCATE_HEAD + TRUNCATE_TAIL
+                    ? value
+                    : `${value.slice(0, TRUNCATE_HEAD)}…${value.slice(-TRUNCATE_TAIL)}`;
+            case 'drop':
+                return REDACTED;
+        }
+    };
+
+    const redactDeep = (value: unknown, mode: RedactionMode): unknown => {
+        if (mode === 'keep') return value;
+        if (typeof value === 'string') return redactValue(value, mode);
+        if (typeof value === 'number' || typeof value === 'bigint') {
+            return redactValue(value.toString(), mode);
+        }
+        if (Array.isArray(value)) return value.map((item) => redactDeep(item, mode));
+        if (value !== null && typeof value === 'object') {
+            return Object.fromEntries(
+                Object.entries(value).map(([k, v]) => [k, redactDeep(v, mode)])
+            );
+        }
+        return value;
+    };
+
+    const redactPath = (path: string): string =>
+        path
+            .split('/')
+            .map((segment) =>
+                IDENTIFIER_SEGMENT.test(segment) ? redactValue(segment, policy.nullifier) : segment
+            )
+            .join('/');
+
+    const keys = [...Object.values(FIELD_KEYS).flat(), PATH_KEY];
+
+    return {
+        paths: keys.flatMap((k) => [k, `*.${k}`]),
+        censor: (value, path) => {
+            const last = path[path.length - 1];
+            if (last === PATH_KEY) return typeof value === 'string' ? redactPath(value) : value;
+
+            const field = fieldOfKey(last);
+            return field ? redactDeep(value, policy[field]) : value;
+        },
+    };
+};
@@ -0,0 +1,90 @@
+import { logger } from './logger.ts';
+
+type Cleanup = () => void | Promise<void>;
+
+export type Runtime = {
+    /** Aborted once shutdown starts; long-running work should stop on it */
+    signal: AbortSignal;
+    /** Registers a cleanup; shutdown runs them in reverse order of registration */
+    onShutdown: (name: string, cleanup: Cleanup) => void;
+    /** Runs every cleanup once and exits, however often it is called */
+    shutdown: (reason: string, exitCode?: number) => Promise<void>;
+};
+
+const EXIT_FAILURE = 1;
+
+/**
+ * Gives each cleanup `graceMs` to finish, so one stuck connection cannot keep
+ * the process from exiting.
+ */
+export const createRuntime = (graceMs: number): Runtime => {
+    const controller = new AbortController();
+    const cleanups: { name: string; cleanup: Cleanup }[] = [];
+    let shuttingDown: Promise<void> | null = null;
+
+    const runCleanup = async (name: string, cleanup: Cleanup) => {
+        let timer: ReturnType<typeof setTimeout> | undefined;
+        const timedOut = new Promise<boolean>((resolve) => {
+            timer = setTimeout(() => resolve(true), graceMs);
+        });
+
+        try {
+            const finished = (async () => {
+                await cleanup();
+                return false;
+            })();
+            if (await Promise.race([finished, timedOut])) {
+                logger.warn({ task: name, graceMs }, 'Cleanup did not finish in time');
+            }
+        } catch (err: unknown) {
+            logger.error({ err, task: name }, 'Cleanup failed');
+        } finally {
+            clearTimeout(timer);
+        }
+    };
+
+    return {
+        signal: controller.signal,
+        onShutdown: (name, cleanup) => {
+            cleanups.push({ name, cleanup });
+        },
+        shutdown: (reason, exitCode = 0) => {
+            shuttingDown ??= (async () => {
+                logger.info({ reason }, 'Shutting down...');
+                controller.abort(reason);
+
+                for (const { name, cleanup } of cleanups.toReversed()) {
+                    await runCleanup(name, cleanup);
+                }
+                process.exit(exitCode);
+            })();
+            return shuttingDown;
+        },
+    };
+};
+
+/**
+ * Shuts `runtime` down on SIGINT/SIGTERM, and on errors nothing else caught,
+ * which would otherwise leave the process running in an unknown state.
+ */
+export const installShutdownHandlers = (runtime: Runtime): void => {
+    const shutdown = (reason: string, exitCode?: number) => {
+        runtime.shutdown(reason, exitCode).catch((err: unknown) => {
+            logger.error({ err }, 'Shutdown error');
+        });
+    };
+
+    for (const signal of ['SIGINT', 'SIGTERM'] as const) {
+        process.on(signal, () => {
+            shutdown(signal);
+        });
+    }
+    process.on('uncaughtException', (err) => {
+        logger.fatal({ err }, 'Uncaught exception');
+        shutdown('uncaughtException', EXIT_FAILURE);
+    });
+    process.on('unhandledRejection', (err: unknown) => {
+        logger.fatal({ err }, 'Unhandled rejection');
+        shutdown('unhandledRejection', EXIT_FAILURE);
+    });
+};
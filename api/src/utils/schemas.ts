@@ -1,15 +1,44 @@
-import { isValidSuiAddress, isValidSuiObjectId } from '@mysten/sui/utils';
+import { isValidSuiAddress, isValidSuiObjectId, normalizeStructTag } from '@mysten/sui/utils';
 import { z } from 'zod';
+import { parseField } from '@/utils/field.ts';
+import { normalizeU256 } from '@/utils/hex.ts';
 
 export const suiAddressSchema = z.string().refine(isValidSuiAddress, 'Invalid Sui address');
 
 export const suiObjectIdSchema = z.string().refine(isValidSuiObjectId, 'Invalid Sui object ID');
 
-export const poseidonHashSchema = z
-    .string()
-    .regex(/^[0-9]+$/)
-    .max(80);
+/** A field element in any form `parseField` reads, normalized to decimal. */
+export const poseidonHashSchema = z.string().transform((value, ctx) => {
+    const normalized = parseField(value);
+    if (normalized === null) {
+        ctx.addIssue({
+            code: z.ZodIssueCode.custom,
+            message: 'Expected a field element as decimal, 0x-hex or a byte array',
+        });
+        return z.NEVER;
+    }
+    return normalized;
+});
+
+export const u256Schema = z.string().transform((value, ctx) => {
+    const normalized = normalizeU256(value);
+    if (normalized === null) {
+        ctx.addIssue({
+            code: z.ZodIssueCode.custom,
+            message: 'Expected decimal or 64 hex digits',
+        });
+        return z.NEVER;
+    }
+    return normalized;
+});
 
-export const coinTypeSchema = z.string().regex(/^0x[a-fA-F0-9]+::\w+::\w+$/);
+/**
+ * Accepts short (`0x2::sui::SUI`) and long-form addresses, normalized to the
+ * long form the indexer stores.
+ */
+export const coinTypeSchema = z
+    .string()
+    .regex(/^0x[a-fA-F0-9]+::\w+::\w+$/)
+    .transform((value) => normalizeStructTag(value));
 
 export const indexSchema = z.coerce.number().int().min(0);
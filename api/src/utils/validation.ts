@@ -39,3 +39,13 @@ export const validateQuery = <T extends z.ZodSchema>(
     c: Context,
     schema: T
 ): ValidationResult<z.infer<T>> => validate(c, schema, c.req.query());
+
+export const validateHeaders = <T extends z.ZodSchema>(
+    c: Context,
+    schema: T
+): ValidationResult<z.infer<T>> => validate(c, schema, c.req.header());
+
+export const validateParams = <T extends z.ZodSchema>(
+    c: Context,
+    schema: T
+): ValidationResult<z.infer<T>> => validate(c, schema, c.req.param());
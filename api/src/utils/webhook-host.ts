@@ -0,0 +1,66 @@
+import { lookup } from 'node:dns/promises';
+import { isIPv4, isIPv6 } from 'node:net';
+
+const IPV4_MAPPED_PREFIX = '::ffff:';
+
+const isPrivateIpv4 = (address: string): boolean => {
+    const [a = 0, b = 0] = address.split('.').map(Number);
+
+    return (
+        a === 0 ||
+        a === 10 ||
+        a === 127 ||
+        (a === 100 && b >= 64 && b <= 127) ||
+        (a === 169 && b === 254) ||
+        (a === 172 && b >= 16 && b <= 31) ||
+        (a === 192 && b === 168) ||
+        (a === 198 && (b === 18 || b === 19)) ||
+        a >= 224
+    );
+};
+
+const isPrivateIpv6 = (address: string): boolean => {
+    const normalized = address.toLowerCase();
+
+    if (normalized.startsWith(IPV4_MAPPED_PREFIX)) {
+        const mapped = normalized.slice(IPV4_MAPPED_PREFIX.length);
+        return !isIPv4(mapped) || isPrivateIpv4(mapped);
+    }
+
+    return (
+        normalized === '::' ||
+        normalized === '::1' ||
+        /^f[cd]/.test(normalized) ||
+        /^fe[89ab]/.test(normalized) ||
+        normalized.startsWith('ff')
+    );
+};
+
+/**
+ * Whether an address is loopback, private, link-local, CGNAT, multicast or
+ * otherwise not routable on the public internet.
+ */
+export const isNonPublicAddress = (address: string): boolean => {
+    if (isIPv4(address)) return isPrivateIpv4(address);
+    if (isIPv6(address)) return isPrivateIpv6(address);
+    return true;
+};
+
+/**
+ * Resolves the webhook host and reports whether every address it resolves to
+ * is public. Checked on registration and again before each delivery, since the
+ * DNS record can change in between.
+ */
+export const isPublicWebhookUrl = async (url: string): Promise<boolean> => {
+    const hostname = new URL(url).hostname.replace(/^\[(.*)\]$/, '$1');
+
+    try {
+        const addresses = await lookup(hostname, { all: true, verbatim: true });
+        return (
+            addresses.length > 0 &&
+            addresses.every(({ address }) => !isNonPublicAddress(address))
+        );
+    } catch {
+        return false;
+    }
+};
@@ -0,0 +1,60 @@
+//! Replaying historical commitments into a pool tree with
+//! `SparseMerkleTree::insert_batch`, hashing up to the root per pair, against
+//! `insert_batch_fast`, updating each level once per batch. Run with
+//! `cargo bench --bench merkle`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use vortex::constants::{MERKLE_TREE_LEVEL, ZERO_VALUE};
+use vortex::merkle_tree::SparseMerkleTree;
+use vortex::poseidon_opt::{fr_from_str, PoseidonOptimized};
+
+use ark_bn254::Fr;
+
+const PAIR_COUNTS: [usize; 3] = [1 << 10, 1 << 13, 1 << 15];
+
+type Tree = SparseMerkleTree<MERKLE_TREE_LEVEL>;
+
+fn pairs(count: usize) -> Vec<(Fr, Fr)> {
+    (0..count as u64)
+        .map(|i| (Fr::from(2 * i), Fr::from(2 * i + 1)))
+        .collect()
+}
+
+fn report(label: &str, count: usize, elapsed: Duration) {
+    println!(
+        "{label:<34} {count:>7} pairs  {:>10.2?}  {:>10.0} pairs/s",
+        elapsed,
+        count as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn time(run: impl FnOnce() -> Tree) -> Duration {
+    let start = Instant::now();
+    black_box(run());
+    start.elapsed()
+}
+
+fn main() {
+    let hasher = PoseidonOptimized::new_t3();
+    let empty = Tree::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+
+    for count in PAIR_COUNTS {
+        let pairs = pairs(count);
+
+        let elapsed = time(|| {
+            let mut tree = empty.clone();
+            tree.insert_batch(&pairs, &hasher).expect("Tree has room");
+            tree
+        });
+        report("SparseMerkleTree::insert_batch", count, elapsed);
+
+        let elapsed = time(|| {
+            let mut tree = empty.clone();
+            tree.insert_batch_fast(&pairs, &hasher)
+                .expect("Tree has room");
+            tree
+        });
+        report("SparseMerkleTree::insert_batch_fast", count, elapsed);
+    }
+}
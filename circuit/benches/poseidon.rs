@@ -0,0 +1,90 @@
+//! Throughput of each hasher width, and of `PoseidonOptimized::hash_many_pairs`
+//! against hashing the same pairs one call at a time. Run with
+//! `cargo bench --bench poseidon`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use vortex::poseidon_opt::{hash2, hasher_for_arity, PoseidonOptimized};
+
+use ark_bn254::Fr;
+
+const PAIR_COUNTS: [usize; 3] = [1 << 10, 1 << 14, 1 << 16];
+const WIDTH_HASHES: usize = 1 << 14;
+const MAX_ARITY: usize = 5;
+
+fn pairs(count: usize) -> Vec<(Fr, Fr)> {
+    (0..count as u64)
+        .map(|i| (Fr::from(i), Fr::from(i + 1)))
+        .collect()
+}
+
+fn report(label: &str, count: usize, elapsed: Duration) {
+    println!(
+        "{label:<36} {count:>7} pairs  {:>10.2?}  {:>12.0} hashes/s",
+        elapsed,
+        count as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn time<T>(run: impl FnOnce() -> T) -> Duration {
+    let start = Instant::now();
+    black_box(run());
+    start.elapsed()
+}
+
+fn widths() {
+    for arity in 1..=MAX_ARITY {
+        let Some(hasher) = hasher_for_arity(arity) else {
+            continue;
+        };
+        let inputs = (0..WIDTH_HASHES as u64)
+            .map(|i| {
+                (0..arity as u64)
+                    .map(|j| Fr::from(i + j))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let elapsed = time(|| {
+            inputs
+                .iter()
+                .map(|inputs| hasher.hash(inputs))
+                .collect::<Vec<_>>()
+        });
+        println!(
+            "t={:<2} {WIDTH_HASHES:>7} hashes  {:>10.2?}  {:>12.0} hashes/s",
+            arity + 1,
+            elapsed,
+            WIDTH_HASHES as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
+
+fn main() {
+    widths();
+
+    let hasher = PoseidonOptimized::new_t3();
+
+    for count in PAIR_COUNTS {
+        let pairs = pairs(count);
+
+        let elapsed = time(|| {
+            pairs
+                .iter()
+                .map(|(left, right)| hash2(left, right))
+                .collect::<Vec<_>>()
+        });
+        report("hash2() (new hasher per call)", count, elapsed);
+
+        let elapsed = time(|| {
+            pairs
+                .iter()
+                .map(|(left, right)| hasher.hash2(left, right))
+                .collect::<Vec<_>>()
+        });
+        report("PoseidonOptimized::hash2", count, elapsed);
+
+        let elapsed = time(|| hasher.hash_many_pairs(&pairs));
+        report("PoseidonOptimized::hash_many_pairs", count, elapsed);
+    }
+}
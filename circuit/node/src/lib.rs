@@ -0,0 +1,117 @@
+use napi::{bindgen_prelude::AsyncTask, Env, Task};
+use napi_derive::napi;
+use vortex::{note, prover, tree_state};
+
+/// Proof generation running on the libuv thread pool so the event loop stays responsive
+pub struct ProveTask {
+    input_json: String,
+    proving_key_hex: String,
+    descriptor_json: Option<String>,
+}
+
+impl Task for ProveTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        prover::prove_json(
+            &self.input_json,
+            &self.proving_key_hex,
+            self.descriptor_json.as_deref(),
+        )
+        .map_err(|error| to_napi_error(error.into()))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Generates a proof; same JSON input/output contract as the WASM `prove`.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn prove(
+    input_json: String,
+    proving_key_hex: String,
+    descriptor_json: Option<String>,
+) -> napi::Result<AsyncTask<ProveTask>> {
+    Ok(AsyncTask::new(ProveTask {
+        input_json,
+        proving_key_hex,
+        descriptor_json,
+    }))
+}
+
+/// Batch proof generation on the libuv thread pool; the proofs themselves run in parallel
+pub struct ProveBatchTask {
+    inputs_json: String,
+    proving_key_hex: String,
+    descriptor_json: Option<String>,
+}
+
+impl Task for ProveBatchTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        prover::prove_batch_json(
+            &self.inputs_json,
+            &self.proving_key_hex,
+            self.descriptor_json.as_deref(),
+        )
+        .map_err(|error| to_napi_error(error.into()))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Generates proofs for a JSON array of inputs with one decoded proving key;
+/// same contract as the WASM `prove_batch`.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn prove_batch(
+    inputs_json: String,
+    proving_key_hex: String,
+    descriptor_json: Option<String>,
+) -> napi::Result<AsyncTask<ProveBatchTask>> {
+    Ok(AsyncTask::new(ProveBatchTask {
+        inputs_json,
+        proving_key_hex,
+        descriptor_json,
+    }))
+}
+
+/// Verifies a proof produced by `prove`; same contract as the WASM `verify`.
+#[napi]
+pub fn verify(
+    proof_json: String,
+    verifying_key_hex: String,
+    descriptor_json: Option<String>,
+) -> napi::Result<bool> {
+    prover::verify_json(&proof_json, &verifying_key_hex, descriptor_json.as_deref())
+        .map_err(|error| to_napi_error(error.into()))
+}
+
+/// Checks a leaf inclusion claim; same contract as the WASM `verify_inclusion`.
+#[napi]
+pub fn verify_inclusion(
+    root: String,
+    index: i64,
+    commitment: String,
+    path_json: String,
+) -> napi::Result<bool> {
+    let index = u64::try_from(index)
+        .map_err(|_| napi::Error::from_reason(format!("Invalid leaf index {index}")))?;
+    tree_state::verify_inclusion_json(&root, index, &commitment, &path_json).map_err(to_napi_error)
+}
+
+/// Scans a JSON array of hex `encrypted_output`s for notes owned by
+/// `viewing_key`; same contract as the WASM `scan_notes`.
+#[napi]
+pub fn scan(outputs_json: String, viewing_key: String) -> napi::Result<String> {
+    note::scan_notes_json(&outputs_json, &viewing_key).map_err(to_napi_error)
+}
+
+fn to_napi_error(error: anyhow::Error) -> napi::Error {
+    napi::Error::from_reason(format!("{:#}", error))
+}
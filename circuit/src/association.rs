@@ -0,0 +1,54 @@
+use crate::{
+    constants::{MERKLE_TREE_LEVEL, ZERO_VALUE},
+    merkle_tree::{Path, SparseMerkleTree},
+    poseidon_opt::{fr_from_str, hasher_t3},
+};
+use anyhow::anyhow;
+use ark_bn254::Fr;
+
+/// Approved subset of deposit commitments (privacy-pools style).
+///
+/// Built with the same tree layout as the pool so its paths can be fed to
+/// [`TransactionCircuit::with_association`](crate::circuit::TransactionCircuit::with_association).
+#[derive(Debug, Clone)]
+pub struct AssociationSet {
+    tree: SparseMerkleTree<MERKLE_TREE_LEVEL>,
+}
+
+impl AssociationSet {
+    /// Builds the set from approved commitments, in the order they are given.
+    pub fn new(commitments: &[Fr]) -> anyhow::Result<Self> {
+        let hasher = hasher_t3();
+        let mut tree = SparseMerkleTree::new_empty(hasher, &fr_from_str(ZERO_VALUE));
+
+        for pair in commitments.chunks(2) {
+            match pair {
+                [left, right] => tree.insert_pair(*left, *right, hasher)?,
+                [leaf] => tree.insert(*leaf, hasher)?,
+                _ => unreachable!("chunks(2) yields one or two leaves"),
+            }
+        }
+
+        Ok(Self { tree })
+    }
+
+    /// Returns the association root to publish as a public input.
+    pub fn root(&self) -> Fr {
+        self.tree.root()
+    }
+
+    /// Returns the membership path of `commitment`.
+    ///
+    /// # Errors
+    /// Returns error if `commitment` is not in the set.
+    pub fn proof(&self, commitment: &Fr) -> anyhow::Result<Path<MERKLE_TREE_LEVEL>> {
+        let index = self
+            .tree
+            .leaves()
+            .iter()
+            .position(|leaf| leaf == commitment)
+            .ok_or_else(|| anyhow!("Commitment {} is not in the association set", commitment))?;
+
+        self.tree.generate_membership_proof(index)
+    }
+}
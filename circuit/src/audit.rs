@@ -0,0 +1,540 @@
+use crate::backend::{Groth16Backend, ProvingBackend};
+use crate::prover::parse_field_element;
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::{Bn254, Fr};
+use ark_ff::AdditiveGroup;
+use ark_groth16::{Proof, VerifyingKey};
+use serde_json::{json, Value};
+use std::fmt;
+
+const PROOF_MODULE: &str = "vortex_proof";
+const PROOF_CONSTRUCTOR: &str = "new";
+const VORTEX_MODULE: &str = "vortex";
+const TRANSACT_WITH_ACCOUNT: &str = "transact_with_account";
+
+/// Argument positions of `vortex_proof::new`.
+const PROOF_ARGUMENTS: usize = 8;
+/// Argument positions of the account and proof in `vortex::transact_with_account`.
+const ACCOUNT_ARGUMENT: usize = 1;
+const ACCOUNT_PROOF_ARGUMENT: usize = 3;
+
+/// Abort code of `vortex_errors::invalid_proof`.
+const INVALID_PROOF_ABORT: u64 = 3;
+
+/// A `vortex_proof::new` call decoded from a transaction's inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofCall {
+    pub vortex: Fr,
+    pub proof_points: Vec<u8>,
+    pub root: Fr,
+    pub public_value: Fr,
+    pub input_nullifiers: [Fr; 2],
+    pub output_commitments: [Fr; 2],
+    /// Account whose hashed secret is bound by `transact_with_account`.
+    pub account: Option<String>,
+}
+
+impl ProofCall {
+    /// Public inputs in the order `vortex_proof::make_public_inputs` builds them.
+    pub fn public_inputs(&self, hashed_secret: Fr) -> Vec<Fr> {
+        vec![
+            self.vortex,
+            self.root,
+            self.public_value,
+            self.input_nullifiers[0],
+            self.input_nullifiers[1],
+            self.output_commitments[0],
+            self.output_commitments[1],
+            hashed_secret,
+        ]
+    }
+
+    /// Verifies the proof off-chain. Points that fail to decode count as a rejection.
+    pub fn verify(&self, vk: &VerifyingKey<Bn254>, hashed_secret: Fr) -> anyhow::Result<bool> {
+        let Ok(proof) = Groth16Backend::deserialize::<Proof<Bn254>>(&self.proof_points) else {
+            return Ok(false);
+        };
+        Groth16Backend::verify(vk, &self.public_inputs(hashed_secret), &proof)
+    }
+}
+
+/// How the on-chain verifier treated a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnChainOutcome {
+    Accepted,
+    RejectedProof,
+    /// Failed for another reason, so the verifier may never have run.
+    Failed(String),
+}
+
+impl OnChainOutcome {
+    /// Reads the outcome from the `effects` of a transaction block response.
+    pub fn from_effects(effects: &Value) -> Self {
+        let status = &effects["status"];
+        if status["status"] == "success" {
+            return Self::Accepted;
+        }
+
+        let error = status["error"]
+            .as_str()
+            .unwrap_or("unknown error")
+            .to_string();
+        if abort_code(&error, VORTEX_MODULE) == Some(INVALID_PROOF_ABORT) {
+            Self::RejectedProof
+        } else {
+            Self::Failed(error)
+        }
+    }
+}
+
+/// Result of re-verifying one proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditStatus {
+    /// Both verifiers agree
+    Consistent { accepted: bool },
+    /// The verifiers disagree on the same proof and inputs
+    Divergent { on_chain: bool, off_chain: bool },
+    /// The transaction failed before or without reaching the verifier
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub digest: String,
+    pub status: AuditStatus,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            AuditStatus::Consistent { accepted: true } => {
+                write!(f, "[OK] {}: accepted on-chain and off-chain", self.digest)
+            }
+            AuditStatus::Consistent { accepted: false } => {
+                write!(f, "[OK] {}: rejected on-chain and off-chain", self.digest)
+            }
+            AuditStatus::Divergent {
+                on_chain,
+                off_chain,
+            } => write!(
+                f,
+                "[DIVERGENT] {}: on-chain {}, off-chain {}",
+                self.digest,
+                verdict(*on_chain),
+                verdict(*off_chain)
+            ),
+            AuditStatus::Skipped(reason) => write!(f, "[SKIP] {}: {}", self.digest, reason),
+        }
+    }
+}
+
+fn verdict(accepted: bool) -> &'static str {
+    if accepted {
+        "accepted"
+    } else {
+        "rejected"
+    }
+}
+
+/// Compares the on-chain outcome with an off-chain verification.
+pub fn compare(on_chain: &OnChainOutcome, off_chain: bool) -> AuditStatus {
+    let on_chain = match on_chain {
+        OnChainOutcome::Accepted => true,
+        OnChainOutcome::RejectedProof => false,
+        OnChainOutcome::Failed(error) => return AuditStatus::Skipped(error.clone()),
+    };
+
+    if on_chain == off_chain {
+        AuditStatus::Consistent { accepted: on_chain }
+    } else {
+        AuditStatus::Divergent {
+            on_chain,
+            off_chain,
+        }
+    }
+}
+
+/// Decodes every `vortex_proof::new` call of `package` in a programmable
+/// transaction, as returned by the JSON-RPC with `showInput`.
+pub fn extract_proof_calls(transaction: &Value, package: &str) -> anyhow::Result<Vec<ProofCall>> {
+    let kind = &transaction["data"]["transaction"];
+    let inputs = kind["inputs"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+    let commands = kind["transactions"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    let mut calls = Vec::new();
+    for (index, command) in commands.iter().enumerate() {
+        let Some(call) = move_call(command, package, PROOF_MODULE, PROOF_CONSTRUCTOR) else {
+            continue;
+        };
+
+        let arguments = call["arguments"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        if arguments.len() != PROOF_ARGUMENTS {
+            bail!(
+                "{PROOF_MODULE}::{PROOF_CONSTRUCTOR} takes {PROOF_ARGUMENTS} arguments, found {}",
+                arguments.len()
+            );
+        }
+        let pure = |position: usize| pure_input(inputs, &arguments[position]);
+
+        let account = commands.iter().find_map(|command| {
+            let call = move_call(command, package, VORTEX_MODULE, TRANSACT_WITH_ACCOUNT)?;
+            let arguments = call["arguments"].as_array()?;
+            (result_index(arguments.get(ACCOUNT_PROOF_ARGUMENT)?) == Some(index))
+                .then(|| object_input(inputs, arguments.get(ACCOUNT_ARGUMENT)?))
+                .flatten()
+        });
+
+        calls.push(ProofCall {
+            vortex: parse_field_element(&address_value(pure(0)?)?)?,
+            proof_points: bytes_value(pure(1)?)?,
+            root: parse_field_element(&number_value(pure(2)?)?)?,
+            public_value: parse_field_element(&number_value(pure(3)?)?)?,
+            input_nullifiers: [
+                parse_field_element(&number_value(pure(4)?)?)?,
+                parse_field_element(&number_value(pure(5)?)?)?,
+            ],
+            output_commitments: [
+                parse_field_element(&number_value(pure(6)?)?)?,
+                parse_field_element(&number_value(pure(7)?)?)?,
+            ],
+            account,
+        });
+    }
+
+    Ok(calls)
+}
+
+fn move_call<'a>(
+    command: &'a Value,
+    package: &str,
+    module: &str,
+    function: &str,
+) -> Option<&'a Value> {
+    let call = &command["MoveCall"];
+    (same_address(&call["package"], package)
+        && call["module"] == module
+        && call["function"] == function)
+        .then_some(call)
+}
+
+fn same_address(value: &Value, address: &str) -> bool {
+    let normalize = |s: &str| {
+        s.trim_start_matches("0x")
+            .trim_start_matches('0')
+            .to_lowercase()
+    };
+    value
+        .as_str()
+        .is_some_and(|value| normalize(value) == normalize(address))
+}
+
+fn result_index(argument: &Value) -> Option<usize> {
+    argument["Result"]
+        .as_u64()
+        .or_else(|| argument["NestedResult"][0].as_u64())
+        .map(|index| index as usize)
+}
+
+fn input<'a>(inputs: &'a [Value], argument: &Value) -> Option<&'a Value> {
+    inputs.get(argument["Input"].as_u64()? as usize)
+}
+
+fn object_input(inputs: &[Value], argument: &Value) -> Option<String> {
+    let input = input(inputs, argument)?;
+    (input["type"] == "object")
+        .then(|| input["objectId"].as_str().map(str::to_string))
+        .flatten()
+}
+
+fn pure_input<'a>(inputs: &'a [Value], argument: &Value) -> anyhow::Result<&'a Value> {
+    let input = input(inputs, argument)
+        .ok_or_else(|| anyhow!("Proof argument {argument} is not a transaction input"))?;
+    if input["type"] != "pure" {
+        bail!("Proof argument {argument} is not a pure input");
+    }
+    Ok(&input["value"])
+}
+
+fn address_value(value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::String(address) => Ok(address.clone()),
+        Value::Array(_) => Ok(format!("0x{}", hex::encode(bytes_value(value)?))),
+        _ => bail!("Expected an address, found {value}"),
+    }
+}
+
+/// Numbers arrive as decimal strings, or as raw little-endian BCS bytes when
+/// the fullnode could not resolve the argument type.
+fn number_value(value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::String(number) => Ok(number.clone()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Array(_) => {
+            let mut bytes = bytes_value(value)?;
+            bytes.reverse();
+            Ok(format!("0x{}", hex::encode(bytes)))
+        }
+        _ => bail!("Expected a number, found {value}"),
+    }
+}
+
+fn bytes_value(value: &Value) -> anyhow::Result<Vec<u8>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("Expected bytes, found {value}"))?
+        .iter()
+        .map(|byte| {
+            byte.as_u64()
+                .and_then(|byte| u8::try_from(byte).ok())
+                .ok_or_else(|| anyhow!("Invalid byte {byte}"))
+        })
+        .collect()
+}
+
+/// Extracts the abort code from a `MoveAbort` raised in `module`.
+fn abort_code(error: &str, module: &str) -> Option<u64> {
+    if !error.starts_with("MoveAbort") || !error.contains(&format!("Identifier(\"{module}\")")) {
+        return None;
+    }
+    let (_, code) = error.rsplit_once("}, ")?;
+    code.split(')').next()?.trim().parse().ok()
+}
+
+/// Minimal Sui JSON-RPC client for the audit and the tree rebuild.
+#[derive(Debug, Clone)]
+pub struct SuiRpc {
+    pub url: String,
+}
+
+/// One page of transactions calling `vortex_proof::new`.
+#[derive(Debug, Clone)]
+pub struct TransactionPage {
+    pub transactions: Vec<Value>,
+    pub next_cursor: Option<String>,
+}
+
+impl SuiRpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// Lists transactions that build a proof, oldest first.
+    pub fn proof_transactions(
+        &self,
+        package: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<TransactionPage> {
+        let query = json!({
+            "filter": {
+                "MoveFunction": {
+                    "package": package,
+                    "module": PROOF_MODULE,
+                    "function": PROOF_CONSTRUCTOR,
+                }
+            },
+            "options": { "showInput": true, "showEffects": true },
+        });
+        let page = self.call(
+            "suix_queryTransactionBlocks",
+            json!([query, cursor, limit, false]),
+        )?;
+
+        Ok(TransactionPage {
+            transactions: page["data"].as_array().cloned().unwrap_or_default(),
+            next_cursor: page["hasNextPage"]
+                .as_bool()
+                .unwrap_or(false)
+                .then(|| page["nextCursor"].as_str().map(str::to_string))
+                .flatten(),
+        })
+    }
+
+    /// Reads the immutable `hashed_secret` of a `VortexAccount`.
+    pub fn hashed_secret(&self, account: &str) -> anyhow::Result<Fr> {
+        let object = self.call("sui_getObject", json!([account, { "showContent": true }]))?;
+        let hashed_secret = object["data"]["content"]["fields"]["hashed_secret"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Account {account} has no hashed_secret"))?;
+        parse_field_element(hashed_secret)
+    }
+
+    pub fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = ureq::post(&self.url)
+            .header("Content-Type", "application/json")
+            .send(body.to_string())
+            .with_context(|| format!("Failed to call {method} on {}", self.url))?
+            .into_body()
+            .read_to_string()
+            .with_context(|| format!("Failed to read {method} response"))?;
+
+        let response: Value = serde_json::from_str(&response)
+            .with_context(|| format!("Invalid {method} response"))?;
+        match response.get("error") {
+            Some(error) => bail!("{method} failed: {error}"),
+            None => Ok(response["result"].clone()),
+        }
+    }
+}
+
+/// Re-verifies every proof of one transaction against `vk`.
+pub fn audit_transaction(
+    rpc: &SuiRpc,
+    vk: &VerifyingKey<Bn254>,
+    package: &str,
+    transaction: &Value,
+) -> Vec<AuditEntry> {
+    let digest = transaction["digest"]
+        .as_str()
+        .unwrap_or("<unknown>")
+        .to_string();
+    let entry = |status| AuditEntry {
+        digest: digest.clone(),
+        status,
+    };
+
+    let calls = match extract_proof_calls(&transaction["transaction"], package) {
+        Ok(calls) => calls,
+        Err(e) => return vec![entry(AuditStatus::Skipped(format!("{e:#}")))],
+    };
+    let on_chain = OnChainOutcome::from_effects(&transaction["effects"]);
+
+    calls
+        .iter()
+        .map(|call| {
+            let hashed_secret = match &call.account {
+                Some(account) => rpc.hashed_secret(account),
+                None => Ok(Fr::ZERO),
+            };
+            let off_chain = hashed_secret.and_then(|secret| call.verify(vk, secret));
+            entry(match off_chain {
+                Ok(off_chain) => compare(&on_chain, off_chain),
+                Err(e) => AuditStatus::Skipped(format!("{e:#}")),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE: &str = "0x00ab";
+
+    fn pure(value_type: &str, value: Value) -> Value {
+        json!({ "type": "pure", "valueType": value_type, "value": value })
+    }
+
+    fn transaction(function: &str, proof_argument: Value) -> Value {
+        json!({
+            "data": { "transaction": {
+                "kind": "ProgrammableTransaction",
+                "inputs": [
+                    pure("address", json!("0x05")),
+                    pure("vector<u8>", json!([1, 2, 3])),
+                    pure("u256", json!("7")),
+                    pure("u256", json!("8")),
+                    pure("u256", json!("9")),
+                    // Unresolved type: raw little-endian BCS
+                    pure("", json!([10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                                    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])),
+                    pure("u256", json!("11")),
+                    pure("u256", json!("12")),
+                    { "type": "object", "objectType": "sharedObject", "objectId": "0xacc" },
+                ],
+                "transactions": [
+                    { "MoveCall": {
+                        "package": "0xab",
+                        "module": "vortex_proof",
+                        "function": "new",
+                        "arguments": (0..8).map(|i| json!({ "Input": i })).collect::<Vec<_>>(),
+                    }},
+                    { "MoveCall": {
+                        "package": PACKAGE,
+                        "module": "vortex",
+                        "function": function,
+                        "arguments": [{ "Input": 9 }, { "Input": 8 }, { "Input": 10 },
+                                      proof_argument, { "Input": 11 }],
+                    }},
+                ],
+            }}
+        })
+    }
+
+    #[test]
+    fn test_extract_proof_calls() {
+        let calls = extract_proof_calls(
+            &transaction("transact_with_account", json!({ "Result": 0 })),
+            PACKAGE,
+        )
+        .unwrap();
+        assert_eq!(calls.len(), 1);
+
+        let call = &calls[0];
+        assert_eq!(call.vortex, Fr::from(5u64));
+        assert_eq!(call.proof_points, vec![1, 2, 3]);
+        assert_eq!(call.root, Fr::from(7u64));
+        assert_eq!(call.public_value, Fr::from(8u64));
+        assert_eq!(call.input_nullifiers, [Fr::from(9u64), Fr::from(10u64)]);
+        assert_eq!(call.output_commitments, [Fr::from(11u64), Fr::from(12u64)]);
+        assert_eq!(call.account.as_deref(), Some("0xacc"));
+
+        let calls =
+            extract_proof_calls(&transaction("transact", json!({ "Result": 0 })), PACKAGE).unwrap();
+        assert_eq!(calls[0].account, None);
+
+        assert!(
+            extract_proof_calls(&transaction("transact", json!({})), "0xcd")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_compare_outcomes() {
+        let invalid_proof = json!({ "status": { "status": "failure", "error":
+            "MoveAbort(MoveLocation { module: ModuleId { address: ab, name: Identifier(\"vortex\") }, \
+             function: 7, instruction: 63, function_name: Some(\"process_transaction\") }, 3) in command 1" } });
+        let unknown_root = json!({ "status": { "status": "failure", "error":
+            "MoveAbort(MoveLocation { module: ModuleId { address: ab, name: Identifier(\"vortex\") }, \
+             function: 7, instruction: 12, function_name: Some(\"process_transaction\") }, 2) in command 1" } });
+
+        let accepted = OnChainOutcome::from_effects(&json!({ "status": { "status": "success" } }));
+        let rejected = OnChainOutcome::from_effects(&invalid_proof);
+        assert_eq!(rejected, OnChainOutcome::RejectedProof);
+
+        assert_eq!(
+            compare(&accepted, true),
+            AuditStatus::Consistent { accepted: true }
+        );
+        assert_eq!(
+            compare(&rejected, false),
+            AuditStatus::Consistent { accepted: false }
+        );
+        assert_eq!(
+            compare(&accepted, false),
+            AuditStatus::Divergent {
+                on_chain: true,
+                off_chain: false
+            }
+        );
+        assert_eq!(
+            compare(&rejected, true),
+            AuditStatus::Divergent {
+                on_chain: false,
+                off_chain: true
+            }
+        );
+        assert!(matches!(
+            compare(&OnChainOutcome::from_effects(&unknown_root), true),
+            AuditStatus::Skipped(_)
+        ));
+    }
+}
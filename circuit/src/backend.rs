@@ -0,0 +1,141 @@
+use anyhow::anyhow;
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::snark::SNARK;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand_core::{CryptoRng, RngCore};
+
+/// Proof system the transaction circuits are proven with.
+///
+/// Keys and proofs use the compressed canonical encoding, which is what
+/// `keygen` writes and the Move verifier reads for Groth16.
+pub trait ProvingBackend {
+    type ProvingKey: CanonicalSerialize + CanonicalDeserialize;
+    type VerifyingKey: CanonicalSerialize + CanonicalDeserialize;
+    type Proof: CanonicalSerialize + CanonicalDeserialize;
+
+    /// Generates keys for the constraint system of `circuit`. Universal-setup
+    /// backends derive their circuit-specific index here as well.
+    fn setup<C, R>(
+        circuit: C,
+        rng: &mut R,
+    ) -> anyhow::Result<(Self::ProvingKey, Self::VerifyingKey)>
+    where
+        C: ConstraintSynthesizer<Fr>,
+        R: RngCore + CryptoRng;
+
+    fn prove<C, R>(pk: &Self::ProvingKey, circuit: C, rng: &mut R) -> anyhow::Result<Self::Proof>
+    where
+        C: ConstraintSynthesizer<Fr>,
+        R: RngCore + CryptoRng;
+
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Fr],
+        proof: &Self::Proof,
+    ) -> anyhow::Result<bool>;
+
+    fn serialize<T: CanonicalSerialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        value
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| anyhow!("Failed to serialize: {}", e))?;
+        Ok(bytes)
+    }
+
+    fn deserialize<T: CanonicalDeserialize>(bytes: &[u8]) -> anyhow::Result<T> {
+        T::deserialize_compressed(bytes).map_err(|e| anyhow!("Failed to deserialize: {}", e))
+    }
+}
+
+/// Groth16 over BN254, the deployed backend.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Groth16Backend;
+
+impl ProvingBackend for Groth16Backend {
+    type ProvingKey = ProvingKey<Bn254>;
+    type VerifyingKey = VerifyingKey<Bn254>;
+    type Proof = Proof<Bn254>;
+
+    fn setup<C, R>(
+        circuit: C,
+        rng: &mut R,
+    ) -> anyhow::Result<(Self::ProvingKey, Self::VerifyingKey)>
+    where
+        C: ConstraintSynthesizer<Fr>,
+        R: RngCore + CryptoRng,
+    {
+        let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit, rng)
+            .map_err(|e| anyhow!("Failed to generate keys: {}", e))?;
+        let vk = pk.vk.clone();
+        Ok((pk, vk))
+    }
+
+    fn prove<C, R>(pk: &Self::ProvingKey, circuit: C, rng: &mut R) -> anyhow::Result<Self::Proof>
+    where
+        C: ConstraintSynthesizer<Fr>,
+        R: RngCore + CryptoRng,
+    {
+        Groth16::<Bn254>::prove(pk, circuit, rng)
+            .map_err(|e| anyhow!("Failed to generate proof: {}", e))
+    }
+
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Fr],
+        proof: &Self::Proof,
+    ) -> anyhow::Result<bool> {
+        let pvk = ark_groth16::prepare_verifying_key(vk);
+        Groth16::<Bn254>::verify_proof(&pvk, proof, public_inputs)
+            .map_err(|e| anyhow!("Verify failed (inputs={}): {}", public_inputs.len(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_r1cs_std::{
+        fields::fp::FpVar,
+        prelude::{AllocVar, EqGadget},
+    };
+    use ark_relations::r1cs::{self, ConstraintSystemRef};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    /// Knowledge of a square root of the public input
+    #[derive(Clone)]
+    struct Square {
+        root: Fr,
+        square: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> r1cs::Result<()> {
+            let square = FpVar::new_input(cs.clone(), || Ok(self.square))?;
+            let root = FpVar::new_witness(cs, || Ok(self.root))?;
+            (&root * &root).enforce_equal(&square)
+        }
+    }
+
+    fn roundtrip<B: ProvingBackend>() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let circuit = Square {
+            root: Fr::from(3u64),
+            square: Fr::from(9u64),
+        };
+
+        let (pk, vk) = B::setup(circuit.clone(), &mut rng).unwrap();
+        let proof = B::prove(&pk, circuit, &mut rng).unwrap();
+
+        let proof: B::Proof = B::deserialize(&B::serialize(&proof).unwrap()).unwrap();
+        let vk: B::VerifyingKey = B::deserialize(&B::serialize(&vk).unwrap()).unwrap();
+        assert!(B::verify(&vk, &[Fr::from(9u64)], &proof).unwrap());
+        assert!(!B::verify(&vk, &[Fr::from(10u64)], &proof).unwrap());
+    }
+
+    #[test]
+    fn test_groth16_backend_roundtrip() {
+        roundtrip::<Groth16Backend>();
+    }
+}
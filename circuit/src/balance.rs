@@ -0,0 +1,280 @@
+use crate::{
+    circuit::{
+        alloc_witnesses, enforce_range_check, note_commitment, note_commitment_var, note_nullifier,
+        note_nullifier_var, CircuitVersion, HashDomain,
+    },
+    constants::MERKLE_TREE_LEVEL,
+    merkle_tree::{Path, PathVar},
+    poseidon_opt::{hash1, hash2, PoseidonOptimizedVar},
+};
+use ark_bn254::Fr;
+use ark_ff::AdditiveGroup;
+use ark_r1cs_std::{
+    fields::fp::FpVar,
+    prelude::{AllocVar, Boolean, EqGadget, FieldVar},
+};
+use ark_relations::{
+    ns,
+    r1cs::{self, ConstraintSynthesizer, ConstraintSystemRef},
+};
+use std::ops::Not;
+
+/// Unspent note owned by the attesting wallet.
+#[derive(Debug, Clone, Copy)]
+pub struct OwnedNote {
+    pub amount: Fr,
+    pub blinding: Fr,
+    pub path_index: Fr,
+    pub path: Path<MERKLE_TREE_LEVEL>,
+}
+
+impl OwnedNote {
+    /// Zero-amount padding note, skipped by the Merkle check.
+    pub fn padding(blinding: Fr) -> Self {
+        Self {
+            amount: Fr::ZERO,
+            blinding,
+            path_index: Fr::ZERO,
+            path: Path::empty(),
+        }
+    }
+}
+
+/// Attests that a balance commitment opens to the sum of up to `N` notes.
+///
+/// Public inputs: `vortex`, `root`, `balance_commitment` and the `N` note
+/// nullifiers. The commitment is `Poseidon2(total, salt)`, so the dashboard
+/// shows it and only parties given `salt` learn the total. The verifier checks
+/// the nullifiers against the pool's spent set to confirm the notes are still
+/// unspent; it learns when they are later spent, but never amounts, keys or
+/// which leaves they are.
+#[derive(Debug, Clone)]
+pub struct BalanceCircuit<const N: usize> {
+    pub version: CircuitVersion,
+
+    // Public inputs
+    pub vortex: Fr,
+    pub root: Fr,
+    pub balance_commitment: Fr,
+    pub nullifiers: [Fr; N],
+
+    // Private inputs
+    pub private_key: Fr,
+    pub salt: Fr,
+    pub notes: [OwnedNote; N],
+}
+
+impl<const N: usize> BalanceCircuit<N> {
+    /// Computes the nullifiers and balance commitment of `notes`.
+    pub fn new(
+        version: CircuitVersion,
+        vortex: Fr,
+        root: Fr,
+        private_key: Fr,
+        salt: Fr,
+        notes: [OwnedNote; N],
+    ) -> Self {
+        let public_key = hash1(&private_key);
+
+        let nullifiers = notes.map(|note| {
+            let commitment = note_commitment(
+                version,
+                vortex,
+                None,
+                note.amount,
+                public_key,
+                note.blinding,
+            );
+            note_nullifier(version, private_key, commitment, note.path_index)
+        });
+
+        let total = notes.iter().map(|note| note.amount).sum();
+
+        Self {
+            version,
+            vortex,
+            root,
+            balance_commitment: hash2(&total, &salt),
+            nullifiers,
+            private_key,
+            salt,
+            notes,
+        }
+    }
+
+    /// Returns public inputs in the order they are allocated in `generate_constraints()`.
+    pub fn get_public_inputs(&self) -> Vec<Fr> {
+        let mut inputs = Vec::with_capacity(N + 3);
+        inputs.extend([self.vortex, self.root, self.balance_commitment]);
+        inputs.extend(self.nullifiers);
+        inputs
+    }
+}
+
+impl<const N: usize> ConstraintSynthesizer<Fr> for BalanceCircuit<N> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> r1cs::Result<()> {
+        let commitment_domain = self.version.domain_tag(HashDomain::Commitment);
+
+        // ============================================
+        // ALLOCATE PUBLIC INPUTS
+        // ============================================
+        let vortex = FpVar::new_input(ns!(cs, "vortex"), || Ok(self.vortex))?;
+        let root = FpVar::new_input(ns!(cs, "root"), || Ok(self.root))?;
+        let balance_commitment =
+            FpVar::new_input(
+                ns!(cs, "balance_commitment"),
+                || Ok(self.balance_commitment),
+            )?;
+        let nullifiers = self
+            .nullifiers
+            .iter()
+            .map(|nullifier| FpVar::new_input(ns!(cs, "nullifier"), || Ok(*nullifier)))
+            .collect::<r1cs::Result<Vec<_>>>()?;
+
+        // ============================================
+        // ALLOCATE PRIVATE WITNESS INPUTS
+        // ============================================
+        let private_key = FpVar::new_witness(ns!(cs, "private_key"), || Ok(self.private_key))?;
+        let salt = FpVar::new_witness(ns!(cs, "salt"), || Ok(self.salt))?;
+        let amounts = alloc_witnesses(&cs, &self.notes.map(|note| note.amount))?;
+        let blindings = alloc_witnesses(&cs, &self.notes.map(|note| note.blinding))?;
+        let path_indices = alloc_witnesses(&cs, &self.notes.map(|note| note.path_index))?;
+        let paths = self
+            .notes
+            .iter()
+            .map(|note| PathVar::new_witness(ns!(cs, "merkle_path"), || Ok(note.path)))
+            .collect::<r1cs::Result<Vec<_>>>()?;
+
+        let hasher_t2 = PoseidonOptimizedVar::new_t2();
+        let hasher_t3 = PoseidonOptimizedVar::new_t3();
+        let hasher_t5 = PoseidonOptimizedVar::new_t5();
+        let zero = FpVar::<Fr>::zero();
+
+        // Every note is owned by the same key
+        let public_key = hasher_t2.hash1(&private_key)?;
+
+        // ============================================
+        // VERIFY NOTES
+        // ============================================
+        let mut total = FpVar::<Fr>::zero();
+
+        for i in 0..N {
+            let commitment = note_commitment_var(
+                &hasher_t5,
+                commitment_domain,
+                &vortex,
+                None,
+                &amounts[i],
+                &public_key,
+                &blindings[i],
+            )?;
+
+            // SECURITY: Public nullifiers let the verifier check the notes are unspent
+            note_nullifier_var(self.version, &private_key, &commitment, &path_indices[i])?
+                .enforce_equal(&nullifiers[i])?;
+
+            let amount_is_zero = amounts[i].is_eq(&zero)?;
+            enforce_range_check(self.version, &amounts[i], Some(&amount_is_zero))?;
+            let amount_is_non_zero = amount_is_zero.not();
+
+            let membership = if self.version.binds_path_indices() {
+                let (path_root, path_index) =
+                    paths[i].root_hash_and_index(&commitment, &hasher_t3)?;
+                path_index.conditional_enforce_equal(&path_indices[i], &amount_is_non_zero)?;
+                root.is_eq(&path_root)?
+            } else {
+                paths[i].check_membership(&root, &commitment, &hasher_t3)?
+            };
+            membership.conditional_enforce_equal(&Boolean::constant(true), &amount_is_non_zero)?;
+
+            total += &amounts[i];
+        }
+
+        // SECURITY: The same note cannot be counted twice
+        for i in 0..N {
+            for j in (i + 1)..N {
+                nullifiers[i].enforce_not_equal(&nullifiers[j])?;
+            }
+        }
+
+        // ============================================
+        // VERIFY BALANCE COMMITMENT
+        // ============================================
+        hasher_t3
+            .hash2(&total, &salt)?
+            .enforce_equal(&balance_commitment)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_balance_attestation() {
+        let hasher = PoseidonOptimized::new_t3();
+        let vortex = Fr::from(7u64);
+        let private_key = Fr::from(12345u64);
+        let public_key = hash1(&private_key);
+
+        let amounts = [Fr::from(30u64), Fr::from(12u64)];
+        let blindings = [Fr::from(1u64), Fr::from(2u64)];
+        let commitments = [0, 1].map(|i| {
+            note_commitment(
+                CircuitVersion::V1,
+                vortex,
+                None,
+                amounts[i],
+                public_key,
+                blindings[i],
+            )
+        });
+
+        let mut tree =
+            SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+        tree.insert_pair(commitments[0], commitments[1], &hasher)
+            .unwrap();
+
+        let notes = [0, 1].map(|i| OwnedNote {
+            amount: amounts[i],
+            blinding: blindings[i],
+            path_index: Fr::from(i as u64),
+            path: tree.generate_membership_proof(i).unwrap(),
+        });
+
+        let salt = Fr::from(99u64);
+        let circuit = BalanceCircuit::new(
+            CircuitVersion::V1,
+            vortex,
+            tree.root(),
+            private_key,
+            salt,
+            [notes[0], notes[1], OwnedNote::padding(Fr::from(3u64))],
+        );
+        assert_eq!(circuit.balance_commitment, hash2(&Fr::from(42u64), &salt));
+        assert_eq!(circuit.get_public_inputs().len(), 6);
+
+        let satisfied = |circuit: BalanceCircuit<3>| {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            circuit.generate_constraints(cs.clone()).unwrap();
+            cs.is_satisfied().unwrap()
+        };
+        assert!(satisfied(circuit.clone()));
+
+        // Overstating the balance is rejected
+        let mut inflated = circuit.clone();
+        inflated.balance_commitment = hash2(&Fr::from(43u64), &salt);
+        assert!(!satisfied(inflated));
+
+        // Notes outside the pool tree cannot be counted
+        let mut foreign = circuit;
+        foreign.root = Fr::from(1u64);
+        assert!(!satisfied(foreign));
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{bail, Context};
+use std::fs;
+use vortex::audit::{audit_transaction, AuditStatus, SuiRpc};
+use vortex::prover::verifying_key_from_bytes;
+
+const DEFAULT_VK_PATH: &str = "keys/verification_key.bin";
+const PAGE_SIZE: usize = 50;
+
+const USAGE: &str = "Usage: vortex-audit --rpc <url> --package <id> [options]
+
+Re-verifies the Groth16 proofs of historical transact transactions against the
+registered verifying key and flags any transaction where the on-chain verifier
+and this one disagree.
+
+Options:
+  --rpc <url>        Sui fullnode JSON-RPC endpoint
+  --package <id>     Vortex package ID
+  --vk <path>        Compressed verifying key (default: keys/verification_key.bin)
+  --cursor <digest>  Resume after this transaction
+  --max <n>          Stop after auditing n transactions";
+
+struct Args {
+    rpc: String,
+    package: String,
+    vk: String,
+    cursor: Option<String>,
+    max: Option<usize>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut rpc = None;
+    let mut package = None;
+    let mut vk = DEFAULT_VK_PATH.to_string();
+    let mut cursor = None;
+    let mut max = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().with_context(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--rpc" => rpc = Some(value()?),
+            "--package" => package = Some(value()?),
+            "--vk" => vk = value()?,
+            "--cursor" => cursor = Some(value()?),
+            "--max" => max = Some(value()?.parse().context("--max must be a number")?),
+            _ => bail!("Unknown argument {flag}\n\n{USAGE}"),
+        }
+    }
+
+    Ok(Args {
+        rpc: rpc.with_context(|| format!("--rpc is required\n\n{USAGE}"))?,
+        package: package.with_context(|| format!("--package is required\n\n{USAGE}"))?,
+        vk,
+        cursor,
+        max,
+    })
+}
+
+pub fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    let vk_bytes = fs::read(&args.vk).with_context(|| format!("Failed to read {}", args.vk))?;
+    let vk = verifying_key_from_bytes(&vk_bytes)?;
+    let rpc = SuiRpc::new(&args.rpc);
+
+    let mut cursor = args.cursor;
+    let mut audited = 0;
+    let mut divergent = 0;
+    let mut skipped = 0;
+
+    'pages: loop {
+        let page = rpc.proof_transactions(&args.package, cursor.as_deref(), PAGE_SIZE)?;
+
+        for transaction in &page.transactions {
+            if args.max.is_some_and(|max| audited >= max) {
+                break 'pages;
+            }
+            audited += 1;
+
+            for entry in audit_transaction(&rpc, &vk, &args.package, transaction) {
+                match entry.status {
+                    AuditStatus::Divergent { .. } => divergent += 1,
+                    AuditStatus::Skipped(_) => skipped += 1,
+                    AuditStatus::Consistent { .. } => {}
+                }
+                println!("{entry}");
+            }
+            cursor = transaction["digest"].as_str().map(str::to_string);
+        }
+
+        if page.next_cursor.is_none() {
+            break;
+        }
+    }
+
+    println!("Audited {audited} transactions: {divergent} divergent, {skipped} skipped");
+    if let Some(cursor) = cursor {
+        println!("Resume with --cursor {cursor}");
+    }
+
+    if divergent > 0 {
+        bail!("{divergent} proofs were judged differently by the on-chain verifier");
+    }
+    Ok(())
+}
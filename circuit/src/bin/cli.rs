@@ -0,0 +1,518 @@
+use anyhow::{bail, Context};
+use ark_bn254::Bn254;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use vortex::backend::{Groth16Backend, ProvingBackend};
+use vortex::circuit::{CircuitDescriptor, CircuitVersion};
+use vortex::deposit::{build_split_deposit, DepositNote, Payee};
+use vortex::field::FieldEncoding;
+use vortex::note::EncryptionKey;
+use vortex::offline::{build_withdrawal, OfflineBundle};
+use vortex::output::{Envelope, OutputFormat};
+use vortex::prover::{parse_field_element, prove_circuit, ProofOutput, ProverRng};
+
+#[cfg(feature = "fetch")]
+const PAGE_SIZE: usize = 50;
+
+const USAGE: &str = "Usage: vortex-cli <command> [options]
+
+Cold-storage withdrawals: export the pool's commitments on an online machine,
+then prove the withdrawal on an air-gapped one that never touches the network.
+
+Field elements are read as decimal, 0x-hex or [b0, b1, ...] big-endian bytes.
+--encoding hex|dec picks how every command prints them (default dec); proofs
+keep the encoding the Sui contract expects.
+
+--output json prints every result and error as a JSON envelope of
+{ version, status, data, warnings } instead of text.
+
+Commands:
+  export-bundle --coin-type <type> (--api <url> | --rpc <url> --package <id>) --out <file>
+      Writes the pool's commitments and root to <file>. Needs the fetch feature.
+
+  prove --offline --bundle <file> --note <file> [options]
+      Proves a withdrawal of the vortex-note-v1 note in <file> against the
+      bundle and prints the proof and the change note, if any, as JSON.
+
+      --proving-key <file>   Proving key to use instead of the key cache
+      --descriptor <file>    Check the key and circuit against this descriptor.json
+      --amount <n>           Withdraw only <n>; the rest goes to a new change note
+      --out <file>           Write the JSON to <file> instead of stdout
+
+  deposit-split --vortex <id> --root <root> --amount <n> --payee-key <key>
+                --payee-encryption-key <hex> --payee-amount <n> [options]
+      Proves a deposit of <n> that pays --payee-amount to the payee's public key
+      and keeps the rest as a new note, and prints the proof, the note and the
+      payee's output with its encrypted_output as JSON.
+
+      --proving-key <file>   Proving key to use instead of the key cache
+      --descriptor <file>    Check the key and circuit against this descriptor.json
+      --out <file>           Write the JSON to <file> instead of stdout
+
+  verify-onchain-format --proof <file> --verifying-key <file>
+      Re-verifies a proof written by prove after encoding it exactly as the
+      Sui contract receives it, to catch byte layout mismatches before
+      submitting. Fails if it does not verify.
+
+  tree diff --against api --api <url> (--bundle <file> | --snapshot <file>) [--coin-type <type>]
+      Compares the local tree, from an exported bundle or a tree snapshot,
+      with the roots the API recorded at the leaves both hold. On a mismatch
+      it bisects the leaf range and fails naming the first divergent pair.
+      --coin-type defaults to the bundle's. Needs the fetch feature.
+
+Without --proving-key, prove and deposit-split load the published key from the
+key cache (needs the fetch feature), downloading it from VORTEX_KEYS_MIRROR
+first if it is missing. With --offline only the cache is read; fill it with
+`keys fetch` on an online machine. VORTEX_KEYS_CACHE_DIR and
+VORTEX_CEREMONY_HASH apply as for `keys`.";
+
+#[derive(Default)]
+struct Flags {
+    values: Vec<(String, String)>,
+    offline: bool,
+    output: OutputFormat,
+    encoding: FieldEncoding,
+}
+
+impl Flags {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut flags = Self::default();
+        while let Some(flag) = args.next() {
+            if flag == "--offline" {
+                flags.offline = true;
+                continue;
+            }
+            if !flag.starts_with("--") {
+                bail!("Unexpected argument {flag}\n\n{USAGE}");
+            }
+            let value = args
+                .next()
+                .with_context(|| format!("{flag} needs a value"))?;
+            if flag == "--output" {
+                flags.output = value.parse()?;
+                continue;
+            }
+            if flag == "--encoding" {
+                flags.encoding = value.parse()?;
+                continue;
+            }
+            flags.values.push((flag, value));
+        }
+        Ok(flags)
+    }
+
+    fn get(&self, flag: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(name, _)| name == flag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn required(&self, flag: &str) -> anyhow::Result<&str> {
+        self.get(flag)
+            .with_context(|| format!("{flag} is required\n\n{USAGE}"))
+    }
+
+    /// Prints `text`, if any, and the warnings to stderr, or the envelope.
+    fn emit(&self, text: Option<&str>, envelope: Envelope<impl Serialize>) -> anyhow::Result<()> {
+        match self.output {
+            OutputFormat::Text => {
+                for warning in &envelope.warnings {
+                    eprintln!("Warning: {warning}");
+                }
+                if let Some(text) = text {
+                    println!("{text}");
+                }
+            }
+            OutputFormat::Json => println!("{}", envelope.to_json()?),
+        }
+        Ok(())
+    }
+
+    fn only(&self, allowed: &[&str]) -> anyhow::Result<()> {
+        match self
+            .values
+            .iter()
+            .find(|(name, _)| !allowed.contains(&name.as_str()))
+        {
+            Some((name, _)) => bail!("Unknown argument {name}\n\n{USAGE}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+fn export_bundle(flags: &Flags) -> anyhow::Result<()> {
+    use vortex::audit::SuiRpc;
+    use vortex::tree_state::{fetch_commitments_from_api, fetch_commitments_from_rpc};
+
+    flags.only(&["--coin-type", "--api", "--rpc", "--package", "--out"])?;
+    let coin_type = flags.required("--coin-type")?;
+    let commitments = match (flags.get("--api"), flags.get("--rpc")) {
+        (Some(url), None) => fetch_commitments_from_api(url, coin_type, PAGE_SIZE)?,
+        (None, Some(url)) => fetch_commitments_from_rpc(
+            &SuiRpc::new(url),
+            flags.required("--package")?,
+            coin_type,
+            PAGE_SIZE,
+        )?,
+        _ => bail!("Exactly one of --api and --rpc is required\n\n{USAGE}"),
+    };
+
+    let bundle = OfflineBundle::new(coin_type, commitments)?;
+    let out = flags.required("--out")?;
+    fs::write(out, serde_json::to_string_pretty(&bundle)?)
+        .with_context(|| format!("Failed to write {out}"))?;
+    let root = flags.encoding.encode(&parse_field_element(&bundle.root)?);
+    flags.emit(
+        Some(&format!(
+            "Wrote {} commitments with root {} to {}",
+            bundle.commitments.len(),
+            root,
+            out
+        )),
+        Envelope::ok(json!({
+            "coinType": coin_type,
+            "root": root,
+            "commitments": bundle.commitments.len(),
+            "out": out,
+        })),
+    )
+}
+
+#[cfg(not(feature = "fetch"))]
+fn export_bundle(_flags: &Flags) -> anyhow::Result<()> {
+    bail!("export-bundle needs network access; rebuild with --features fetch")
+}
+
+#[cfg(feature = "fetch")]
+fn tree_diff(flags: &Flags) -> anyhow::Result<()> {
+    use vortex::tree_state::{fetch_root_from_api, TreeState};
+    use vortex::tree_sync::{diff_trees, TreeDiff};
+
+    flags.only(&[
+        "--against",
+        "--api",
+        "--bundle",
+        "--snapshot",
+        "--coin-type",
+    ])?;
+    if flags.required("--against")? != "api" {
+        bail!("Only --against api is supported\n\n{USAGE}");
+    }
+    let api = flags.required("--api")?;
+
+    let (tree, bundle_coin_type) = match (flags.get("--bundle"), flags.get("--snapshot")) {
+        (Some(path), None) => {
+            let bundle: OfflineBundle =
+                serde_json::from_str(&read(path)?).context("Invalid bundle")?;
+            (bundle.tree()?, Some(bundle.coin_type))
+        }
+        (None, Some(path)) => (
+            TreeState::from_snapshot(
+                &fs::read(path).with_context(|| format!("Failed to read {path}"))?,
+            )?,
+            None,
+        ),
+        _ => bail!("Exactly one of --bundle and --snapshot is required\n\n{USAGE}"),
+    };
+    let coin_type = match (flags.get("--coin-type"), &bundle_coin_type) {
+        (Some(coin_type), _) => coin_type,
+        (None, Some(coin_type)) => coin_type.as_str(),
+        (None, None) => flags.required("--coin-type")?,
+    };
+
+    let remote_leaf_count = fetch_root_from_api(api, coin_type, 0)?.latest_leaf_count;
+    let diff = diff_trees(&tree, remote_leaf_count, |leaf_count| {
+        Ok(fetch_root_from_api(api, coin_type, leaf_count)?.root)
+    })?;
+
+    match diff {
+        TreeDiff::Matches { leaf_count } => flags.emit(
+            Some(&format!(
+                "Local tree matches the API at {leaf_count} leaves \
+                 (local {}, API {})",
+                tree.len(),
+                remote_leaf_count
+            )),
+            Envelope::ok(json!({
+                "matches": true,
+                "leafCount": leaf_count,
+                "localLeafCount": tree.len(),
+                "apiLeafCount": remote_leaf_count,
+            })),
+        ),
+        TreeDiff::Diverges {
+            agree_until,
+            differ_at,
+            local,
+            remote,
+        } => bail!(
+            "Local tree diverges from the API between leaves {agree_until} and {}: \
+             after {differ_at} leaves the local root is {} and the API's is {}",
+            differ_at - 1,
+            flags.encoding.encode(&local),
+            flags.encoding.encode(&remote)
+        ),
+    }
+}
+
+#[cfg(not(feature = "fetch"))]
+fn tree_diff(_flags: &Flags) -> anyhow::Result<()> {
+    bail!("tree diff needs network access; rebuild with --features fetch")
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProveOutput {
+    proof: ProofOutput,
+    change_note: Option<String>,
+}
+
+fn read(path: &str) -> anyhow::Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))
+}
+
+/// Reads --proving-key, or the cached key of the 2-input `version` circuit.
+fn proving_key(flags: &Flags, version: CircuitVersion) -> anyhow::Result<ProvingKey<Bn254>> {
+    let key_path = match flags.get("--proving-key") {
+        Some(path) => path.into(),
+        None => cached_proving_key(flags, version)?,
+    };
+    Groth16Backend::deserialize(
+        &fs::read(&key_path).with_context(|| format!("Failed to read {}", key_path.display()))?,
+    )
+}
+
+#[cfg(feature = "fetch")]
+fn cached_proving_key(
+    flags: &Flags,
+    version: CircuitVersion,
+) -> anyhow::Result<std::path::PathBuf> {
+    use vortex::keys::{cached_keys, ensure_keys, KeysConfig};
+
+    let config = KeysConfig::from_env()?;
+    let paths = if flags.offline {
+        cached_keys(&config, version).with_context(|| {
+            format!(
+                "No verified {version:?} keys in {}. Run `keys fetch` on an online machine \
+                 and copy the cache, or pass --proving-key",
+                config.version_dir(version).display()
+            )
+        })?
+    } else {
+        ensure_keys(&config, version)?
+    };
+    Ok(paths.proving_key)
+}
+
+#[cfg(not(feature = "fetch"))]
+fn cached_proving_key(
+    _flags: &Flags,
+    _version: CircuitVersion,
+) -> anyhow::Result<std::path::PathBuf> {
+    bail!(
+        "--proving-key is required; rebuild with --features fetch to use the key cache\n\n{USAGE}"
+    )
+}
+
+fn descriptor(flags: &Flags) -> anyhow::Result<Option<CircuitDescriptor>> {
+    flags
+        .get("--descriptor")
+        .map(|path| -> anyhow::Result<CircuitDescriptor> {
+            serde_json::from_str(&read(path)?).context("Invalid descriptor")
+        })
+        .transpose()
+}
+
+/// Writes `output` to --out or stdout, warning when the proving key was not
+/// checked against a `descriptor`.
+fn write_output(
+    flags: &Flags,
+    output: &impl Serialize,
+    descriptor: Option<&CircuitDescriptor>,
+) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(output)?;
+    let (text, envelope) = match flags.get("--out") {
+        Some(out) => {
+            fs::write(out, json).with_context(|| format!("Failed to write {out}"))?;
+            (None, Envelope::ok(json!({ "out": out })))
+        }
+        None => (Some(json), Envelope::ok(serde_json::to_value(output)?)),
+    };
+    let envelope = match descriptor {
+        Some(_) => envelope,
+        None => envelope.with_warning(
+            "The proving key was not checked against a descriptor, pass --descriptor",
+        ),
+    };
+    flags.emit(text.as_deref(), envelope)
+}
+
+fn prove(flags: &Flags) -> anyhow::Result<()> {
+    if !flags.offline {
+        bail!("prove only runs from an exported bundle, pass --offline\n\n{USAGE}");
+    }
+    flags.only(&[
+        "--bundle",
+        "--note",
+        "--proving-key",
+        "--descriptor",
+        "--amount",
+        "--out",
+    ])?;
+
+    let bundle: OfflineBundle =
+        serde_json::from_str(&read(flags.required("--bundle")?)?).context("Invalid bundle")?;
+    let note: DepositNote = read(flags.required("--note")?)?.parse()?;
+    let amount = flags.get("--amount").map(parse_field_element).transpose()?;
+    let descriptor = descriptor(flags)?;
+
+    let withdrawal = build_withdrawal(&bundle, &note, amount, &mut OsRng)?;
+    let pk = proving_key(flags, withdrawal.circuit.version)?;
+    write_output(
+        flags,
+        &ProveOutput {
+            proof: prove_circuit(
+                withdrawal.circuit,
+                &pk,
+                descriptor.as_ref(),
+                ProverRng::default(),
+            )?,
+            change_note: withdrawal.change.map(|change| change.to_string()),
+        },
+        descriptor.as_ref(),
+    )
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PayeeOutput {
+    public_key: String,
+    amount: String,
+    blinding: String,
+    encrypted_output: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DepositSplitOutput {
+    proof: ProofOutput,
+    note: String,
+    payee: PayeeOutput,
+}
+
+fn deposit_split(flags: &Flags) -> anyhow::Result<()> {
+    flags.only(&[
+        "--vortex",
+        "--root",
+        "--amount",
+        "--payee-key",
+        "--payee-encryption-key",
+        "--payee-amount",
+        "--proving-key",
+        "--descriptor",
+        "--out",
+    ])?;
+
+    let encryption_key = flags.required("--payee-encryption-key")?;
+    let payee = Payee {
+        public_key: parse_field_element(flags.required("--payee-key")?)?,
+        encryption_key: EncryptionKey(
+            hex::decode(encryption_key.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .with_context(|| {
+                    format!("--payee-encryption-key must be 32 hex bytes, got {encryption_key}")
+                })?,
+        ),
+        amount: parse_field_element(flags.required("--payee-amount")?)?,
+    };
+    let split = build_split_deposit(
+        parse_field_element(flags.required("--vortex")?)?,
+        parse_field_element(flags.required("--root")?)?,
+        parse_field_element(flags.required("--amount")?)?,
+        &payee,
+        &mut OsRng,
+    )?;
+    let pk = proving_key(flags, split.circuit.version)?;
+    let descriptor = descriptor(flags)?;
+
+    write_output(
+        flags,
+        &DepositSplitOutput {
+            proof: prove_circuit(
+                split.circuit,
+                &pk,
+                descriptor.as_ref(),
+                ProverRng::default(),
+            )?,
+            note: split.note.to_string(),
+            payee: PayeeOutput {
+                public_key: flags.encoding.encode(&split.payee.public_key),
+                amount: flags.encoding.encode(&split.payee.amount),
+                blinding: flags.encoding.encode(&split.payee.blinding),
+                encrypted_output: hex::encode(split.payee_encrypted_output),
+            },
+        },
+        descriptor.as_ref(),
+    )
+}
+
+fn verify_onchain_format(flags: &Flags) -> anyhow::Result<()> {
+    flags.only(&["--proof", "--verifying-key"])?;
+
+    let proof_json = read(flags.required("--proof")?)?;
+    let proof = match serde_json::from_str::<ProveOutput>(&proof_json) {
+        Ok(output) => output.proof,
+        Err(_) => serde_json::from_str::<ProofOutput>(&proof_json).context("Invalid proof")?,
+    };
+    let key_path = flags.required("--verifying-key")?;
+    let vk: VerifyingKey<Bn254> = Groth16Backend::deserialize(
+        &fs::read(key_path).with_context(|| format!("Failed to read {key_path}"))?,
+    )?;
+
+    if !proof.verify_onchain_format(&vk)? {
+        bail!("Proof does not verify in the Sui byte layout");
+    }
+    flags.emit(
+        Some("Proof verifies in the Sui byte layout"),
+        Envelope::ok(json!({ "verified": true })),
+    )
+}
+
+pub fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args
+        .windows(2)
+        .any(|pair| pair[0] == "--output" && pair[1].eq_ignore_ascii_case("json"));
+
+    match run(args) {
+        Err(e) if json => {
+            println!("{}", Envelope::error(&e).to_json()?);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+fn run(args: Vec<String>) -> anyhow::Result<()> {
+    let mut args = args.into_iter();
+    let command = match args.next() {
+        Some(command) if command == "tree" => args.next().map(|sub| format!("tree {sub}")),
+        command => command,
+    };
+    let flags = Flags::parse(args)?;
+
+    match command.as_deref() {
+        Some("export-bundle") => export_bundle(&flags),
+        Some("prove") => prove(&flags),
+        Some("deposit-split") => deposit_split(&flags),
+        Some("verify-onchain-format") => verify_onchain_format(&flags),
+        Some("tree diff") => tree_diff(&flags),
+        _ => bail!("{USAGE}"),
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::{bail, Context};
+use rand_core::OsRng;
+use vortex::deposit::{DepositNote, DepositNoteJson};
+use vortex::field::FieldEncoding;
+use vortex::prover::parse_field_element;
+
+const USAGE: &str = "Usage: deposit --vortex <id> --amount <n>
+
+Generates a deposit note with a random spend key and blinding and prints its
+vortex-note-v1 string, followed by the note, public key and commitment as JSON.
+
+Options:
+  --vortex <id>       Pool the deposit goes to (decimal, 0x-hex or byte array)
+  --amount <n>        Deposited amount
+  --encoding <enc>    Print field elements as hex or dec (default dec)";
+
+pub fn main() -> anyhow::Result<()> {
+    let mut vortex = None;
+    let mut amount = None;
+    let mut encoding = FieldEncoding::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().with_context(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--vortex" => vortex = Some(parse_field_element(&value()?)?),
+            "--amount" => amount = Some(parse_field_element(&value()?)?),
+            "--encoding" => encoding = value()?.parse()?,
+            _ => bail!("Unknown argument {flag}\n\n{USAGE}"),
+        }
+    }
+
+    let note = DepositNote::generate(
+        vortex.with_context(|| format!("--vortex is required\n\n{USAGE}"))?,
+        amount.with_context(|| format!("--amount is required\n\n{USAGE}"))?,
+        &mut OsRng,
+    );
+
+    println!("{note}");
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&DepositNoteJson::new(note, encoding))
+            .context("Failed to serialize note")?
+    );
+    Ok(())
+}
@@ -1,28 +1,266 @@
-use ark_bn254::Bn254;
-use ark_groth16::Groth16;
+use ark_bn254::Fr;
+use ark_ff::AdditiveGroup;
 
 use ark_serialize::CanonicalSerialize;
 use rand_chacha::ChaCha20Rng;
 use rand_core::SeedableRng;
+use serde::Serialize;
 
 use std::fs;
-use std::path::Path;
-use vortex::circuit::TransactionCircuit;
+use std::path::{Path, PathBuf};
+use vortex::backend::{Groth16Backend, ProvingBackend};
+use vortex::circuit::{
+    CircuitKind, CircuitVersion, ExtData, ExtDataHasher, TransactionCircuit, DESCRIPTOR_FILE,
+};
+use vortex::constants::{
+    LARGE_TREE_LEVEL, MEDIUM_TREE_LEVEL, MERKLE_TREE_LEVEL, N_INS, N_INS_1, N_INS_16, N_OUTS,
+    SMALL_TREE_LEVEL, SUPPORTED_TREE_LEVELS,
+};
+use vortex::merkle_tree::Path as MerklePath;
+use vortex::output::{Envelope, OutputFormat};
+
+const FEE_FLAG: &str = "--fee";
+const ASSET_FLAG: &str = "--asset";
+const ASSOCIATION_FLAG: &str = "--association";
+const EXT_DATA_FLAG: &str = "--ext-data";
+const TAGGED_EXT_DATA_FLAG: &str = "--ext-data-tagged";
+const ROOTS_FLAG: &str = "--roots=";
+const LEVEL_FLAG: &str = "--level=";
+const OUTPUT_FLAG: &str = "--output";
+#[cfg(feature = "test-circuit")]
+const TEST_CIRCUIT_FLAG: &str = "--test-circuit";
+
+const FIXED_SEED_WARNING: &str =
+    "Keys come from a fixed-seed setup and are not safe for production; run a trusted setup";
+
+/// What a run wrote, the `data` of `--output json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeneratedKeys {
+    keys_dir: PathBuf,
+    files: Vec<String>,
+}
 
 pub fn main() -> anyhow::Result<()> {
-    println!("Generating Groth16 proving and verifying keys...");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let output = match args.iter().position(|arg| arg == OUTPUT_FLAG) {
+        Some(i) => {
+            let format = args
+                .get(i + 1)
+                .ok_or_else(|| anyhow::anyhow!("{OUTPUT_FLAG} needs a value"))?
+                .parse()?;
+            args.drain(i..=i + 1);
+            format
+        }
+        None => OutputFormat::Text,
+    };
 
-    let circuit = TransactionCircuit::empty();
+    match (generate(&args), output) {
+        (Ok(keys), OutputFormat::Text) => {
+            println!("✅ Keys generated successfully!");
+            println!("  Keys written to {}/", keys.keys_dir.display());
+            for file in &keys.files {
+                println!("    - {file}");
+            }
+            Ok(())
+        }
+        (Ok(keys), OutputFormat::Json) => {
+            let envelope = Envelope::ok(keys).with_warning(FIXED_SEED_WARNING);
+            println!("{}", envelope.to_json()?);
+            Ok(())
+        }
+        (Err(e), OutputFormat::Text) => Err(e),
+        (Err(e), OutputFormat::Json) => {
+            println!("{}", Envelope::error(&e).to_json()?);
+            std::process::exit(1);
+        }
+    }
+}
 
-    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+fn generate(args: &[String]) -> anyhow::Result<GeneratedKeys> {
+    #[cfg(feature = "test-circuit")]
+    if args.iter().any(|arg| arg == TEST_CIRCUIT_FLAG) {
+        return generate_test_keys(Path::new("keys").join("test").as_path());
+    }
+    let with_fee = args.iter().any(|arg| arg == FEE_FLAG);
+    let with_asset = args.iter().any(|arg| arg == ASSET_FLAG);
+    let with_association = args.iter().any(|arg| arg == ASSOCIATION_FLAG);
+    let ext_data_hasher = if args.iter().any(|arg| arg == TAGGED_EXT_DATA_FLAG) {
+        Some(ExtDataHasher::Tagged)
+    } else if args.iter().any(|arg| arg == EXT_DATA_FLAG) {
+        Some(ExtDataHasher::Poseidon)
+    } else {
+        None
+    };
+    let roots: usize = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix(ROOTS_FLAG))
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(1);
+    if roots == 0 {
+        anyhow::bail!("{ROOTS_FLAG}<K> needs at least one root");
+    }
+    let level: usize = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix(LEVEL_FLAG))
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(MERKLE_TREE_LEVEL);
+    let mut positional = args.iter().filter(|arg| {
+        ![
+            FEE_FLAG,
+            ASSET_FLAG,
+            ASSOCIATION_FLAG,
+            EXT_DATA_FLAG,
+            TAGGED_EXT_DATA_FLAG,
+        ]
+        .contains(&arg.as_str())
+            && !arg.starts_with(ROOTS_FLAG)
+            && !arg.starts_with(LEVEL_FLAG)
+    });
+
+    let version: CircuitVersion = positional
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or_default();
+
+    let n_ins: usize = positional
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or(N_INS);
+
+    // Prepare keys directory (v1 keys stay at the root for compatibility)
+    let mut keys_dir = match version {
+        CircuitVersion::V1 => PathBuf::from("keys"),
+        CircuitVersion::V2 => PathBuf::from("keys").join("v2"),
+        CircuitVersion::V3 => PathBuf::from("keys").join("v3"),
+    };
+    if n_ins == N_INS_1 {
+        keys_dir.extend(CircuitKind::Transaction1.key_dir());
+    }
+    if n_ins == N_INS_16 {
+        keys_dir.extend(CircuitKind::Transaction16.key_dir());
+    }
+    if with_asset {
+        keys_dir.push("asset");
+    }
+    if with_association {
+        keys_dir.push("association");
+    }
+    if with_fee {
+        keys_dir.push("fee");
+    }
+    match ext_data_hasher {
+        Some(ExtDataHasher::Poseidon) => keys_dir.push("ext_data"),
+        Some(ExtDataHasher::Tagged) => keys_dir.push("ext_data_tagged"),
+        None => {}
+    }
+    if roots > 1 {
+        keys_dir.push(format!("roots{roots}"));
+    }
+
+    if level != MERKLE_TREE_LEVEL {
+        keys_dir.push(format!("level{level}"));
+    }
 
-    println!("Running setup (this may take several minutes)...");
-    let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit, &mut rng)?;
+    let options = Options {
+        version,
+        with_asset,
+        with_association,
+        with_fee,
+        ext_data_hasher,
+        roots,
+    };
+    match level {
+        SMALL_TREE_LEVEL => generate_leveled::<SMALL_TREE_LEVEL>(n_ins, options, &keys_dir),
+        MEDIUM_TREE_LEVEL => generate_leveled::<MEDIUM_TREE_LEVEL>(n_ins, options, &keys_dir),
+        LARGE_TREE_LEVEL => generate_leveled::<LARGE_TREE_LEVEL>(n_ins, options, &keys_dir),
+        MERKLE_TREE_LEVEL => generate_leveled::<MERKLE_TREE_LEVEL>(n_ins, options, &keys_dir),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported tree level {}. Use one of {:?}",
+            level,
+            SUPPORTED_TREE_LEVELS
+        )),
+    }
+}
 
-    let vk = pk.vk.clone();
+#[derive(Clone, Copy)]
+struct Options {
+    version: CircuitVersion,
+    with_asset: bool,
+    with_association: bool,
+    with_fee: bool,
+    ext_data_hasher: Option<ExtDataHasher>,
+    roots: usize,
+}
+
+fn generate_leveled<const L: usize>(
+    n_ins: usize,
+    options: Options,
+    keys_dir: &Path,
+) -> anyhow::Result<GeneratedKeys> {
+    match n_ins {
+        N_INS => generate_keys(
+            configure(TransactionCircuit::<N_INS, N_OUTS, L>::empty(), options),
+            keys_dir,
+        ),
+        N_INS_1 => generate_keys(
+            configure(TransactionCircuit::<N_INS_1, N_OUTS, L>::empty(), options),
+            keys_dir,
+        ),
+        N_INS_16 => generate_keys(
+            configure(TransactionCircuit::<N_INS_16, N_OUTS, L>::empty(), options),
+            keys_dir,
+        ),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported input count {}. Use: {}, {} or {}",
+            n_ins,
+            N_INS,
+            N_INS_1,
+            N_INS_16
+        )),
+    }
+}
+
+fn configure<const NI: usize, const NO: usize, const L: usize>(
+    circuit: TransactionCircuit<NI, NO, L>,
+    options: Options,
+) -> TransactionCircuit<NI, NO, L> {
+    let mut circuit = circuit.with_version(options.version);
+    if options.with_asset {
+        circuit = circuit.with_asset_id(Fr::ZERO);
+    }
+    if options.with_association {
+        circuit = circuit.with_association(Fr::ZERO, [MerklePath::empty(); NI]);
+    }
+    if options.with_fee {
+        circuit = circuit.with_fee(Fr::ZERO);
+    }
+    if let Some(hasher) = options.ext_data_hasher {
+        circuit = circuit.with_ext_data(ExtData::default(), hasher);
+    }
+    if options.roots > 1 {
+        circuit = circuit.with_root_history(vec![Fr::ZERO; options.roots - 1]);
+    }
+    circuit
+}
+
+fn generate_keys<const NI: usize, const NO: usize, const L: usize>(
+    circuit: TransactionCircuit<NI, NO, L>,
+    keys_dir: &Path,
+) -> anyhow::Result<GeneratedKeys> {
+    eprintln!("Generating Groth16 proving and verifying keys...");
+
+    let descriptor = circuit.descriptor();
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    eprintln!("Running setup (this may take several minutes)...");
+    let (pk, vk) = Groth16Backend::setup(circuit, &mut rng)?;
 
-    // Prepare keys directory
-    let keys_dir = Path::new("keys");
     if !keys_dir.exists() {
         fs::create_dir_all(keys_dir)?;
     }
@@ -46,10 +284,48 @@ pub fn main() -> anyhow::Result<()> {
     fs::write(keys_dir.join("proving_key.bin"), &pk_bytes)?;
     fs::write(keys_dir.join("proving_key.hex"), hex::encode(&pk_bytes))?;
 
-    println!("✅ Keys generated successfully!");
-    println!("  Keys written to ./keys/");
-    println!("    - proving_key.bin / .hex");
-    println!("    - verification_key.bin / .hex");
+    fs::write(
+        keys_dir.join(DESCRIPTOR_FILE),
+        serde_json::to_string_pretty(&descriptor)?,
+    )?;
+
+    Ok(GeneratedKeys {
+        keys_dir: keys_dir.to_path_buf(),
+        files: [
+            "proving_key.bin",
+            "proving_key.hex",
+            "verification_key.bin",
+            "verification_key.hex",
+            DESCRIPTOR_FILE,
+        ]
+        .map(String::from)
+        .to_vec(),
+    })
+}
+
+#[cfg(feature = "test-circuit")]
+fn generate_test_keys(keys_dir: &Path) -> anyhow::Result<GeneratedKeys> {
+    use vortex::test_circuit::{setup, TestCircuit};
+
+    let (pk, vk) = setup()?;
+    fs::create_dir_all(keys_dir)?;
+
+    let mut vk_bytes = Vec::new();
+    vk.serialize_compressed(&mut vk_bytes)?;
+    let mut pk_bytes = Vec::new();
+    pk.serialize_compressed(&mut pk_bytes)?;
+
+    fs::write(keys_dir.join("verification_key.bin"), &vk_bytes)?;
+    fs::write(keys_dir.join("proving_key.bin"), &pk_bytes)?;
+    fs::write(
+        keys_dir.join(DESCRIPTOR_FILE),
+        serde_json::to_string_pretty(&TestCircuit::empty().descriptor())?,
+    )?;
 
-    Ok(())
+    Ok(GeneratedKeys {
+        keys_dir: keys_dir.to_path_buf(),
+        files: ["proving_key.bin", "verification_key.bin", DESCRIPTOR_FILE]
+            .map(String::from)
+            .to_vec(),
+    })
 }
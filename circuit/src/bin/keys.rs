@@ -0,0 +1,70 @@
+use anyhow::{bail, Context};
+use vortex::circuit::{CircuitKind, CircuitVersion};
+use vortex::keys::{doctor, fetch_keys, KeysConfig, MIRROR_URL_ENV};
+
+const USAGE: &str = "Usage: keys <fetch|doctor> [v1|v2] [transaction2|transaction1|transaction16]
+
+  fetch   Download and verify the keys into the cache
+  doctor  Check the mirror and the cached keys without downloading them
+
+Environment:
+  VORTEX_KEYS_MIRROR     Base URL serving <version>[/<kind>]/manifest.json and key files (required)
+  VORTEX_KEYS_CACHE_DIR  Override the cache directory (default: $XDG_CACHE_HOME/vortex)
+  VORTEX_CEREMONY_HASH   Expected SHA-256 of the ceremony verifying key (default: the
+                         published one), or `unpinned` to skip the check";
+
+pub fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    let command = match args.next().as_deref() {
+        Some(command @ ("fetch" | "doctor")) => command.to_string(),
+        _ => bail!(USAGE),
+    };
+
+    let version: CircuitVersion = args
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or_default();
+
+    let kind: CircuitKind = args
+        .next()
+        .map(|arg| arg.parse())
+        .transpose()?
+        .unwrap_or_default();
+
+    std::env::var(MIRROR_URL_ENV)
+        .with_context(|| format!("{MIRROR_URL_ENV} must be set\n\n{USAGE}"))?;
+
+    let config = KeysConfig::from_env()?.with_kind(kind);
+
+    if command == "doctor" {
+        let checks = doctor(&config, version);
+        for check in &checks {
+            match &check.result {
+                Ok(detail) => println!("[PASS] {}: {detail}", check.name),
+                Err(e) => println!("[FAIL] {}: {e:#}", check.name),
+            }
+        }
+
+        let failed = checks.iter().filter(|check| check.result.is_err()).count();
+        if failed > 0 {
+            bail!("{failed} of {} checks failed", checks.len());
+        }
+        println!("All {} checks passed", checks.len());
+        return Ok(());
+    }
+
+    println!(
+        "Fetching {version:?} keys into {}...",
+        config.version_dir(version).display()
+    );
+
+    let paths = fetch_keys(&config, version)?;
+
+    println!("✅ Keys downloaded and verified!");
+    println!("    - {}", paths.proving_key.display());
+    println!("    - {}", paths.verifying_key.display());
+
+    Ok(())
+}
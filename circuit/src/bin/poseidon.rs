@@ -0,0 +1,232 @@
+use anyhow::{bail, ensure, Context};
+use ark_bn254::Fr;
+use ark_ff::{AdditiveGroup, PrimeField, UniformRand};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use vortex::field::FieldEncoding;
+use vortex::poseidon_opt::hasher_for_arity;
+use vortex::prover::parse_field_element;
+
+const USAGE: &str = "Usage: poseidon [options] [<input>...]
+
+Hashes the inputs given as arguments, or else one set of inputs per line of
+stdin, with the circomlib-compatible Poseidon the circuit uses. Inputs are
+decimal or 0x-prefixed hex, separated by spaces or commas; 1 to 5 per hash.
+--json inputs may also be [b0, b1, ...] big-endian byte arrays.
+
+Options:
+  --json             Read a JSON array of input arrays from stdin and print a
+                     JSON array of hashes
+  --encoding <enc>   Print hashes as hex (32-byte, 0x-prefixed) or dec (default)
+  --hex              Same as --encoding hex
+  --domain <value>   Initial capacity element, e.g. a DOMAIN_* constant (default 0)
+  --random <n>       Print n vectors of random inputs and their hashes as JSON
+  --arity <k>        Inputs per random vector (default 2)
+  --seed <n>         Seed of the random vectors (default 0)
+  --check <file>     Recompute the hashes of a JSON vector file, e.g. one
+                     generated with circomlibjs, and fail on any mismatch";
+
+const DEFAULT_ARITY: usize = 2;
+
+/// One entry of a vector file: `{ "inputs": [...], "output": "..." }`.
+#[derive(Serialize, Deserialize)]
+struct Vector {
+    inputs: Vec<String>,
+    output: String,
+}
+
+enum Mode {
+    Hash,
+    Json,
+    Random {
+        count: usize,
+        arity: usize,
+        seed: u64,
+    },
+    Check(String),
+}
+
+struct Args {
+    mode: Mode,
+    encoding: FieldEncoding,
+    domain: Fr,
+    inputs: Vec<String>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut json = false;
+    let mut random = None;
+    let mut arity = DEFAULT_ARITY;
+    let mut seed = 0;
+    let mut check = None;
+    let mut args = Args {
+        mode: Mode::Hash,
+        encoding: FieldEncoding::Decimal,
+        domain: Fr::ZERO,
+        inputs: Vec::new(),
+    };
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || argv.next().with_context(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--json" => json = true,
+            "--hex" => args.encoding = FieldEncoding::Hex,
+            "--encoding" => args.encoding = value()?.parse()?,
+            "--domain" => {
+                args.domain = parse_field_element(&value()?).context("Invalid --domain")?;
+            }
+            "--random" => random = Some(value()?.parse().context("--random must be a number")?),
+            "--arity" => arity = value()?.parse().context("--arity must be a number")?,
+            "--seed" => seed = value()?.parse().context("--seed must be a number")?,
+            "--check" => check = Some(value()?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            flag if flag.starts_with("--") => bail!("Unknown argument {flag}\n\n{USAGE}"),
+            _ => args.inputs.push(arg),
+        }
+    }
+
+    args.mode = match (json, random, check) {
+        (false, None, None) => Mode::Hash,
+        (true, None, None) => Mode::Json,
+        (false, Some(count), None) => Mode::Random { count, arity, seed },
+        (false, None, Some(path)) => Mode::Check(path),
+        _ => bail!("--json, --random and --check are exclusive\n\n{USAGE}"),
+    };
+    if !matches!(args.mode, Mode::Hash) && !args.inputs.is_empty() {
+        bail!("Inputs are only taken as arguments without --json, --random or --check\n\n{USAGE}");
+    }
+    Ok(args)
+}
+
+fn hash(inputs: &[String], domain: Fr) -> anyhow::Result<Fr> {
+    let Some(hasher) = hasher_for_arity(inputs.len()) else {
+        bail!("Expected 1 to 5 inputs, got {}", inputs.len());
+    };
+    let inputs = inputs
+        .iter()
+        .map(|input| parse_field_element(input))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(hasher.hash_with_domain(&inputs, domain))
+}
+
+fn split_line(line: &str) -> Vec<String> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|input| !input.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Inputs of one JSON hash: strings as given, numbers in decimal.
+fn json_inputs(value: &Value) -> anyhow::Result<Vec<String>> {
+    let Value::Array(inputs) = value else {
+        bail!("Expected an array of inputs, got {value}");
+    };
+    inputs
+        .iter()
+        .map(|input| match input {
+            Value::String(s) => Ok(s.clone()),
+            Value::Number(n) => Ok(n.to_string()),
+            _ => bail!("Expected a string or number input, got {input}"),
+        })
+        .collect()
+}
+
+fn random_vectors(
+    count: usize,
+    arity: usize,
+    seed: u64,
+    args: &Args,
+) -> anyhow::Result<Vec<Vector>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| {
+            let inputs = (0..arity)
+                .map(|_| Fr::rand(&mut rng).into_bigint().to_string())
+                .collect::<Vec<_>>();
+            let output = args.encoding.encode(&hash(&inputs, args.domain)?);
+            Ok(Vector { inputs, output })
+        })
+        .collect()
+}
+
+fn check_vectors(path: &str, domain: Fr) -> anyhow::Result<usize> {
+    let file = std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+    let vectors: Vec<Vector> =
+        serde_json::from_str(&file).with_context(|| format!("Failed to parse {path}"))?;
+
+    for (i, vector) in vectors.iter().enumerate() {
+        let expected = parse_field_element(&vector.output)
+            .with_context(|| format!("Vector {i} has an invalid output"))?;
+        let actual = hash(&vector.inputs, domain).with_context(|| format!("Vector {i}"))?;
+        ensure!(
+            actual == expected,
+            "Vector {i} {:?}: expected {}, got {}",
+            vector.inputs,
+            vector.output,
+            actual.into_bigint()
+        );
+    }
+    Ok(vectors.len())
+}
+
+pub fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    match &args.mode {
+        Mode::Hash if !args.inputs.is_empty() => {
+            let hash = hash(&args.inputs, args.domain)?;
+            println!("{}", args.encoding.encode(&hash));
+            return Ok(());
+        }
+        Mode::Random { count, arity, seed } => {
+            let vectors = random_vectors(*count, *arity, *seed, &args)?;
+            println!("{}", serde_json::to_string_pretty(&vectors)?);
+            return Ok(());
+        }
+        Mode::Check(path) => {
+            let count = check_vectors(path, args.domain)?;
+            println!("All {count} vectors match");
+            return Ok(());
+        }
+        Mode::Hash | Mode::Json => {}
+    }
+
+    let mut stdin = String::new();
+    std::io::stdin()
+        .read_to_string(&mut stdin)
+        .context("Failed to read stdin")?;
+
+    if matches!(args.mode, Mode::Json) {
+        let batch: Vec<Value> =
+            serde_json::from_str(&stdin).context("Expected a JSON array of input arrays")?;
+        let hashes = batch
+            .iter()
+            .enumerate()
+            .map(|(i, inputs)| {
+                let hash = json_inputs(inputs)
+                    .and_then(|inputs| hash(&inputs, args.domain))
+                    .with_context(|| format!("Batch entry {i}"))?;
+                Ok(args.encoding.encode(&hash))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        println!("{}", serde_json::to_string_pretty(&hashes)?);
+        return Ok(());
+    }
+
+    for (number, line) in stdin.lines().enumerate() {
+        let inputs = split_line(line);
+        if inputs.is_empty() {
+            continue;
+        }
+        let hash = hash(&inputs, args.domain).with_context(|| format!("Line {}", number + 1))?;
+        println!("{}", args.encoding.encode(&hash));
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anyhow::{bail, Context};
+use vortex::audit::SuiRpc;
+use vortex::field::FieldEncoding;
+use vortex::prover::parse_field_element;
+use vortex::tree_state::{fetch_commitments_from_api, fetch_commitments_from_rpc, TreeState};
+
+const PAGE_SIZE: usize = 50;
+
+const USAGE: &str =
+    "Usage: tree-state --coin-type <type> (--api <url> | --rpc <url> --package <id>) [options]
+
+Rebuilds a pool's Merkle tree from its commitments and prints its root and,
+when a leaf is selected, that leaf's path as JSON in the proof input encoding.
+
+Options:
+  --coin-type <type>       Pool coin type, e.g. 0x2::sui::SUI
+  --api <url>              Vortex API base URL to read commitments from
+  --rpc <url>              Sui fullnode JSON-RPC endpoint to read NewCommitment events from
+  --package <id>           Vortex package ID (with --rpc)
+  --index <n>              Print the path of the leaf at this index
+  --commitment <value>     Print the path of this commitment
+  --encoding <enc>         Print field elements as hex or dec (default dec)";
+
+enum Source {
+    Api(String),
+    Rpc { url: String, package: String },
+}
+
+enum Leaf {
+    Index(usize),
+    Commitment(String),
+}
+
+struct Args {
+    coin_type: String,
+    source: Source,
+    leaf: Option<Leaf>,
+    encoding: FieldEncoding,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut coin_type = None;
+    let mut api = None;
+    let mut rpc = None;
+    let mut package = None;
+    let mut leaf = None;
+    let mut encoding = FieldEncoding::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().with_context(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--coin-type" => coin_type = Some(value()?),
+            "--api" => api = Some(value()?),
+            "--rpc" => rpc = Some(value()?),
+            "--package" => package = Some(value()?),
+            "--index" => {
+                leaf = Some(Leaf::Index(
+                    value()?.parse().context("--index must be a number")?,
+                ))
+            }
+            "--commitment" => leaf = Some(Leaf::Commitment(value()?)),
+            "--encoding" => encoding = value()?.parse()?,
+            _ => bail!("Unknown argument {flag}\n\n{USAGE}"),
+        }
+    }
+
+    let source = match (api, rpc) {
+        (Some(url), None) => Source::Api(url),
+        (None, Some(url)) => Source::Rpc {
+            url,
+            package: package.with_context(|| format!("--rpc needs --package\n\n{USAGE}"))?,
+        },
+        _ => bail!("Exactly one of --api and --rpc is required\n\n{USAGE}"),
+    };
+
+    Ok(Args {
+        coin_type: coin_type.with_context(|| format!("--coin-type is required\n\n{USAGE}"))?,
+        source,
+        leaf,
+        encoding,
+    })
+}
+
+pub fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    let commitments = match &args.source {
+        Source::Api(url) => fetch_commitments_from_api(url, &args.coin_type, PAGE_SIZE)?,
+        Source::Rpc { url, package } => {
+            fetch_commitments_from_rpc(&SuiRpc::new(url), package, &args.coin_type, PAGE_SIZE)?
+        }
+    };
+    let tree = TreeState::new(commitments)?;
+
+    let index = match args.leaf {
+        None => {
+            println!(
+                "Root {} over {} leaves",
+                args.encoding.display(&tree.root()),
+                tree.len()
+            );
+            return Ok(());
+        }
+        Some(Leaf::Index(index)) => index,
+        Some(Leaf::Commitment(commitment)) => tree
+            .position(&parse_field_element(&commitment)?)
+            .with_context(|| format!("Commitment {commitment} is not in the tree"))?,
+    };
+
+    let path = tree.path_encoded(index, args.encoding)?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&path).context("Failed to serialize path")?
+    );
+    Ok(())
+}
@@ -0,0 +1,458 @@
+use crate::{
+    circuit::{
+        note_commitment, note_nullifier, CircuitVersion, ExtData, ExtDataHasher, TransactionCircuit,
+    },
+    constants::{MERKLE_TREE_LEVEL, N_INS, N_OUTS},
+    merkle_tree::Path,
+    nullifiers::SpentNullifiers,
+    poseidon_opt::{hash1, hash2},
+};
+use anyhow::{anyhow, bail};
+use ark_bn254::Fr;
+use ark_ff::AdditiveGroup;
+
+/// Note being spent, as stored by the wallet.
+#[derive(Debug, Clone, Copy)]
+pub struct InputUtxo<const L: usize = MERKLE_TREE_LEVEL> {
+    pub amount: Fr,
+    pub blinding: Fr,
+    pub private_key: Fr,
+    pub path_index: u64,
+    pub path: Path<L>,
+}
+
+/// Note being created for `public_key`.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputUtxo {
+    pub amount: Fr,
+    pub public_key: Fr,
+    pub blinding: Fr,
+}
+
+/// Computes every public input of a transaction from raw UTXOs.
+///
+/// Nullifiers, output commitments, `public_amount` and `hashed_account_secret`
+/// are derived with the same hashes and ordering as the constraints, so the
+/// built circuit is ready to prove.
+///
+/// Unused input and output slots are filled with zero-amount notes derived
+/// from the padding seed: padding input `i` is owned by `seed` with blinding
+/// `Poseidon2(seed, i)`, padding output `j` by `Poseidon1(seed)` with blinding
+/// `Poseidon2(seed, NI + j)`. The seed must be fresh randomness, otherwise
+/// observers can recognise the padding nullifiers and commitments. With
+/// [`TransactionBuilder::empty_outputs`] unused outputs are the canonical
+/// [`empty_output_commitment`](crate::circuit::empty_output_commitment) instead.
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder<
+    const NI: usize = N_INS,
+    const NO: usize = N_OUTS,
+    const L: usize = MERKLE_TREE_LEVEL,
+> {
+    version: CircuitVersion,
+    vortex: Fr,
+    root: Fr,
+    account_secret: Option<Fr>,
+    asset_id: Option<Fr>,
+    association: Option<(Fr, [Path<L>; NI])>,
+    fee: Option<Fr>,
+    ext_data: Option<(ExtData, ExtDataHasher)>,
+    padding_seed: Option<Fr>,
+    empty_outputs: bool,
+    inputs: Vec<InputUtxo<L>>,
+    outputs: Vec<OutputUtxo>,
+}
+
+impl<const NI: usize, const NO: usize, const L: usize> TransactionBuilder<NI, NO, L> {
+    pub fn new(vortex: Fr, root: Fr) -> Self {
+        Self {
+            version: CircuitVersion::V1,
+            vortex,
+            root,
+            account_secret: None,
+            asset_id: None,
+            association: None,
+            fee: None,
+            ext_data: None,
+            padding_seed: None,
+            empty_outputs: false,
+            inputs: Vec::with_capacity(NI),
+            outputs: Vec::with_capacity(NO),
+        }
+    }
+
+    pub fn version(mut self, version: CircuitVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Proves knowledge of `account_secret`; without it `hashed_account_secret` is zero.
+    pub fn account_secret(mut self, account_secret: Fr) -> Self {
+        self.account_secret = Some(account_secret);
+        self
+    }
+
+    /// See [`TransactionCircuit::with_asset_id`].
+    pub fn asset_id(mut self, asset_id: Fr) -> Self {
+        self.asset_id = Some(asset_id);
+        self
+    }
+
+    /// See [`TransactionCircuit::with_association`]. Paths follow the order
+    /// inputs are added in, padding slots included.
+    pub fn association(mut self, root: Fr, paths: [Path<L>; NI]) -> Self {
+        self.association = Some((root, paths));
+        self
+    }
+
+    /// See [`TransactionCircuit::with_fee`].
+    pub fn fee(mut self, fee: Fr) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    /// See [`TransactionCircuit::with_ext_data`].
+    pub fn ext_data(mut self, ext_data: ExtData, hasher: ExtDataHasher) -> Self {
+        self.ext_data = Some((ext_data, hasher));
+        self
+    }
+
+    /// Seeds the zero-amount notes filling unused slots.
+    pub fn padding_seed(mut self, seed: Fr) -> Self {
+        self.padding_seed = Some(seed);
+        self
+    }
+
+    /// Fills unused output slots with the canonical empty output instead of
+    /// seeded notes, so their `encrypted_output` can be the empty-output marker.
+    pub fn empty_outputs(mut self) -> Self {
+        self.empty_outputs = true;
+        self
+    }
+
+    pub fn input(mut self, utxo: InputUtxo<L>) -> Self {
+        self.inputs.push(utxo);
+        self
+    }
+
+    pub fn output(mut self, utxo: OutputUtxo) -> Self {
+        self.outputs.push(utxo);
+        self
+    }
+
+    /// Builds the circuit, with `public_amount = Σoutputs - Σinputs`.
+    ///
+    /// # Errors
+    /// Returns error if:
+    /// - More than `NI` inputs or `NO` outputs were added
+    /// - Slots need padding and no padding seed was set
+    /// - A non-zero input's Merkle path does not lead to `root`
+    pub fn build(self) -> anyhow::Result<TransactionCircuit<NI, NO, L>> {
+        if self.inputs.len() > NI || self.outputs.len() > NO {
+            return Err(anyhow!(
+                "Transaction has {} inputs and {} outputs, the circuit takes at most {} and {}",
+                self.inputs.len(),
+                self.outputs.len(),
+                NI,
+                NO
+            ));
+        }
+
+        let padding_seed = || {
+            self.padding_seed
+                .ok_or_else(|| anyhow!("A padding seed is required to fill unused slots"))
+        };
+
+        let mut inputs = self.inputs.clone();
+        for i in inputs.len()..NI {
+            let seed = padding_seed()?;
+            inputs.push(InputUtxo {
+                amount: Fr::ZERO,
+                blinding: hash2(&seed, &Fr::from(i as u64)),
+                private_key: seed,
+                path_index: 0,
+                path: Path::empty(),
+            });
+        }
+
+        let mut outputs = self.outputs.clone();
+        for j in outputs.len()..NO {
+            outputs.push(if self.empty_outputs {
+                OutputUtxo {
+                    amount: Fr::ZERO,
+                    public_key: Fr::ZERO,
+                    blinding: Fr::ZERO,
+                }
+            } else {
+                let seed = padding_seed()?;
+                OutputUtxo {
+                    amount: Fr::ZERO,
+                    public_key: hash1(&seed),
+                    blinding: hash2(&seed, &Fr::from((NI + j) as u64)),
+                }
+            });
+        }
+
+        let commitment = |amount, public_key, blinding| {
+            note_commitment(
+                self.version,
+                self.vortex,
+                self.asset_id,
+                amount,
+                public_key,
+                blinding,
+            )
+        };
+
+        let input_nullifiers: [Fr; NI] = std::array::from_fn(|i| {
+            let input = &inputs[i];
+            note_nullifier(
+                self.version,
+                input.private_key,
+                commitment(input.amount, hash1(&input.private_key), input.blinding),
+                Fr::from(input.path_index),
+            )
+        });
+        let output_commitments: [Fr; NO] = std::array::from_fn(|j| {
+            let output = &outputs[j];
+            commitment(output.amount, output.public_key, output.blinding)
+        });
+
+        let sum_ins: Fr = inputs.iter().map(|input| input.amount).sum();
+        let sum_outs: Fr = outputs.iter().map(|output| output.amount).sum();
+
+        let mut circuit = TransactionCircuit::from_arrays(
+            self.vortex,
+            self.root,
+            sum_outs - sum_ins,
+            input_nullifiers,
+            output_commitments,
+            self.account_secret
+                .map_or(Fr::ZERO, |secret| hash1(&secret)),
+            self.account_secret.unwrap_or(Fr::ZERO),
+            std::array::from_fn(|i| inputs[i].private_key),
+            std::array::from_fn(|i| inputs[i].amount),
+            std::array::from_fn(|i| inputs[i].blinding),
+            std::array::from_fn(|i| Fr::from(inputs[i].path_index)),
+            std::array::from_fn(|i| inputs[i].path),
+            std::array::from_fn(|j| outputs[j].public_key),
+            std::array::from_fn(|j| outputs[j].amount),
+            std::array::from_fn(|j| outputs[j].blinding),
+        )?
+        .with_version(self.version);
+
+        if let Some(asset_id) = self.asset_id {
+            circuit = circuit.with_asset_id(asset_id);
+        }
+        if let Some((root, paths)) = self.association {
+            circuit = circuit.with_association(root, paths);
+        }
+        if let Some(fee) = self.fee {
+            circuit = circuit.with_fee(fee);
+        }
+        if let Some((ext_data, hasher)) = self.ext_data {
+            circuit = circuit.with_ext_data(ext_data, hasher);
+        }
+
+        circuit.select_root(&[self.root])
+    }
+
+    /// [`build`](Self::build), then checks the added inputs' nullifiers
+    /// against `spent`, so a note another device already spent fails here
+    /// rather than after proving or on chain. Zero-amount inputs are skipped,
+    /// as they are never in the pool.
+    ///
+    /// # Errors
+    /// Returns error if building fails, the lookup fails, or an input is
+    /// already spent.
+    pub fn build_unspent(
+        self,
+        spent: &impl SpentNullifiers,
+    ) -> anyhow::Result<TransactionCircuit<NI, NO, L>> {
+        let checked: Vec<usize> = (0..self.inputs.len())
+            .filter(|&i| self.inputs[i].amount != Fr::ZERO)
+            .collect();
+        let circuit = self.build()?;
+
+        let nullifiers: Vec<Fr> = checked
+            .iter()
+            .map(|&i| circuit.input_nullifiers[i])
+            .collect();
+        let spent = spent.spent(&nullifiers)?;
+        let spent_inputs: Vec<String> = checked
+            .iter()
+            .filter(|&&i| spent.contains(&circuit.input_nullifiers[i]))
+            .map(|i| i.to_string())
+            .collect();
+
+        if !spent_inputs.is_empty() {
+            bail!(
+                "Input {} already spent, refresh the wallet's notes",
+                spent_inputs.join(", ")
+            );
+        }
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::empty_output_commitment;
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, PoseidonOptimized};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builder_produces_satisfiable_circuit() {
+        let hasher = PoseidonOptimized::new_t3();
+        let vortex = Fr::from(7u64);
+        let private_key = Fr::from(12345u64);
+        let blinding = Fr::from(1u64);
+        let amount = Fr::from(100u64);
+
+        let leaf = note_commitment(
+            CircuitVersion::V2,
+            vortex,
+            None,
+            amount,
+            hash1(&private_key),
+            blinding,
+        );
+        let mut tree =
+            SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+        tree.insert(leaf, &hasher).unwrap();
+
+        let recipient = OutputUtxo {
+            amount: Fr::from(60u64),
+            public_key: Fr::from(42u64),
+            blinding: Fr::from(2u64),
+        };
+
+        let circuit = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .version(CircuitVersion::V2)
+            .account_secret(Fr::from(5u64))
+            .padding_seed(Fr::from(99u64))
+            .input(InputUtxo {
+                amount,
+                blinding,
+                private_key,
+                path_index: 0,
+                path: tree.generate_membership_proof(0).unwrap(),
+            })
+            .output(recipient)
+            .build()
+            .unwrap();
+
+        // Withdraws the 40 not sent to the recipient
+        assert_eq!(circuit.public_amount, -Fr::from(40u64));
+        assert_eq!(circuit.output_commitments[0], circuit.output_commitment(0));
+        assert_ne!(circuit.input_nullifiers[0], circuit.input_nullifiers[1]);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(
+            cs.borrow().unwrap().instance_assignment[1..],
+            circuit.get_public_inputs()[..]
+        );
+
+        // A single-output transfer can pad with the canonical empty output
+        let single = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .version(CircuitVersion::V2)
+            .padding_seed(Fr::from(99u64))
+            .empty_outputs()
+            .input(InputUtxo {
+                amount,
+                blinding,
+                private_key,
+                path_index: 0,
+                path: tree.generate_membership_proof(0).unwrap(),
+            })
+            .output(recipient)
+            .build()
+            .unwrap();
+        assert_eq!(
+            single.output_commitments[1],
+            empty_output_commitment(CircuitVersion::V2, vortex, None)
+        );
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        single.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Padding needs a seed, and paths must lead to the root
+        assert!(
+            TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+                .build()
+                .is_err()
+        );
+        assert!(
+            TransactionBuilder::<N_INS, N_OUTS>::new(vortex, Fr::from(1u64))
+                .padding_seed(Fr::from(99u64))
+                .input(InputUtxo {
+                    amount,
+                    blinding,
+                    private_key,
+                    path_index: 0,
+                    path: tree.generate_membership_proof(0).unwrap(),
+                })
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_unspent_rejects_spent_inputs() {
+        let hasher = PoseidonOptimized::new_t3();
+        let vortex = Fr::from(7u64);
+        let private_key = Fr::from(12345u64);
+        let amount = Fr::from(100u64);
+
+        let mut tree =
+            SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+        let input = |index: u64, tree: &SparseMerkleTree<MERKLE_TREE_LEVEL>| InputUtxo {
+            amount,
+            blinding: Fr::from(index),
+            private_key,
+            path_index: index,
+            path: tree.generate_membership_proof(index as usize).unwrap(),
+        };
+        let leaf = |index: u64| {
+            note_commitment(
+                CircuitVersion::V1,
+                vortex,
+                None,
+                amount,
+                hash1(&private_key),
+                Fr::from(index),
+            )
+        };
+        tree.insert_pair(leaf(0), leaf(1), &hasher).unwrap();
+
+        let builder = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .padding_seed(Fr::from(99u64))
+            .input(input(0, &tree))
+            .input(input(1, &tree));
+        let circuit = builder.clone().build_unspent(&HashSet::new()).unwrap();
+
+        let spent = HashSet::from([circuit.input_nullifiers[1]]);
+        let err = builder.build_unspent(&spent).unwrap_err();
+        assert!(err.to_string().contains("Input 1 already spent"), "{err}");
+
+        // Padding nullifiers are never looked up
+        let padded = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .padding_seed(Fr::from(99u64))
+            .input(input(0, &tree))
+            .build()
+            .unwrap();
+        let spent = HashSet::from([padded.input_nullifiers[1]]);
+        assert!(
+            TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+                .padding_seed(Fr::from(99u64))
+                .input(input(0, &tree))
+                .build_unspent(&spent)
+                .is_ok()
+        );
+    }
+}
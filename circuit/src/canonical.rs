@@ -0,0 +1,83 @@
+use anyhow::bail;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Serializes `value` as canonical JSON: object keys sorted by byte order, no
+/// insignificant whitespace, integers in plain decimal.
+///
+/// Floating point numbers are rejected since they have no single textual form.
+pub fn to_canonical_json<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    let value = serde_json::to_value(value)?;
+    let mut out = String::new();
+    write_canonical(&value, &mut out)?;
+    Ok(out)
+}
+
+/// Hex-encoded SHA-256 of the canonical JSON encoding of `value`.
+pub fn canonical_hash<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    let json = to_canonical_json(value)?;
+    Ok(hex::encode(Sha256::digest(json.as_bytes())))
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> anyhow::Result<()> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(number) => {
+            if number.is_f64() {
+                bail!("Cannot canonicalize floating point number {}", number);
+            }
+            out.push_str(&number.to_string());
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(item, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_json_sorts_keys_and_strips_whitespace() {
+        let a: Value =
+            serde_json::from_str(r#"{ "b": [1, 2], "a": { "y": "x", "x": null } }"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"a":{"x":null,"y":"x"},"b":[1,2]}"#).unwrap();
+
+        assert_eq!(
+            to_canonical_json(&a).unwrap(),
+            r#"{"a":{"x":null,"y":"x"},"b":[1,2]}"#
+        );
+        assert_eq!(canonical_hash(&a).unwrap(), canonical_hash(&b).unwrap());
+    }
+
+    #[test]
+    fn test_canonical_json_rejects_floats() {
+        assert!(to_canonical_json(&json!({ "amount": 1.5 })).is_err());
+    }
+}
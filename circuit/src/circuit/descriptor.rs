@@ -0,0 +1,137 @@
+use super::{CircuitVersion, TransactionCircuit};
+use anyhow::bail;
+use ark_bn254::Bn254;
+use ark_groth16::VerifyingKey;
+use serde::{Deserialize, Serialize};
+
+/// File `keygen` writes next to `proving_key.bin` and `verification_key.bin`.
+pub const DESCRIPTOR_FILE: &str = "descriptor.json";
+
+/// Hash construction the circuit commits notes and nullifiers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashScheme {
+    /// Poseidon over BN254 with a zero capacity element
+    Poseidon,
+    /// Poseidon with per-role domain tags
+    TaggedPoseidon,
+    /// Tagged Poseidon with nullifiers derived from the spending key
+    TaggedPoseidonKeyNullifier,
+}
+
+impl From<CircuitVersion> for HashScheme {
+    fn from(version: CircuitVersion) -> Self {
+        match version {
+            CircuitVersion::V1 => Self::Poseidon,
+            CircuitVersion::V2 => Self::TaggedPoseidon,
+            CircuitVersion::V3 => Self::TaggedPoseidonKeyNullifier,
+        }
+    }
+}
+
+/// Shape of the circuit a key pair was generated for.
+///
+/// Keys only fit the exact constraint system they were generated from, and a
+/// mismatch otherwise surfaces as an unverifiable proof. Comparing descriptors
+/// turns that into an error naming what drifted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitDescriptor {
+    pub version: CircuitVersion,
+    /// Merkle tree height
+    pub level: usize,
+    pub n_ins: usize,
+    pub n_outs: usize,
+    pub hash_scheme: HashScheme,
+    /// Public input names in allocation order
+    pub public_input_layout: Vec<String>,
+}
+
+impl CircuitDescriptor {
+    /// Fails with every field where `circuit` differs from this descriptor.
+    pub fn check_circuit(&self, circuit: &CircuitDescriptor) -> anyhow::Result<()> {
+        let mut mismatches = Vec::new();
+        let mut compare = |field: &str, keys: String, input: String| {
+            if keys != input {
+                mismatches.push(format!("{field}: keys {keys}, input {input}"));
+            }
+        };
+
+        compare(
+            "version",
+            format!("{:?}", self.version),
+            format!("{:?}", circuit.version),
+        );
+        compare("level", self.level.to_string(), circuit.level.to_string());
+        compare("nIns", self.n_ins.to_string(), circuit.n_ins.to_string());
+        compare("nOuts", self.n_outs.to_string(), circuit.n_outs.to_string());
+        compare(
+            "hashScheme",
+            format!("{:?}", self.hash_scheme),
+            format!("{:?}", circuit.hash_scheme),
+        );
+        compare(
+            "publicInputLayout",
+            self.public_input_layout.join(","),
+            circuit.public_input_layout.join(","),
+        );
+
+        if !mismatches.is_empty() {
+            bail!(
+                "Keys do not match the input circuit ({}). Use the keys generated for this circuit",
+                mismatches.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Fails when `vk` takes a different number of public inputs than the layout.
+    pub fn check_verifying_key(&self, vk: &VerifyingKey<Bn254>) -> anyhow::Result<()> {
+        let key_inputs = vk.gamma_abc_g1.len().saturating_sub(1);
+        if key_inputs != self.public_input_layout.len() {
+            bail!(
+                "Key takes {} public inputs but the descriptor lists {}",
+                key_inputs,
+                self.public_input_layout.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<const NI: usize, const NO: usize, const L: usize> TransactionCircuit<NI, NO, L> {
+    /// Describes the constraint system this circuit synthesizes.
+    pub fn descriptor(&self) -> CircuitDescriptor {
+        let mut layout = vec![
+            "vortex".to_string(),
+            "root".to_string(),
+            "public_amount".to_string(),
+        ];
+        layout.extend((0..NI).map(|i| format!("input_nullifier_{i}")));
+        layout.extend((0..NO).map(|i| format!("output_commitment_{i}")));
+        layout.push("hashed_account_secret".to_string());
+
+        let optional = [
+            ("asset_id", self.asset_id.is_some()),
+            ("association_root", self.association_root.is_some()),
+            ("fee", self.fee.is_some()),
+            ("ext_data_hash", self.ext_data_hash.is_some()),
+        ];
+        layout.extend(
+            optional
+                .into_iter()
+                .filter(|(_, present)| *present)
+                .map(|(name, _)| name.to_string()),
+        );
+        layout.extend((0..self.root_history.len()).map(|i| format!("root_history_{i}")));
+
+        CircuitDescriptor {
+            version: self.version,
+            level: L,
+            n_ins: NI,
+            n_outs: NO,
+            hash_scheme: self.version.into(),
+            public_input_layout: layout,
+        }
+    }
+}
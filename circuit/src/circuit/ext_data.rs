@@ -0,0 +1,75 @@
+use crate::{
+    constants::DOMAIN_EXT_DATA,
+    poseidon_opt::{hasher_t5, PoseidonOptimizedVar},
+};
+use ark_bn254::Fr;
+use ark_ff::AdditiveGroup;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs;
+use serde::{Deserialize, Serialize};
+
+/// Transaction data outside the notes that a proof can bind through
+/// [`TransactionCircuit::with_ext_data`](super::TransactionCircuit::with_ext_data).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtData {
+    /// Withdrawal recipient address as a field element
+    pub recipient: Fr,
+    /// Relayer address as a field element
+    pub relayer: Fr,
+    pub fee: Fr,
+    /// Hash of the encrypted outputs posted with the transaction
+    pub encrypted_output_hash: Fr,
+}
+
+/// Hash function computing `ext_data_hash` from [`ExtData`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtDataHasher {
+    /// `Poseidon4(recipient, relayer, fee, encrypted_output_hash)`, the same
+    /// hash as `sui::poseidon::poseidon_bn254`, so Move can recompute it
+    #[default]
+    Poseidon,
+    /// The same hash with `DOMAIN_EXT_DATA` as capacity element, so its
+    /// outputs can never collide with a note commitment
+    Tagged,
+}
+
+impl ExtDataHasher {
+    fn domain(self) -> Fr {
+        match self {
+            Self::Poseidon => Fr::ZERO,
+            Self::Tagged => Fr::from(DOMAIN_EXT_DATA),
+        }
+    }
+
+    pub fn hash(self, ext_data: &ExtData) -> Fr {
+        hasher_t5().hash_with_domain(
+            &[
+                ext_data.recipient,
+                ext_data.relayer,
+                ext_data.fee,
+                ext_data.encrypted_output_hash,
+            ],
+            self.domain(),
+        )
+    }
+
+    /// In-circuit counterpart of [`Self::hash`], inputs in the same order.
+    pub(crate) fn hash_var(
+        self,
+        recipient: &FpVar<Fr>,
+        relayer: &FpVar<Fr>,
+        fee: &FpVar<Fr>,
+        encrypted_output_hash: &FpVar<Fr>,
+    ) -> r1cs::Result<FpVar<Fr>> {
+        PoseidonOptimizedVar::new_t5().hash_with_domain(
+            &[
+                recipient.clone(),
+                relayer.clone(),
+                fee.clone(),
+                encrypted_output_hash.clone(),
+            ],
+            self.domain(),
+        )
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    constants::{MAX_AMOUNT_BITS, MERKLE_TREE_LEVEL, N_INS, N_OUTS},
+    constants::{
+        AMOUNT_LIMBS, DOMAIN_COMMITMENT, DOMAIN_NULLIFIER, DOMAIN_SIGNATURE, MAX_AMOUNT_BITS,
+        MERKLE_TREE_LEVEL, N_INS, N_INS_1, N_INS_16, N_OUTS,
+    },
     merkle_tree::{Path, PathVar},
-    poseidon_opt::PoseidonOptimizedVar,
+    poseidon_opt::{hash1, hasher_t3, hasher_t4, hasher_t5, PoseidonOptimizedVar},
 };
 use ark_bn254::Fr;
 use ark_ff::AdditiveGroup;
@@ -15,15 +18,166 @@ use ark_relations::{
     r1cs::{self, ConstraintSynthesizer, ConstraintSystemRef},
 };
 use ark_serialize::CanonicalSerialize;
-use std::ops::Not;
+use serde::{Deserialize, Serialize};
+use std::{ops::Not, str::FromStr};
+
+mod descriptor;
+mod ext_data;
+mod report;
+
+pub use descriptor::{CircuitDescriptor, HashScheme, DESCRIPTOR_FILE};
+pub use ext_data::{ExtData, ExtDataHasher};
+pub use report::{ConstraintReport, GadgetCost};
+
+/// Hash layout revision of the transaction circuit.
+///
+/// Each version produces a different constraint system and therefore needs its
+/// own proving/verifying keys and a matching verifying key in the Move package.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CircuitVersion {
+    /// Untagged Poseidon hashes (deployed circuit)
+    #[default]
+    V1,
+    /// Commitment, nullifier and signature hashes carry distinct domain tags,
+    /// input path indices are bound to their Merkle paths, and amounts are
+    /// range checked with byte limbs
+    V2,
+    /// V2 with nullifiers derived directly from the spending key, see
+    /// [`CircuitVersion::derives_nullifier_from_key`]
+    V3,
+}
+
+/// Role of a Poseidon hash inside the transaction circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashDomain {
+    Commitment,
+    Nullifier,
+    Signature,
+}
+
+impl CircuitVersion {
+    /// Returns the initial capacity element used for hashes in `domain`.
+    pub fn domain_tag(self, domain: HashDomain) -> Fr {
+        match self {
+            Self::V1 => Fr::ZERO,
+            Self::V2 | Self::V3 => Fr::from(match domain {
+                HashDomain::Commitment => DOMAIN_COMMITMENT,
+                HashDomain::Nullifier => DOMAIN_NULLIFIER,
+                HashDomain::Signature => DOMAIN_SIGNATURE,
+            }),
+        }
+    }
+
+    /// Whether each input's path index must match the leaf position its
+    /// Merkle path proves.
+    pub fn binds_path_indices(self) -> bool {
+        !matches!(self, Self::V1)
+    }
+
+    /// Whether amounts are range checked with [`enforce_amount_limbs`]
+    /// instead of a full canonical bit decomposition.
+    pub fn decomposes_amounts_into_limbs(self) -> bool {
+        !matches!(self, Self::V1)
+    }
+
+    /// Whether nullifiers are `Poseidon3(private_key, commitment, path_index)`
+    /// instead of `Poseidon3(commitment, path_index, signature)`.
+    ///
+    /// This drops the signature hash, one Poseidon3 per input. The nullifier
+    /// still hashes the commitment, which commits to the pool's `vortex`, so
+    /// one key spending the same leaf index in two pools reveals unrelated
+    /// nullifiers.
+    ///
+    /// A note has a different nullifier under each derivation, so a pool must
+    /// only ever accept one of them: notes move to a V3 pool by being spent
+    /// through their old version.
+    pub fn derives_nullifier_from_key(self) -> bool {
+        matches!(self, Self::V3)
+    }
+}
+
+impl FromStr for CircuitVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "v1" | "1" => Ok(Self::V1),
+            "v2" | "2" => Ok(Self::V2),
+            "v3" | "3" => Ok(Self::V3),
+            _ => Err(anyhow::anyhow!(
+                "Unknown circuit version '{}'. Use: v1, v2 or v3",
+                s
+            )),
+        }
+    }
+}
+
+/// Input/output shape of the transaction circuit.
+///
+/// Like [`CircuitVersion`], every kind has its own proving/verifying keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CircuitKind {
+    /// 2-input, 2-output circuit (deployed)
+    #[default]
+    Transaction2,
+    /// 1-input, 2-output "transfer-lite" circuit for single-note spends
+    Transaction1,
+    /// 16-input, 2-output circuit consolidating many small notes
+    Transaction16,
+}
+
+impl CircuitKind {
+    /// Number of input UTXOs the circuit spends.
+    pub fn n_ins(self) -> usize {
+        match self {
+            Self::Transaction2 => N_INS,
+            Self::Transaction1 => N_INS_1,
+            Self::Transaction16 => N_INS_16,
+        }
+    }
+
+    /// Subdirectory holding this kind's keys. The 2-input keys stay at the
+    /// root of their version directory for compatibility.
+    pub fn key_dir(self) -> Option<&'static str> {
+        match self {
+            Self::Transaction2 => None,
+            Self::Transaction1 => Some("transaction1"),
+            Self::Transaction16 => Some("transaction16"),
+        }
+    }
+}
+
+impl FromStr for CircuitKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "transaction2" => Ok(Self::Transaction2),
+            "transaction1" | "transfer-lite" => Ok(Self::Transaction1),
+            "transaction16" => Ok(Self::Transaction16),
+            _ => Err(anyhow::anyhow!(
+                "Unknown circuit kind '{}'. Use: transaction2, transaction1 or transaction16",
+                s
+            )),
+        }
+    }
+}
 
 /// Transaction circuit for privacy-preserving value transfers on Sui.
 ///
-/// This circuit implements a 2-input, 2-output transaction model where:
+/// The circuit is generic over the number of input (`NI`) and output (`NO`)
+/// UTXOs and defaults to the deployed 2-input, 2-output model where:
 /// - Users can spend up to 2 input UTXOs (zero amounts allowed)
 /// - Create up to 2 output UTXOs (zero amounts allowed)
 /// - Add/remove value from the pool via `public_amount`
 ///
+/// [`Transaction1Circuit`] is the 1-input "transfer-lite" variant for the common
+/// single-note spend, and [`Transaction16Circuit`] the 16-input variant used to
+/// consolidate many small UTXOs in a single transaction. Every shape has its
+/// own keys.
+///
 /// # Privacy Guarantees
 ///
 /// - Input amounts, recipients, and senders are hidden
@@ -45,61 +199,83 @@ use std::ops::Not;
 /// - Nullifier: `Poseidon3(commitment, path_index, signature)`
 /// - Signature: `Poseidon3(privkey, commitment, path_index)`
 /// - Public key: `Poseidon1(privkey)`
+///
+/// From [`CircuitVersion::V2`] the commitment, signature and nullifier hashes are
+/// domain separated via [`CircuitVersion::domain_tag`].
+///
+/// With an asset identifier (see [`TransactionCircuit::with_asset_id`]) every
+/// commitment becomes `Poseidon4(amount, asset_id, pubkey, blinding)`, so notes
+/// of different assets can share one shielded pool without being mixed up.
+///
+/// With an association root (see [`TransactionCircuit::with_association`]) every
+/// non-zero input must also be a leaf of that second tree, proving its deposit
+/// belongs to an approved subset without revealing which one.
+///
+/// With external data (see [`TransactionCircuit::with_ext_data`]) the circuit
+/// recomputes `ext_data_hash` from the recipient, relayer and fee, so their
+/// binding no longer relies on the Move contract alone.
+///
+/// With a root history (see [`TransactionCircuit::with_root_history`]) the
+/// Merkle paths may lead to `root` or to any of the history roots, all public
+/// inputs, so a proof survives the tree advancing before it lands on-chain.
 #[derive(Debug, Clone)]
-pub struct TransactionCircuit {
+pub struct TransactionCircuit<
+    const NI: usize = N_INS,
+    const NO: usize = N_OUTS,
+    const L: usize = MERKLE_TREE_LEVEL,
+> {
+    pub version: CircuitVersion,
+
     // Public inputs (must match order expected by Move contract verification)
-    // Individual fields to match how they're allocated in generate_constraints()
     pub vortex: Fr,
     pub root: Fr,
     pub public_amount: Fr,
-    pub input_nullifier_0: Fr,
-    pub input_nullifier_1: Fr,
-    pub output_commitment_0: Fr,
-    pub output_commitment_1: Fr,
+    pub input_nullifiers: [Fr; NI],
+    pub output_commitments: [Fr; NO],
     pub hashed_account_secret: Fr,
+    /// Asset every input and output note is bound to. `None` keeps the
+    /// single-asset `Poseidon4(amount, pubkey, blinding, vortex)` commitments.
+    pub asset_id: Option<Fr>,
+    /// Root of the approved deposit subset. `None` skips the association check.
+    pub association_root: Option<Fr>,
+    /// Relayer fee bound by the proof. `None` synthesizes the circuit without
+    /// the fee public input (the deployed layout).
+    pub fee: Option<Fr>,
+    /// Hash of `ext_data` recomputed by the circuit. `None` leaves the
+    /// binding of recipient, relayer and fee to the Move contract.
+    pub ext_data_hash: Option<Fr>,
+    pub ext_data_hasher: ExtDataHasher,
+    /// Roots accepted besides `root`. Empty keeps the single-root layout.
+    pub root_history: Vec<Fr>,
 
     // Private inputs - Input UTXOs
     pub account_secret: Fr,
-    pub in_private_keys: [Fr; N_INS],
-    pub in_amounts: [Fr; N_INS],
-    pub in_blindings: [Fr; N_INS],
-    pub in_path_indices: [Fr; N_INS],
-    pub merkle_paths: [Path<MERKLE_TREE_LEVEL>; N_INS],
+    pub in_private_keys: [Fr; NI],
+    pub in_amounts: [Fr; NI],
+    pub in_blindings: [Fr; NI],
+    pub in_path_indices: [Fr; NI],
+    pub merkle_paths: [Path<L>; NI],
+    pub association_paths: [Path<L>; NI],
+    pub ext_data: ExtData,
 
     // Private inputs - Output UTXOs
-    pub out_public_keys: [Fr; N_OUTS],
-    pub out_amounts: [Fr; N_OUTS],
-    pub out_blindings: [Fr; N_OUTS],
+    pub out_public_keys: [Fr; NO],
+    pub out_amounts: [Fr; NO],
+    pub out_blindings: [Fr; NO],
 }
 
-impl TransactionCircuit {
-    /// Creates an empty circuit with all values set to zero.
-    /// Used for setup phase and testing.
-    pub fn empty() -> Self {
-        Self {
-            vortex: Fr::ZERO,
-            root: Fr::ZERO,
-            public_amount: Fr::ZERO,
-            input_nullifier_0: Fr::ZERO,
-            input_nullifier_1: Fr::ZERO,
-            output_commitment_0: Fr::ZERO,
-            output_commitment_1: Fr::ZERO,
-            hashed_account_secret: Fr::ZERO,
+/// Deployed 2-input, 2-output circuit, usable where the default parameters
+/// cannot be inferred (e.g. `Transaction2Circuit::empty()`).
+pub type Transaction2Circuit = TransactionCircuit<N_INS, N_OUTS>;
 
-            account_secret: Fr::ZERO,
-            in_private_keys: [Fr::ZERO; N_INS],
-            in_amounts: [Fr::ZERO; N_INS],
-            in_blindings: [Fr::ZERO; N_INS],
-            in_path_indices: [Fr::ZERO; N_INS],
-            merkle_paths: [Path::empty(); N_INS],
-
-            out_public_keys: [Fr::ZERO; N_OUTS],
-            out_amounts: [Fr::ZERO; N_OUTS],
-            out_blindings: [Fr::ZERO; N_OUTS],
-        }
-    }
+/// 1-input, 2-output circuit for spending a single UTXO.
+pub type Transaction1Circuit = TransactionCircuit<N_INS_1, N_OUTS>;
+
+/// 16-input, 2-output circuit for consolidating UTXOs.
+pub type Transaction16Circuit = TransactionCircuit<N_INS_16, N_OUTS>;
 
-    /// Creates a new circuit with validation.
+impl TransactionCircuit {
+    /// Creates a new 2-input, 2-output circuit with validation.
     ///
     /// # Errors
     /// Returns error if:
@@ -123,40 +299,249 @@ impl TransactionCircuit {
         out_public_keys: [Fr; N_OUTS],
         out_amounts: [Fr; N_OUTS],
         out_blindings: [Fr; N_OUTS],
+    ) -> anyhow::Result<Self> {
+        Self::from_arrays(
+            vortex,
+            root,
+            public_amount,
+            [input_nullifier_0, input_nullifier_1],
+            [output_commitment_0, output_commitment_1],
+            hashed_account_secret,
+            account_secret,
+            in_private_keys,
+            in_amounts,
+            in_blindings,
+            in_path_indices,
+            merkle_paths,
+            out_public_keys,
+            out_amounts,
+            out_blindings,
+        )
+    }
+}
+
+impl<const NI: usize, const NO: usize, const L: usize> TransactionCircuit<NI, NO, L> {
+    /// Creates an empty circuit with all values set to zero.
+    /// Used for setup phase and testing.
+    pub fn empty() -> Self {
+        Self {
+            version: CircuitVersion::V1,
+            vortex: Fr::ZERO,
+            root: Fr::ZERO,
+            public_amount: Fr::ZERO,
+            input_nullifiers: [Fr::ZERO; NI],
+            output_commitments: [Fr::ZERO; NO],
+            hashed_account_secret: Fr::ZERO,
+            asset_id: None,
+            association_root: None,
+            fee: None,
+            ext_data_hash: None,
+            ext_data_hasher: ExtDataHasher::Poseidon,
+            root_history: Vec::new(),
+
+            account_secret: Fr::ZERO,
+            in_private_keys: [Fr::ZERO; NI],
+            in_amounts: [Fr::ZERO; NI],
+            in_blindings: [Fr::ZERO; NI],
+            in_path_indices: [Fr::ZERO; NI],
+            merkle_paths: [Path::empty(); NI],
+            association_paths: [Path::empty(); NI],
+            ext_data: ExtData::default(),
+
+            out_public_keys: [Fr::ZERO; NO],
+            out_amounts: [Fr::ZERO; NO],
+            out_blindings: [Fr::ZERO; NO],
+        }
+    }
+
+    /// Creates a circuit of any shape with validation.
+    ///
+    /// # Errors
+    /// Returns error if:
+    /// - Path indices exceed tree capacity (>= 2^LEVEL)
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_arrays(
+        vortex: Fr,
+        root: Fr,
+        public_amount: Fr,
+        input_nullifiers: [Fr; NI],
+        output_commitments: [Fr; NO],
+        hashed_account_secret: Fr,
+        account_secret: Fr,
+        in_private_keys: [Fr; NI],
+        in_amounts: [Fr; NI],
+        in_blindings: [Fr; NI],
+        in_path_indices: [Fr; NI],
+        merkle_paths: [Path<L>; NI],
+        out_public_keys: [Fr; NO],
+        out_amounts: [Fr; NO],
+        out_blindings: [Fr; NO],
     ) -> anyhow::Result<Self> {
         // Validate path indices fit in tree
-        let max_index = Fr::from(1u128 << MERKLE_TREE_LEVEL);
+        let max_index = Fr::from(1u128 << L);
         for (i, idx) in in_path_indices.iter().enumerate() {
             if *idx >= max_index {
                 return Err(anyhow::anyhow!(
                     "Input {} path index exceeds tree capacity (>= 2^{})",
                     i,
-                    MERKLE_TREE_LEVEL
+                    L
                 ));
             }
         }
 
         Ok(Self {
+            version: CircuitVersion::V1,
             vortex,
             root,
             public_amount,
-            input_nullifier_0,
-            input_nullifier_1,
-            output_commitment_0,
-            output_commitment_1,
+            input_nullifiers,
+            output_commitments,
             hashed_account_secret,
+            asset_id: None,
+            association_root: None,
+            fee: None,
+            ext_data_hash: None,
+            ext_data_hasher: ExtDataHasher::Poseidon,
+            root_history: Vec::new(),
             account_secret,
             in_private_keys,
             in_amounts,
             in_blindings,
             in_path_indices,
             merkle_paths,
+            association_paths: [Path::empty(); NI],
+            ext_data: ExtData::default(),
             out_public_keys,
             out_amounts,
             out_blindings,
         })
     }
 
+    /// Adds the relayer fee as the last public input.
+    ///
+    /// The circuit then range checks the fee and, for withdrawals, enforces
+    /// `fee <= -public_amount`. Use `Fr::ZERO` when generating keys.
+    pub fn with_fee(mut self, fee: Fr) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    /// Adds the asset identifier as a public input and binds every input and
+    /// output commitment to it. Use `Fr::ZERO` when generating keys.
+    pub fn with_asset_id(mut self, asset_id: Fr) -> Self {
+        self.asset_id = Some(asset_id);
+        self
+    }
+
+    /// Adds the association set root as a public input and requires every
+    /// non-zero input to be a leaf of it via `paths`. Use `Fr::ZERO` and empty
+    /// paths when generating keys.
+    pub fn with_association(mut self, root: Fr, paths: [Path<L>; NI]) -> Self {
+        self.association_root = Some(root);
+        self.association_paths = paths;
+        self
+    }
+
+    /// Adds `ext_data_hash = hasher(recipient, relayer, fee, encrypted_output_hash)`
+    /// as the last public input and recomputes it in-circuit from `ext_data`,
+    /// so the proof itself binds where the funds and the fee go. With
+    /// [`Self::with_fee`] the hashed fee must equal the fee public input. Use
+    /// `ExtData::default()` when generating keys.
+    pub fn with_ext_data(mut self, ext_data: ExtData, hasher: ExtDataHasher) -> Self {
+        self.ext_data_hash = Some(hasher.hash(&ext_data));
+        self.ext_data_hasher = hasher;
+        self.ext_data = ext_data;
+        self
+    }
+
+    /// Adds `roots` as public inputs after all others and accepts Merkle paths
+    /// leading to `root` or any of them, mirroring the root history the Move
+    /// contract keeps. Use `K - 1` zero roots when generating keys for `K`
+    /// accepted roots.
+    pub fn with_root_history(mut self, roots: Vec<Fr>) -> Self {
+        self.root_history = roots;
+        self
+    }
+
+    /// Sets the hash layout version the circuit is synthesized with.
+    pub fn with_version(mut self, version: CircuitVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Recomputes the commitment of input `i` with the circuit's hash version.
+    pub fn input_commitment(&self, i: usize) -> Fr {
+        note_commitment(
+            self.version,
+            self.vortex,
+            self.asset_id,
+            self.in_amounts[i],
+            hash1(&self.in_private_keys[i]),
+            self.in_blindings[i],
+        )
+    }
+
+    /// Recomputes the commitment of output `i` with the circuit's hash version.
+    pub fn output_commitment(&self, i: usize) -> Fr {
+        note_commitment(
+            self.version,
+            self.vortex,
+            self.asset_id,
+            self.out_amounts[i],
+            self.out_public_keys[i],
+            self.out_blindings[i],
+        )
+    }
+
+    /// Sets `root` to the first of `candidates` that every non-zero input's
+    /// Merkle path leads to.
+    ///
+    /// The contract accepts any root in its history, so a caller can pass all
+    /// currently valid roots (most recent first) instead of pinning the one its
+    /// paths were fetched against and failing if it rotates out mid-proving.
+    ///
+    /// # Errors
+    /// Returns error if the inputs' paths disagree on the root or none of the
+    /// candidates matches.
+    pub fn select_root(mut self, candidates: &[Fr]) -> anyhow::Result<Self> {
+        let hasher = hasher_t3();
+        let mut path_root = None;
+
+        for i in 0..NI {
+            if self.in_amounts[i] == Fr::ZERO {
+                continue;
+            }
+
+            let root = self.merkle_paths[i].calculate_root(&self.input_commitment(i), hasher)?;
+            match path_root {
+                Some(previous) if previous != root => {
+                    return Err(anyhow::anyhow!(
+                        "Input {} Merkle path leads to a different root than the previous inputs",
+                        i
+                    ));
+                }
+                _ => path_root = Some(root),
+            }
+        }
+
+        self.root = match path_root {
+            Some(root) => *candidates
+                .iter()
+                .find(|candidate| **candidate == root)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Merkle paths lead to root {} which is not a valid root",
+                        root
+                    )
+                })?,
+            None => *candidates
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("No candidate roots provided"))?,
+        };
+
+        Ok(self)
+    }
+
     /// Returns public inputs in the order they are allocated in `generate_constraints()`.
     ///
     /// This order MUST match the order in which `FpVar::new_input()` is called in
@@ -166,26 +551,30 @@ impl TransactionCircuit {
     /// 1. vortex
     /// 2. root
     /// 3. public_amount
-    /// 4. input_nullifier_0
-    /// 5. input_nullifier_1
-    /// 6. output_commitment_0
-    /// 7. output_commitment_1
-    /// 8. hashed_account_secret
+    /// 4. input_nullifiers (NI elements)
+    /// 5. output_commitments (NO elements)
+    /// 6. hashed_account_secret
+    /// 7. asset_id (only when set via `with_asset_id`)
+    /// 8. association_root (only when set via `with_association`)
+    /// 9. fee (only when set via `with_fee`)
+    /// 10. ext_data_hash (only when set via `with_ext_data`)
+    /// 11. root_history (only when set via `with_root_history`)
     ///
     /// # Note
     /// This method extracts public inputs from the circuit struct. Groth16's `prove()` function
     /// extracts them from the constraint system in the same order. The values should match exactly.
     pub fn get_public_inputs(&self) -> Vec<Fr> {
-        vec![
-            self.vortex,
-            self.root,
-            self.public_amount,
-            self.input_nullifier_0,
-            self.input_nullifier_1,
-            self.output_commitment_0,
-            self.output_commitment_1,
-            self.hashed_account_secret,
-        ]
+        let mut inputs = Vec::with_capacity(NI + NO + 8 + self.root_history.len());
+        inputs.extend([self.vortex, self.root, self.public_amount]);
+        inputs.extend(self.input_nullifiers);
+        inputs.extend(self.output_commitments);
+        inputs.push(self.hashed_account_secret);
+        inputs.extend(self.asset_id);
+        inputs.extend(self.association_root);
+        inputs.extend(self.fee);
+        inputs.extend(self.ext_data_hash);
+        inputs.extend(&self.root_history);
+        inputs
     }
 
     /// Returns serialized public inputs in compressed format.
@@ -207,8 +596,12 @@ impl TransactionCircuit {
     }
 }
 
-impl ConstraintSynthesizer<Fr> for TransactionCircuit {
+impl<const NI: usize, const NO: usize, const L: usize> ConstraintSynthesizer<Fr>
+    for TransactionCircuit<NI, NO, L>
+{
     fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> r1cs::Result<()> {
+        let commitment_domain = self.version.domain_tag(HashDomain::Commitment);
+
         // ============================================
         // ALLOCATE PUBLIC INPUTS
         // Order must match Move contract's verification expectations
@@ -220,27 +613,41 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
 
         let public_amount = FpVar::new_input(ns!(cs, "public_amount"), || Ok(self.public_amount))?;
 
-        let input_nullifier_0 =
-            FpVar::new_input(ns!(cs, "input_nullifier_0"), || Ok(self.input_nullifier_0))?;
-
-        let input_nullifier_1 =
-            FpVar::new_input(ns!(cs, "input_nullifier_1"), || Ok(self.input_nullifier_1))?;
-
-        let output_commitment_0 = FpVar::new_input(ns!(cs, "output_commitment_0"), || {
-            Ok(self.output_commitment_0)
-        })?;
+        let input_nullifiers = alloc_inputs(&cs, &self.input_nullifiers)?;
 
-        let output_commitment_1 = FpVar::new_input(ns!(cs, "output_commitment_1"), || {
-            Ok(self.output_commitment_1)
-        })?;
+        let output_commitment = alloc_inputs(&cs, &self.output_commitments)?;
 
         let hashed_account_secret = FpVar::new_input(ns!(cs, "hashed_account_secret"), || {
             Ok(self.hashed_account_secret)
         })?;
 
-        // Create arrays from individual variables for use in loops
-        let input_nullifiers = [input_nullifier_0, input_nullifier_1];
-        let output_commitment = [output_commitment_0, output_commitment_1];
+        let asset_id = self
+            .asset_id
+            .map(|asset_id| FpVar::new_input(ns!(cs, "asset_id"), || Ok(asset_id)))
+            .transpose()?;
+
+        let association_root = self
+            .association_root
+            .map(|root| FpVar::new_input(ns!(cs, "association_root"), || Ok(root)))
+            .transpose()?;
+
+        let fee = self
+            .fee
+            .map(|fee| FpVar::new_input(ns!(cs, "fee"), || Ok(fee)))
+            .transpose()?;
+
+        let ext_data_hash = self
+            .ext_data_hash
+            .map(|hash| FpVar::new_input(ns!(cs, "ext_data_hash"), || Ok(hash)))
+            .transpose()?;
+
+        let accepted_roots = std::iter::once(Ok(root))
+            .chain(
+                self.root_history
+                    .iter()
+                    .map(|root| FpVar::new_input(ns!(cs, "root_history"), || Ok(*root))),
+            )
+            .collect::<r1cs::Result<Vec<_>>>()?;
 
         // ============================================
         // ALLOCATE PRIVATE WITNESS INPUTS
@@ -248,54 +655,37 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
         let account_secret =
             FpVar::new_witness(ns!(cs, "account_secret"), || Ok(self.account_secret))?;
 
-        let in_private_key = [
-            FpVar::new_witness(ns!(cs, "in_private_key_0"), || Ok(self.in_private_keys[0]))?,
-            FpVar::new_witness(ns!(cs, "in_private_key_1"), || Ok(self.in_private_keys[1]))?,
-        ];
-
-        let in_amounts = [
-            FpVar::new_witness(ns!(cs, "in_amount_0"), || Ok(self.in_amounts[0]))?,
-            FpVar::new_witness(ns!(cs, "in_amount_1"), || Ok(self.in_amounts[1]))?,
-        ];
-
-        let in_blindings = [
-            FpVar::new_witness(ns!(cs, "in_blinding_0"), || Ok(self.in_blindings[0]))?,
-            FpVar::new_witness(ns!(cs, "in_blinding_1"), || Ok(self.in_blindings[1]))?,
-        ];
-
-        let in_path_indices = [
-            FpVar::new_witness(ns!(cs, "in_path_index_0"), || Ok(self.in_path_indices[0]))?,
-            FpVar::new_witness(ns!(cs, "in_path_index_1"), || Ok(self.in_path_indices[1]))?,
-        ];
-
-        let merkle_paths = [
-            PathVar::new_witness(ns!(cs, "merkle_path_0"), || Ok(self.merkle_paths[0]))?,
-            PathVar::new_witness(ns!(cs, "merkle_path_1"), || Ok(self.merkle_paths[1]))?,
-        ];
+        let in_private_key = alloc_witnesses(&cs, &self.in_private_keys)?;
+        let in_amounts = alloc_witnesses(&cs, &self.in_amounts)?;
+        let in_blindings = alloc_witnesses(&cs, &self.in_blindings)?;
+        let in_path_indices = alloc_witnesses(&cs, &self.in_path_indices)?;
+
+        let merkle_paths = self
+            .merkle_paths
+            .iter()
+            .map(|path| PathVar::new_witness(ns!(cs, "merkle_path"), || Ok(*path)))
+            .collect::<r1cs::Result<Vec<_>>>()?;
+
+        let association_paths = match association_root {
+            Some(_) => self
+                .association_paths
+                .iter()
+                .map(|path| PathVar::new_witness(ns!(cs, "association_path"), || Ok(*path)))
+                .collect::<r1cs::Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
 
         // Allocate output witnesses early (before input processing)
         // This improves constraint ordering and can help with optimization
-        let out_public_key = [
-            FpVar::new_witness(ns!(cs, "out_public_key_0"), || Ok(self.out_public_keys[0]))?,
-            FpVar::new_witness(ns!(cs, "out_public_key_1"), || Ok(self.out_public_keys[1]))?,
-        ];
-
-        let out_amounts = [
-            FpVar::new_witness(ns!(cs, "out_amount_0"), || Ok(self.out_amounts[0]))?,
-            FpVar::new_witness(ns!(cs, "out_amount_1"), || Ok(self.out_amounts[1]))?,
-        ];
-
-        let out_blindings = [
-            FpVar::new_witness(ns!(cs, "out_blinding_0"), || Ok(self.out_blindings[0]))?,
-            FpVar::new_witness(ns!(cs, "out_blinding_1"), || Ok(self.out_blindings[1]))?,
-        ];
+        let out_public_key = alloc_witnesses(&cs, &self.out_public_keys)?;
+        let out_amounts = alloc_witnesses(&cs, &self.out_amounts)?;
+        let out_blindings = alloc_witnesses(&cs, &self.out_blindings)?;
 
         // ============================================
         // CREATE HASHERS (constants, no allocation needed)
         // ============================================
         let hasher_t2 = PoseidonOptimizedVar::new_t2();
         let hasher_t3 = PoseidonOptimizedVar::new_t3();
-        let hasher_t4 = PoseidonOptimizedVar::new_t4();
         let hasher_t5 = PoseidonOptimizedVar::new_t5();
 
         // ============================================
@@ -319,20 +709,27 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
         // ============================================
         let mut sum_ins = FpVar::<Fr>::zero();
 
-        for i in 0..N_INS {
+        for i in 0..NI {
             // Derive public key from private key: pubkey = Poseidon1(privkey)
             let public_key = hasher_t2.hash1(&in_private_key[i])?;
 
-            // Calculate commitment: commitment = Poseidon3(amount, pubkey, blinding)
-            let commitment =
-                hasher_t5.hash4(&in_amounts[i], &public_key, &in_blindings[i], &vortex)?;
-
-            // Calculate signature: sig = Poseidon3(privkey, commitment, path_index)
-            let signature =
-                hasher_t4.hash3(&in_private_key[i], &commitment, &in_path_indices[i])?;
+            // Calculate commitment: commitment = Poseidon4(amount, pubkey, blinding, vortex)
+            let commitment = note_commitment_var(
+                &hasher_t5,
+                commitment_domain,
+                &vortex,
+                asset_id.as_ref(),
+                &in_amounts[i],
+                &public_key,
+                &in_blindings[i],
+            )?;
 
-            // Calculate nullifier: nullifier = Poseidon3(commitment, path_index, signature)
-            let nullifier = hasher_t4.hash3(&commitment, &in_path_indices[i], &signature)?;
+            let nullifier = note_nullifier_var(
+                self.version,
+                &in_private_key[i],
+                &commitment,
+                &in_path_indices[i],
+            )?;
 
             // Enforce computed nullifier matches public input
             nullifier.enforce_equal(&input_nullifiers[i])?;
@@ -342,18 +739,35 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
 
             // SECURITY: Range check - ensure input amount fits in MAX_AMOUNT_BITS
             // This prevents overflow attacks
-            enforce_range_check(&in_amounts[i], &amount_is_zero)?;
+            enforce_range_check(self.version, &in_amounts[i], Some(&amount_is_zero))?;
 
             // SECURITY: Verify Merkle proof only if amount is non-zero
             // This optimization reduces constraints for zero-value inputs
-            let merkle_path_membership =
-                merkle_paths[i].check_membership(&root, &commitment, &hasher_t3)?;
+            let amount_is_non_zero = amount_is_zero.not();
+            let merkle_path_membership = if self.version.binds_path_indices() {
+                let (path_root, path_index) =
+                    merkle_paths[i].root_hash_and_index(&commitment, &hasher_t3)?;
+
+                // SECURITY: The nullifier index must be the leaf the path proves,
+                // otherwise one note could be nullified once per index
+                path_index.conditional_enforce_equal(&in_path_indices[i], &amount_is_non_zero)?;
+                is_accepted_root(&accepted_roots, &path_root)?
+            } else {
+                let path_root = merkle_paths[i].root_hash(&commitment, &hasher_t3)?;
+                is_accepted_root(&accepted_roots, &path_root)?
+            };
 
             // Only enforce Merkle membership when amount is non-zero
-            let amount_is_non_zero = amount_is_zero.not();
             merkle_path_membership
                 .conditional_enforce_equal(&Boolean::constant(true), &amount_is_non_zero)?;
 
+            // SECURITY: Non-zero inputs must also belong to the association set
+            if let Some(association_root) = &association_root {
+                association_paths[i]
+                    .check_membership(association_root, &commitment, &hasher_t3)?
+                    .conditional_enforce_equal(&Boolean::constant(true), &amount_is_non_zero)?;
+            }
+
             sum_ins += &in_amounts[i];
         }
 
@@ -362,21 +776,23 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
         // ============================================
         let mut sum_outs = FpVar::<Fr>::zero();
 
-        for i in 0..N_OUTS {
-            // Calculate output commitment: commitment = Poseidon3(amount, pubkey, blinding)
-            let expected_commitment = hasher_t5.hash4(
+        for i in 0..NO {
+            // Calculate output commitment: commitment = Poseidon4(amount, pubkey, blinding, vortex)
+            let expected_commitment = note_commitment_var(
+                &hasher_t5,
+                commitment_domain,
+                &vortex,
+                asset_id.as_ref(),
                 &out_amounts[i],
                 &out_public_key[i],
                 &out_blindings[i],
-                &vortex,
             )?;
 
             // Enforce computed commitment matches public input
             expected_commitment.enforce_equal(&output_commitment[i])?;
 
             // SECURITY: Range check - ensure output amount fits in MAX_AMOUNT_BITS
-            let amount_is_zero = out_amounts[i].is_eq(&zero)?;
-            enforce_range_check(&out_amounts[i], &amount_is_zero)?;
+            enforce_range_check(self.version, &out_amounts[i], None)?;
 
             sum_outs += &out_amounts[i];
         }
@@ -386,16 +802,75 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
         // ============================================
         // SECURITY: Prevent using same nullifier twice in one transaction
         //
-        // Optimization: For N_INS=2, we only need 1 comparison (nullifiers[0] != nullifiers[1])
-        // This is the minimal constraint set - exactly 1 enforce_not_equal constraint.
-        //
-        // Alternative approaches considered:
-        // - Loop over all pairs: Same constraint count for N_INS=2, but adds loop overhead
-        // - Product of differences: More expensive (requires multiplications)
-        // - Direct check: Optimal for fixed N_INS=2, explicit and clear
-        //
-        // If N_INS changes in the future, generalize to: for i in 0..N_INS { for j in (i+1)..N_INS { ... } }
-        input_nullifiers[0].enforce_not_equal(&input_nullifiers[1])?;
+        // Every pair is compared, i.e. NI * (NI - 1) / 2 enforce_not_equal constraints:
+        // exactly 1 for the 2-input circuit and 120 for the 16-input one.
+        for i in 0..NI {
+            for j in (i + 1)..NI {
+                input_nullifiers[i].enforce_not_equal(&input_nullifiers[j])?;
+            }
+        }
+
+        // ============================================
+        // VERIFY RELAYER FEE
+        // ============================================
+        // SECURITY: The fee is a public input, so a relayer cannot change it
+        // without invalidating the proof. On withdrawals (public_amount = p - w)
+        // it must not exceed the withdrawn amount w: w - fee stays below
+        // 2^MAX_AMOUNT_BITS only when fee <= w, since both are range checked.
+        let withdrawn = public_amount.negate()?;
+        let mut is_withdrawal = None;
+        if let Some(fee) = &fee {
+            enforce_range_check(self.version, fee, None)?;
+
+            let withdrawal = fits_amount_bits(&withdrawn)? & !withdrawn.is_eq(&zero)?;
+            let remaining = &withdrawn - fee;
+            fits_amount_bits(&remaining)?
+                .conditional_enforce_equal(&Boolean::constant(true), &withdrawal)?;
+            is_withdrawal = Some(withdrawal);
+        }
+
+        // ============================================
+        // VERIFY EXTERNAL DATA
+        // ============================================
+        // SECURITY: Recipient, relayer and fee are hashed in-circuit, so a
+        // relayer cannot redirect a withdrawal or raise its fee without
+        // invalidating the proof. With the fee public input, the hashed fee is
+        // that input rather than a separate witness. Withdrawals must name a
+        // recipient, otherwise the contract would send the funds to address 0x0.
+        if let Some(ext_data_hash) = &ext_data_hash {
+            let recipient =
+                FpVar::new_witness(ns!(cs, "recipient"), || Ok(self.ext_data.recipient))?;
+            let is_withdrawal = match is_withdrawal {
+                Some(is_withdrawal) => is_withdrawal,
+                None => fits_amount_bits(&withdrawn)? & !withdrawn.is_eq(&zero)?,
+            };
+            recipient
+                .is_eq(&zero)?
+                .conditional_enforce_equal(&Boolean::constant(false), &is_withdrawal)?;
+            let relayer = FpVar::new_witness(ns!(cs, "relayer"), || Ok(self.ext_data.relayer))?;
+            let encrypted_output_hash =
+                FpVar::new_witness(ns!(cs, "encrypted_output_hash"), || {
+                    Ok(self.ext_data.encrypted_output_hash)
+                })?;
+            let ext_fee = match &fee {
+                Some(fee) => fee.clone(),
+                None => FpVar::new_witness(ns!(cs, "ext_data_fee"), || Ok(self.ext_data.fee))?,
+            };
+
+            self.ext_data_hasher
+                .hash_var(&recipient, &relayer, &ext_fee, &encrypted_output_hash)?
+                .enforce_equal(ext_data_hash)?;
+        }
+
+        // ============================================
+        // BIND VORTEX
+        // ============================================
+        // SECURITY: Asset-bound commitments do not hash `vortex`. Squaring it
+        // keeps the public input in a constraint, so it cannot be swapped on a
+        // valid proof.
+        if asset_id.is_some() {
+            let _vortex_square = &vortex * &vortex;
+        }
 
         // ============================================
         // VERIFY AMOUNT CONSERVATION
@@ -408,7 +883,214 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
     }
 }
 
-/// Optimized range check: ensures `value` < 2^MAX_AMOUNT_BITS
+/// Computes a note commitment natively.
+///
+/// Without an asset this is the single-asset `Poseidon4(amount, pubkey, blinding, vortex)`,
+/// with one it is `Poseidon4(amount, asset_id, pubkey, blinding)`.
+pub fn note_commitment(
+    version: CircuitVersion,
+    vortex: Fr,
+    asset_id: Option<Fr>,
+    amount: Fr,
+    public_key: Fr,
+    blinding: Fr,
+) -> Fr {
+    let inputs = match asset_id {
+        Some(asset_id) => [amount, asset_id, public_key, blinding],
+        None => [amount, public_key, blinding, vortex],
+    };
+    hasher_t5().hash_with_domain(&inputs, version.domain_tag(HashDomain::Commitment))
+}
+
+/// Commitment of the canonical empty output: a zero-amount note with zero
+/// public key and blinding.
+///
+/// It is the same in every transaction, so a single-output transfer can fill
+/// its second slot with it and publish
+/// [`EMPTY_OUTPUT_MARKER`](crate::note::EMPTY_OUTPUT_MARKER) instead of a
+/// ciphertext. This reveals the transfer has one real output.
+pub fn empty_output_commitment(version: CircuitVersion, vortex: Fr, asset_id: Option<Fr>) -> Fr {
+    note_commitment(version, vortex, asset_id, Fr::ZERO, Fr::ZERO, Fr::ZERO)
+}
+
+/// Computes the nullifier of the note `commitment` at leaf `path_index` natively.
+///
+/// `Poseidon3(commitment, path_index, Poseidon3(private_key, commitment, path_index))`,
+/// or `Poseidon3(private_key, commitment, path_index)` for versions that
+/// [derive it from the key](CircuitVersion::derives_nullifier_from_key).
+pub fn note_nullifier(
+    version: CircuitVersion,
+    private_key: Fr,
+    commitment: Fr,
+    path_index: Fr,
+) -> Fr {
+    let hasher = hasher_t4();
+    if version.derives_nullifier_from_key() {
+        return hasher.hash_with_domain(
+            &[private_key, commitment, path_index],
+            version.domain_tag(HashDomain::Nullifier),
+        );
+    }
+
+    let signature = hasher.hash_with_domain(
+        &[private_key, commitment, path_index],
+        version.domain_tag(HashDomain::Signature),
+    );
+    hasher.hash_with_domain(
+        &[commitment, path_index, signature],
+        version.domain_tag(HashDomain::Nullifier),
+    )
+}
+
+/// Commitments of one note before and after moving it into an asset-bound pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetMigration {
+    /// Single-asset commitment the note is currently stored under
+    pub legacy: Fr,
+    /// Commitment of the same note bound to `asset_id`
+    pub migrated: Fr,
+}
+
+/// Maps a single-asset note onto its asset-bound commitment.
+///
+/// Legacy notes cannot be spent by an asset-bound circuit, so wallets migrate
+/// by withdrawing them through the legacy circuit and depositing `migrated`.
+/// The legacy commitment is returned so stored notes can be matched first.
+pub fn migrate_commitment(
+    version: CircuitVersion,
+    vortex: Fr,
+    asset_id: Fr,
+    amount: Fr,
+    public_key: Fr,
+    blinding: Fr,
+) -> AssetMigration {
+    AssetMigration {
+        legacy: note_commitment(version, vortex, None, amount, public_key, blinding),
+        migrated: note_commitment(
+            version,
+            vortex,
+            Some(asset_id),
+            amount,
+            public_key,
+            blinding,
+        ),
+    }
+}
+
+pub(crate) fn note_commitment_var(
+    hasher: &PoseidonOptimizedVar,
+    domain: Fr,
+    vortex: &FpVar<Fr>,
+    asset_id: Option<&FpVar<Fr>>,
+    amount: &FpVar<Fr>,
+    public_key: &FpVar<Fr>,
+    blinding: &FpVar<Fr>,
+) -> r1cs::Result<FpVar<Fr>> {
+    let inputs = match asset_id {
+        Some(asset_id) => [
+            amount.clone(),
+            asset_id.clone(),
+            public_key.clone(),
+            blinding.clone(),
+        ],
+        None => [
+            amount.clone(),
+            public_key.clone(),
+            blinding.clone(),
+            vortex.clone(),
+        ],
+    };
+    hasher.hash_with_domain(&inputs, domain)
+}
+
+/// In-circuit counterpart of [`note_nullifier`].
+pub(crate) fn note_nullifier_var(
+    version: CircuitVersion,
+    private_key: &FpVar<Fr>,
+    commitment: &FpVar<Fr>,
+    path_index: &FpVar<Fr>,
+) -> r1cs::Result<FpVar<Fr>> {
+    let nullifier_domain = version.domain_tag(HashDomain::Nullifier);
+    let hasher = PoseidonOptimizedVar::new_t4();
+    if version.derives_nullifier_from_key() {
+        return hasher.hash_with_domain(
+            &[private_key.clone(), commitment.clone(), path_index.clone()],
+            nullifier_domain,
+        );
+    }
+
+    // Calculate signature: sig = Poseidon3(privkey, commitment, path_index)
+    let signature = hasher.hash_with_domain(
+        &[private_key.clone(), commitment.clone(), path_index.clone()],
+        version.domain_tag(HashDomain::Signature),
+    )?;
+    // Calculate nullifier: nullifier = Poseidon3(commitment, path_index, signature)
+    hasher.hash_with_domain(
+        &[commitment.clone(), path_index.clone(), signature],
+        nullifier_domain,
+    )
+}
+
+/// Returns whether `path_root` is one of `accepted_roots`. With a single root
+/// this is the plain equality check of the deployed circuit.
+fn is_accepted_root(
+    accepted_roots: &[FpVar<Fr>],
+    path_root: &FpVar<Fr>,
+) -> r1cs::Result<Boolean<Fr>> {
+    let matches = accepted_roots
+        .iter()
+        .map(|root| root.is_eq(path_root))
+        .collect::<r1cs::Result<Vec<_>>>()?;
+    Boolean::kary_or(&matches)
+}
+
+fn alloc_inputs(cs: &ConstraintSystemRef<Fr>, values: &[Fr]) -> r1cs::Result<Vec<FpVar<Fr>>> {
+    values
+        .iter()
+        .map(|value| FpVar::new_input(cs.clone(), || Ok(*value)))
+        .collect()
+}
+
+pub(crate) fn alloc_witnesses(
+    cs: &ConstraintSystemRef<Fr>,
+    values: &[Fr],
+) -> r1cs::Result<Vec<FpVar<Fr>>> {
+    values
+        .iter()
+        .map(|value| FpVar::new_witness(cs.clone(), || Ok(*value)))
+        .collect()
+}
+
+/// Returns whether `value` < 2^MAX_AMOUNT_BITS, i.e. its bits [MAX_AMOUNT_BITS..254) are zero.
+fn fits_amount_bits(value: &FpVar<Fr>) -> r1cs::Result<Boolean<Fr>> {
+    use ark_r1cs_std::prelude::ToBitsGadget;
+
+    let value_bits = value.to_bits_le()?;
+    Ok(!Boolean::kary_or(&value_bits[MAX_AMOUNT_BITS..])?)
+}
+
+/// Range check: ensures `value` < 2^MAX_AMOUNT_BITS, using the gadget of `version`.
+///
+/// V1 keeps the deployed layout, where zero values skip the check; later
+/// versions use [`enforce_amount_limbs`], which accepts zero unconditionally.
+/// `value_is_zero` is only used by V1, which computes it when the caller has
+/// not, so later versions pay nothing for it.
+pub(crate) fn enforce_range_check(
+    version: CircuitVersion,
+    value: &FpVar<Fr>,
+    value_is_zero: Option<&Boolean<Fr>>,
+) -> r1cs::Result<()> {
+    if version.decomposes_amounts_into_limbs() {
+        return enforce_amount_limbs(value).map(|_| ());
+    }
+
+    match value_is_zero {
+        Some(value_is_zero) => enforce_upper_bits_zero(value, value_is_zero),
+        None => enforce_upper_bits_zero(value, &value.is_eq(&FpVar::zero())?),
+    }
+}
+
+/// Range check of the V1 circuit: ensures `value` < 2^MAX_AMOUNT_BITS
 ///
 /// More efficient than Circom's Num2Bits approach: instead of reconstructing from 248 bits,
 /// we only check that the upper 6 bits [248..254) are zero when value is non-zero.
@@ -419,17 +1101,11 @@ impl ConstraintSynthesizer<Fr> for TransactionCircuit {
 /// * `value_is_zero` - Boolean indicating if value is zero (skip check if true)
 ///
 /// # Constraints
-/// - Always: ~254 constraints for bit decomposition (unavoidable with ark_r1cs_std)
+/// - Always: ~254 constraints for bit decomposition plus the canonical
+///   representation check performed by `to_bits_le()`
 /// - When value_is_zero = true: Only bit decomposition, no range check constraints
 /// - When value_is_zero = false: Bit decomposition + 6 conditional equality checks
-///
-/// # Note on Optimization
-/// Unfortunately, ark_r1cs_std's `to_bits_le()` always performs full bit decomposition
-/// (~254 constraints) regardless of whether we conditionally use the bits. The optimization
-/// here is that we only enforce the 6 upper-bit checks when the value is non-zero, saving
-/// 6 constraints for zero values. A more efficient implementation would require custom
-/// bit decomposition that can be conditionally skipped entirely.
-fn enforce_range_check(value: &FpVar<Fr>, value_is_zero: &Boolean<Fr>) -> r1cs::Result<()> {
+fn enforce_upper_bits_zero(value: &FpVar<Fr>, value_is_zero: &Boolean<Fr>) -> r1cs::Result<()> {
     use ark_r1cs_std::prelude::ToBitsGadget;
 
     // Decompose value into bits (all 254 bits for BN254 field)
@@ -455,6 +1131,58 @@ fn enforce_range_check(value: &FpVar<Fr>, value_is_zero: &Boolean<Fr>) -> r1cs::
     Ok(())
 }
 
+/// Decomposes `value` into `AMOUNT_LIMBS` little-endian byte limbs, which
+/// enforces `value` < 2^MAX_AMOUNT_BITS.
+///
+/// Each limb is a linear combination of 8 boolean witnesses, so checking a
+/// byte costs its 8 booleanity constraints and recomposing the limbs into
+/// `value` costs one more: 249 constraints in total. 2^MAX_AMOUNT_BITS is
+/// below the BN254 modulus, so the recomposition cannot wrap around and the
+/// canonical-representation check of `to_bits_le()` is not needed. Zero
+/// decomposes into zero limbs, so no `value_is_zero` condition is needed
+/// either.
+pub(crate) fn enforce_amount_limbs(value: &FpVar<Fr>) -> r1cs::Result<Vec<FpVar<Fr>>> {
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_r1cs_std::{alloc::AllocationMode, R1CSVar};
+
+    let cs = value.cs();
+    let mode = if value.is_constant() {
+        AllocationMode::Constant
+    } else {
+        AllocationMode::Witness
+    };
+    // Missing in setup mode, where only the shape of the constraints matters
+    let bigint = value.value().ok().map(|value| value.into_bigint());
+
+    let limbs = (0..AMOUNT_LIMBS)
+        .map(|limb| {
+            let bits = (0..8)
+                .map(|bit| {
+                    Boolean::new_variable(
+                        cs.clone(),
+                        || {
+                            bigint
+                                .map(|value| value.get_bit(limb * 8 + bit))
+                                .ok_or(r1cs::SynthesisError::AssignmentMissing)
+                        },
+                        mode,
+                    )
+                })
+                .collect::<r1cs::Result<Vec<_>>>()?;
+            Boolean::le_bits_to_fp(&bits)
+        })
+        .collect::<r1cs::Result<Vec<_>>>()?;
+
+    let base = Fr::from(256u64);
+    limbs
+        .iter()
+        .rev()
+        .fold(FpVar::zero(), |acc, limb| acc * base + limb)
+        .enforce_equal(value)?;
+
+    Ok(limbs)
+}
+
 #[test]
 fn test_circuit_with_valid_inputs() {
     use crate::poseidon_opt::{hash1, hash3, hash4};
@@ -675,3 +1403,729 @@ fn test_account_secret_verification() {
         );
     }
 }
+
+#[test]
+fn test_key_derived_nullifier_migration() {
+    use crate::builder::{InputUtxo, OutputUtxo, TransactionBuilder};
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(7u64);
+    let private_key = Fr::from(12345u64);
+    let blinding = Fr::from(1u64);
+    let amount = Fr::from(100u64);
+
+    // V3 keeps the V2 commitment, so existing notes open under both versions
+    let leaf = note_commitment(
+        CircuitVersion::V2,
+        vortex,
+        None,
+        amount,
+        hash1(&private_key),
+        blinding,
+    );
+    assert_eq!(
+        leaf,
+        note_commitment(
+            CircuitVersion::V3,
+            vortex,
+            None,
+            amount,
+            hash1(&private_key),
+            blinding
+        )
+    );
+
+    let mut tree =
+        SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(Fr::from(5u64), leaf, &hasher).unwrap();
+
+    let spend = |version: CircuitVersion, path_index: u64| {
+        TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .version(version)
+            .padding_seed(Fr::from(99u64))
+            .input(InputUtxo {
+                amount,
+                blinding,
+                private_key,
+                path_index,
+                path: tree.generate_membership_proof(1).unwrap(),
+            })
+            .output(OutputUtxo {
+                amount,
+                public_key: Fr::from(42u64),
+                blinding: Fr::from(2u64),
+            })
+            .build()
+            .unwrap()
+    };
+    let satisfied = |circuit: Transaction2Circuit| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    let v2 = spend(CircuitVersion::V2, 1);
+    let v3 = spend(CircuitVersion::V3, 1);
+    assert_eq!(
+        v3.input_nullifiers[0],
+        PoseidonOptimized::new_t4().hash_with_domain(
+            &[private_key, leaf, Fr::from(1u64)],
+            Fr::from(crate::constants::DOMAIN_NULLIFIER)
+        )
+    );
+    assert_eq!(
+        v2.input_nullifiers[0],
+        note_nullifier(CircuitVersion::V2, private_key, leaf, Fr::from(1u64))
+    );
+    assert!(satisfied(v2.clone()));
+    assert!(satisfied(v3.clone()));
+
+    // The same note has a different nullifier under each derivation, which is
+    // why a pool must only ever accept one of them
+    assert_ne!(v2.input_nullifiers[0], v3.input_nullifiers[0]);
+    let mut mixed = v3.clone();
+    mixed.input_nullifiers[0] = v2.input_nullifiers[0];
+    assert!(!satisfied(mixed));
+
+    // The nullifier stays bound to the leaf the path proves
+    assert!(!satisfied(spend(CircuitVersion::V3, 0)));
+
+    let v2_report = v2.constraint_report().unwrap();
+    let v3_report = v3.constraint_report().unwrap();
+    assert_eq!(v3_report.poseidon3.count, N_INS);
+    assert_eq!(v2_report.poseidon3.count, 2 * N_INS);
+    assert!(v3_report.total_constraints < v2_report.total_constraints);
+}
+
+#[test]
+fn test_key_derived_nullifier_differs_across_pools() {
+    use crate::poseidon_opt::hash1;
+
+    let private_key = Fr::from(12345u64);
+    let path_index = Fr::from(3u64);
+    let nullifier_in = |vortex: Fr| {
+        let commitment = note_commitment(
+            CircuitVersion::V3,
+            vortex,
+            None,
+            Fr::from(100u64),
+            hash1(&private_key),
+            Fr::from(1u64),
+        );
+        note_nullifier(CircuitVersion::V3, private_key, commitment, path_index)
+    };
+
+    assert_ne!(nullifier_in(Fr::from(7u64)), nullifier_in(Fr::from(8u64)));
+}
+
+#[test]
+fn test_domain_separated_version() {
+    use crate::poseidon_opt::{hash1, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let vortex = Fr::from(0u64);
+    let hasher_t4 = PoseidonOptimized::new_t4();
+    let hasher_t5 = PoseidonOptimized::new_t5();
+
+    let build = |witness_version: CircuitVersion| {
+        let commitment_domain = witness_version.domain_tag(HashDomain::Commitment);
+        let signature_domain = witness_version.domain_tag(HashDomain::Signature);
+        let nullifier_domain = witness_version.domain_tag(HashDomain::Nullifier);
+
+        let private_keys = [Fr::from(12345u64), Fr::from(67890u64)];
+        let blindings = [Fr::from(999u64), Fr::from(888u64)];
+        let path_indices = [Fr::from(0u64), Fr::from(1u64)];
+        let out_blindings = [Fr::from(777u64), Fr::from(666u64)];
+
+        let mut nullifiers = [Fr::ZERO; N_INS];
+        let mut out_commitments = [Fr::ZERO; N_OUTS];
+        let mut public_keys = [Fr::ZERO; N_INS];
+
+        for i in 0..N_INS {
+            public_keys[i] = hash1(&private_keys[i]);
+            let commitment = hasher_t5.hash_with_domain(
+                &[Fr::ZERO, public_keys[i], blindings[i], vortex],
+                commitment_domain,
+            );
+            let signature = hasher_t4.hash_with_domain(
+                &[private_keys[i], commitment, path_indices[i]],
+                signature_domain,
+            );
+            nullifiers[i] = hasher_t4
+                .hash_with_domain(&[commitment, path_indices[i], signature], nullifier_domain);
+            out_commitments[i] = hasher_t5.hash_with_domain(
+                &[Fr::ZERO, public_keys[i], out_blindings[i], vortex],
+                commitment_domain,
+            );
+        }
+
+        TransactionCircuit::new(
+            vortex,
+            Fr::ZERO,
+            Fr::ZERO,
+            nullifiers[0],
+            nullifiers[1],
+            out_commitments[0],
+            out_commitments[1],
+            Fr::ZERO,
+            Fr::ZERO,
+            private_keys,
+            [Fr::ZERO; N_INS],
+            blindings,
+            path_indices,
+            [Path::empty(), Path::empty()],
+            public_keys,
+            [Fr::ZERO; N_OUTS],
+            out_blindings,
+        )
+        .unwrap()
+    };
+
+    // V2 witnesses satisfy the V2 circuit
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    build(CircuitVersion::V2)
+        .with_version(CircuitVersion::V2)
+        .generate_constraints(cs.clone())
+        .unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // Untagged V1 witnesses are rejected by the V2 circuit
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    build(CircuitVersion::V1)
+        .with_version(CircuitVersion::V2)
+        .generate_constraints(cs.clone())
+        .unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+
+    assert_eq!("v2".parse::<CircuitVersion>().unwrap(), CircuitVersion::V2);
+    assert_eq!("v3".parse::<CircuitVersion>().unwrap(), CircuitVersion::V3);
+    assert!("v4".parse::<CircuitVersion>().is_err());
+}
+
+#[test]
+fn test_transaction16_circuit() {
+    use crate::poseidon_opt::{hash1, hash3, hash4};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let vortex = Fr::from(0u64);
+
+    let mut circuit = Transaction16Circuit::empty();
+    for i in 0..N_INS_16 {
+        let private_key = Fr::from(1000 + i as u64);
+        let blinding = Fr::from(2000 + i as u64);
+        let path_index = Fr::from(i as u64);
+
+        let commitment = hash4(&Fr::ZERO, &hash1(&private_key), &blinding, &vortex);
+        let signature = hash3(&private_key, &commitment, &path_index);
+
+        circuit.in_private_keys[i] = private_key;
+        circuit.in_blindings[i] = blinding;
+        circuit.in_path_indices[i] = path_index;
+        circuit.input_nullifiers[i] = hash3(&commitment, &path_index, &signature);
+    }
+    for i in 0..N_OUTS {
+        circuit.out_public_keys[i] = Fr::from(3000 + i as u64);
+        circuit.out_blindings[i] = Fr::from(4000 + i as u64);
+        circuit.output_commitments[i] = hash4(
+            &Fr::ZERO,
+            &circuit.out_public_keys[i],
+            &circuit.out_blindings[i],
+            &vortex,
+        );
+    }
+
+    assert_eq!(circuit.get_public_inputs().len(), N_INS_16 + N_OUTS + 4);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.clone().generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // Reusing a nullifier within the transaction is rejected
+    circuit.input_nullifiers[N_INS_16 - 1] = circuit.input_nullifiers[0];
+    circuit.in_private_keys[N_INS_16 - 1] = circuit.in_private_keys[0];
+    circuit.in_blindings[N_INS_16 - 1] = circuit.in_blindings[0];
+    circuit.in_path_indices[N_INS_16 - 1] = circuit.in_path_indices[0];
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let synthesized = circuit.generate_constraints(cs.clone());
+    assert!(synthesized.is_err() || !cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_transaction1_circuit_is_smaller() {
+    let lite = Transaction1Circuit::empty().constraint_report().unwrap();
+    let full = Transaction2Circuit::empty().constraint_report().unwrap();
+
+    assert_eq!(
+        Transaction1Circuit::empty().get_public_inputs().len(),
+        N_INS_1 + N_OUTS + 4
+    );
+    // Inputs dominate the constraint count, outputs are a small share
+    assert!(
+        lite.total_constraints * 10 < full.total_constraints * 7,
+        "{} vs {} constraints",
+        lite.total_constraints,
+        full.total_constraints
+    );
+
+    assert_eq!(CircuitKind::default().n_ins(), N_INS);
+    assert_eq!(CircuitKind::Transaction1.n_ins(), N_INS_1);
+    assert_eq!(
+        "transaction16".parse::<CircuitKind>().unwrap().n_ins(),
+        N_INS_16
+    );
+    assert!("transaction3".parse::<CircuitKind>().is_err());
+}
+
+#[test]
+fn test_select_root_picks_root_of_paths() {
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, hash3, hash4, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(0u64);
+
+    let mut circuit = TransactionCircuit::<1, 1>::empty();
+    circuit.in_private_keys[0] = Fr::from(12345u64);
+    circuit.in_amounts[0] = Fr::from(10u64);
+    circuit.in_blindings[0] = Fr::from(999u64);
+    let commitment = circuit.input_commitment(0);
+
+    let mut tree =
+        SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(commitment, Fr::from(1u64), &hasher)
+        .unwrap();
+    circuit.merkle_paths[0] = tree.generate_membership_proof(0).unwrap();
+    let path_root = tree.root();
+
+    tree.insert_pair(Fr::from(2u64), Fr::from(3u64), &hasher)
+        .unwrap();
+    let latest_root = tree.root();
+
+    let signature = hash3(&circuit.in_private_keys[0], &commitment, &Fr::ZERO);
+    circuit.input_nullifiers[0] = hash3(&commitment, &Fr::ZERO, &signature);
+    circuit.out_public_keys[0] = Fr::from(42u64);
+    circuit.out_amounts[0] = Fr::from(10u64);
+    circuit.output_commitments[0] = hash4(&Fr::from(10u64), &Fr::from(42u64), &Fr::ZERO, &vortex);
+
+    assert!(circuit.clone().select_root(&[latest_root]).is_err());
+
+    let circuit = circuit.select_root(&[latest_root, path_root]).unwrap();
+    assert_eq!(circuit.root, path_root);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_root_history_accepts_previous_roots() {
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, hash3, hash4, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(0u64);
+
+    let mut circuit = TransactionCircuit::<1, 1>::empty();
+    circuit.in_private_keys[0] = Fr::from(12345u64);
+    circuit.in_amounts[0] = Fr::from(10u64);
+    circuit.in_blindings[0] = Fr::from(999u64);
+    let commitment = circuit.input_commitment(0);
+
+    let mut tree =
+        SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(commitment, Fr::from(1u64), &hasher)
+        .unwrap();
+    circuit.merkle_paths[0] = tree.generate_membership_proof(0).unwrap();
+    let path_root = tree.root();
+
+    tree.insert_pair(Fr::from(2u64), Fr::from(3u64), &hasher)
+        .unwrap();
+    circuit.root = tree.root();
+
+    let signature = hash3(&circuit.in_private_keys[0], &commitment, &Fr::ZERO);
+    circuit.input_nullifiers[0] = hash3(&commitment, &Fr::ZERO, &signature);
+    circuit.out_public_keys[0] = Fr::from(42u64);
+    circuit.out_amounts[0] = Fr::from(10u64);
+    circuit.output_commitments[0] = hash4(&Fr::from(10u64), &Fr::from(42u64), &Fr::ZERO, &vortex);
+
+    let satisfied = |circuit: TransactionCircuit<1, 1>| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    assert!(!satisfied(circuit.clone()));
+    assert!(!satisfied(
+        circuit.clone().with_root_history(vec![Fr::from(5u64)])
+    ));
+    let with_history = circuit.with_root_history(vec![Fr::from(5u64), path_root]);
+    assert!(satisfied(with_history.clone()));
+    assert_eq!(with_history.get_public_inputs().last(), Some(&path_root));
+    assert_eq!(
+        with_history.descriptor().public_input_layout[6..],
+        ["root_history_0", "root_history_1"]
+    );
+}
+
+#[test]
+fn test_fee_bounded_by_withdrawal() {
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, hash3, hash4, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(0u64);
+
+    // Spend a 100 note, withdraw 30 and keep 70 as change
+    let mut circuit = TransactionCircuit::<1, 1>::empty();
+    circuit.in_private_keys[0] = Fr::from(12345u64);
+    circuit.in_amounts[0] = Fr::from(100u64);
+    circuit.in_blindings[0] = Fr::from(999u64);
+    let commitment = circuit.input_commitment(0);
+
+    let mut tree =
+        SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(commitment, Fr::from(1u64), &hasher)
+        .unwrap();
+    circuit.merkle_paths[0] = tree.generate_membership_proof(0).unwrap();
+    circuit.root = tree.root();
+
+    let signature = hash3(&circuit.in_private_keys[0], &commitment, &Fr::ZERO);
+    circuit.input_nullifiers[0] = hash3(&commitment, &Fr::ZERO, &signature);
+    circuit.public_amount = -Fr::from(30u64);
+    circuit.out_public_keys[0] = Fr::from(42u64);
+    circuit.out_amounts[0] = Fr::from(70u64);
+    circuit.output_commitments[0] = hash4(&Fr::from(70u64), &Fr::from(42u64), &Fr::ZERO, &vortex);
+
+    let satisfied = |circuit: TransactionCircuit<1, 1>| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    assert!(satisfied(circuit.clone()));
+    assert!(satisfied(circuit.clone().with_fee(Fr::from(30u64))));
+    assert!(!satisfied(circuit.clone().with_fee(Fr::from(31u64))));
+    assert!(!satisfied(circuit.clone().with_fee(-Fr::from(1u64))));
+
+    // Withdrawals bound to ext data must name a recipient
+    let paid_to = |recipient| ExtData {
+        recipient,
+        ..ExtData::default()
+    };
+    assert!(satisfied(circuit.clone().with_ext_data(
+        paid_to(Fr::from(8u64)),
+        ExtDataHasher::Poseidon
+    )));
+    assert!(!satisfied(
+        circuit
+            .clone()
+            .with_ext_data(paid_to(Fr::ZERO), ExtDataHasher::Poseidon)
+    ));
+
+    let with_fee = circuit.with_fee(Fr::from(5u64));
+    assert_eq!(with_fee.get_public_inputs().last(), Some(&Fr::from(5u64)));
+}
+
+#[test]
+fn test_asset_bound_commitments() {
+    use crate::poseidon_opt::hash3;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let vortex = Fr::from(7u64);
+    let asset_id = Fr::from(2u64);
+
+    let mut circuit = TransactionCircuit::<1, 1>::empty().with_asset_id(asset_id);
+    circuit.vortex = vortex;
+    circuit.in_private_keys[0] = Fr::from(12345u64);
+    circuit.in_blindings[0] = Fr::from(999u64);
+    let commitment = circuit.input_commitment(0);
+    let signature = hash3(&circuit.in_private_keys[0], &commitment, &Fr::ZERO);
+    circuit.input_nullifiers[0] = hash3(&commitment, &Fr::ZERO, &signature);
+    circuit.out_public_keys[0] = Fr::from(42u64);
+    circuit.out_blindings[0] = Fr::from(777u64);
+    circuit.output_commitments[0] = circuit.output_commitment(0);
+
+    let migration = migrate_commitment(
+        CircuitVersion::V1,
+        vortex,
+        asset_id,
+        Fr::ZERO,
+        Fr::from(42u64),
+        Fr::from(777u64),
+    );
+    assert_eq!(migration.migrated, circuit.output_commitments[0]);
+    assert_ne!(migration.legacy, migration.migrated);
+
+    assert_eq!(circuit.get_public_inputs().len(), 1 + 1 + 5);
+    assert_eq!(circuit.get_public_inputs().last(), Some(&asset_id));
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.clone().generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // Notes of another asset do not open under this asset id
+    circuit.asset_id = Some(Fr::from(3u64));
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn test_association_set_membership() {
+    use crate::association::AssociationSet;
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, hash3, hash4, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(0u64);
+
+    let mut circuit = TransactionCircuit::<1, 1>::empty();
+    circuit.in_private_keys[0] = Fr::from(12345u64);
+    circuit.in_amounts[0] = Fr::from(10u64);
+    circuit.in_blindings[0] = Fr::from(999u64);
+    let commitment = circuit.input_commitment(0);
+
+    let mut tree =
+        SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(Fr::from(5u64), commitment, &hasher)
+        .unwrap();
+    circuit.merkle_paths[0] = tree.generate_membership_proof(1).unwrap();
+    circuit.root = tree.root();
+
+    let signature = hash3(&circuit.in_private_keys[0], &commitment, &Fr::from(1u64));
+    circuit.in_path_indices[0] = Fr::from(1u64);
+    circuit.input_nullifiers[0] = hash3(&commitment, &Fr::from(1u64), &signature);
+    circuit.out_public_keys[0] = Fr::from(42u64);
+    circuit.out_amounts[0] = Fr::from(10u64);
+    circuit.output_commitments[0] = hash4(&Fr::from(10u64), &Fr::from(42u64), &Fr::ZERO, &vortex);
+
+    let satisfied = |circuit: TransactionCircuit<1, 1>| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    let approved = AssociationSet::new(&[Fr::from(7u64), commitment, Fr::from(8u64)]).unwrap();
+    let path = approved.proof(&commitment).unwrap();
+    let with_association = circuit.clone().with_association(approved.root(), [path]);
+    assert_eq!(
+        with_association.get_public_inputs().last(),
+        Some(&approved.root())
+    );
+    assert!(satisfied(with_association));
+
+    let excluded = AssociationSet::new(&[Fr::from(7u64), Fr::from(8u64)]).unwrap();
+    assert!(excluded.proof(&commitment).is_err());
+    let forged = circuit.with_association(excluded.root(), [path]);
+    assert!(!satisfied(forged));
+}
+
+#[test]
+fn test_path_index_bound_to_merkle_path() {
+    use crate::builder::{InputUtxo, TransactionBuilder};
+    use crate::constants::ZERO_VALUE;
+    use crate::merkle_tree::SparseMerkleTree;
+    use crate::poseidon_opt::{fr_from_str, PoseidonOptimized};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let hasher = PoseidonOptimized::new_t3();
+    let vortex = Fr::from(7u64);
+    let private_key = Fr::from(12345u64);
+    let amount = Fr::from(100u64);
+    let blinding = Fr::from(1u64);
+
+    let satisfied = |version: CircuitVersion, path_index: u64| {
+        let leaf = note_commitment(version, vortex, None, amount, hash1(&private_key), blinding);
+        let mut tree =
+            SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+        tree.insert_pair(Fr::from(3u64), leaf, &hasher).unwrap();
+
+        let circuit = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, tree.root())
+            .version(version)
+            .padding_seed(Fr::from(99u64))
+            .input(InputUtxo {
+                amount,
+                blinding,
+                private_key,
+                path_index,
+                path: tree.generate_membership_proof(1).unwrap(),
+            })
+            .build()
+            .unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    assert!(satisfied(CircuitVersion::V2, 1));
+    // A different index yields a fresh nullifier for the same note
+    assert!(!satisfied(CircuitVersion::V2, 3));
+    // V1 keeps the deployed constraint system, which leaves the index free
+    assert!(satisfied(CircuitVersion::V1, 3));
+}
+
+#[test]
+fn test_constraint_report() {
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let report = Transaction2Circuit::empty().constraint_report().unwrap();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(ark_relations::r1cs::SynthesisMode::Setup);
+    Transaction2Circuit::empty()
+        .generate_constraints(cs.clone())
+        .unwrap();
+    assert_eq!(report.total_constraints, cs.num_constraints());
+    assert_eq!(
+        report.public_inputs,
+        Transaction2Circuit::empty().get_public_inputs().len()
+    );
+
+    assert_eq!(report.merkle_proofs.count, N_INS);
+    assert_eq!(report.association_proofs.count, 0);
+    assert_eq!(report.range_checks.count, N_INS + N_OUTS);
+    assert_eq!(report.poseidon4.count, N_INS + N_OUTS);
+
+    let accounted = report.merkle_proofs.total()
+        + report.range_checks.total()
+        + report.poseidon1.total()
+        + report.poseidon3.total()
+        + report.poseidon4.total();
+    assert_eq!(accounted + report.other, report.total_constraints);
+
+    // Optional public inputs show up in the breakdown
+    let with_fee = Transaction2Circuit::empty()
+        .with_fee(Fr::ZERO)
+        .constraint_report()
+        .unwrap();
+    assert_eq!(with_fee.range_checks.count, N_INS + N_OUTS + 1);
+    assert!(with_fee.total_constraints > report.total_constraints);
+}
+
+#[test]
+fn test_amount_limb_range_check() {
+    use ark_ff::Field;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let check = |version: CircuitVersion, value: Fr| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let value = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+        let before = cs.num_constraints();
+        enforce_range_check(version, &value, None).unwrap();
+        (cs.is_satisfied().unwrap(), cs.num_constraints() - before)
+    };
+
+    let max = Fr::from(2u64).pow([MAX_AMOUNT_BITS as u64]) - Fr::from(1u64);
+    for value in [Fr::ZERO, Fr::from(1u64), Fr::from(u64::MAX), max] {
+        assert!(check(CircuitVersion::V1, value).0);
+        assert!(check(CircuitVersion::V2, value).0);
+    }
+    for value in [max + Fr::from(1u64), -Fr::from(1u64)] {
+        assert!(!check(CircuitVersion::V1, value).0);
+        assert!(!check(CircuitVersion::V2, value).0);
+    }
+
+    // 8 booleanity constraints per limb plus the recomposition, with no
+    // zero check
+    let (_, limb_cost) = check(CircuitVersion::V2, Fr::from(1u64));
+    let (_, bit_cost) = check(CircuitVersion::V1, Fr::from(1u64));
+    assert_eq!(limb_cost, 8 * AMOUNT_LIMBS + 1);
+    assert!(limb_cost < bit_cost);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let value = FpVar::new_witness(cs, || Ok(Fr::from(0x1234u64))).unwrap();
+    let limbs = enforce_amount_limbs(&value).unwrap();
+    assert_eq!(limbs.len(), AMOUNT_LIMBS);
+    assert_eq!(limbs[0].value().unwrap(), Fr::from(0x34u64));
+    assert_eq!(limbs[1].value().unwrap(), Fr::from(0x12u64));
+}
+
+#[test]
+fn test_ext_data_hash_recomputed() {
+    use crate::builder::TransactionBuilder;
+    use crate::poseidon_opt::hash4;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let satisfied = |circuit: Transaction2Circuit| {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    };
+
+    let ext_data = ExtData {
+        recipient: Fr::from(1u64),
+        relayer: Fr::from(2u64),
+        fee: Fr::from(3u64),
+        encrypted_output_hash: Fr::from(4u64),
+    };
+    let circuit = TransactionBuilder::<N_INS, N_OUTS>::new(Fr::from(7u64), Fr::ZERO)
+        .version(CircuitVersion::V2)
+        .padding_seed(Fr::from(99u64))
+        .build()
+        .unwrap();
+
+    let bound = circuit
+        .clone()
+        .with_ext_data(ext_data, ExtDataHasher::Poseidon);
+    let expected = hash4(
+        &Fr::from(1u64),
+        &Fr::from(2u64),
+        &Fr::from(3u64),
+        &Fr::from(4u64),
+    );
+    assert_eq!(bound.get_public_inputs().last(), Some(&expected));
+    assert!(satisfied(bound.clone()));
+
+    // A relayer cannot swap the recipient or the hash
+    let mut redirected = bound.clone();
+    redirected.ext_data.recipient = Fr::from(5u64);
+    assert!(!satisfied(redirected));
+    let mut forged = bound;
+    forged.ext_data_hash = Some(Fr::from(6u64));
+    assert!(!satisfied(forged));
+
+    let tagged = circuit
+        .clone()
+        .with_ext_data(ext_data, ExtDataHasher::Tagged);
+    assert_ne!(tagged.ext_data_hash, Some(expected));
+    assert!(satisfied(tagged));
+
+    // Transfers need no recipient
+    let no_recipient = ExtData {
+        recipient: Fr::ZERO,
+        ..ext_data
+    };
+    assert!(satisfied(
+        circuit
+            .clone()
+            .with_ext_data(no_recipient, ExtDataHasher::Poseidon)
+    ));
+
+    // The hashed fee is the fee public input
+    assert!(satisfied(
+        circuit
+            .clone()
+            .with_fee(Fr::from(3u64))
+            .with_ext_data(ext_data, ExtDataHasher::Poseidon)
+    ));
+    assert!(!satisfied(
+        circuit
+            .with_fee(Fr::from(4u64))
+            .with_ext_data(ext_data, ExtDataHasher::Poseidon)
+    ));
+}
@@ -0,0 +1,169 @@
+use super::{enforce_range_check, TransactionCircuit};
+use crate::{
+    merkle_tree::{Path, PathVar},
+    poseidon_opt::PoseidonOptimizedVar,
+};
+use ark_bn254::Fr;
+use ark_r1cs_std::{
+    fields::fp::FpVar,
+    prelude::{AllocVar, Boolean, EqGadget, FieldVar},
+};
+use ark_relations::r1cs::{
+    self, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisMode,
+};
+use serde::{Deserialize, Serialize};
+
+/// Constraint cost of one gadget and how often the circuit uses it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GadgetCost {
+    /// Constraints of a single use
+    pub constraints: usize,
+    pub count: usize,
+}
+
+impl GadgetCost {
+    pub fn total(&self) -> usize {
+        self.constraints * self.count
+    }
+}
+
+/// Constraint breakdown of a [`TransactionCircuit`] shape.
+///
+/// Gadget costs are measured by synthesizing each gadget on its own, so
+/// `other` holds whatever the circuit spends outside of them (nullifier
+/// uniqueness, fee bound, conservation, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintReport {
+    pub total_constraints: usize,
+    pub public_inputs: usize,
+    pub witnesses: usize,
+    /// Pool membership of each input, including its Poseidon hashes
+    pub merkle_proofs: GadgetCost,
+    /// Association set membership of each input
+    pub association_proofs: GadgetCost,
+    pub range_checks: GadgetCost,
+    /// Public keys and account secret hash (`Poseidon1`)
+    pub poseidon1: GadgetCost,
+    /// Signatures and nullifiers (`Poseidon3`)
+    pub poseidon3: GadgetCost,
+    /// Note commitments and `ext_data_hash` (`Poseidon4`)
+    pub poseidon4: GadgetCost,
+    pub other: usize,
+}
+
+impl<const NI: usize, const NO: usize, const L: usize> TransactionCircuit<NI, NO, L> {
+    /// Synthesizes the circuit on a fresh constraint system and breaks its
+    /// constraint count down by gadget.
+    ///
+    /// Synthesis runs in setup mode, so witness values are not needed and
+    /// `TransactionCircuit::empty()` with the same builders applied reports the
+    /// same numbers as a real transaction.
+    pub fn constraint_report(&self) -> r1cs::Result<ConstraintReport> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        self.clone().generate_constraints(cs.clone())?;
+
+        let merkle_proof = measure(|cs| {
+            let root = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64)))?;
+            let leaf = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64)))?;
+            let path = PathVar::new_witness(cs.clone(), || Ok(Path::<L>::empty()))?;
+            let hasher = PoseidonOptimizedVar::new_t3();
+            let enabled = Boolean::new_witness(cs, || Ok(false))?;
+
+            let membership = if self.version.binds_path_indices() {
+                let (path_root, index) = path.root_hash_and_index(&leaf, &hasher)?;
+                index.conditional_enforce_equal(&FpVar::zero(), &enabled)?;
+                root.is_eq(&path_root)?
+            } else {
+                path.check_membership(&root, &leaf, &hasher)?
+            };
+            membership.conditional_enforce_equal(&Boolean::constant(true), &enabled)
+        })?;
+        let association_proof = measure(|cs| {
+            let root = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64)))?;
+            let leaf = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64)))?;
+            let path = PathVar::new_witness(cs.clone(), || Ok(Path::<L>::empty()))?;
+            let enabled = Boolean::new_witness(cs, || Ok(false))?;
+            path.check_membership(&root, &leaf, &PoseidonOptimizedVar::new_t3())?
+                .conditional_enforce_equal(&Boolean::constant(true), &enabled)
+        })?;
+        let range_check = measure(|cs| {
+            let value = FpVar::new_witness(cs, || Ok(Fr::from(0u64)))?;
+            enforce_range_check(self.version, &value, None)
+        })?;
+        let poseidon = |hasher: PoseidonOptimizedVar, arity: usize| {
+            measure(|cs| {
+                let inputs = (0..arity)
+                    .map(|_| FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64))))
+                    .collect::<r1cs::Result<Vec<_>>>()?;
+                hasher.hash(&inputs).map(|_| ())
+            })
+        };
+
+        let key_nullifiers = self.version.derives_nullifier_from_key();
+        let report = ConstraintReport {
+            total_constraints: cs.num_constraints(),
+            // The constant one is allocated as an instance variable
+            public_inputs: cs.num_instance_variables() - 1,
+            witnesses: cs.num_witness_variables(),
+            merkle_proofs: GadgetCost {
+                constraints: merkle_proof,
+                count: NI,
+            },
+            association_proofs: GadgetCost {
+                constraints: association_proof,
+                count: if self.association_root.is_some() {
+                    NI
+                } else {
+                    0
+                },
+            },
+            range_checks: GadgetCost {
+                constraints: range_check,
+                count: NI + NO + usize::from(self.fee.is_some()),
+            },
+            poseidon1: GadgetCost {
+                constraints: poseidon(PoseidonOptimizedVar::new_t2(), 1)?,
+                count: 1 + NI,
+            },
+            poseidon3: GadgetCost {
+                constraints: poseidon(PoseidonOptimizedVar::new_t4(), 3)?,
+                count: if key_nullifiers { NI } else { 2 * NI },
+            },
+            poseidon4: GadgetCost {
+                constraints: poseidon(PoseidonOptimizedVar::new_t5(), 4)?,
+                count: NI + NO + usize::from(self.ext_data_hash.is_some()),
+            },
+            other: 0,
+        };
+
+        let accounted = [
+            report.merkle_proofs,
+            report.association_proofs,
+            report.range_checks,
+            report.poseidon1,
+            report.poseidon3,
+            report.poseidon4,
+        ]
+        .iter()
+        .map(GadgetCost::total)
+        .sum::<usize>();
+
+        Ok(ConstraintReport {
+            other: report.total_constraints.saturating_sub(accounted),
+            ..report
+        })
+    }
+}
+
+/// Returns the constraints `gadget` adds to an empty constraint system.
+fn measure(
+    gadget: impl FnOnce(ConstraintSystemRef<Fr>) -> r1cs::Result<()>,
+) -> r1cs::Result<usize> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    gadget(cs.clone())?;
+    Ok(cs.num_constraints())
+}
@@ -4,6 +4,25 @@
 /// Changing this requires redeploying contracts and regenerating keys.
 pub const MERKLE_TREE_LEVEL: usize = 26;
 
+/// Roots the Move contract accepts proofs against, the current one included.
+///
+/// This matches the Sui Move contract's ROOT_HISTORY_SIZE constant.
+pub const ROOT_HISTORY_SIZE: usize = 100;
+
+/// Shallower tree heights keys can be generated for, so devnet and test pools
+/// prove faster. A pool must be deployed with the height of its keys.
+pub const SMALL_TREE_LEVEL: usize = 10;
+pub const MEDIUM_TREE_LEVEL: usize = 16;
+pub const LARGE_TREE_LEVEL: usize = 20;
+
+/// Tree heights `keygen --level` and the prover accept.
+pub const SUPPORTED_TREE_LEVELS: [usize; 4] = [
+    SMALL_TREE_LEVEL,
+    MEDIUM_TREE_LEVEL,
+    LARGE_TREE_LEVEL,
+    MERKLE_TREE_LEVEL,
+];
+
 /// Number of input UTXOs per transaction
 ///
 /// Fixed at 2 for Vortex v1. Each input requires:
@@ -12,6 +31,18 @@ pub const MERKLE_TREE_LEVEL: usize = 26;
 /// - Range check
 pub const N_INS: usize = 2;
 
+/// Number of input UTXOs of the transfer-lite circuit
+///
+/// Most shielded transfers spend a single note. Dropping the second input
+/// halves the Merkle path and nullifier constraints.
+pub const N_INS_1: usize = 1;
+
+/// Number of input UTXOs of the consolidation circuit
+///
+/// Lets a user merge up to 16 notes in one transaction. Duplicate nullifier
+/// checks grow quadratically (120 pairs), everything else linearly.
+pub const N_INS_16: usize = 16;
+
 /// Number of output UTXOs per transaction
 ///
 /// Fixed at 2 for Vortex v1. Each output requires:
@@ -30,6 +61,26 @@ pub const N_OUTS: usize = 2;
 /// this range to prevent arithmetic overflow during sum(inputs) + public_amount.
 pub const MAX_AMOUNT_BITS: usize = 248;
 
+/// Byte limbs an amount is decomposed into by the V2 range check (31)
+pub const AMOUNT_LIMBS: usize = MAX_AMOUNT_BITS / 8;
+
+/// Poseidon domain tags injected as the initial capacity element
+///
+/// Applied from `CircuitVersion::V2`; version 1 hashes with a zero capacity
+/// element. Commitments, nullifiers and signatures therefore never share a hash
+/// domain, so a value valid in one role cannot be replayed in another.
+pub const DOMAIN_COMMITMENT: u64 = 1;
+pub const DOMAIN_NULLIFIER: u64 = 2;
+pub const DOMAIN_SIGNATURE: u64 = 3;
+
+/// Capacity element of `ExtDataHasher::Tagged`, independent of the circuit version
+pub const DOMAIN_EXT_DATA: u64 = 4;
+
+/// Tags separating the keys and blindings derived from a wallet master seed
+pub const DERIVE_SPEND_KEY: u64 = 1;
+pub const DERIVE_VIEWING_KEY: u64 = 2;
+pub const DERIVE_BLINDING: u64 = 3;
+
 pub const ZERO_VALUE: &str =
     "18688842432741139442778047327644092677418528270738216181718229581494125774932";
 
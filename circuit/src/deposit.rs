@@ -0,0 +1,359 @@
+use crate::{
+    builder::{OutputUtxo, TransactionBuilder},
+    circuit::{note_commitment, CircuitVersion, TransactionCircuit},
+    constants::{N_INS, N_OUTS},
+    field::{field_to_hex, FieldEncoding},
+    note::{encrypt_note, EncryptionKey, Note},
+    poseidon_opt::hash1,
+    prover::parse_field_element,
+};
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::Fr;
+use ark_ff::{AdditiveGroup, PrimeField, UniformRand};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Prefix of every [`DepositNote`] string; bumped when the layout changes.
+pub const DEPOSIT_NOTE_PREFIX: &str = "vortex-note-v1";
+
+const DEPOSIT_NOTE_FIELDS: usize = 4;
+
+/// Everything needed to later spend a deposited output: a fresh spend key and
+/// blinding, the amount and the pool it was deposited to.
+///
+/// Encoded as `vortex-note-v1:<vortex>:<amount>:<spendKey>:<blinding>`, with
+/// the amount in decimal and the other fields as 0x-prefixed 32-byte hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositNote {
+    pub vortex: Fr,
+    pub amount: Fr,
+    pub spend_key: Fr,
+    pub blinding: Fr,
+}
+
+impl DepositNote {
+    /// Draws a random spend key and blinding for a deposit of `amount` into `vortex`.
+    pub fn generate<R: RngCore + CryptoRng>(vortex: Fr, amount: Fr, rng: &mut R) -> Self {
+        Self {
+            vortex,
+            amount,
+            spend_key: Fr::rand(rng),
+            blinding: Fr::rand(rng),
+        }
+    }
+
+    pub fn public_key(&self) -> Fr {
+        hash1(&self.spend_key)
+    }
+
+    /// Commitment of the deposit output, as the V1 transaction circuit computes it.
+    pub fn commitment(&self) -> Fr {
+        note_commitment(
+            CircuitVersion::V1,
+            self.vortex,
+            None,
+            self.amount,
+            self.public_key(),
+            self.blinding,
+        )
+    }
+}
+
+impl fmt::Display for DepositNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            DEPOSIT_NOTE_PREFIX,
+            field_to_hex(&self.vortex),
+            self.amount.into_bigint(),
+            field_to_hex(&self.spend_key),
+            field_to_hex(&self.blinding)
+        )
+    }
+}
+
+impl FromStr for DepositNote {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let body = s
+            .trim()
+            .strip_prefix(DEPOSIT_NOTE_PREFIX)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .ok_or_else(|| anyhow!("Deposit note must start with {DEPOSIT_NOTE_PREFIX}:"))?;
+
+        let fields = body.split(':').collect::<Vec<_>>();
+        let [vortex, amount, spend_key, blinding] = fields[..] else {
+            bail!(
+                "Deposit note must have {} fields, got {}",
+                DEPOSIT_NOTE_FIELDS,
+                fields.len()
+            );
+        };
+
+        Ok(Self {
+            vortex: parse_field_element(vortex).context("Invalid deposit note vortex")?,
+            amount: parse_field_element(amount).context("Invalid deposit note amount")?,
+            spend_key: parse_field_element(spend_key).context("Invalid deposit note spend key")?,
+            blinding: parse_field_element(blinding).context("Invalid deposit note blinding")?,
+        })
+    }
+}
+
+/// String-encoded [`DepositNote`] for the JSON bindings, with the derived
+/// public key and commitment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositNoteJson {
+    pub note: String,
+    pub vortex: String,
+    pub amount: String,
+    pub spend_key: String,
+    pub public_key: String,
+    pub blinding: String,
+    pub commitment: String,
+}
+
+impl DepositNoteJson {
+    /// Encodes the field elements of `note` in `encoding`; `note` keeps its
+    /// own fixed encoding.
+    pub fn new(note: DepositNote, encoding: FieldEncoding) -> Self {
+        Self {
+            note: note.to_string(),
+            vortex: encoding.encode(&note.vortex),
+            amount: encoding.encode(&note.amount),
+            spend_key: encoding.encode(&note.spend_key),
+            public_key: encoding.encode(&note.public_key()),
+            blinding: encoding.encode(&note.blinding),
+            commitment: encoding.encode(&note.commitment()),
+        }
+    }
+}
+
+impl From<DepositNote> for DepositNoteJson {
+    fn from(note: DepositNote) -> Self {
+        Self::new(note, FieldEncoding::Decimal)
+    }
+}
+
+/// Generates a deposit note and returns it as JSON.
+///
+/// `entropy_hex` must be 32 fresh random bytes (e.g. `crypto.getRandomValues`),
+/// which seed the spend key and blinding.
+pub fn generate_deposit_note_json(
+    vortex: &str,
+    amount: &str,
+    entropy_hex: &str,
+) -> anyhow::Result<String> {
+    let seed: [u8; 32] = hex::decode(entropy_hex)
+        .context("Failed to decode entropy hex")?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow!("Entropy must be 32 bytes, got {}", bytes.len()))?;
+
+    let note = DepositNote::generate(
+        parse_field_element(vortex)?,
+        parse_field_element(amount)?,
+        &mut ChaCha20Rng::from_seed(seed),
+    );
+    serde_json::to_string(&DepositNoteJson::from(note)).context("Failed to serialize note")
+}
+
+/// Parses a `vortex-note-v1:...` string and returns it as JSON.
+pub fn parse_deposit_note_json(note: &str) -> anyhow::Result<String> {
+    serde_json::to_string(&DepositNoteJson::from(note.parse::<DepositNote>()?))
+        .context("Failed to serialize note")
+}
+
+/// Recipient of part of a split deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Payee {
+    pub public_key: Fr,
+    pub encryption_key: EncryptionKey,
+    pub amount: Fr,
+}
+
+/// Deposit shielded straight into two outputs, with what each side needs to
+/// spend or deliver its share.
+#[derive(Debug, Clone)]
+pub struct SplitDeposit {
+    pub circuit: TransactionCircuit<N_INS, N_OUTS>,
+    /// The depositor's share, under a fresh spend key
+    pub note: DepositNote,
+    pub payee: OutputUtxo,
+    /// `encrypted_output` of the payee's output, which its wallet finds when
+    /// scanning the pool
+    pub payee_encrypted_output: Vec<u8>,
+}
+
+/// Builds a deposit of `amount` into `vortex` that pays `payee` its amount
+/// and keeps the rest as a new note, so paying from a public balance takes a
+/// single transaction. `root` is any root the pool accepts; a deposit spends
+/// no notes, so it proves no membership.
+///
+/// # Errors
+/// Returns error if the payee's amount is zero or exceeds `amount`.
+pub fn build_split_deposit<R: RngCore + CryptoRng>(
+    vortex: Fr,
+    root: Fr,
+    amount: Fr,
+    payee: &Payee,
+    rng: &mut R,
+) -> anyhow::Result<SplitDeposit> {
+    if payee.amount == Fr::ZERO || payee.amount.into_bigint() > amount.into_bigint() {
+        bail!(
+            "Payee amount must be between 1 and the deposit of {}, got {}",
+            amount.into_bigint(),
+            payee.amount.into_bigint()
+        );
+    }
+
+    let note = DepositNote::generate(vortex, amount - payee.amount, rng);
+    let payee_output = OutputUtxo {
+        amount: payee.amount,
+        public_key: payee.public_key,
+        blinding: Fr::rand(rng),
+    };
+    let payee_encrypted_output = encrypt_note(
+        &Note {
+            amount: payee_output.amount,
+            blinding: payee_output.blinding,
+        },
+        &payee.encryption_key,
+        rng,
+    )?;
+
+    let circuit = TransactionBuilder::<N_INS, N_OUTS>::new(vortex, root)
+        .padding_seed(Fr::rand(rng))
+        .output(OutputUtxo {
+            amount: note.amount,
+            public_key: note.public_key(),
+            blinding: note.blinding,
+        })
+        .output(payee_output)
+        .build()?;
+
+    Ok(SplitDeposit {
+        circuit,
+        note,
+        payee: payee_output,
+        payee_encrypted_output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivation::ViewingKey;
+    use crate::note::decrypt_note;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    #[test]
+    fn test_deposit_note_round_trips() {
+        let mut rng = ChaCha20Rng::from_seed([3u8; 32]);
+        let note = DepositNote::generate(Fr::from(7u64), Fr::from(1_000u64), &mut rng);
+        assert_ne!(note.spend_key, note.blinding);
+        assert_ne!(
+            note,
+            DepositNote::generate(Fr::from(7u64), Fr::from(1_000u64), &mut rng)
+        );
+
+        let encoded = note.to_string();
+        assert!(encoded.starts_with("vortex-note-v1:0x"), "{encoded}");
+        assert_eq!(encoded.split(':').nth(2), Some("1000"));
+        assert_eq!(encoded.parse::<DepositNote>().unwrap(), note);
+
+        assert_eq!(
+            note.commitment(),
+            note_commitment(
+                CircuitVersion::V1,
+                Fr::from(7u64),
+                None,
+                Fr::from(1_000u64),
+                hash1(&note.spend_key),
+                note.blinding,
+            )
+        );
+
+        assert!("vortex-note-v2:0x07:1:0x01:0x02"
+            .parse::<DepositNote>()
+            .is_err());
+        let error = "vortex-note-v1:0x07:1:0x01"
+            .parse::<DepositNote>()
+            .unwrap_err();
+        assert!(error.to_string().contains("4 fields, got 3"), "{error}");
+    }
+
+    #[test]
+    fn test_deposit_note_json_matches_note_string() {
+        let generated: DepositNoteJson = serde_json::from_str(
+            &generate_deposit_note_json("7", "1000", &"ab".repeat(32)).unwrap(),
+        )
+        .unwrap();
+        let parsed: DepositNoteJson =
+            serde_json::from_str(&parse_deposit_note_json(&generated.note).unwrap()).unwrap();
+
+        assert_eq!(parsed.commitment, generated.commitment);
+        assert_eq!(parsed.spend_key, generated.spend_key);
+        assert_eq!(parsed.amount, "1000");
+        assert!(generate_deposit_note_json("7", "1000", "ab").is_err());
+    }
+
+    #[test]
+    fn test_split_deposit_pays_payee_and_keeps_rest() {
+        let mut rng = ChaCha20Rng::from_seed([9u8; 32]);
+        let viewing_key = ViewingKey(Fr::from(77u64));
+        let payee = Payee {
+            public_key: hash1(&Fr::from(55u64)),
+            encryption_key: viewing_key.encryption_key(),
+            amount: Fr::from(300u64),
+        };
+        let vortex = Fr::from(7u64);
+
+        let split =
+            build_split_deposit(vortex, Fr::from(1u64), Fr::from(1_000u64), &payee, &mut rng)
+                .unwrap();
+        assert_eq!(split.circuit.public_amount, Fr::from(1_000u64));
+        assert_eq!(split.note.amount, Fr::from(700u64));
+        assert_eq!(split.circuit.output_commitments[0], split.note.commitment());
+        assert_eq!(
+            split.circuit.output_commitments[1],
+            note_commitment(
+                CircuitVersion::V1,
+                vortex,
+                None,
+                payee.amount,
+                payee.public_key,
+                split.payee.blinding,
+            )
+        );
+        assert_eq!(
+            decrypt_note(&split.payee_encrypted_output, &viewing_key).unwrap(),
+            Note {
+                amount: payee.amount,
+                blinding: split.payee.blinding,
+            }
+        );
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        split.circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        for amount in [0u64, 1_001] {
+            let payee = Payee {
+                amount: Fr::from(amount),
+                ..payee
+            };
+            assert!(build_split_deposit(
+                vortex,
+                Fr::from(1u64),
+                Fr::from(1_000u64),
+                &payee,
+                &mut rng
+            )
+            .is_err());
+        }
+    }
+}
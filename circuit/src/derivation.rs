@@ -0,0 +1,146 @@
+use crate::{
+    constants::{DERIVE_BLINDING, DERIVE_SPEND_KEY, DERIVE_VIEWING_KEY},
+    poseidon_opt::{hash1, hash3},
+};
+use anyhow::Context;
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Wallet master seed every key and blinding is derived from.
+///
+/// Derivation is hierarchical and one-way:
+/// - Spend key: `Poseidon3(seed, DERIVE_SPEND_KEY, account)`
+/// - Public key: `Poseidon1(spend_key)`, as used in commitments
+/// - Viewing key: `Poseidon3(seed, DERIVE_VIEWING_KEY, account)`
+/// - Blinding: `Poseidon3(viewing_key, DERIVE_BLINDING, note_index)`
+///
+/// The viewing key can recompute blindings and decrypt outputs but cannot
+/// spend, since the spend key is not derivable from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MasterSeed(Fr);
+
+/// Keys of one wallet account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountKeys {
+    pub spend_key: Fr,
+    pub public_key: Fr,
+    pub viewing_key: ViewingKey,
+}
+
+/// Key that can recompute blindings and decrypt outputs, but not spend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewingKey(pub Fr);
+
+impl MasterSeed {
+    /// Reduces arbitrary seed bytes (e.g. a BIP-39 seed) to a field element via SHA-256.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            bytes.len() >= 16,
+            "Seed must be at least 16 bytes, got {}",
+            bytes.len()
+        );
+        Ok(Self(Fr::from_be_bytes_mod_order(&Sha256::digest(bytes))))
+    }
+
+    /// Derives the spend, public and viewing keys of `account`.
+    pub fn account(&self, account: u64) -> AccountKeys {
+        let spend_key = hash3(&self.0, &Fr::from(DERIVE_SPEND_KEY), &Fr::from(account));
+
+        AccountKeys {
+            spend_key,
+            public_key: hash1(&spend_key),
+            viewing_key: ViewingKey(hash3(
+                &self.0,
+                &Fr::from(DERIVE_VIEWING_KEY),
+                &Fr::from(account),
+            )),
+        }
+    }
+}
+
+impl ViewingKey {
+    /// Derives the blinding of the `note_index`-th note of the account.
+    pub fn blinding(&self, note_index: u64) -> Fr {
+        hash3(&self.0, &Fr::from(DERIVE_BLINDING), &Fr::from(note_index))
+    }
+
+    /// Big-endian bytes of the key, for use as symmetric key material.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&self.0.into_bigint().to_bytes_be());
+        bytes
+    }
+}
+
+/// String-encoded [`AccountKeys`] for the JSON bindings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountKeysOutput {
+    pub spend_key: String,
+    pub public_key: String,
+    pub viewing_key: String,
+}
+
+impl From<AccountKeys> for AccountKeysOutput {
+    fn from(keys: AccountKeys) -> Self {
+        Self {
+            spend_key: keys.spend_key.into_bigint().to_string(),
+            public_key: keys.public_key.into_bigint().to_string(),
+            viewing_key: keys.viewing_key.0.into_bigint().to_string(),
+        }
+    }
+}
+
+/// Derives the keys of `account` from a hex-encoded seed and returns them as JSON.
+pub fn derive_account_json(seed_hex: &str, account: u64) -> anyhow::Result<String> {
+    let seed =
+        hex::decode(seed_hex.trim_start_matches("0x")).context("Failed to decode seed hex")?;
+    let keys = MasterSeed::from_bytes(&seed)?.account(account);
+
+    serde_json::to_string(&AccountKeysOutput::from(keys)).context("Failed to serialize keys")
+}
+
+/// Derives the blinding of `note_index` from a viewing key (decimal or 0x-hex).
+pub fn derive_blinding(viewing_key: &str, note_index: u64) -> anyhow::Result<String> {
+    let viewing_key = ViewingKey(crate::prover::parse_field_element(viewing_key)?);
+    Ok(viewing_key.blinding(note_index).into_bigint().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derivation_is_deterministic_and_separated() {
+        let seed = MasterSeed::from_bytes(&[7u8; 32]).unwrap();
+
+        let account = seed.account(0);
+        assert_eq!(account, seed.account(0));
+        assert_eq!(account.public_key, hash1(&account.spend_key));
+        assert_ne!(account.spend_key, account.viewing_key.0);
+        assert_ne!(account, seed.account(1));
+        assert_ne!(
+            account,
+            MasterSeed::from_bytes(&[8u8; 32]).unwrap().account(0)
+        );
+
+        let blinding = account.viewing_key.blinding(0);
+        assert_ne!(blinding, account.viewing_key.blinding(1));
+        assert_eq!(
+            derive_blinding(&account.viewing_key.0.into_bigint().to_string(), 0).unwrap(),
+            blinding.into_bigint().to_string()
+        );
+
+        assert!(MasterSeed::from_bytes(&[1u8; 8]).is_err());
+
+        let json: AccountKeysOutput =
+            serde_json::from_str(&derive_account_json(&hex::encode([7u8; 32]), 0).unwrap())
+                .unwrap();
+        assert_eq!(
+            json.public_key,
+            account.public_key.into_bigint().to_string()
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Canonical string and byte encodings of BN254 scalar field elements.
+//!
+//! Tools print elements in one [`FieldEncoding`] and parse all three forms
+//! any of them prints: decimal, `0x` hex and big-endian byte arrays.
+//!
+//! Parsing is strict: values at or above the modulus are rejected instead of
+//! reduced, so two different strings never decode to the same element.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use num_bigint::BigUint;
+use std::fmt;
+use std::str::FromStr;
+
+/// Bytes of a big-endian field element encoding.
+pub const FIELD_BYTES: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldParseError {
+    Empty,
+    InvalidHex(String),
+    InvalidDecimal(String),
+    /// Not a `[b0, b1, ...]` list of bytes
+    InvalidBytes(String),
+    /// The value is a number but not below the field modulus
+    NotCanonical(String),
+    /// Byte encodings longer than [`FIELD_BYTES`]
+    TooLong(usize),
+}
+
+impl fmt::Display for FieldParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty field element"),
+            Self::InvalidHex(s) => write!(f, "Failed to parse hex '{s}': invalid hex string"),
+            Self::InvalidDecimal(s) => write!(f, "Failed to parse decimal '{s}'"),
+            Self::InvalidBytes(s) => write!(f, "Failed to parse byte array '{s}'"),
+            Self::NotCanonical(s) => write!(f, "'{s}' is not below the BN254 field modulus"),
+            Self::TooLong(len) => {
+                write!(f, "Expected at most {FIELD_BYTES} bytes, got {len}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldParseError {}
+
+fn canonical(value: BigUint, source: &str) -> Result<Fr, FieldParseError> {
+    if value >= BigUint::from(Fr::MODULUS) {
+        return Err(FieldParseError::NotCanonical(source.to_string()));
+    }
+    Ok(Fr::from(value))
+}
+
+fn parse_number(s: &str) -> Result<BigUint, FieldParseError> {
+    if s.is_empty() {
+        return Err(FieldParseError::Empty);
+    }
+
+    if let Some(list) = s.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        return parse_byte_list(list, s);
+    }
+
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16)
+            .ok_or_else(|| FieldParseError::InvalidHex(s.to_string())),
+        None if s.bytes().all(|b| b.is_ascii_digit()) => {
+            BigUint::from_str(s).map_err(|_| FieldParseError::InvalidDecimal(s.to_string()))
+        }
+        None => Err(FieldParseError::InvalidDecimal(s.to_string())),
+    }
+}
+
+fn parse_byte_list(list: &str, source: &str) -> Result<BigUint, FieldParseError> {
+    let bytes = list
+        .split(',')
+        .map(|byte| byte.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| FieldParseError::InvalidBytes(source.to_string()))?;
+    if bytes.len() > FIELD_BYTES {
+        return Err(FieldParseError::TooLong(bytes.len()));
+    }
+    Ok(BigUint::from_bytes_be(&bytes))
+}
+
+/// Parses a decimal, `0x`-prefixed hex or `[b0, b1, ...]` big-endian byte
+/// array string, ignoring surrounding whitespace.
+///
+/// # Errors
+/// Returns error if the string is not a number or not below the modulus.
+pub fn parse_field(s: &str) -> Result<Fr, FieldParseError> {
+    let s = s.trim();
+    canonical(parse_number(s)?, s)
+}
+
+/// [`parse_field`] reducing values at or above the modulus instead of
+/// rejecting them, as parsing did before it was strict. Only for callers that
+/// still send such values; prefer [`parse_field`].
+///
+/// # Errors
+/// Returns error if the string is not a number.
+pub fn parse_field_reduced(s: &str) -> Result<Fr, FieldParseError> {
+    Ok(Fr::from(parse_number(s.trim())?))
+}
+
+/// Decodes up to [`FIELD_BYTES`] big-endian bytes.
+///
+/// # Errors
+/// Returns error if there are too many bytes or the value is not below the modulus.
+pub fn field_from_be_bytes(bytes: &[u8]) -> Result<Fr, FieldParseError> {
+    if bytes.len() > FIELD_BYTES {
+        return Err(FieldParseError::TooLong(bytes.len()));
+    }
+    canonical(BigUint::from_bytes_be(bytes), &hex::encode(bytes))
+}
+
+pub fn field_to_be_bytes(value: &Fr) -> [u8; FIELD_BYTES] {
+    let mut bytes = [0u8; FIELD_BYTES];
+    bytes.copy_from_slice(&value.into_bigint().to_bytes_be());
+    bytes
+}
+
+/// `0x` followed by the 64 hex digits of the big-endian encoding.
+pub fn field_to_hex(value: &Fr) -> String {
+    format!("0x{}", hex::encode(field_to_be_bytes(value)))
+}
+
+pub fn field_to_decimal(value: &Fr) -> String {
+    value.into_bigint().to_string()
+}
+
+/// How tools print field elements, chosen with `--encoding hex|dec`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldEncoding {
+    #[default]
+    Decimal,
+    /// [`field_to_hex`]
+    Hex,
+}
+
+impl FieldEncoding {
+    pub fn display(self, value: &Fr) -> FrDisplay {
+        FrDisplay {
+            value: *value,
+            encoding: self,
+        }
+    }
+
+    pub fn encode(self, value: &Fr) -> String {
+        self.display(value).to_string()
+    }
+}
+
+impl FromStr for FieldEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dec" | "decimal" => Ok(Self::Decimal),
+            "hex" => Ok(Self::Hex),
+            _ => Err(anyhow::anyhow!(
+                "Unknown field encoding '{}'. Use: hex or dec",
+                s
+            )),
+        }
+    }
+}
+
+/// A field element printed in a [`FieldEncoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrDisplay {
+    pub value: Fr,
+    pub encoding: FieldEncoding,
+}
+
+impl fmt::Display for FrDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.encoding {
+            FieldEncoding::Decimal => write!(f, "{}", field_to_decimal(&self.value)),
+            FieldEncoding::Hex => write!(f, "{}", field_to_hex(&self.value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_field_is_canonical() {
+        assert_eq!(parse_field(" 42 "), Ok(Fr::from(42u64)));
+        assert_eq!(parse_field("0x2a"), Ok(Fr::from(42u64)));
+        assert_eq!(parse_field("0X2A"), Ok(Fr::from(42u64)));
+
+        let max = -Fr::from(1u64);
+        assert_eq!(parse_field(&field_to_decimal(&max)), Ok(max));
+        assert_eq!(parse_field(&field_to_hex(&max)), Ok(max));
+
+        let modulus = BigUint::from(Fr::MODULUS).to_string();
+        assert_eq!(
+            parse_field(&modulus),
+            Err(FieldParseError::NotCanonical(modulus.clone()))
+        );
+
+        assert_eq!(parse_field(""), Err(FieldParseError::Empty));
+        assert!(matches!(
+            parse_field("0xzz"),
+            Err(FieldParseError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            parse_field("-1"),
+            Err(FieldParseError::InvalidDecimal(_))
+        ));
+        assert!(matches!(
+            parse_field("1e3"),
+            Err(FieldParseError::InvalidDecimal(_))
+        ));
+
+        assert_eq!(parse_field_reduced(&modulus), Ok(Fr::from(0u64)));
+        assert_eq!(parse_field_reduced("0x2a"), Ok(Fr::from(42u64)));
+        assert_eq!(parse_field_reduced(" "), Err(FieldParseError::Empty));
+    }
+
+    #[test]
+    fn test_every_encoding_parses_back() {
+        let values = [Fr::from(0u64), Fr::from(0x0102_0304u64), -Fr::from(1u64)];
+        for value in values {
+            for encoding in [FieldEncoding::Decimal, FieldEncoding::Hex] {
+                assert_eq!(parse_field(&encoding.encode(&value)), Ok(value));
+            }
+            let bytes = format!("{:?}", field_to_be_bytes(&value));
+            assert_eq!(parse_field(&bytes), Ok(value));
+        }
+
+        assert_eq!(parse_field("[1, 2,3 ,4]"), Ok(Fr::from(0x0102_0304u64)));
+        assert_eq!(FieldEncoding::Hex.encode(&Fr::from(42u64)).len(), 66);
+        assert_eq!(FieldEncoding::Decimal.encode(&Fr::from(42u64)), "42");
+        assert_eq!(
+            "HEX".parse::<FieldEncoding>().ok(),
+            Some(FieldEncoding::Hex)
+        );
+        assert_eq!(
+            "dec".parse::<FieldEncoding>().ok(),
+            Some(FieldEncoding::Decimal)
+        );
+        assert!("base64".parse::<FieldEncoding>().is_err());
+
+        assert!(matches!(
+            parse_field("[1, 256]"),
+            Err(FieldParseError::InvalidBytes(_))
+        ));
+        assert!(matches!(
+            parse_field("[]"),
+            Err(FieldParseError::InvalidBytes(_))
+        ));
+        assert_eq!(
+            parse_field(&format!("{:?}", [1u8; FIELD_BYTES + 1])),
+            Err(FieldParseError::TooLong(FIELD_BYTES + 1))
+        );
+        assert!(matches!(
+            parse_field(&format!("{:?}", [0xffu8; FIELD_BYTES])),
+            Err(FieldParseError::NotCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_byte_and_hex_codecs_round_trip() {
+        let value = Fr::from(0x0102_0304u64);
+        let bytes = field_to_be_bytes(&value);
+        assert_eq!(bytes[FIELD_BYTES - 4..], [1, 2, 3, 4]);
+        assert_eq!(field_from_be_bytes(&bytes), Ok(value));
+        assert_eq!(field_from_be_bytes(&[1, 2, 3, 4]), Ok(value));
+        assert_eq!(field_to_hex(&value).len(), 2 + 2 * FIELD_BYTES);
+
+        assert_eq!(
+            field_from_be_bytes(&[0u8; FIELD_BYTES + 1]),
+            Err(FieldParseError::TooLong(FIELD_BYTES + 1))
+        );
+        assert!(matches!(
+            field_from_be_bytes(&[0xff; FIELD_BYTES]),
+            Err(FieldParseError::NotCanonical(_))
+        ));
+    }
+}
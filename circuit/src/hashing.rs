@@ -0,0 +1,161 @@
+//! The note hashes wallets compute outside a proof, taking and returning
+//! field elements as strings so the JS SDK does not need its own Poseidon.
+
+use crate::{
+    circuit::{note_commitment, note_nullifier, CircuitVersion},
+    field::field_to_decimal,
+    poseidon_opt::{hash1, hash2, hash3},
+    prover::parse_field_element,
+};
+use anyhow::Context;
+use ark_bn254::Fr;
+
+fn parse(name: &str, value: &str) -> anyhow::Result<Fr> {
+    parse_field_element(value).with_context(|| format!("Invalid {name}"))
+}
+
+fn parse_version(version: Option<&str>) -> anyhow::Result<CircuitVersion> {
+    version.map_or(Ok(CircuitVersion::default()), str::parse)
+}
+
+/// Poseidon of one element, as circomlibjs `poseidon([x])`.
+///
+/// # Errors
+/// Returns error if an input is not a decimal or 0x-prefixed hex number.
+pub fn poseidon1(x: &str) -> anyhow::Result<String> {
+    Ok(field_to_decimal(&hash1(&parse("input", x)?)))
+}
+
+/// Poseidon of two elements, as circomlibjs `poseidon([x, y])`.
+///
+/// # Errors
+/// Returns error if an input is not a decimal or 0x-prefixed hex number.
+pub fn poseidon2(x: &str, y: &str) -> anyhow::Result<String> {
+    Ok(field_to_decimal(&hash2(
+        &parse("input", x)?,
+        &parse("input", y)?,
+    )))
+}
+
+/// Poseidon of three elements, as circomlibjs `poseidon([x, y, z])`.
+///
+/// # Errors
+/// Returns error if an input is not a decimal or 0x-prefixed hex number.
+pub fn poseidon3(x: &str, y: &str, z: &str) -> anyhow::Result<String> {
+    Ok(field_to_decimal(&hash3(
+        &parse("input", x)?,
+        &parse("input", y)?,
+        &parse("input", z)?,
+    )))
+}
+
+/// Commitment of a note as the transaction circuit of `version` (V1 when
+/// `None`) computes it; `asset_id` is only set for notes of asset-bound pools.
+///
+/// # Errors
+/// Returns error if an input is not a field element or the version is unknown.
+pub fn commitment(
+    amount: &str,
+    public_key: &str,
+    blinding: &str,
+    vortex: &str,
+    asset_id: Option<&str>,
+    version: Option<&str>,
+) -> anyhow::Result<String> {
+    let asset_id = asset_id.map(|id| parse("asset ID", id)).transpose()?;
+    Ok(field_to_decimal(&note_commitment(
+        parse_version(version)?,
+        parse("vortex", vortex)?,
+        asset_id,
+        parse("amount", amount)?,
+        parse("public key", public_key)?,
+        parse("blinding", blinding)?,
+    )))
+}
+
+/// Nullifier revealed when spending the note `commitment` at leaf
+/// `path_index`, as the transaction circuit of `version` (V1 when `None`)
+/// computes it.
+///
+/// # Errors
+/// Returns error if an input is not a field element or the version is unknown.
+pub fn nullifier(
+    private_key: &str,
+    commitment: &str,
+    path_index: &str,
+    version: Option<&str>,
+) -> anyhow::Result<String> {
+    Ok(field_to_decimal(&note_nullifier(
+        parse_version(version)?,
+        parse("private key", private_key)?,
+        parse("commitment", commitment)?,
+        parse("path index", path_index)?,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon_opt::fr_from_str;
+
+    #[test]
+    fn test_poseidon_matches_circomlibjs() {
+        assert_eq!(
+            poseidon1("1").unwrap(),
+            "18586133768512220936620570745912940619677854269274689475585506675881198879027"
+        );
+        assert_eq!(
+            poseidon2("0x01", "2").unwrap(),
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+        );
+        assert_eq!(
+            poseidon3("1", "2", "3").unwrap(),
+            "6542985608222806190361240322586112750744169038454362455181422643027100751666"
+        );
+        assert!(poseidon1("one").is_err());
+    }
+
+    #[test]
+    fn test_note_hashes_match_circuit() {
+        let private_key = Fr::from(11u64);
+        let public_key = hash1(&private_key);
+
+        for version in [CircuitVersion::V1, CircuitVersion::V2, CircuitVersion::V3] {
+            let name = format!("{version:?}");
+            let expected = note_commitment(
+                version,
+                Fr::from(7u64),
+                None,
+                Fr::from(100u64),
+                public_key,
+                Fr::from(5u64),
+            );
+            let commitment = commitment(
+                "100",
+                &field_to_decimal(&public_key),
+                "5",
+                "7",
+                None,
+                Some(&name),
+            )
+            .unwrap();
+            assert_eq!(fr_from_str(&commitment), expected);
+
+            let nullifier = nullifier("11", &commitment, "3", Some(&name)).unwrap();
+            assert_eq!(
+                fr_from_str(&nullifier),
+                note_nullifier(version, private_key, expected, Fr::from(3u64))
+            );
+        }
+
+        assert_eq!(
+            commitment("100", "1", "5", "7", None, None).unwrap(),
+            commitment("100", "1", "5", "7", None, Some("v1")).unwrap()
+        );
+        assert_ne!(
+            commitment("100", "1", "5", "7", Some("9"), None).unwrap(),
+            commitment("100", "1", "5", "7", None, None).unwrap()
+        );
+        assert!(nullifier("11", "1", "3", Some("v9")).is_err());
+    }
+}
@@ -0,0 +1,669 @@
+use crate::backend::{Groth16Backend, ProvingBackend};
+use crate::circuit::{CircuitKind, CircuitVersion};
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::Bn254;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const PROVING_KEY_FILE: &str = "proving_key.bin";
+pub const VERIFYING_KEY_FILE: &str = "verification_key.bin";
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+pub const MIRROR_URL_ENV: &str = "VORTEX_KEYS_MIRROR";
+pub const CACHE_DIR_ENV: &str = "VORTEX_KEYS_CACHE_DIR";
+pub const CEREMONY_HASH_ENV: &str = "VORTEX_CEREMONY_HASH";
+/// [`CEREMONY_HASH_ENV`] value opting out of the ceremony pin
+pub const UNPINNED: &str = "unpinned";
+
+const CACHE_DIR_NAME: &str = "vortex";
+const PARTIAL_SUFFIX: &str = ".partial";
+const HTTP_PARTIAL_CONTENT: u16 = 206;
+
+/// SHA-256 of the verifying keys published from the trusted setup ceremony
+/// (`keys/verification_key.bin` for v1), the default pin of [`KeysConfig`].
+const PUBLISHED_CEREMONY_HASHES: &[(CircuitVersion, CircuitKind, &str)] = &[(
+    CircuitVersion::V1,
+    CircuitKind::Transaction2,
+    "2fb2a41486befbf3df920a3849f107c8310a42d53690919d9c61d6c6d289f7e6",
+)];
+
+/// Published description of a key set, served by the mirror at
+/// `<mirror>/<version>[/<kind>]/manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyManifest {
+    pub version: CircuitVersion,
+    #[serde(default)]
+    pub kind: CircuitKind,
+    pub proving_key: KeyFile,
+    pub verifying_key: KeyFile,
+    /// SHA-256 of the verifying key produced by the trusted setup ceremony.
+    pub ceremony_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyFile {
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Ceremony hash a mirror's manifest must carry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CeremonyPin {
+    /// The hash compiled into this crate for the version and kind fetched.
+    /// Keys without a published hash are refused.
+    #[default]
+    Published,
+    /// A hash supplied by the caller.
+    Hash(String),
+    /// Explicit opt-out: the manifest is only checked against itself, so a
+    /// compromised mirror can serve any keys.
+    Unpinned,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeysConfig {
+    pub mirror_url: String,
+    pub cache_dir: PathBuf,
+    /// A mirror serving a manifest or verifying key with a different hash than
+    /// the pinned one is rejected.
+    pub ceremony_pin: CeremonyPin,
+    /// Circuit shape whose keys are fetched, the 2-input circuit by default.
+    pub kind: CircuitKind,
+}
+
+impl KeysConfig {
+    /// Uses `$XDG_CACHE_HOME/vortex` (or the platform equivalent) as cache.
+    pub fn new(mirror_url: impl Into<String>) -> anyhow::Result<Self> {
+        let cache_dir = dirs::cache_dir()
+            .context("Failed to determine the user cache directory")?
+            .join(CACHE_DIR_NAME);
+
+        Ok(Self {
+            mirror_url: mirror_url.into(),
+            cache_dir,
+            ceremony_pin: CeremonyPin::default(),
+            kind: CircuitKind::default(),
+        })
+    }
+
+    /// [`KeysConfig::new`] configured from [`MIRROR_URL_ENV`],
+    /// [`CACHE_DIR_ENV`] and [`CEREMONY_HASH_ENV`]. Without a mirror only the
+    /// cache can be read.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut config = Self::new(std::env::var(MIRROR_URL_ENV).unwrap_or_default())?;
+        if let Ok(cache_dir) = std::env::var(CACHE_DIR_ENV) {
+            config = config.with_cache_dir(cache_dir);
+        }
+        match std::env::var(CEREMONY_HASH_ENV) {
+            Ok(value) if value == UNPINNED => config = config.without_ceremony_pin(),
+            Ok(ceremony_hash) => config = config.with_ceremony_hash(ceremony_hash),
+            Err(_) => {}
+        }
+        Ok(config)
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn with_ceremony_hash(mut self, ceremony_hash: impl Into<String>) -> Self {
+        self.ceremony_pin = CeremonyPin::Hash(ceremony_hash.into());
+        self
+    }
+
+    pub fn without_ceremony_pin(mut self) -> Self {
+        self.ceremony_pin = CeremonyPin::Unpinned;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: CircuitKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn version_dir(&self, version: CircuitVersion) -> PathBuf {
+        let mut dir = self.cache_dir.join(version_name(version));
+        dir.extend(self.kind.key_dir());
+        dir
+    }
+
+    fn version_url(&self, version: CircuitVersion) -> String {
+        let mut url = format!(
+            "{}/{}",
+            self.mirror_url.trim_end_matches('/'),
+            version_name(version)
+        );
+        if let Some(kind_dir) = self.kind.key_dir() {
+            url = format!("{url}/{kind_dir}");
+        }
+        url
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyPaths {
+    pub proving_key: PathBuf,
+    pub verifying_key: PathBuf,
+}
+
+/// Returns the cached keys for `version`, downloading and verifying them first
+/// if they are missing or do not match the cached manifest.
+pub fn ensure_keys(config: &KeysConfig, version: CircuitVersion) -> anyhow::Result<KeyPaths> {
+    cached_keys(config, version).or_else(|_| fetch_keys(config, version))
+}
+
+/// Returns the cached keys for `version` without touching the network, failing
+/// when they are missing, do not match the cached manifest, are not one key
+/// pair or the manifest does not carry the pinned ceremony hash.
+pub fn cached_keys(config: &KeysConfig, version: CircuitVersion) -> anyhow::Result<KeyPaths> {
+    let dir = config.version_dir(version);
+    let paths = key_paths(&dir);
+
+    let manifest = read_manifest(&dir.join(MANIFEST_FILE))?;
+    check_ceremony_hash(config, &manifest)?;
+    verify_file(&paths.proving_key, &manifest.proving_key)?;
+    verify_file(&paths.verifying_key, &manifest.verifying_key)?;
+    check_key_pair(&paths)?;
+
+    Ok(paths)
+}
+
+/// Downloads the manifest and both keys for `version` into the cache.
+///
+/// Interrupted downloads are resumed from their `.partial` file. Files only
+/// replace the cached copy once their size and SHA-256 match the manifest, and
+/// the manifest is only cached once the proving key embeds the verifying key.
+pub fn fetch_keys(config: &KeysConfig, version: CircuitVersion) -> anyhow::Result<KeyPaths> {
+    if config.mirror_url.is_empty() {
+        bail!("No key mirror configured, set {MIRROR_URL_ENV}");
+    }
+
+    let dir = config.version_dir(version);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+
+    let base_url = config.version_url(version);
+    let manifest_url = format!("{base_url}/{MANIFEST_FILE}");
+    let manifest_json = http_get_string(&manifest_url)?;
+    let manifest: KeyManifest = serde_json::from_str(&manifest_json)
+        .with_context(|| format!("Failed to parse manifest from {manifest_url}"))?;
+
+    if manifest.version != version || manifest.kind != config.kind {
+        bail!(
+            "Manifest at {manifest_url} describes {:?} {:?} keys, expected {version:?} {:?}",
+            manifest.version,
+            manifest.kind,
+            config.kind
+        );
+    }
+    check_ceremony_hash(config, &manifest)?;
+
+    let paths = key_paths(&dir);
+    download_verified(
+        &format!("{base_url}/{VERIFYING_KEY_FILE}"),
+        &paths.verifying_key,
+        &manifest.verifying_key,
+    )?;
+    download_verified(
+        &format!("{base_url}/{PROVING_KEY_FILE}"),
+        &paths.proving_key,
+        &manifest.proving_key,
+    )?;
+    check_key_pair(&paths)?;
+
+    fs::write(dir.join(MANIFEST_FILE), manifest_json).context("Failed to cache manifest")?;
+
+    Ok(paths)
+}
+
+/// Result of one `keys doctor` check: what was verified, or why it failed.
+#[derive(Debug)]
+pub struct KeyCheck {
+    pub name: &'static str,
+    pub result: anyhow::Result<String>,
+}
+
+/// Checks that the mirror serves a manifest for `version` that agrees with the
+/// pinned ceremony hash, and that the cached keys match their manifest and the
+/// verifying key parses. Nothing is downloaded besides the manifest.
+pub fn doctor(config: &KeysConfig, version: CircuitVersion) -> Vec<KeyCheck> {
+    let dir = config.version_dir(version);
+    let paths = key_paths(&dir);
+    let manifest_url = format!("{}/{MANIFEST_FILE}", config.version_url(version));
+
+    let mirror = http_get_string(&manifest_url).and_then(|json| {
+        let manifest: KeyManifest = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse manifest from {manifest_url}"))?;
+        if manifest.version != version || manifest.kind != config.kind {
+            bail!(
+                "Manifest describes {:?} {:?} keys, expected {version:?} {:?}",
+                manifest.version,
+                manifest.kind,
+                config.kind
+            );
+        }
+        check_ceremony_hash(config, &manifest)?;
+        Ok(manifest)
+    });
+
+    let cached = read_manifest(&dir.join(MANIFEST_FILE))
+        .and_then(|manifest| {
+            check_ceremony_hash(config, &manifest)?;
+            verify_file(&paths.proving_key, &manifest.proving_key)?;
+            verify_file(&paths.verifying_key, &manifest.verifying_key)?;
+            check_key_pair(&paths)?;
+            Ok(manifest)
+        })
+        .context("Run `keys fetch` to download them");
+
+    let up_to_date = match (&mirror, &cached) {
+        (Ok(mirror), Ok(cached))
+            if !hashes_equal(&mirror.proving_key.sha256, &cached.proving_key.sha256)
+                || !hashes_equal(&mirror.verifying_key.sha256, &cached.verifying_key.sha256) =>
+        {
+            Err(anyhow!(
+                "Cached keys differ from the mirror. Run `keys fetch` to update them"
+            ))
+        }
+        _ => Ok(()),
+    };
+
+    let verifying_key = fs::read(&paths.verifying_key)
+        .with_context(|| format!("Failed to read {}", paths.verifying_key.display()))
+        .and_then(|bytes| Groth16Backend::deserialize::<VerifyingKey<Bn254>>(&bytes))
+        .map(|vk| format!("{} public inputs", vk.gamma_abc_g1.len() - 1));
+
+    vec![
+        KeyCheck {
+            name: "Mirror manifest",
+            result: mirror.map(|_| manifest_url),
+        },
+        KeyCheck {
+            name: "Cached keys",
+            result: cached.and(up_to_date).map(|_| dir.display().to_string()),
+        },
+        KeyCheck {
+            name: "Verifying key parses",
+            result: verifying_key,
+        },
+    ]
+}
+
+pub fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn version_name(version: CircuitVersion) -> &'static str {
+    match version {
+        CircuitVersion::V1 => "v1",
+        CircuitVersion::V2 => "v2",
+        CircuitVersion::V3 => "v3",
+    }
+}
+
+fn key_paths(dir: &Path) -> KeyPaths {
+    KeyPaths {
+        proving_key: dir.join(PROVING_KEY_FILE),
+        verifying_key: dir.join(VERIFYING_KEY_FILE),
+    }
+}
+
+fn read_manifest(path: &Path) -> anyhow::Result<KeyManifest> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn check_ceremony_hash(config: &KeysConfig, manifest: &KeyManifest) -> anyhow::Result<()> {
+    if !hashes_equal(&manifest.ceremony_hash, &manifest.verifying_key.sha256) {
+        bail!(
+            "Manifest ceremony hash {} does not match its verifying key hash {}",
+            manifest.ceremony_hash,
+            manifest.verifying_key.sha256
+        );
+    }
+
+    let pinned = match &config.ceremony_pin {
+        CeremonyPin::Published => published_ceremony_hash(manifest.version, manifest.kind)
+            .with_context(|| {
+                format!(
+                    "No published ceremony hash for {:?} {:?} keys. Pin one or opt out explicitly",
+                    manifest.version, manifest.kind
+                )
+            })?,
+        CeremonyPin::Hash(hash) => hash.as_str(),
+        CeremonyPin::Unpinned => return Ok(()),
+    };
+
+    if !hashes_equal(pinned, &manifest.ceremony_hash) {
+        bail!(
+            "Ceremony hash mismatch: expected {}, mirror published {}",
+            pinned,
+            manifest.ceremony_hash
+        );
+    }
+
+    Ok(())
+}
+
+fn published_ceremony_hash(version: CircuitVersion, kind: CircuitKind) -> Option<&'static str> {
+    PUBLISHED_CEREMONY_HASHES
+        .iter()
+        .find(|(v, k, _)| *v == version && *k == kind)
+        .map(|(_, _, hash)| *hash)
+}
+
+/// Fails unless the proving key embeds the verifying key cached next to it.
+/// Matching hashes only prove each file is the one the manifest lists, not
+/// that both come from the same setup.
+fn check_key_pair(paths: &KeyPaths) -> anyhow::Result<()> {
+    let read =
+        |path: &Path| fs::read(path).with_context(|| format!("Failed to read {}", path.display()));
+    let pk: ProvingKey<Bn254> = Groth16Backend::deserialize(&read(&paths.proving_key)?)
+        .with_context(|| format!("Invalid proving key {}", paths.proving_key.display()))?;
+    let vk: VerifyingKey<Bn254> = Groth16Backend::deserialize(&read(&paths.verifying_key)?)
+        .with_context(|| format!("Invalid verifying key {}", paths.verifying_key.display()))?;
+
+    if pk.vk != vk {
+        bail!(
+            "Proving key {} was not generated with verifying key {}",
+            paths.proving_key.display(),
+            paths.verifying_key.display()
+        );
+    }
+    Ok(())
+}
+
+fn verify_file(path: &Path, expected: &KeyFile) -> anyhow::Result<()> {
+    let size = fs::metadata(path)
+        .with_context(|| format!("Failed to stat {}", path.display()))?
+        .len();
+    if size != expected.size {
+        bail!(
+            "{} is {} bytes, expected {}",
+            path.display(),
+            size,
+            expected.size
+        );
+    }
+
+    let actual = sha256_file(path)?;
+    if !hashes_equal(&actual, &expected.sha256) {
+        bail!(
+            "SHA-256 mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected.sha256,
+            actual
+        );
+    }
+
+    Ok(())
+}
+
+fn hashes_equal(a: &str, b: &str) -> bool {
+    let normalize = |h: &str| h.trim().trim_start_matches("0x").to_ascii_lowercase();
+    normalize(a) == normalize(b)
+}
+
+fn download_verified(url: &str, destination: &Path, expected: &KeyFile) -> anyhow::Result<()> {
+    if verify_file(destination, expected).is_ok() {
+        return Ok(());
+    }
+
+    let partial = destination.with_extension(format!("bin{PARTIAL_SUFFIX}"));
+    let mut offset = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+    if offset > expected.size {
+        fs::remove_file(&partial).context("Failed to discard oversized partial download")?;
+        offset = 0;
+    }
+
+    if offset < expected.size {
+        let mut request = ureq::get(url);
+        if offset > 0 {
+            request = request.header("Range", &format!("bytes={offset}-"));
+        }
+
+        let response = request
+            .call()
+            .with_context(|| format!("Failed to download {url}"))?;
+        let resumed = offset > 0 && response.status().as_u16() == HTTP_PARTIAL_CONTENT;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&partial)
+            .with_context(|| format!("Failed to open {}", partial.display()))?;
+
+        let mut reader = response.into_body().into_reader();
+        let mut buffer = vec![0u8; 1 << 20];
+        loop {
+            let read = reader
+                .read(&mut buffer)
+                .with_context(|| format!("Download of {url} interrupted"))?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read])
+                .with_context(|| format!("Failed to write {}", partial.display()))?;
+        }
+        file.flush()?;
+    }
+
+    if let Err(e) = verify_file(&partial, expected) {
+        fs::remove_file(&partial).ok();
+        return Err(e.context(format!("Downloaded {url} failed verification")));
+    }
+
+    fs::rename(&partial, destination)
+        .with_context(|| format!("Failed to move key into {}", destination.display()))
+}
+
+fn http_get_string(url: &str) -> anyhow::Result<String> {
+    ureq::get(url)
+        .call()
+        .with_context(|| format!("Failed to fetch {url}"))?
+        .into_body()
+        .read_to_string()
+        .with_context(|| format!("Failed to read response from {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_PROVING_KEY: &[u8] = include_bytes!("../keys/test/proving_key.bin");
+    const TEST_VERIFYING_KEY: &[u8] = include_bytes!("../keys/test/verification_key.bin");
+    const PUBLISHED_VERIFYING_KEY: &[u8] = include_bytes!("../keys/verification_key.bin");
+
+    fn write_key_set(dir: &Path, pk: &[u8], vk: &[u8]) -> KeyManifest {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(PROVING_KEY_FILE), pk).unwrap();
+        fs::write(dir.join(VERIFYING_KEY_FILE), vk).unwrap();
+
+        let vk_hash = hex::encode(Sha256::digest(vk));
+        let manifest = KeyManifest {
+            version: CircuitVersion::V1,
+            kind: CircuitKind::Transaction2,
+            proving_key: KeyFile {
+                sha256: hex::encode(Sha256::digest(pk)),
+                size: pk.len() as u64,
+            },
+            verifying_key: KeyFile {
+                sha256: vk_hash.clone(),
+                size: vk.len() as u64,
+            },
+            ceremony_hash: vk_hash,
+        };
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        manifest
+    }
+
+    fn temp_cache(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vortex-keys-{}-{name}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn test_ensure_keys_uses_verified_cache() {
+        let cache = temp_cache("cached");
+        let config = KeysConfig::new("http://127.0.0.1:1")
+            .unwrap()
+            .with_cache_dir(&cache);
+        let manifest = write_key_set(
+            &config.version_dir(CircuitVersion::V1),
+            TEST_PROVING_KEY,
+            TEST_VERIFYING_KEY,
+        );
+
+        let config = config.with_ceremony_hash(format!("0x{}", manifest.ceremony_hash));
+        let paths = ensure_keys(&config, CircuitVersion::V1).unwrap();
+        assert_eq!(fs::read(paths.verifying_key).unwrap(), TEST_VERIFYING_KEY);
+        assert!(cached_keys(&config, CircuitVersion::V1).is_ok());
+        assert!(cached_keys(&config, CircuitVersion::V2).is_err());
+
+        let offline = KeysConfig::new("")
+            .unwrap()
+            .with_cache_dir(&cache)
+            .without_ceremony_pin();
+        let error = ensure_keys(&offline, CircuitVersion::V2).unwrap_err();
+        assert!(error.to_string().contains(MIRROR_URL_ENV), "{error}");
+
+        fs::remove_dir_all(&cache).ok();
+    }
+
+    #[test]
+    fn test_doctor_reports_each_check() {
+        let cache = temp_cache("doctor");
+        let config = KeysConfig::new("http://127.0.0.1:1")
+            .unwrap()
+            .with_cache_dir(&cache)
+            .without_ceremony_pin();
+        write_key_set(
+            &config.version_dir(CircuitVersion::V1),
+            TEST_PROVING_KEY,
+            TEST_VERIFYING_KEY,
+        );
+
+        let checks = doctor(&config, CircuitVersion::V1);
+        let names: Vec<_> = checks.iter().map(|check| check.name).collect();
+        assert_eq!(
+            names,
+            ["Mirror manifest", "Cached keys", "Verifying key parses"]
+        );
+        // The mirror is unreachable, but the cache itself matches its manifest
+        assert!(checks[0].result.is_err());
+        assert!(checks[1].result.is_ok());
+        assert!(checks[2].result.is_ok());
+
+        write_key_set(&config.version_dir(CircuitVersion::V1), b"pk", b"vk");
+        let checks = doctor(&config, CircuitVersion::V1);
+        assert!(checks[1].result.is_err());
+        assert!(checks[2].result.is_err());
+
+        fs::remove_dir_all(&cache).ok();
+    }
+
+    #[test]
+    fn test_mismatched_key_pair_rejected() {
+        let cache = temp_cache("pair");
+        let config = KeysConfig::new("")
+            .unwrap()
+            .with_cache_dir(&cache)
+            .without_ceremony_pin();
+        write_key_set(
+            &config.version_dir(CircuitVersion::V1),
+            TEST_PROVING_KEY,
+            PUBLISHED_VERIFYING_KEY,
+        );
+
+        let error = cached_keys(&config, CircuitVersion::V1).unwrap_err();
+        assert!(
+            error.to_string().contains("was not generated with"),
+            "{error}"
+        );
+        assert!(ensure_keys(&config, CircuitVersion::V1).is_err());
+
+        fs::remove_dir_all(&cache).ok();
+    }
+
+    #[test]
+    fn test_ceremony_hash_mismatch_rejected() {
+        let cache = temp_cache("mismatch");
+        let config = KeysConfig::new("http://127.0.0.1:1")
+            .unwrap()
+            .with_cache_dir(&cache)
+            .with_ceremony_hash("00".repeat(32));
+        let manifest = write_key_set(&config.version_dir(CircuitVersion::V1), b"pk", b"vk");
+
+        assert!(check_ceremony_hash(&config, &manifest).is_err());
+
+        fs::write(
+            config
+                .version_dir(CircuitVersion::V1)
+                .join(PROVING_KEY_FILE),
+            b"tampered",
+        )
+        .unwrap();
+        assert!(verify_file(
+            &config
+                .version_dir(CircuitVersion::V1)
+                .join(PROVING_KEY_FILE),
+            &manifest.proving_key
+        )
+        .is_err());
+
+        fs::remove_dir_all(&cache).ok();
+    }
+
+    #[test]
+    fn test_published_ceremony_hash_pinned_by_default() {
+        let published = sha256_file(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("keys")
+                .join(VERIFYING_KEY_FILE),
+        )
+        .unwrap();
+        assert_eq!(
+            published_ceremony_hash(CircuitVersion::V1, CircuitKind::Transaction2),
+            Some(published.as_str())
+        );
+
+        let cache = temp_cache("published");
+        let config = KeysConfig::new("http://127.0.0.1:1")
+            .unwrap()
+            .with_cache_dir(&cache);
+        let manifest = write_key_set(&config.version_dir(CircuitVersion::V1), b"pk", b"vk");
+        assert!(check_ceremony_hash(&config, &manifest).is_err());
+
+        let unpublished = KeyManifest {
+            version: CircuitVersion::V2,
+            ..manifest.clone()
+        };
+        assert!(check_ceremony_hash(&config, &unpublished).is_err());
+
+        let config = config.without_ceremony_pin();
+        assert!(check_ceremony_hash(&config, &manifest).is_ok());
+        assert!(check_ceremony_hash(&config, &unpublished).is_ok());
+
+        fs::remove_dir_all(&cache).ok();
+    }
+}
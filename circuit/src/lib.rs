@@ -1,5 +1,58 @@
+#[cfg(feature = "prover")]
+pub mod association;
+#[cfg(feature = "fetch")]
+pub mod audit;
+#[cfg(feature = "prover")]
+pub mod backend;
+#[cfg(feature = "balance")]
+pub mod balance;
+#[cfg(feature = "prover")]
+pub mod builder;
+#[cfg(feature = "prover")]
+pub mod canonical;
+#[cfg(feature = "prover")]
 pub mod circuit;
+#[cfg(feature = "prover")]
 pub mod constants;
+#[cfg(feature = "prover")]
+pub mod deposit;
+#[cfg(feature = "prover")]
+pub mod derivation;
+#[cfg(feature = "prover")]
+pub mod field;
+#[cfg(feature = "prover")]
+pub mod hashing;
+#[cfg(feature = "fetch")]
+pub mod keys;
+#[cfg(feature = "prover")]
+pub mod memory;
+#[cfg(feature = "store")]
+pub mod merkle_store;
+#[cfg(feature = "prover")]
 pub mod merkle_tree;
+#[cfg(feature = "prover")]
+pub mod note;
+#[cfg(feature = "prover")]
+pub mod nullifiers;
+#[cfg(feature = "prover")]
+pub mod offline;
+#[cfg(feature = "prover")]
+pub mod output;
+#[cfg(feature = "prover")]
 pub mod poseidon_opt;
+#[cfg(feature = "prover")]
+pub mod prover;
+#[cfg(feature = "prover")]
+pub mod relay;
+#[cfg(feature = "prover")]
+pub mod sync;
+#[cfg(feature = "test-circuit")]
+pub mod test_circuit;
+#[cfg(feature = "prover")]
+pub mod tree_state;
+#[cfg(feature = "prover")]
+pub mod tree_sync;
+#[cfg(feature = "verifier")]
+pub mod verifier;
+#[cfg(feature = "prover")]
 pub mod wasm;
@@ -0,0 +1,104 @@
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Peak proving memory per byte of compressed proving key.
+///
+/// Proving with the published 3.9 MB key peaks at about 130 MB natively:
+/// decompressed points, the constraint system and the MSM buffers. The factor
+/// leaves some headroom on top of that.
+pub const PROVING_MEMORY_FACTOR: usize = 40;
+
+const MIB: usize = 1024 * 1024;
+
+/// Halvings tried to find how much memory can still be reserved after a
+/// reservation failed.
+const AVAILABLE_PROBES: usize = 8;
+
+/// Returned instead of loading a proving key the runtime cannot hold, so
+/// callers can fall back to the prover service or the native app rather than
+/// hitting an out-of-memory abort mid-proof.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsufficientMemory {
+    #[wasm_bindgen(readonly, js_name = requiredBytes)]
+    pub required_bytes: usize,
+    /// Largest reservation that succeeded, a lower bound of what is free
+    #[wasm_bindgen(readonly, js_name = availableBytes)]
+    pub available_bytes: usize,
+}
+
+#[wasm_bindgen]
+impl InsufficientMemory {
+    /// Discriminant for JavaScript callers, always `"InsufficientMemory"`
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        "InsufficientMemory".to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for InsufficientMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Proving needs about {} MiB of memory but only {} MiB could be reserved. \
+             Generate the proof with the prover service or the native app instead",
+            self.required_bytes.div_ceil(MIB),
+            self.available_bytes / MIB
+        )
+    }
+}
+
+impl std::error::Error for InsufficientMemory {}
+
+/// Memory proving with a compressed proving key of `proving_key_len` bytes needs.
+pub fn proving_memory_estimate(proving_key_len: usize) -> usize {
+    proving_key_len.saturating_mul(PROVING_MEMORY_FACTOR)
+}
+
+/// Checks that the allocator can still provide enough memory to prove with a
+/// key of `proving_key_len` bytes.
+///
+/// The memory is reserved and released right away. In wasm this grows the
+/// linear memory, which the allocator then reuses for the proof itself.
+pub fn ensure_proving_memory(proving_key_len: usize) -> Result<(), InsufficientMemory> {
+    let required_bytes = proving_memory_estimate(proving_key_len);
+    if reservable(required_bytes) {
+        return Ok(());
+    }
+
+    let available_bytes = std::iter::successors(Some(required_bytes / 2), |bytes| Some(bytes / 2))
+        .take(AVAILABLE_PROBES)
+        .find(|bytes| reservable(*bytes))
+        .unwrap_or(0);
+
+    Err(InsufficientMemory {
+        required_bytes,
+        available_bytes,
+    })
+}
+
+fn reservable(bytes: usize) -> bool {
+    Vec::<u8>::new().try_reserve_exact(bytes).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proving_memory_guard() {
+        let key_len = include_bytes!("../keys/proving_key.bin").len();
+        assert!(ensure_proving_memory(key_len).is_ok());
+
+        let error = ensure_proving_memory(usize::MAX / 2).unwrap_err();
+        assert_eq!(error.required_bytes, usize::MAX);
+        assert!(error.available_bytes < error.required_bytes);
+        assert_eq!(error.kind(), "InsufficientMemory");
+        assert!(error.message().contains("prover service or the native app"));
+    }
+}
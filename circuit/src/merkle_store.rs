@@ -0,0 +1,328 @@
+//! Disk-backed pool tree, so a service following the chain keeps its tree
+//! across restarts instead of re-hashing every commitment from genesis.
+
+use crate::constants::ZERO_VALUE;
+use crate::field::{field_from_be_bytes, field_to_be_bytes};
+use crate::merkle_tree::{empty_hashes, Path};
+use crate::poseidon_opt::{fr_from_str, hasher_t3};
+use anyhow::{bail, Context};
+use ark_bn254::Fr;
+use std::collections::HashMap;
+
+const NODE_PREFIX: u8 = b'n';
+const LEN_KEY: &[u8] = b"len";
+const HEIGHT_KEY: &[u8] = b"height";
+const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+
+/// Append-only pool tree with the layout of
+/// [`SparseMerkleTree`](crate::merkle_tree::SparseMerkleTree).
+pub trait MerkleStore<const N: usize> {
+    /// Appends two leaves, as the contract does for every transaction.
+    fn append_pair(&mut self, left: Fr, right: Fr) -> anyhow::Result<()>;
+
+    /// Returns the membership path of the leaf at `index`.
+    fn proof(&self, index: usize) -> anyhow::Result<Path<N>>;
+
+    fn root(&self) -> Fr;
+
+    /// Returns the number of leaves, checkpointed or not.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Persists the pairs appended since the last checkpoint together with
+    /// `seq`, the position in the source they were read up to.
+    fn checkpoint(&mut self, seq: u64) -> anyhow::Result<()>;
+
+    /// Sequence number of the last checkpoint, where a restarted follower resumes.
+    fn last_checkpoint(&self) -> Option<u64>;
+}
+
+/// [`MerkleStore`] in a sled database, one key per filled node.
+///
+/// Appended pairs are kept in memory until [`MerkleStore::checkpoint`] writes
+/// them in one atomic batch, so the database always holds the tree as of a
+/// checkpoint. Pairs not checkpointed yet are lost when the store is dropped.
+pub struct SledMerkleStore<const N: usize> {
+    db: sled::Db,
+    empty_hashes: [Fr; N],
+    /// Nodes written since the last checkpoint, by `(level, index)`
+    pending: HashMap<(usize, usize), Fr>,
+    len: usize,
+    root: Fr,
+    last_checkpoint: Option<u64>,
+}
+
+fn node_key(level: usize, index: usize) -> Vec<u8> {
+    let mut key = vec![NODE_PREFIX];
+    key.extend_from_slice(&(level as u64).to_be_bytes());
+    key.extend_from_slice(&(index as u64).to_be_bytes());
+    key
+}
+
+impl<const N: usize> SledMerkleStore<N> {
+    /// Opens the store at `path`, creating an empty tree if there is none.
+    ///
+    /// # Errors
+    /// Returns error if the database cannot be opened or holds a tree of
+    /// another height.
+    pub fn open(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        // Checkpoints flush explicitly; a background flusher would also keep
+        // the database locked for a moment after the store is dropped
+        let db = sled::Config::new()
+            .path(path)
+            .flush_every_ms(None)
+            .open()
+            .with_context(|| format!("Failed to open Merkle store at {}", path.display()))?;
+
+        let height = read_u64(&db, HEIGHT_KEY)?;
+        if let Some(height) = height.filter(|&height| height != N as u64) {
+            bail!("Merkle store has height {height}, expected {N}");
+        }
+
+        let hasher = hasher_t3();
+        let empty_hashes = empty_hashes(hasher, &fr_from_str(ZERO_VALUE));
+        let mut store = Self {
+            db,
+            empty_hashes,
+            pending: HashMap::new(),
+            len: 0,
+            root: hasher.hash2(&empty_hashes[N - 1], &empty_hashes[N - 1]),
+            last_checkpoint: None,
+        };
+
+        store.len = read_u64(&store.db, LEN_KEY)?.unwrap_or(0) as usize;
+        store.last_checkpoint = read_u64(&store.db, CHECKPOINT_KEY)?;
+        if let Some(root) = store.node(N, 0)? {
+            store.root = root;
+        }
+        Ok(store)
+    }
+
+    fn node(&self, level: usize, index: usize) -> anyhow::Result<Option<Fr>> {
+        if let Some(node) = self.pending.get(&(level, index)) {
+            return Ok(Some(*node));
+        }
+        self.db
+            .get(node_key(level, index))
+            .context("Failed to read Merkle store")?
+            .map(|bytes| field_from_be_bytes(&bytes))
+            .transpose()
+            .with_context(|| format!("Corrupt node {index} at level {level}"))
+    }
+
+    fn filled_node(&self, level: usize, index: usize) -> anyhow::Result<Fr> {
+        self.node(level, index)?
+            .with_context(|| format!("Merkle store is missing node {index} at level {level}"))
+    }
+}
+
+fn read_u64(db: &sled::Db, key: &[u8]) -> anyhow::Result<Option<u64>> {
+    db.get(key)
+        .context("Failed to read Merkle store")?
+        .map(|bytes| {
+            let bytes = bytes
+                .as_ref()
+                .try_into()
+                .with_context(|| format!("Corrupt {} entry", String::from_utf8_lossy(key)))?;
+            Ok(u64::from_be_bytes(bytes))
+        })
+        .transpose()
+}
+
+impl<const N: usize> MerkleStore<N> for SledMerkleStore<N> {
+    fn append_pair(&mut self, left: Fr, right: Fr) -> anyhow::Result<()> {
+        let max_leaves = 1usize << N;
+        if self.len + 2 > max_leaves {
+            bail!("Merkle tree is full (capacity: {max_leaves})");
+        }
+
+        let hasher = hasher_t3();
+        let pair_index = self.len / 2;
+        self.pending.insert((0, 2 * pair_index), left);
+        self.pending.insert((0, 2 * pair_index + 1), right);
+
+        let mut current_index = pair_index;
+        let mut current_level_hash = hasher.hash2(&left, &right);
+
+        for level in 1..N {
+            self.pending
+                .insert((level, current_index), current_level_hash);
+
+            let (left, right) = if current_index.is_multiple_of(2) {
+                (current_level_hash, self.empty_hashes[level])
+            } else {
+                (
+                    self.filled_node(level, current_index - 1)?,
+                    current_level_hash,
+                )
+            };
+
+            current_level_hash = hasher.hash2(&left, &right);
+            current_index /= 2;
+        }
+
+        self.pending.insert((N, 0), current_level_hash);
+        self.root = current_level_hash;
+        self.len += 2;
+        Ok(())
+    }
+
+    fn proof(&self, index: usize) -> anyhow::Result<Path<N>> {
+        if index >= self.len {
+            bail!(
+                "Index {} out of bounds (tree has {} leaves)",
+                index,
+                self.len
+            );
+        }
+
+        let mut path = Path::empty();
+        let pair_index = index / 2;
+        path.path[0] = (
+            self.filled_node(0, 2 * pair_index)?,
+            self.filled_node(0, 2 * pair_index + 1)?,
+        );
+
+        let mut current_index = pair_index;
+        for (level, pair) in path.path.iter_mut().enumerate().skip(1) {
+            let node = self.filled_node(level, current_index)?;
+            let sibling = self
+                .node(level, current_index ^ 1)?
+                .unwrap_or(self.empty_hashes[level]);
+
+            *pair = if current_index.is_multiple_of(2) {
+                (node, sibling)
+            } else {
+                (sibling, node)
+            };
+            current_index /= 2;
+        }
+
+        Ok(path)
+    }
+
+    fn root(&self) -> Fr {
+        self.root
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn checkpoint(&mut self, seq: u64) -> anyhow::Result<()> {
+        if let Some(last) = self.last_checkpoint.filter(|&last| seq < last) {
+            bail!("Checkpoint {seq} is behind the last checkpoint {last}");
+        }
+
+        let mut batch = sled::Batch::default();
+        for (&(level, index), node) in &self.pending {
+            batch.insert(node_key(level, index), &field_to_be_bytes(node)[..]);
+        }
+        batch.insert(LEN_KEY, &(self.len as u64).to_be_bytes()[..]);
+        batch.insert(HEIGHT_KEY, &(N as u64).to_be_bytes()[..]);
+        batch.insert(CHECKPOINT_KEY, &seq.to_be_bytes()[..]);
+
+        self.db
+            .apply_batch(batch)
+            .context("Failed to write Merkle store checkpoint")?;
+        self.db
+            .flush()
+            .context("Failed to flush Merkle store checkpoint")?;
+
+        self.pending.clear();
+        self.last_checkpoint = Some(seq);
+        Ok(())
+    }
+
+    fn last_checkpoint(&self) -> Option<u64> {
+        self.last_checkpoint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle_tree::SparseMerkleTree;
+    use std::path::PathBuf;
+
+    const HEIGHT: usize = 4;
+
+    fn temp_store(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("vortex-merkle-store-{}-{name}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn pair(i: u64) -> (Fr, Fr) {
+        (Fr::from(2 * i + 1), Fr::from(2 * i + 2))
+    }
+
+    #[test]
+    fn test_store_matches_in_memory_tree_across_reopen() {
+        let dir = temp_store("reopen");
+        let hasher = hasher_t3();
+        let mut tree = SparseMerkleTree::<HEIGHT>::new_empty(hasher, &fr_from_str(ZERO_VALUE));
+
+        {
+            let mut store = SledMerkleStore::<HEIGHT>::open(&dir).unwrap();
+            assert_eq!(store.root(), tree.root());
+            assert_eq!(store.last_checkpoint(), None);
+
+            for i in 0..3 {
+                let (left, right) = pair(i);
+                store.append_pair(left, right).unwrap();
+                tree.insert_pair(left, right, hasher).unwrap();
+            }
+            store.checkpoint(7).unwrap();
+
+            store
+                .append_pair(Fr::from(99u64), Fr::from(100u64))
+                .unwrap();
+            assert_eq!(store.len(), 8);
+        }
+
+        let mut store = SledMerkleStore::<HEIGHT>::open(&dir).unwrap();
+        assert_eq!(store.last_checkpoint(), Some(7));
+        assert_eq!(store.len(), tree.len());
+        assert_eq!(store.root(), tree.root());
+        assert!(store.checkpoint(6).is_err());
+
+        for i in 3..5 {
+            let (left, right) = pair(i);
+            store.append_pair(left, right).unwrap();
+            tree.insert_pair(left, right, hasher).unwrap();
+        }
+        assert_eq!(store.root(), tree.root());
+
+        for (index, leaf) in tree.leaves().iter().enumerate() {
+            let path = store.proof(index).unwrap();
+            assert_eq!(path, tree.generate_membership_proof(index).unwrap());
+            assert!(path.check_membership(&store.root(), leaf, hasher).unwrap());
+        }
+        assert!(store.proof(tree.len()).is_err());
+
+        drop(store);
+        assert!(SledMerkleStore::<{ HEIGHT + 1 }>::open(&dir).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_store_rejects_pairs_past_capacity() {
+        let dir = temp_store("full");
+        let mut store = SledMerkleStore::<2>::open(&dir).unwrap();
+
+        for i in 0..2 {
+            let (left, right) = pair(i);
+            store.append_pair(left, right).unwrap();
+        }
+        assert!(store.append_pair(Fr::from(5u64), Fr::from(6u64)).is_err());
+
+        drop(store);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,16 +1,25 @@
 use std::borrow::Borrow;
+use std::collections::VecDeque;
 
 use anyhow::{anyhow, Context};
 use ark_bn254::Fr;
 use ark_ff::AdditiveGroup;
 use ark_r1cs_std::{
     fields::fp::FpVar,
-    prelude::{AllocVar, AllocationMode, Boolean, EqGadget},
+    prelude::{AllocVar, AllocationMode, Boolean, EqGadget, FieldVar},
     select::CondSelectGadget,
 };
 use ark_relations::r1cs::{Namespace, SynthesisError};
 
-use crate::poseidon_opt::{PoseidonOptimized, PoseidonOptimizedVar};
+use crate::constants::ROOT_HISTORY_SIZE;
+use crate::field::{field_from_be_bytes, field_to_be_bytes, FIELD_BYTES};
+use crate::poseidon_opt::{hasher_t3, PoseidonOptimized, PoseidonOptimizedVar};
+
+/// Leading bytes of a [`SparseMerkleTree::serialize`] snapshot.
+pub const TREE_SNAPSHOT_MAGIC: &[u8; 4] = b"VXMT";
+
+/// Layout version of tree snapshots; bumped when the layout changes.
+pub const TREE_SNAPSHOT_VERSION: u8 = 1;
 
 /// Merkle tree path structure
 /// Each level contains (left_hash, right_hash) pair
@@ -96,6 +105,108 @@ impl<const N: usize> Path<N> {
 
         Ok(index)
     }
+
+    /// Builds a pair-style path from a sibling-style proof.
+    ///
+    /// Bit `i` of `index` tells whether the node at level `i` is a right child,
+    /// and `siblings[i]` is its sibling. The running hash is recomputed from
+    /// `leaf` so each level stores the full (left, right) pair.
+    ///
+    /// # Errors
+    /// Returns error if `index` does not fit in `N` bits.
+    pub fn from_siblings(
+        leaf: &Fr,
+        index: u64,
+        siblings: &[Fr; N],
+        hasher: &PoseidonOptimized,
+    ) -> anyhow::Result<Self> {
+        if N < u64::BITS as usize && index >> N != 0 {
+            return Err(anyhow!(
+                "Index {} exceeds tree capacity (>= 2^{})",
+                index,
+                N
+            ));
+        }
+
+        let mut path = [(Fr::ZERO, Fr::ZERO); N];
+        let mut current = *leaf;
+
+        for (level, (pair, sibling)) in path.iter_mut().zip(siblings).enumerate() {
+            *pair = if (index >> level) & 1 == 0 {
+                (current, *sibling)
+            } else {
+                (*sibling, current)
+            };
+            current = hasher.hash2(&pair.0, &pair.1);
+        }
+
+        Ok(Self { path })
+    }
+
+    /// Converts the path into a sibling-style proof `(index, siblings)` for `leaf`.
+    ///
+    /// Uses the same rule as [`Self::calculate_root`]: the running hash is the
+    /// left child whenever it equals the stored left value.
+    ///
+    /// # Errors
+    /// Returns error if the running hash is on neither side at some level, i.e.
+    /// the path does not belong to `leaf`.
+    pub fn to_siblings(
+        &self,
+        leaf: &Fr,
+        hasher: &PoseidonOptimized,
+    ) -> anyhow::Result<(u64, [Fr; N])> {
+        let mut siblings = [Fr::ZERO; N];
+        let mut index = 0u64;
+        let mut current = *leaf;
+
+        for (level, ((left, right), sibling)) in self.path.iter().zip(&mut siblings).enumerate() {
+            if current == *left {
+                *sibling = *right;
+            } else if current == *right {
+                *sibling = *left;
+                index |= 1 << level;
+            } else {
+                return Err(anyhow!(
+                    "Path level {} does not contain the running hash of the leaf",
+                    level
+                ));
+            }
+            current = hasher.hash2(left, right);
+        }
+
+        Ok((index, siblings))
+    }
+}
+
+/// Checks that `commitment` is leaf `index` of the tree with `root`, given
+/// the sibling of each level from the leaf up.
+///
+/// Needs nothing but the claim itself, so third parties can check a deposit
+/// without running any Vortex infrastructure.
+///
+/// # Errors
+/// Returns error if `index` does not fit in `N` bits.
+pub fn verify_inclusion<const N: usize>(
+    root: &Fr,
+    index: u64,
+    commitment: &Fr,
+    siblings: &[Fr; N],
+) -> anyhow::Result<bool> {
+    let hasher = hasher_t3();
+    let path = Path::from_siblings(commitment, index, siblings, hasher)?;
+    path.check_membership(root, commitment, hasher)
+}
+
+/// Hashes of the empty subtree at each level, from the empty leaf up.
+pub(crate) fn empty_hashes<const N: usize>(hasher: &PoseidonOptimized, empty_leaf: &Fr) -> [Fr; N] {
+    let mut empty_hashes = [*empty_leaf; N];
+    let mut empty_hash = *empty_leaf;
+    for hash in empty_hashes.iter_mut().skip(1) {
+        empty_hash = hasher.hash2(&empty_hash, &empty_hash);
+        *hash = empty_hash;
+    }
+    empty_hashes
 }
 
 /// Sparse Merkle Tree using Nova's paired insertion strategy
@@ -103,12 +214,16 @@ impl<const N: usize> Path<N> {
 pub struct SparseMerkleTree<const N: usize> {
     /// Stored leaves (in insertion order)
     pub leaves: Vec<Fr>,
-    /// Cached left subtrees at each level
-    subtrees: Vec<Fr>,
+    /// Node hashes at levels 1 to N-1, `nodes[i - 1][j]` being node `j` of
+    /// level `i`; the last node of a level covers the empty leaves after it
+    nodes: Vec<Vec<Fr>>,
     /// Default empty hashes for each level
     empty_hashes: [Fr; N],
     /// Current root
     root: Fr,
+    /// The last [`ROOT_HISTORY_SIZE`] roots, oldest first, one per appended
+    /// pair as the contract records them
+    root_history: VecDeque<Fr>,
 }
 
 impl<const N: usize> SparseMerkleTree<N> {
@@ -118,31 +233,17 @@ impl<const N: usize> SparseMerkleTree<N> {
         hasher: &PoseidonOptimized,
         empty_leaf: &Fr,
     ) -> anyhow::Result<Self> {
-        // Build empty hashes array
-        let empty_hashes = {
-            let mut empty_hashes = [Fr::ZERO; N];
-            empty_hashes[0] = *empty_leaf;
-
-            let mut empty_hash = *empty_leaf;
-            for hash in empty_hashes.iter_mut().skip(1) {
-                empty_hash = hasher.hash2(&empty_hash, &empty_hash);
-                *hash = empty_hash;
-            }
-
-            empty_hashes
-        };
-
-        // Initialize subtrees
-        let subtrees = empty_hashes.to_vec();
+        let empty_hashes = empty_hashes(hasher, empty_leaf);
 
-        // Empty tree root
-        let root = empty_hashes[N - 1];
+        // Empty tree root, one level above the highest subtree
+        let root = hasher.hash2(&empty_hashes[N - 1], &empty_hashes[N - 1]);
 
         let mut smt = SparseMerkleTree {
             leaves: Vec::new(),
-            subtrees,
+            nodes: vec![Vec::new(); N.saturating_sub(1)],
             empty_hashes,
             root,
+            root_history: VecDeque::from([root]),
         };
 
         // Insert leaf pairs
@@ -164,6 +265,18 @@ impl<const N: usize> SparseMerkleTree<N> {
         leaf1: Fr,
         leaf2: Fr,
         hasher: &PoseidonOptimized,
+    ) -> anyhow::Result<()> {
+        // Level 0: Hash the leaf pair
+        self.append_pair(leaf1, leaf2, hasher.hash2(&leaf1, &leaf2), hasher)
+    }
+
+    /// Appends a leaf pair whose level-0 hash is already known
+    fn append_pair(
+        &mut self,
+        leaf1: Fr,
+        leaf2: Fr,
+        pair_hash: Fr,
+        hasher: &PoseidonOptimized,
     ) -> anyhow::Result<()> {
         let max_leaves = 1usize << N;
         if self.leaves.len() + 2 > max_leaves {
@@ -174,69 +287,205 @@ impl<const N: usize> SparseMerkleTree<N> {
         self.leaves.push(leaf1);
         self.leaves.push(leaf2);
 
-        // Level 0: Hash the leaf pair
         let mut current_index = (self.leaves.len() - 2) / 2;
-        let mut current_level_hash = hasher.hash2(&leaf1, &leaf2);
+        let mut current_level_hash = pair_hash;
 
         // Levels 1 to N-1 (matching Move: for i in 1..HEIGHT)
         for i in 1..N {
-            let left: Fr;
-            let right: Fr;
-
-            if current_index % 2 == 0 {
-                // Current is left child
-                left = current_level_hash;
-                right = self.empty_hashes[i];
-                self.subtrees[i] = current_level_hash; // Cache left subtree
+            let level = &mut self.nodes[i - 1];
+            if current_index == level.len() {
+                level.push(current_level_hash);
             } else {
-                // Current is right child
-                left = self.subtrees[i]; // Get cached left subtree
-                right = current_level_hash;
+                level[current_index] = current_level_hash;
             }
 
+            let (left, right) = if current_index.is_multiple_of(2) {
+                (current_level_hash, self.empty_hashes[i])
+            } else {
+                (level[current_index - 1], current_level_hash)
+            };
+
             current_level_hash = hasher.hash2(&left, &right);
             current_index /= 2;
         }
 
         self.root = current_level_hash;
+        self.record_root(current_level_hash);
         Ok(())
     }
 
+    fn record_root(&mut self, root: Fr) {
+        if self.root_history.len() == ROOT_HISTORY_SIZE {
+            self.root_history.pop_front();
+        }
+        self.root_history.push_back(root);
+    }
+
     /// Insert single leaf (pairs with zero)
     pub fn insert(&mut self, leaf: Fr, hasher: &PoseidonOptimized) -> anyhow::Result<()> {
         self.insert_pair(leaf, self.empty_hashes[0], hasher)
     }
 
-    /// Insert batch of leaf pairs
+    /// Insert batch of leaf pairs, hashing all pairs in one
+    /// [`PoseidonOptimized::hash_many_pairs`] call
     pub fn insert_batch(
         &mut self,
         leaf_pairs: &[(Fr, Fr)],
         hasher: &PoseidonOptimized,
     ) -> anyhow::Result<()> {
-        for (leaf1, leaf2) in leaf_pairs {
-            self.insert_pair(*leaf1, *leaf2, hasher)?;
+        let max_leaves = 1usize << N;
+        if self.leaves.len() + 2 * leaf_pairs.len() > max_leaves {
+            return Err(anyhow!("Merkle tree is full (capacity: {})", max_leaves));
+        }
+
+        let pair_hashes = hasher.hash_many_pairs(leaf_pairs);
+        for ((leaf1, leaf2), pair_hash) in leaf_pairs.iter().zip(pair_hashes) {
+            self.append_pair(*leaf1, *leaf2, pair_hash, hasher)?;
         }
         Ok(())
     }
 
-    /// Bulk insert (must be even number of leaves)
-    pub fn bulk_insert(&mut self, leaves: &[Fr], hasher: &PoseidonOptimized) -> anyhow::Result<()> {
-        if leaves.len() % 2 != 0 {
-            return Err(anyhow!("Must insert even number of leaves (pairs)"));
+    /// [`Self::insert_batch`] updating each level once for the whole batch
+    /// rather than hashing up to the root per pair, so nodes shared by the
+    /// new pairs are hashed once.
+    ///
+    /// The roots of the last [`ROOT_HISTORY_SIZE`] pairs are recomputed for
+    /// the root history, as the contract recorded one per pair.
+    pub fn insert_batch_fast(
+        &mut self,
+        leaf_pairs: &[(Fr, Fr)],
+        hasher: &PoseidonOptimized,
+    ) -> anyhow::Result<()> {
+        let max_leaves = 1usize << N;
+        if self.leaves.len() + 2 * leaf_pairs.len() > max_leaves {
+            return Err(anyhow!("Merkle tree is full (capacity: {})", max_leaves));
+        }
+        if leaf_pairs.is_empty() {
+            return Ok(());
         }
 
-        for i in (0..leaves.len()).step_by(2) {
-            self.insert_pair(leaves[i], leaves[i + 1], hasher)?;
+        let mut first_dirty = self.leaves.len() / 2;
+        self.leaves
+            .extend(leaf_pairs.iter().flat_map(|&(left, right)| [left, right]));
+
+        // Nodes of the current level from `first_dirty` on; the node before it
+        // covers complete subtrees only and is unchanged
+        let mut dirty = hasher.hash_many_pairs(leaf_pairs);
+        for (level, empty_hash) in self.nodes.iter_mut().zip(&self.empty_hashes[1..]) {
+            level.truncate(first_dirty);
+            level.extend_from_slice(&dirty);
+
+            first_dirty /= 2;
+            let parents = (first_dirty..level.len().div_ceil(2))
+                .map(|k| {
+                    let right = level.get(2 * k + 1).copied().unwrap_or(*empty_hash);
+                    (level[2 * k], right)
+                })
+                .collect::<Vec<_>>();
+            dirty = hasher.hash_many_pairs(&parents);
         }
+        self.root = dirty[0];
 
+        let recorded = leaf_pairs.len().min(ROOT_HISTORY_SIZE);
+        let first_recorded = self.leaves.len() - 2 * (recorded - 1);
+        for leaf_count in (first_recorded..self.leaves.len()).step_by(2) {
+            let root = self.recompute_root_at(leaf_count, hasher);
+            self.record_root(root);
+        }
+        self.record_root(self.root);
         Ok(())
     }
 
+    /// Bulk insert (must be even number of leaves)
+    pub fn bulk_insert(&mut self, leaves: &[Fr], hasher: &PoseidonOptimized) -> anyhow::Result<()> {
+        if !leaves.len().is_multiple_of(2) {
+            return Err(anyhow!("Must insert even number of leaves (pairs)"));
+        }
+
+        let pairs = leaves
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        self.insert_batch(&pairs, hasher)
+    }
+
     /// Returns the Merkle tree root
     pub fn root(&self) -> Fr {
         self.root
     }
 
+    /// Returns the root the tree had when it held `leaf_count` leaves, or
+    /// `None` if it never did, leaves being appended in pairs.
+    ///
+    /// Roots still in the history are returned as recorded; older ones are
+    /// recomputed from the stored nodes.
+    pub fn root_at(&self, leaf_count: usize, hasher: &PoseidonOptimized) -> Option<Fr> {
+        if leaf_count > self.leaves.len() || !leaf_count.is_multiple_of(2) {
+            return None;
+        }
+
+        let age = (self.leaves.len() - leaf_count) / 2;
+        if let Some(root) = self
+            .root_history
+            .len()
+            .checked_sub(age + 1)
+            .and_then(|i| self.root_history.get(i))
+        {
+            return Some(*root);
+        }
+        Some(self.recompute_root_at(leaf_count, hasher))
+    }
+
+    /// Recomputes the root at `leaf_count`, an even number of at most
+    /// [`Self::len`] leaves, from the stored nodes.
+    fn recompute_root_at(&self, leaf_count: usize, hasher: &PoseidonOptimized) -> Fr {
+        if leaf_count == 0 {
+            return hasher.hash2(&self.empty_hashes[N - 1], &self.empty_hashes[N - 1]);
+        }
+
+        // Re-run the append of the last pair, whose right siblings were still empty
+        let mut current_index = leaf_count / 2 - 1;
+        let mut current_level_hash = hasher.hash2(
+            &self.leaves[2 * current_index],
+            &self.leaves[2 * current_index + 1],
+        );
+        for (level, empty_hash) in self.nodes.iter().zip(&self.empty_hashes[1..]) {
+            let (left, right) = if current_index.is_multiple_of(2) {
+                (current_level_hash, *empty_hash)
+            } else {
+                (level[current_index - 1], current_level_hash)
+            };
+            current_level_hash = hasher.hash2(&left, &right);
+            current_index /= 2;
+        }
+        current_level_hash
+    }
+
+    /// Returns the number of pairs appended since `root` was the current
+    /// root, if it is one of the last [`ROOT_HISTORY_SIZE`] roots the contract
+    /// accepts. A proof against it stays valid for
+    /// `ROOT_HISTORY_SIZE - 1 - age` more appends.
+    pub fn root_age(&self, root: &Fr) -> Option<usize> {
+        // The contract never accepts a zero root
+        if *root == Fr::ZERO {
+            return None;
+        }
+        self.root_history
+            .iter()
+            .rev()
+            .position(|known| known == root)
+    }
+
+    /// Returns true if the contract still accepts proofs against `root`.
+    pub fn is_known_root(&self, root: &Fr) -> bool {
+        self.root_age(root).is_some()
+    }
+
+    /// Returns the roots the contract accepts, newest first.
+    pub fn recent_roots(&self) -> impl Iterator<Item = &Fr> {
+        self.root_history.iter().rev()
+    }
+
     /// Returns the number of leaves in the tree
     pub fn len(&self) -> usize {
         self.leaves.len()
@@ -257,12 +506,17 @@ impl<const N: usize> SparseMerkleTree<N> {
         &self.leaves
     }
 
-    /// Generate membership proof for leaf at given index
-    ///
-    /// Returns a Path containing siblings at each level:
-    /// - Level 0: (left_leaf, right_leaf) - the pair
-    /// - Levels 1 to N-1: (left_sibling, right_sibling) at each level
-    pub fn generate_membership_proof(&self, index: usize) -> anyhow::Result<Path<N>> {
+    /// Node `index` of `level`, level 0 being the leaves. Nodes past the
+    /// last stored one cover only empty leaves.
+    fn node(&self, level: usize, index: usize) -> Fr {
+        let stored = match level {
+            0 => self.leaves.get(index),
+            _ => self.nodes[level - 1].get(index),
+        };
+        stored.copied().unwrap_or(self.empty_hashes[level])
+    }
+
+    fn check_index(&self, index: usize) -> anyhow::Result<()> {
         if index >= self.leaves.len() {
             return Err(anyhow!(
                 "Index {} out of bounds (tree has {} leaves)",
@@ -270,129 +524,142 @@ impl<const N: usize> SparseMerkleTree<N> {
                 self.leaves.len()
             ));
         }
+        Ok(())
+    }
 
-        let mut path = [(Fr::ZERO, Fr::ZERO); N];
-        let hasher = PoseidonOptimized::new_t3();
-
-        // Level 0: Store the pair of leaves
-        let pair_index = index / 2;
-        let leaf_left = self.leaves[pair_index * 2];
-        let leaf_right = if pair_index * 2 + 1 < self.leaves.len() {
-            self.leaves[pair_index * 2 + 1]
-        } else {
-            self.empty_hashes[0]
-        };
-
-        path[0] = (leaf_left, leaf_right);
-
-        // Compute pair hash
-        let mut current_hash = hasher.hash2(&leaf_left, &leaf_right);
-        let mut current_index = pair_index;
-
-        // Rebuild tree state by simulating all insertions up to this point
-        // This matches the Move append_pair logic exactly
-        let num_pairs = self.leaves.len().div_ceil(2);
-        let mut pair_hashes = Vec::with_capacity(num_pairs);
+    /// Generate membership proof for leaf at given index
+    ///
+    /// Reads both children of every level from the stored nodes, addressed
+    /// by the index alone, so left and right leaves are handled alike and no
+    /// hashing is needed.
+    ///
+    /// Returns a Path containing siblings at each level:
+    /// - Level 0: (left_leaf, right_leaf) - the pair
+    /// - Levels 1 to N-1: (left_sibling, right_sibling) at each level
+    pub fn generate_membership_proof(&self, index: usize) -> anyhow::Result<Path<N>> {
+        self.check_index(index)?;
 
-        // Compute all pair hashes
-        for p in 0..num_pairs {
-            let left = self.leaves[p * 2];
-            let right = if p * 2 + 1 < self.leaves.len() {
-                self.leaves[p * 2 + 1]
-            } else {
-                self.empty_hashes[0]
-            };
-            pair_hashes.push(hasher.hash2(&left, &right));
+        let mut path = [(Fr::ZERO, Fr::ZERO); N];
+        for (level, pair) in path.iter_mut().enumerate() {
+            let left = (index >> level) & !1;
+            *pair = (self.node(level, left), self.node(level, left | 1));
         }
 
-        // Rebuild tree state by simulating all insertions
-        // We need to track the hash at each position at each level BEFORE combining with siblings
-        // This allows us to extract the correct sibling for the path
-        let mut level_child_hashes: Vec<Vec<Fr>> = Vec::new();
-
-        for level in 1..N {
-            // Initialize subtrees for this level (matching Move's subtrees array)
-            let mut level_subtrees = self.empty_hashes.to_vec();
-            // Track child hashes (before combining with siblings) at each position
-            let mut child_hashes: Vec<Fr> = Vec::new();
-
-            // Simulate inserting each pair sequentially (matching insert_pair logic)
-            for (pair_idx, &pair_hash) in pair_hashes.iter().enumerate() {
-                let mut pos = pair_idx;
-                let mut hash = pair_hash;
-
-                // Walk up from level 1 to current level
-                for (empty_hash, subtree) in self.empty_hashes[1..level]
-                    .iter()
-                    .zip(level_subtrees[1..level].iter_mut())
-                {
-                    let is_left = pos % 2 == 0;
-                    let left: Fr;
-                    let right: Fr;
-
-                    if is_left {
-                        left = hash;
-                        right = *empty_hash;
-                        *subtree = hash; // Cache left subtree
-                    } else {
-                        left = *subtree; // Get cached left subtree
-                        right = hash;
-                    }
+        Ok(Path { path })
+    }
 
-                    hash = hasher.hash2(&left, &right);
-                    pos /= 2;
-                }
+    /// Sibling of each level from leaf `index` up, the proof
+    /// [`verify_inclusion`] takes.
+    ///
+    /// Unlike [`Path::to_siblings`] the index is not recovered by hashing up
+    /// the path, which cannot tell the two leaves of a pair apart when they
+    /// are equal.
+    pub fn generate_siblings(&self, index: usize) -> anyhow::Result<[Fr; N]> {
+        self.check_index(index)?;
+        Ok(std::array::from_fn(|level| {
+            self.node(level, (index >> level) ^ 1)
+        }))
+    }
 
-                // At the current level, store the child hash (before combining with sibling)
-                let level_pos = pair_idx >> (level - 1);
-                if child_hashes.len() <= level_pos {
-                    child_hashes.resize(level_pos + 1, self.empty_hashes[level]);
-                }
-                child_hashes[level_pos] = hash;
-            }
+    /// Encodes the tree as a snapshot that [`Self::deserialize`] loads
+    /// without re-hashing the leaves.
+    ///
+    /// Layout: magic, version and height bytes, the empty leaf, the leaf
+    /// count as a little-endian `u64`, the leaves, the nodes of levels 1 to
+    /// N-1 and the root. Field elements are 32 big-endian bytes; each level
+    /// holds one node per started pair of the level below.
+    pub fn serialize(&self) -> Vec<u8> {
+        let node_count = self.nodes.iter().map(Vec::len).sum::<usize>();
+        let mut bytes = Vec::with_capacity(
+            TREE_SNAPSHOT_MAGIC.len() + 2 + 8 + FIELD_BYTES * (self.leaves.len() + node_count + 2),
+        );
 
-            level_child_hashes.push(child_hashes);
+        bytes.extend_from_slice(TREE_SNAPSHOT_MAGIC);
+        bytes.push(TREE_SNAPSHOT_VERSION);
+        bytes.push(N as u8);
+        bytes.extend_from_slice(&field_to_be_bytes(&self.empty_hashes[0]));
+        bytes.extend_from_slice(&(self.leaves.len() as u64).to_le_bytes());
+        for node in self.leaves.iter().chain(self.nodes.iter().flatten()) {
+            bytes.extend_from_slice(&field_to_be_bytes(node));
         }
+        bytes.extend_from_slice(&field_to_be_bytes(&self.root));
+        bytes
+    }
 
-        // Extract siblings from rebuilt tree
-        for (level, path_elem) in path.iter_mut().enumerate().skip(1) {
-            let is_left = current_index % 2 == 0;
-            let level_idx = level - 1;
-            let child_hashes = &level_child_hashes[level_idx];
-
-            let sibling = if is_left {
-                // We're on the left, sibling is on the right
-                let sibling_pos = current_index + 1;
-                child_hashes
-                    .get(sibling_pos)
-                    .copied()
-                    .unwrap_or(self.empty_hashes[level])
-            } else {
-                // We're on the right, sibling is on the left
-                if current_index > 0 {
-                    child_hashes
-                        .get(current_index - 1)
-                        .copied()
-                        .unwrap_or(self.subtrees[level])
-                } else {
-                    self.subtrees[level]
-                }
-            };
+    /// Loads a [`Self::serialize`] snapshot.
+    ///
+    /// Only the top level is hashed to check the root, so inner nodes are
+    /// taken as stored: compare [`Self::root`] with the on-chain root before
+    /// using proofs from an untrusted snapshot.
+    ///
+    /// # Errors
+    /// Returns error if the snapshot is truncated, has another version or
+    /// height, or its top level does not hash to its root.
+    pub fn deserialize(bytes: &[u8], hasher: &PoseidonOptimized) -> anyhow::Result<Self> {
+        let mut reader = SnapshotReader { bytes };
+
+        if reader.take(TREE_SNAPSHOT_MAGIC.len())? != TREE_SNAPSHOT_MAGIC {
+            return Err(anyhow!("Not a Merkle tree snapshot"));
+        }
+        let header = reader.take(2)?;
+        let (version, height) = (header[0], header[1]);
+        if version != TREE_SNAPSHOT_VERSION {
+            return Err(anyhow!(
+                "Unsupported snapshot version {}, expected {}",
+                version,
+                TREE_SNAPSHOT_VERSION
+            ));
+        }
+        if usize::from(height) != N {
+            return Err(anyhow!("Snapshot has height {}, expected {}", height, N));
+        }
 
-            *path_elem = if is_left {
-                (current_hash, sibling)
-            } else {
-                (sibling, current_hash)
-            };
+        let empty_hashes = empty_hashes(hasher, &reader.field()?);
+        let len = u64::from_le_bytes(reader.take(8)?.try_into()?);
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|&len| len.is_multiple_of(2) && len <= 1usize << N)
+            .ok_or_else(|| anyhow!("Invalid snapshot leaf count {}", len))?;
+
+        let leaves = reader.fields(len)?;
+        let nodes = (1..N)
+            .map(|level| reader.fields(len.div_ceil(1 << level)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let root = reader.field()?;
+        if !reader.bytes.is_empty() {
+            return Err(anyhow!(
+                "Snapshot has {} trailing bytes",
+                reader.bytes.len()
+            ));
+        }
 
-            current_hash = hasher.hash2(
-                if is_left { &current_hash } else { &sibling },
-                if is_left { &sibling } else { &current_hash },
-            );
-            current_index /= 2;
+        let top = nodes.last().unwrap_or(&leaves);
+        let top_hash = hasher.hash2(
+            top.first().unwrap_or(&empty_hashes[N - 1]),
+            top.get(1).unwrap_or(&empty_hashes[N - 1]),
+        );
+        if top_hash != root {
+            return Err(anyhow!("Snapshot nodes do not hash to its root"));
         }
 
-        Ok(Path { path })
+        let mut tree = Self {
+            leaves,
+            nodes,
+            empty_hashes,
+            root,
+            root_history: VecDeque::new(),
+        };
+
+        // Snapshots do not carry the history; recompute the roots it holds
+        let pairs = len / 2;
+        let root_history = (pairs.saturating_sub(ROOT_HISTORY_SIZE - 1)..=pairs)
+            .map(|pair_count| {
+                tree.root_at(2 * pair_count, hasher)
+                    .context("Snapshot root history is out of range")
+            })
+            .collect::<anyhow::Result<_>>()?;
+        tree.root_history = root_history;
+        Ok(tree)
     }
 
     /// Verify a path leads to the expected root
@@ -408,6 +675,29 @@ impl<const N: usize> SparseMerkleTree<N> {
     }
 }
 
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(anyhow!("Snapshot is truncated"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn field(&mut self) -> anyhow::Result<Fr> {
+        Ok(field_from_be_bytes(self.take(FIELD_BYTES)?)?)
+    }
+
+    fn fields(&mut self, count: usize) -> anyhow::Result<Vec<Fr>> {
+        (0..count).map(|_| self.field()).collect()
+    }
+}
+
 /// Circuit variable for Merkle path
 #[derive(Debug, Clone)]
 pub struct PathVar<const N: usize> {
@@ -448,6 +738,33 @@ impl<const N: usize> PathVar<N> {
 
         Ok(previous_hash)
     }
+
+    /// Calculate root hash and leaf index in circuit
+    ///
+    /// Bit `i` of the index is set when the node at level `i` is a right child,
+    /// as selected by the same comparison `root_hash` uses.
+    pub fn root_hash_and_index(
+        &self,
+        leaf: &FpVar<Fr>,
+        hasher: &PoseidonOptimizedVar,
+    ) -> Result<(FpVar<Fr>, FpVar<Fr>), SynthesisError> {
+        let mut previous_hash = leaf.clone();
+        let mut index = FpVar::<Fr>::zero();
+
+        for (level, (p_left_hash, p_right_hash)) in self.path.iter().enumerate() {
+            let previous_is_left = previous_hash.is_eq(p_left_hash)?;
+
+            let left_hash =
+                FpVar::conditionally_select(&previous_is_left, &previous_hash, p_left_hash)?;
+            let right_hash =
+                FpVar::conditionally_select(&previous_is_left, p_right_hash, &previous_hash)?;
+
+            index += FpVar::from(!previous_is_left) * Fr::from(1u128 << level);
+            previous_hash = hasher.hash2(&left_hash, &right_hash)?;
+        }
+
+        Ok((previous_hash, index))
+    }
 }
 
 impl<const N: usize> AllocVar<Path<N>, Fr> for PathVar<N> {
@@ -487,13 +804,13 @@ mod tests {
 
     /// Convert ZERO_VALUE string constant to Fr field element
     fn zero_value() -> Fr {
-        use num_bigint::BigUint;
-        use std::str::FromStr;
-
-        Fr::from(BigUint::from_str(ZERO_VALUE).expect("Failed to parse ZERO_VALUE"))
+        crate::field::parse_field(ZERO_VALUE).expect("Failed to parse ZERO_VALUE")
     }
     use ark_r1cs_std::R1CSVar;
     use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{RngCore, SeedableRng};
 
     #[test]
     fn test_path_verification_matches_circuit() {
@@ -660,6 +977,234 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sibling_conversion_roundtrip_all_leaves() {
+        let hasher = PoseidonOptimized::new_t3();
+        let leaves: Vec<Fr> = (1..=16u64).map(Fr::from).collect();
+
+        let mut tree = SparseMerkleTree::<4>::new_empty(&hasher, &zero_value());
+        tree.bulk_insert(&leaves, &hasher).unwrap();
+        let root = tree.root();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.generate_membership_proof(index).unwrap();
+            let (sibling_index, siblings) = path.to_siblings(leaf, &hasher).unwrap();
+
+            assert_eq!(sibling_index, index as u64);
+            assert_eq!(
+                Path::from_siblings(leaf, sibling_index, &siblings, &hasher).unwrap(),
+                path
+            );
+
+            // Any other index yields a path to a different root
+            let wrong_index = sibling_index ^ 1;
+            let wrong = Path::from_siblings(leaf, wrong_index, &siblings, &hasher).unwrap();
+            assert_ne!(wrong.calculate_root(leaf, &hasher).unwrap(), root);
+        }
+
+        let path = tree.generate_membership_proof(0).unwrap();
+        assert!(path.to_siblings(&Fr::from(99u64), &hasher).is_err());
+        assert!(Path::<4>::from_siblings(&leaves[0], 16, &[Fr::ZERO; 4], &hasher).is_err());
+    }
+
+    #[test]
+    fn test_sibling_conversion_partial_tree() {
+        let hasher = PoseidonOptimized::new_t3();
+        let mut tree = SparseMerkleTree::<4>::new_empty(&hasher, &zero_value());
+        for leaf in [1u64, 2, 3] {
+            tree.insert(Fr::from(leaf), &hasher).unwrap();
+        }
+
+        for (index, leaf) in tree.leaves().iter().enumerate() {
+            let path = tree.generate_membership_proof(index).unwrap();
+            let (sibling_index, siblings) = path.to_siblings(leaf, &hasher).unwrap();
+            let rebuilt = Path::from_siblings(leaf, sibling_index, &siblings, &hasher).unwrap();
+            assert!(rebuilt
+                .check_membership(&tree.root(), leaf, &hasher)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_proofs_track_every_insertion() {
+        let hasher = PoseidonOptimized::new_t3();
+        let mut tree = SparseMerkleTree::<6>::new_empty(&hasher, &zero_value());
+
+        for pair in 0..13u64 {
+            if pair % 3 == 0 {
+                tree.insert(Fr::from(pair), &hasher).unwrap();
+            } else {
+                tree.insert_pair(Fr::from(2 * pair), Fr::from(2 * pair + 1), &hasher)
+                    .unwrap();
+            }
+
+            for (index, leaf) in tree.leaves().iter().enumerate() {
+                let path = tree.generate_membership_proof(index).unwrap();
+                assert!(path.check_membership(&tree.root(), leaf, &hasher).unwrap());
+                assert_eq!(path.to_siblings(leaf, &hasher).unwrap().0, index as u64);
+            }
+        }
+        assert!(tree.generate_membership_proof(tree.len()).is_err());
+    }
+
+    /// Every index of random trees up to 2^12 leaves, full tree included,
+    /// proves membership under the root and round-trips through siblings.
+    #[test]
+    fn test_proofs_round_trip_on_random_trees() {
+        const HEIGHT: usize = 12;
+        let hasher = PoseidonOptimized::new_t3();
+        let mut rng = ChaCha20Rng::from_seed([12u8; 32]);
+        let max_pairs = 1 << (HEIGHT - 1);
+
+        let pair_counts = (0..2)
+            .map(|_| 1 + rng.next_u32() as usize % max_pairs)
+            .chain([1, max_pairs])
+            .collect::<Vec<_>>();
+        for pairs in pair_counts {
+            let leaves: Vec<Fr> = (0..2 * pairs).map(|_| Fr::rand(&mut rng)).collect();
+            let mut tree = SparseMerkleTree::<HEIGHT>::new_empty(&hasher, &zero_value());
+            tree.bulk_insert(&leaves, &hasher).unwrap();
+            let root = tree.root();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let path = tree.generate_membership_proof(index).unwrap();
+                let siblings = tree.generate_siblings(index).unwrap();
+
+                assert!(path.check_membership(&root, leaf, &hasher).unwrap());
+                // The sibling sits on the side the index bit points away from
+                for (level, ((left, right), sibling)) in path.path.iter().zip(&siblings).enumerate()
+                {
+                    let expected = if (index >> level) & 1 == 0 {
+                        right
+                    } else {
+                        left
+                    };
+                    assert_eq!(
+                        expected,
+                        sibling,
+                        "Leaf {index} of {}, level {level}",
+                        leaves.len()
+                    );
+                }
+            }
+            assert!(tree.generate_siblings(leaves.len()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_siblings_prove_right_leaf_of_equal_pair() {
+        let hasher = PoseidonOptimized::new_t3();
+        let leaf = Fr::from(7u64);
+        let mut tree = SparseMerkleTree::<4>::new_empty(&hasher, &zero_value());
+        tree.insert_pair(Fr::from(1u64), Fr::from(2u64), &hasher)
+            .unwrap();
+        tree.insert_pair(leaf, leaf, &hasher).unwrap();
+
+        let siblings = tree.generate_siblings(3).unwrap();
+        assert!(verify_inclusion(&tree.root(), 3, &leaf, &siblings).unwrap());
+        assert_eq!(
+            Path::from_siblings(&leaf, 3, &siblings, &hasher).unwrap(),
+            tree.generate_membership_proof(3).unwrap()
+        );
+
+        // Recovering the index from the path takes the equal left leaf
+        let path = tree.generate_membership_proof(3).unwrap();
+        assert_eq!(path.to_siblings(&leaf, &hasher).unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_insert_batch_fast_matches_pairwise_insertion() {
+        let hasher = PoseidonOptimized::new_t3();
+        let pairs = (0..150u64)
+            .map(|i| (Fr::from(2 * i), Fr::from(2 * i + 1)))
+            .collect::<Vec<_>>();
+
+        let mut expected = SparseMerkleTree::<9>::new_empty(&hasher, &zero_value());
+        let mut fast = expected.clone();
+        fast.insert_batch_fast(&[], &hasher).unwrap();
+        assert_eq!(fast, expected);
+
+        // Batches starting on both sides of a pair of level-1 nodes, and one
+        // longer than the root history
+        let mut start = 0;
+        for size in [1, 1, 2, 3, 5, 120, 18] {
+            let batch = &pairs[start..start + size];
+            expected.insert_batch(batch, &hasher).unwrap();
+            fast.insert_batch_fast(batch, &hasher).unwrap();
+            assert_eq!(
+                fast,
+                expected,
+                "After inserting pairs {start}..{}",
+                start + size
+            );
+            start += size;
+        }
+
+        let mut full = SparseMerkleTree::<9>::new_empty(&hasher, &zero_value());
+        full.insert_batch_fast(&pairs, &hasher).unwrap();
+        assert_eq!(full, expected);
+        assert!(full.insert_batch_fast(&pairs[..110], &hasher).is_err());
+        assert_eq!(full, expected);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let hasher = PoseidonOptimized::new_t3();
+        let mut tree = SparseMerkleTree::<4>::new_empty(&hasher, &zero_value());
+        let empty = SparseMerkleTree::<4>::deserialize(&tree.serialize(), &hasher).unwrap();
+        assert_eq!(empty, tree);
+
+        for leaf in 1..=5u64 {
+            tree.insert(Fr::from(leaf), &hasher).unwrap();
+        }
+        let snapshot = tree.serialize();
+        let loaded = SparseMerkleTree::<4>::deserialize(&snapshot, &hasher).unwrap();
+        assert_eq!(loaded, tree);
+        assert_eq!(
+            loaded.generate_membership_proof(7).unwrap(),
+            tree.generate_membership_proof(7).unwrap()
+        );
+
+        let mut wrong_root = snapshot.clone();
+        *wrong_root.last_mut().unwrap() ^= 1;
+        assert!(SparseMerkleTree::<4>::deserialize(&wrong_root, &hasher).is_err());
+        assert!(
+            SparseMerkleTree::<4>::deserialize(&snapshot[..snapshot.len() - 1], &hasher).is_err()
+        );
+        assert!(SparseMerkleTree::<5>::deserialize(&snapshot, &hasher).is_err());
+    }
+
+    #[test]
+    fn test_root_history_matches_contract_window() {
+        let hasher = PoseidonOptimized::new_t3();
+        let mut tree = SparseMerkleTree::<8>::new_empty(&hasher, &zero_value());
+        let mut roots = vec![tree.root()];
+
+        let pairs = ROOT_HISTORY_SIZE + 10;
+        for i in 0..pairs as u64 {
+            tree.insert_pair(Fr::from(2 * i + 1), Fr::from(2 * i + 2), &hasher)
+                .unwrap();
+            roots.push(tree.root());
+        }
+
+        for (pair_count, root) in roots.iter().enumerate() {
+            assert_eq!(tree.root_at(2 * pair_count, &hasher), Some(*root));
+
+            let age = pairs - pair_count;
+            let expected_age = (age < ROOT_HISTORY_SIZE).then_some(age);
+            assert_eq!(tree.root_age(root), expected_age, "pair count {pair_count}");
+        }
+        assert_eq!(tree.recent_roots().count(), ROOT_HISTORY_SIZE);
+        assert_eq!(tree.recent_roots().next(), Some(&tree.root()));
+
+        assert_eq!(tree.root_at(3, &hasher), None);
+        assert_eq!(tree.root_at(tree.len() + 2, &hasher), None);
+        assert!(!tree.is_known_root(&Fr::ZERO));
+
+        let loaded = SparseMerkleTree::<8>::deserialize(&tree.serialize(), &hasher).unwrap();
+        assert_eq!(loaded, tree);
+    }
+
     /// Reference Move-style implementation for testing
     fn move_style_root<const N: usize>(
         leaf_pairs: &[(Fr, Fr)],
@@ -690,7 +1235,7 @@ mod tests {
 
             for i in 1..N {
                 let subtree = &mut subtrees[i];
-                let (left, right) = if current_index % 2 == 0 {
+                let (left, right) = if current_index.is_multiple_of(2) {
                     *subtree = current_level_hash;
                     (current_level_hash, empty_subtree_hashes[i])
                 } else {
@@ -0,0 +1,335 @@
+use crate::{derivation::ViewingKey, prover::parse_field_element};
+use anyhow::{anyhow, Context};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use hkdf::Hkdf;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Version byte leading every `encrypted_output`
+pub const NOTE_VERSION: u8 = 1;
+
+const FIELD_BYTES: usize = 32;
+const PLAINTEXT_LEN: usize = 2 * FIELD_BYTES;
+const TAG_LEN: usize = 16;
+
+/// Length of an `encrypted_output`: version, ephemeral public key, ciphertext and tag
+pub const ENCRYPTED_NOTE_LEN: usize = 1 + 32 + PLAINTEXT_LEN + TAG_LEN;
+
+/// `encrypted_output` of a canonical empty output (see
+/// [`empty_output_commitment`](crate::circuit::empty_output_commitment)). It
+/// carries no note, so scanners skip it without a key agreement.
+pub const EMPTY_OUTPUT_MARKER: [u8; 1] = [0];
+
+const SECRET_INFO: &[u8] = b"vortex-note-secret";
+const CIPHER_INFO: &[u8] = b"vortex-note-cipher";
+
+/// Private data a recipient needs to spend an output.
+///
+/// The public key is the recipient's own, so it is not part of the note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note {
+    pub amount: Fr,
+    pub blinding: Fr,
+}
+
+/// x25519 key senders encrypt outputs to. Derived from the viewing key, so
+/// anyone holding the viewing key can decrypt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionKey(pub [u8; 32]);
+
+impl ViewingKey {
+    fn encryption_secret(&self) -> StaticSecret {
+        let mut secret = [0u8; 32];
+        Hkdf::<Sha256>::new(None, &self.to_bytes())
+            .expand(SECRET_INFO, &mut secret)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        StaticSecret::from(secret)
+    }
+
+    /// Returns the public key senders encrypt this account's outputs to.
+    pub fn encryption_key(&self) -> EncryptionKey {
+        EncryptionKey(PublicKey::from(&self.encryption_secret()).to_bytes())
+    }
+}
+
+/// Encrypts `note` to `recipient` (x25519 ECIES with ChaCha20-Poly1305).
+///
+/// Layout: `version (1) || ephemeral public key (32) || ciphertext (64) || tag (16)`.
+/// Every note uses a fresh ephemeral key, so the derived cipher key is never
+/// reused and a zero nonce is safe.
+pub fn encrypt_note<R: RngCore + CryptoRng>(
+    note: &Note,
+    recipient: &EncryptionKey,
+    rng: &mut R,
+) -> anyhow::Result<Vec<u8>> {
+    let ephemeral = StaticSecret::random_from_rng(&mut *rng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(recipient.0));
+
+    let mut plaintext = Vec::with_capacity(PLAINTEXT_LEN);
+    plaintext.extend(field_bytes(&note.amount));
+    plaintext.extend(field_bytes(&note.blinding));
+
+    let ciphertext = cipher(shared.as_bytes(), ephemeral_public.as_bytes())
+        .encrypt(&Nonce::default(), plaintext.as_slice())
+        .map_err(|_| anyhow!("Failed to encrypt note"))?;
+
+    let mut output = Vec::with_capacity(ENCRYPTED_NOTE_LEN);
+    output.push(NOTE_VERSION);
+    output.extend(ephemeral_public.as_bytes());
+    output.extend(ciphertext);
+    Ok(output)
+}
+
+/// Decrypts an `encrypted_output` with the recipient's viewing key.
+///
+/// # Errors
+/// Returns error if the output has the wrong version or length, or was not
+/// encrypted to this viewing key.
+pub fn decrypt_note(encrypted: &[u8], viewing_key: &ViewingKey) -> anyhow::Result<Note> {
+    if is_empty_output(encrypted) {
+        return Err(anyhow!(
+            "Output is an empty-output marker and holds no note"
+        ));
+    }
+    if encrypted.len() != ENCRYPTED_NOTE_LEN {
+        return Err(anyhow!(
+            "Encrypted note must be {} bytes, got {}",
+            ENCRYPTED_NOTE_LEN,
+            encrypted.len()
+        ));
+    }
+    if encrypted[0] != NOTE_VERSION {
+        return Err(anyhow!(
+            "Unsupported note version {}, expected {}",
+            encrypted[0],
+            NOTE_VERSION
+        ));
+    }
+
+    let ephemeral_public: [u8; 32] = encrypted[1..33].try_into().expect("slice is 32 bytes long");
+    let shared = viewing_key
+        .encryption_secret()
+        .diffie_hellman(&PublicKey::from(ephemeral_public));
+
+    let plaintext = cipher(shared.as_bytes(), &ephemeral_public)
+        .decrypt(&Nonce::default(), &encrypted[33..])
+        .map_err(|_| anyhow!("Note was not encrypted to this viewing key"))?;
+
+    Ok(Note {
+        amount: Fr::from_be_bytes_mod_order(&plaintext[..FIELD_BYTES]),
+        blinding: Fr::from_be_bytes_mod_order(&plaintext[FIELD_BYTES..]),
+    })
+}
+
+/// Returns whether `encrypted` is the [`EMPTY_OUTPUT_MARKER`].
+pub fn is_empty_output(encrypted: &[u8]) -> bool {
+    encrypted == EMPTY_OUTPUT_MARKER
+}
+
+/// Returns the position and note of every output encrypted to `viewing_key`.
+///
+/// Empty-output markers are skipped before any decryption is attempted, and
+/// outputs for other recipients are ignored.
+pub fn scan_notes<'a>(
+    outputs: impl IntoIterator<Item = &'a [u8]>,
+    viewing_key: &ViewingKey,
+) -> Vec<(usize, Note)> {
+    outputs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, encrypted)| !is_empty_output(encrypted))
+        .filter_map(|(position, encrypted)| {
+            decrypt_note(encrypted, viewing_key)
+                .ok()
+                .map(|note| (position, note))
+        })
+        .collect()
+}
+
+fn cipher(shared_secret: &[u8; 32], ephemeral_public: &[u8; 32]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(ephemeral_public), shared_secret)
+        .expand(CIPHER_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn field_bytes(value: &Fr) -> Vec<u8> {
+    value.into_bigint().to_bytes_be()
+}
+
+/// String-encoded [`Note`] for the JSON bindings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteJson {
+    pub amount: String,
+    pub blinding: String,
+}
+
+/// Returns the hex-encoded encryption key of a viewing key (decimal or 0x-hex).
+pub fn encryption_key_hex(viewing_key: &str) -> anyhow::Result<String> {
+    let viewing_key = ViewingKey(parse_field_element(viewing_key)?);
+    Ok(hex::encode(viewing_key.encryption_key().0))
+}
+
+/// Encrypts a JSON [`NoteJson`] to a hex-encoded encryption key.
+///
+/// `entropy_hex` must be 32 fresh random bytes (e.g. `crypto.getRandomValues`),
+/// which seed the ephemeral key.
+pub fn encrypt_note_json(
+    note_json: &str,
+    encryption_key_hex: &str,
+    entropy_hex: &str,
+) -> anyhow::Result<String> {
+    let note: NoteJson = serde_json::from_str(note_json).context("Failed to parse note JSON")?;
+    let note = Note {
+        amount: parse_field_element(&note.amount)?,
+        blinding: parse_field_element(&note.blinding)?,
+    };
+
+    let recipient: [u8; 32] = hex::decode(encryption_key_hex)
+        .context("Failed to decode encryption key hex")?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| {
+            anyhow!("Encryption key must be 32 bytes, got {}", bytes.len())
+        })?;
+
+    let seed: [u8; 32] = hex::decode(entropy_hex)
+        .context("Failed to decode entropy hex")?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow!("Entropy must be 32 bytes, got {}", bytes.len()))?;
+
+    let encrypted = encrypt_note(
+        &note,
+        &EncryptionKey(recipient),
+        &mut ChaCha20Rng::from_seed(seed),
+    )?;
+    Ok(hex::encode(encrypted))
+}
+
+/// Decrypts a hex-encoded `encrypted_output` and returns the note as JSON.
+pub fn decrypt_note_json(encrypted_hex: &str, viewing_key: &str) -> anyhow::Result<String> {
+    let encrypted = hex::decode(encrypted_hex).context("Failed to decode encrypted note hex")?;
+    let note = decrypt_note(&encrypted, &ViewingKey(parse_field_element(viewing_key)?))?;
+
+    serde_json::to_string(&NoteJson {
+        amount: note.amount.into_bigint().to_string(),
+        blinding: note.blinding.into_bigint().to_string(),
+    })
+    .context("Failed to serialize note")
+}
+
+/// [`NoteJson`] found by [`scan_notes_json`], with its position in the scanned list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedNoteJson {
+    pub position: usize,
+    #[serde(flatten)]
+    pub note: NoteJson,
+}
+
+/// Scans a JSON array of hex-encoded `encrypted_output`s for notes owned by
+/// `viewing_key` and returns them as a JSON array of [`ScannedNoteJson`].
+pub fn scan_notes_json(outputs_json: &str, viewing_key: &str) -> anyhow::Result<String> {
+    let outputs: Vec<String> =
+        serde_json::from_str(outputs_json).context("Failed to parse outputs JSON")?;
+    let outputs = outputs
+        .iter()
+        .map(|output| hex::decode(output).context("Failed to decode encrypted note hex"))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let viewing_key = ViewingKey(parse_field_element(viewing_key)?);
+
+    let notes = scan_notes(outputs.iter().map(Vec::as_slice), &viewing_key)
+        .into_iter()
+        .map(|(position, note)| ScannedNoteJson {
+            position,
+            note: NoteJson {
+                amount: note.amount.into_bigint().to_string(),
+                blinding: note.blinding.into_bigint().to_string(),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string(&notes).context("Failed to serialize notes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivation::MasterSeed;
+
+    #[test]
+    fn test_note_roundtrip_and_tamper_detection() {
+        let recipient = MasterSeed::from_bytes(&[1u8; 32]).unwrap().account(0);
+        let other = MasterSeed::from_bytes(&[2u8; 32]).unwrap().account(0);
+        let note = Note {
+            amount: Fr::from(1_000_000_000u64),
+            blinding: recipient.viewing_key.blinding(3),
+        };
+
+        let mut rng = ChaCha20Rng::from_seed([9u8; 32]);
+        let encrypted =
+            encrypt_note(&note, &recipient.viewing_key.encryption_key(), &mut rng).unwrap();
+        assert_eq!(encrypted.len(), ENCRYPTED_NOTE_LEN);
+        assert_eq!(
+            decrypt_note(&encrypted, &recipient.viewing_key).unwrap(),
+            note
+        );
+
+        assert!(decrypt_note(&encrypted, &other.viewing_key).is_err());
+
+        let mut tampered = encrypted.clone();
+        tampered[40] ^= 1;
+        assert!(decrypt_note(&tampered, &recipient.viewing_key).is_err());
+        assert!(decrypt_note(&encrypted[1..], &recipient.viewing_key).is_err());
+
+        // Fresh ephemeral keys make repeated encryptions unlinkable
+        let again = encrypt_note(&note, &recipient.viewing_key.encryption_key(), &mut rng).unwrap();
+        assert_ne!(encrypted, again);
+    }
+
+    #[test]
+    fn test_note_json_roundtrip() {
+        let viewing_key = "12345";
+        let encryption_key = encryption_key_hex(viewing_key).unwrap();
+
+        let encrypted = encrypt_note_json(
+            r#"{"amount":"42","blinding":"0x07"}"#,
+            &encryption_key,
+            &hex::encode([5u8; 32]),
+        )
+        .unwrap();
+
+        let note: NoteJson =
+            serde_json::from_str(&decrypt_note_json(&encrypted, viewing_key).unwrap()).unwrap();
+        assert_eq!(note.amount, "42");
+        assert_eq!(note.blinding, "7");
+    }
+
+    #[test]
+    fn test_scan_skips_empty_outputs() {
+        let recipient = MasterSeed::from_bytes(&[1u8; 32]).unwrap().account(0);
+        let other = MasterSeed::from_bytes(&[2u8; 32]).unwrap().account(0);
+        let note = Note {
+            amount: Fr::from(5u64),
+            blinding: Fr::from(6u64),
+        };
+
+        let mut rng = ChaCha20Rng::from_seed([9u8; 32]);
+        let mine = encrypt_note(&note, &recipient.viewing_key.encryption_key(), &mut rng).unwrap();
+        let theirs = encrypt_note(&note, &other.viewing_key.encryption_key(), &mut rng).unwrap();
+
+        let outputs = [theirs.as_slice(), &EMPTY_OUTPUT_MARKER, mine.as_slice()];
+        assert_eq!(scan_notes(outputs, &recipient.viewing_key), vec![(2, note)]);
+        assert!(decrypt_note(&EMPTY_OUTPUT_MARKER, &recipient.viewing_key).is_err());
+    }
+}
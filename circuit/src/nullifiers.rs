@@ -0,0 +1,84 @@
+#[cfg(feature = "fetch")]
+use crate::field::field_to_decimal;
+#[cfg(feature = "fetch")]
+use crate::prover::parse_field_element;
+#[cfg(feature = "fetch")]
+use anyhow::Context;
+use ark_bn254::Fr;
+#[cfg(feature = "fetch")]
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// The pool's spent nullifiers, checked before proving a spend.
+pub trait SpentNullifiers {
+    /// Returns those of `nullifiers` that are already spent.
+    fn spent(&self, nullifiers: &[Fr]) -> anyhow::Result<Vec<Fr>>;
+}
+
+impl SpentNullifiers for HashSet<Fr> {
+    fn spent(&self, nullifiers: &[Fr]) -> anyhow::Result<Vec<Fr>> {
+        Ok(nullifiers
+            .iter()
+            .filter(|nullifier| self.contains(nullifier))
+            .copied()
+            .collect())
+    }
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+struct ApiResponse {
+    data: ApiSpent,
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+struct ApiSpent {
+    spent: Vec<String>,
+}
+
+/// [`SpentNullifiers`] of one pool, looked up in the indexer through
+/// `GET /api/v1/nullifiers/spent` of the Vortex API at `api_url`.
+#[cfg(feature = "fetch")]
+#[derive(Debug, Clone)]
+pub struct ApiSpentNullifiers {
+    pub api_url: String,
+    pub coin_type: String,
+}
+
+#[cfg(feature = "fetch")]
+impl SpentNullifiers for ApiSpentNullifiers {
+    fn spent(&self, nullifiers: &[Fr]) -> anyhow::Result<Vec<Fr>> {
+        if nullifiers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!(
+            "{}/api/v1/nullifiers/spent",
+            self.api_url.trim_end_matches('/')
+        );
+        let query = nullifiers
+            .iter()
+            .map(field_to_decimal)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let body = ureq::get(&url)
+            .query("coin_type", &self.coin_type)
+            .query("nullifiers", query)
+            .call()
+            .with_context(|| format!("Failed to fetch spent nullifiers from {url}"))?
+            .into_body()
+            .read_to_string()
+            .context("Failed to read spent nullifiers response")?;
+        let response: ApiResponse =
+            serde_json::from_str(&body).context("Invalid spent nullifiers response")?;
+
+        response
+            .data
+            .spent
+            .iter()
+            .map(|nullifier| parse_field_element(nullifier))
+            .collect()
+    }
+}
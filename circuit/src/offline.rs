@@ -0,0 +1,212 @@
+use crate::{
+    builder::{InputUtxo, OutputUtxo, TransactionBuilder},
+    circuit::TransactionCircuit,
+    constants::{N_INS, N_OUTS},
+    deposit::DepositNote,
+    prover::parse_field_element,
+    tree_state::{CommitmentEntry, IndexedCommitment, TreeState},
+};
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::Fr;
+use ark_ff::{PrimeField, UniformRand};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Layout version of [`OfflineBundle`]; bumped when its fields change.
+pub const OFFLINE_BUNDLE_VERSION: u32 = 1;
+
+/// Everything an air-gapped machine needs to prove a withdrawal: the pool's
+/// commitments and the root they hash to, exported on an online machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineBundle {
+    pub version: u32,
+    pub coin_type: String,
+    pub root: String,
+    pub commitments: Vec<CommitmentEntry>,
+}
+
+impl OfflineBundle {
+    /// Rebuilds the tree from `commitments` and records its root.
+    pub fn new(coin_type: &str, commitments: Vec<IndexedCommitment>) -> anyhow::Result<Self> {
+        let tree = TreeState::new(commitments.clone())?;
+
+        Ok(Self {
+            version: OFFLINE_BUNDLE_VERSION,
+            coin_type: coin_type.to_string(),
+            root: tree.root().into_bigint().to_string(),
+            commitments: commitments.into_iter().map(CommitmentEntry::from).collect(),
+        })
+    }
+
+    /// Rebuilds the tree of the bundle.
+    ///
+    /// # Errors
+    /// Returns error if the bundle has another version or its commitments do
+    /// not hash to its root, e.g. after it was truncated in transit.
+    pub fn tree(&self) -> anyhow::Result<TreeState> {
+        if self.version != OFFLINE_BUNDLE_VERSION {
+            bail!(
+                "Unsupported bundle version {}, expected {}",
+                self.version,
+                OFFLINE_BUNDLE_VERSION
+            );
+        }
+
+        let commitments = self
+            .commitments
+            .iter()
+            .map(IndexedCommitment::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let tree = TreeState::new(commitments)?;
+
+        let root = parse_field_element(&self.root).context("Invalid bundle root")?;
+        if tree.root() != root {
+            bail!(
+                "Bundle commitments hash to root {}, the bundle records {}",
+                tree.root(),
+                root
+            );
+        }
+        Ok(tree)
+    }
+}
+
+/// Withdrawal built from a bundle, with the note holding the change, if any.
+#[derive(Debug, Clone)]
+pub struct Withdrawal {
+    pub circuit: TransactionCircuit<N_INS, N_OUTS>,
+    pub change: Option<DepositNote>,
+}
+
+/// Builds a withdrawal of `amount` from `note`, or of the whole note when
+/// `amount` is `None`, against the tree of `bundle`.
+///
+/// The change goes back to the note's spend key with a fresh blinding, and is
+/// returned as a new note since it is not encrypted to any viewing key.
+pub fn build_withdrawal<R: RngCore + CryptoRng>(
+    bundle: &OfflineBundle,
+    note: &DepositNote,
+    amount: Option<Fr>,
+    rng: &mut R,
+) -> anyhow::Result<Withdrawal> {
+    let tree = bundle.tree()?;
+    let index = tree
+        .position(&note.commitment())
+        .ok_or_else(|| anyhow!("The note's commitment is not in the bundle"))?;
+
+    let amount = amount.unwrap_or(note.amount);
+    if amount.into_bigint() > note.amount.into_bigint() {
+        bail!(
+            "Cannot withdraw {} from a note of {}",
+            amount.into_bigint(),
+            note.amount.into_bigint()
+        );
+    }
+
+    let mut builder = TransactionBuilder::<N_INS, N_OUTS>::new(note.vortex, tree.root())
+        .padding_seed(Fr::rand(rng))
+        .empty_outputs()
+        .input(InputUtxo {
+            amount: note.amount,
+            blinding: note.blinding,
+            private_key: note.spend_key,
+            path_index: index as u64,
+            path: tree.merkle_path(index)?,
+        });
+
+    let change = (amount != note.amount).then(|| DepositNote {
+        amount: note.amount - amount,
+        blinding: Fr::rand(rng),
+        ..*note
+    });
+    if let Some(change) = &change {
+        builder = builder.output(OutputUtxo {
+            amount: change.amount,
+            public_key: change.public_key(),
+            blinding: change.blinding,
+        });
+    }
+
+    Ok(Withdrawal {
+        circuit: builder.build()?,
+        change,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{note_commitment, CircuitVersion};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn bundle_with(note: &DepositNote) -> OfflineBundle {
+        let commitments = [Fr::from(11u64), note.commitment(), Fr::from(13u64)]
+            .into_iter()
+            .zip(0u64..)
+            .map(|(commitment, index)| IndexedCommitment { index, commitment })
+            .collect();
+        OfflineBundle::new("0x2::sui::SUI", commitments).unwrap()
+    }
+
+    #[test]
+    fn test_bundle_round_trips_and_detects_tampering() {
+        let mut rng = ChaCha20Rng::from_seed([5u8; 32]);
+        let note = DepositNote::generate(Fr::from(7u64), Fr::from(1_000u64), &mut rng);
+        let bundle = bundle_with(&note);
+
+        let decoded: OfflineBundle =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert_eq!(
+            decoded.tree().unwrap().root().into_bigint().to_string(),
+            bundle.root
+        );
+
+        let mut truncated = decoded.clone();
+        truncated.commitments.pop();
+        let err = truncated.tree().unwrap_err();
+        assert!(err.to_string().contains("hash to root"), "{err}");
+
+        let mut future = decoded;
+        future.version += 1;
+        assert!(future.tree().is_err());
+    }
+
+    #[test]
+    fn test_withdrawal_spends_note_and_keeps_change() {
+        let mut rng = ChaCha20Rng::from_seed([5u8; 32]);
+        let note = DepositNote::generate(Fr::from(7u64), Fr::from(1_000u64), &mut rng);
+        let bundle = bundle_with(&note);
+
+        let full = build_withdrawal(&bundle, &note, None, &mut rng).unwrap();
+        assert!(full.change.is_none());
+        assert_eq!(full.circuit.public_amount, -Fr::from(1_000u64));
+
+        let partial = build_withdrawal(&bundle, &note, Some(Fr::from(400u64)), &mut rng).unwrap();
+        let change = partial.change.unwrap();
+        assert_eq!(change.amount, Fr::from(600u64));
+        assert_eq!(change.spend_key, note.spend_key);
+        assert_eq!(partial.circuit.public_amount, -Fr::from(400u64));
+        assert_eq!(
+            partial.circuit.get_public_inputs()[5],
+            note_commitment(
+                CircuitVersion::V1,
+                note.vortex,
+                None,
+                change.amount,
+                change.public_key(),
+                change.blinding,
+            )
+        );
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        partial.circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        assert!(build_withdrawal(&bundle, &note, Some(Fr::from(1_001u64)), &mut rng).is_err());
+        let stranger = DepositNote::generate(note.vortex, note.amount, &mut rng);
+        assert!(build_withdrawal(&bundle, &stranger, None, &mut rng).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Versioned JSON envelope the CLIs print with `--output json`, so scripts
+//! read results and failures alike from stdout instead of scraping logs.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Bumped when the envelope or the `data` of a command changes incompatibly.
+pub const OUTPUT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow::anyhow!("Unknown output '{}'. Use: text or json", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub status: Status,
+    pub data: Option<T>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T: Serialize> Envelope<T> {
+    pub fn ok(data: T) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            status: Status::Ok,
+            data: Some(data),
+            warnings: Vec::new(),
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.push(warning.into());
+        self
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl Envelope<()> {
+    /// A failed command; `{error:#}` keeps the context chain of anyhow errors.
+    pub fn error(error: impl fmt::Display) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            status: Status::Error,
+            data: None,
+            warnings: Vec::new(),
+            error: Some(format!("{error:#}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_envelope_shape() {
+        let ok = Envelope::ok(json!({ "root": "1" })).with_warning("fixed seed");
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            json!({
+                "version": OUTPUT_VERSION,
+                "status": "ok",
+                "data": { "root": "1" },
+                "warnings": ["fixed seed"],
+            })
+        );
+
+        let error = Envelope::error(anyhow::anyhow!("inner").context("outer"));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "version": OUTPUT_VERSION,
+                "status": "error",
+                "data": null,
+                "warnings": [],
+                "error": "outer: inner",
+            })
+        );
+
+        assert_eq!(
+            "JSON".parse::<OutputFormat>().ok(),
+            Some(OutputFormat::Json)
+        );
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
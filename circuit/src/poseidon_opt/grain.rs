@@ -0,0 +1,271 @@
+// src/poseidon_opt/grain.rs
+//
+// Derives the optimized Poseidon constants from the reference Grain LFSR
+// (generate_parameters_grain.sage) instead of trusting the copied tables.
+//
+// The LFSR yields the round constants and the Cauchy MDS matrix; the optimized
+// C, S and P then follow the Poseidon paper's appendix B: partial round
+// constants are moved up to single scalars and the partial round matrices are
+// split into a pre-sparse matrix and one sparse matrix per round.
+
+use anyhow::{anyhow, bail};
+use ark_bn254::Fr;
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use num_bigint::BigUint;
+
+/// Width of a BN254 element in the LFSR parameters.
+const FIELD_BITS: usize = 254;
+/// LFSR `field` tag for prime fields.
+const PRIME_FIELD: usize = 1;
+/// LFSR `sbox` tag for x^alpha.
+const POWER_SBOX: usize = 0;
+const LFSR_SIZE: usize = 80;
+/// Clocks discarded after seeding the LFSR.
+const WARMUP_CLOCKS: usize = 160;
+
+/// The 80-bit Grain LFSR of the Poseidon reference, with its self-shrinking output.
+struct Grain {
+    state: Vec<bool>,
+}
+
+impl Grain {
+    fn new(t: usize, n_rounds_f: usize, n_rounds_p: usize) -> Self {
+        let fields = [
+            (PRIME_FIELD, 2),
+            (POWER_SBOX, 4),
+            (FIELD_BITS, 12),
+            (t, 12),
+            (n_rounds_f, 10),
+            (n_rounds_p, 10),
+        ];
+        let mut state = fields
+            .iter()
+            .flat_map(|&(value, width)| (0..width).rev().map(move |bit| (value >> bit) & 1 == 1))
+            .collect::<Vec<_>>();
+        state.resize(LFSR_SIZE, true);
+
+        let mut grain = Self { state };
+        for _ in 0..WARMUP_CLOCKS {
+            grain.clock();
+        }
+        grain
+    }
+
+    fn clock(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.remove(0);
+        self.state.push(bit);
+        bit
+    }
+
+    /// Clocks pairs of bits and keeps the second of each pair whose first is set.
+    fn bit(&mut self) -> bool {
+        loop {
+            let keep = self.clock();
+            let bit = self.clock();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Next `FIELD_BITS` output bits as a big-endian integer.
+    fn integer(&mut self) -> BigUint {
+        (0..FIELD_BITS).fold(BigUint::ZERO, |acc, _| (acc << 1u8) + u8::from(self.bit()))
+    }
+
+    /// Next integer below the modulus, rejecting the others.
+    fn field_element(&mut self) -> Fr {
+        let modulus = BigUint::from(Fr::MODULUS);
+        loop {
+            let value = self.integer();
+            if value < modulus {
+                return Fr::from(value);
+            }
+        }
+    }
+}
+
+type Matrix = Vec<Vec<Fr>>;
+
+fn transpose(matrix: &Matrix) -> Matrix {
+    (0..matrix[0].len())
+        .map(|j| matrix.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| row.iter().zip(b).map(|(x, b_row)| *x * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+fn apply(matrix: &Matrix, vector: &[Fr]) -> Vec<Fr> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(vector).map(|(a, b)| *a * b).sum())
+        .collect()
+}
+
+/// Gauss-Jordan inversion.
+fn invert(matrix: &Matrix) -> anyhow::Result<Matrix> {
+    let n = matrix.len();
+    let mut rows = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|j| if i == j { Fr::ONE } else { Fr::ZERO }));
+            row
+        })
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|&r| rows[r][col] != Fr::ZERO)
+            .ok_or_else(|| anyhow!("Matrix is singular"))?;
+        rows.swap(col, pivot);
+
+        let inverse = rows[col][col]
+            .inverse()
+            .ok_or_else(|| anyhow!("Matrix is singular"))?;
+        rows[col].iter_mut().for_each(|x| *x *= inverse);
+
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != col && factor != Fr::ZERO {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(x, p)| *x -= factor * p);
+            }
+        }
+    }
+
+    Ok(rows.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+/// Cauchy matrix `1 / (x_i + y_j)` from the first `2t` distinct LFSR elements,
+/// reduced modulo the field rather than rejected.
+fn cauchy_matrix(grain: &mut Grain, t: usize) -> Matrix {
+    loop {
+        let values = (0..2 * t)
+            .map(|_| Fr::from(grain.integer()))
+            .collect::<Vec<_>>();
+        let distinct = values
+            .iter()
+            .enumerate()
+            .all(|(i, value)| !values[..i].contains(value));
+        if !distinct {
+            continue;
+        }
+
+        let (xs, ys) = values.split_at(t);
+        let matrix = xs
+            .iter()
+            .map(|x| {
+                ys.iter()
+                    .map(|y| (*x + y).inverse())
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Matrix>>();
+        if let Some(matrix) = matrix {
+            return matrix;
+        }
+    }
+}
+
+/// Generates the optimized constants `(c, s, m, p)` for width `t`, in the
+/// layout of [`super::poseidon_constants_opt`].
+///
+/// The MDS matrix is the first Cauchy matrix the LFSR yields. The reference
+/// script also runs its security checks on it and resamples on failure; those
+/// checks are not repeated here, so for parameters other than circomlib's
+/// compare the result against the reference.
+///
+/// # Errors
+/// Returns error if `t < 2`, `n_rounds_f` is odd or zero, or a matrix in the
+/// derivation is singular.
+#[allow(clippy::type_complexity)]
+pub fn generate_constants(
+    t: usize,
+    n_rounds_f: usize,
+    n_rounds_p: usize,
+) -> anyhow::Result<(Vec<Fr>, Vec<Fr>, Matrix, Matrix)> {
+    if t < 2 || n_rounds_f == 0 || !n_rounds_f.is_multiple_of(2) || n_rounds_p == 0 {
+        bail!("Unsupported Poseidon parameters t={t}, RF={n_rounds_f}, RP={n_rounds_p}");
+    }
+
+    let mut grain = Grain::new(t, n_rounds_f, n_rounds_p);
+    let mut rounds = (0..n_rounds_f + n_rounds_p)
+        .map(|_| (0..t).map(|_| grain.field_element()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mds = cauchy_matrix(&mut grain, t);
+    let mds_inverse = invert(&mds)?;
+    let half = n_rounds_f / 2;
+
+    // Move each partial round's constants, and the first second-half round's,
+    // before the preceding matrix: the first element becomes a scalar added
+    // after the previous S-box, the rest passes the partial S-box unchanged
+    let mut partial_scalars = vec![Fr::ZERO; n_rounds_p];
+    for round in (half..half + n_rounds_p).rev() {
+        let moved = apply(&mds_inverse, &rounds[round + 1]);
+        for (constant, value) in rounds[round].iter_mut().zip(&moved).skip(1) {
+            *constant += value;
+        }
+        partial_scalars[round - half] = moved[0];
+    }
+
+    let mut c = rounds[0].clone();
+    for round in &rounds[1..=half] {
+        c.extend(apply(&mds_inverse, round));
+    }
+    c.extend(partial_scalars);
+    for round in &rounds[half + n_rounds_p + 1..] {
+        c.extend(apply(&mds_inverse, round));
+    }
+
+    // Split the partial rounds' matrix into the pre-sparse matrix and one
+    // sparse matrix [[M00, v], [w_hat, I]] per round, last round first
+    let mds_transpose = transpose(&mds);
+    let mut product = mds_transpose.clone();
+    let mut sparse = Vec::with_capacity(n_rounds_p);
+    let mut pre_sparse = Matrix::new();
+    for _ in 0..n_rounds_p {
+        let m_hat = product[1..]
+            .iter()
+            .map(|row| row[1..].to_vec())
+            .collect::<Matrix>();
+        let w = product[1..].iter().map(|row| row[0]).collect::<Vec<_>>();
+        let v = product[0][1..].to_vec();
+        let w_hat = apply(&invert(&m_hat)?, &w);
+        sparse.push((w_hat, v));
+
+        pre_sparse = (0..t)
+            .map(|i| {
+                (0..t)
+                    .map(|j| match (i, j) {
+                        (0, 0) => Fr::ONE,
+                        (0, _) | (_, 0) => Fr::ZERO,
+                        _ => m_hat[i - 1][j - 1],
+                    })
+                    .collect()
+            })
+            .collect();
+        product = multiply(&mds_transpose, &pre_sparse);
+    }
+
+    let s = sparse
+        .into_iter()
+        .rev()
+        .flat_map(|(w_hat, v)| std::iter::once(mds[0][0]).chain(w_hat).chain(v))
+        .collect();
+    let p = multiply(&mds_transpose, &pre_sparse);
+
+    Ok((c, s, mds_transpose, p))
+}
@@ -8,24 +8,29 @@
 //
 // This module provides both native computation and R1CS constraint generation.
 
+pub mod grain;
 pub mod poseidon_constants_opt;
 
 use ark_bn254::Fr;
-use ark_ff::Field;
+use ark_ff::{AdditiveGroup, Field};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     fields::fp::FpVar,
     prelude::FieldVar,
 };
 use ark_relations::r1cs::{Namespace, SynthesisError};
-use num_bigint::BigUint;
-use num_traits::Num;
 use std::borrow::Borrow;
+use std::sync::LazyLock;
 
 // =============================================================================
 // NATIVE IMPLEMENTATION
 // =============================================================================
 
+/// Pairs each rayon task of [`PoseidonOptimized::hash_many_pairs`] hashes with
+/// one set of state buffers
+#[cfg(not(target_arch = "wasm32"))]
+const PAIR_CHUNK_SIZE: usize = 256;
+
 /// Optimized Poseidon hasher for circomlib compatibility (native computation)
 #[derive(Clone)]
 pub struct PoseidonOptimized {
@@ -95,6 +100,20 @@ impl PoseidonOptimized {
         }
     }
 
+    /// Create hasher for t=6 (5 inputs)
+    pub fn new_t6() -> Self {
+        let (c, s, m, p) = poseidon_constants_opt::constants_t6();
+        Self {
+            t: 6,
+            n_rounds_f: 8,
+            n_rounds_p: 60,
+            c,
+            s,
+            m,
+            p,
+        }
+    }
+
     /// S-box: x^5
     #[inline]
     fn pow5(x: Fr) -> Fr {
@@ -103,32 +122,83 @@ impl PoseidonOptimized {
         x4 * x
     }
 
-    /// Matrix-vector multiplication
-    fn mix(&self, state: &[Fr], matrix: &[Vec<Fr>]) -> Vec<Fr> {
-        let mut result = vec![Fr::from(0u64); self.t];
+    /// Matrix-vector multiplication of `state` into `result`
+    fn mix_into(&self, state: &[Fr], matrix: &[Vec<Fr>], result: &mut [Fr]) {
         #[allow(clippy::needless_range_loop)]
         for i in 0..self.t {
+            result[i] = Fr::ZERO;
             for j in 0..self.t {
                 result[i] += matrix[j][i] * state[j];
             }
         }
-        result
     }
 
     /// Hash inputs using optimized Poseidon algorithm
     ///
     /// This matches the circomlibjs implementation exactly.
     pub fn hash(&self, inputs: &[Fr]) -> Fr {
+        self.hash_with_domain(inputs, Fr::ZERO)
+    }
+
+    /// Hash inputs with `domain` as the initial capacity element
+    ///
+    /// Equivalent to circomlib's `PoseidonEx` with `initialState = domain`.
+    /// A zero domain reproduces [`Self::hash`] exactly.
+    pub fn hash_with_domain(&self, inputs: &[Fr], domain: Fr) -> Fr {
         assert_eq!(
             inputs.len(),
             self.t - 1,
             "Wrong number of inputs for this hasher"
         );
 
-        // Initialize state: [0, input1, input2, ...]
-        let mut state = vec![Fr::from(0u64)];
+        // Initialize state: [domain, input1, input2, ...]
+        let mut state = vec![domain];
         state.extend_from_slice(inputs);
+        let mut scratch = vec![Fr::ZERO; self.t];
 
+        self.permute(&mut state, &mut scratch);
+        state[0]
+    }
+
+    /// Hashes every `(left, right)` pair, giving the same results as calling
+    /// [`Self::hash2`] on each.
+    ///
+    /// The state buffers are allocated once per chunk of pairs instead of once
+    /// per hash, and native builds hash the chunks in parallel with rayon.
+    pub fn hash_many_pairs(&self, pairs: &[(Fr, Fr)]) -> Vec<Fr> {
+        assert_eq!(self.t, 3, "hash_many_pairs needs the t=3 hasher");
+
+        let hash_chunk = |chunk: &[(Fr, Fr)]| {
+            let mut state = [Fr::ZERO; 3];
+            let mut scratch = [Fr::ZERO; 3];
+            chunk
+                .iter()
+                .map(|(left, right)| {
+                    state = [Fr::ZERO, *left, *right];
+                    self.permute(&mut state, &mut scratch);
+                    state[0]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use rayon::prelude::*;
+            pairs
+                .par_chunks(PAIR_CHUNK_SIZE)
+                .flat_map_iter(hash_chunk)
+                .collect()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            hash_chunk(pairs)
+        }
+    }
+
+    /// Applies the permutation to `state` in place, using `scratch` (also of
+    /// length `t`) for the matrix multiplications.
+    fn permute(&self, state: &mut [Fr], scratch: &mut [Fr]) {
         // Add initial round constants
         #[allow(clippy::needless_range_loop)]
         for i in 0..self.t {
@@ -138,24 +208,26 @@ impl PoseidonOptimized {
         // First half of full rounds (minus 1)
         for r in 0..(self.n_rounds_f / 2 - 1) {
             // Apply S-box to all elements
-            state = state.iter().map(|&x| Self::pow5(x)).collect();
+            state.iter_mut().for_each(|x| *x = Self::pow5(*x));
             // Add round constants
             #[allow(clippy::needless_range_loop)]
             for i in 0..self.t {
                 state[i] += self.c[(r + 1) * self.t + i];
             }
             // Mix with MDS matrix
-            state = self.mix(&state, &self.m);
+            self.mix_into(state, &self.m, scratch);
+            state.copy_from_slice(scratch);
         }
 
         // Last round of first half (uses P matrix instead of M)
-        state = state.iter().map(|&x| Self::pow5(x)).collect();
+        state.iter_mut().for_each(|x| *x = Self::pow5(*x));
         #[allow(clippy::needless_range_loop)]
         for i in 0..self.t {
             state[i] += self.c[(self.n_rounds_f / 2 - 1 + 1) * self.t + i];
         }
         // Mix with pre-sparse matrix P
-        state = self.mix(&state, &self.p);
+        self.mix_into(state, &self.p, scratch);
+        state.copy_from_slice(scratch);
 
         // Partial rounds (optimized sparse multiplication)
         for r in 0..self.n_rounds_p {
@@ -185,7 +257,7 @@ impl PoseidonOptimized {
         // Second half of full rounds (minus 1)
         for r in 0..(self.n_rounds_f / 2 - 1) {
             // Apply S-box to all elements
-            state = state.iter().map(|&x| Self::pow5(x)).collect();
+            state.iter_mut().for_each(|x| *x = Self::pow5(*x));
             // Add round constants
             #[allow(clippy::needless_range_loop)]
             for i in 0..self.t {
@@ -193,14 +265,14 @@ impl PoseidonOptimized {
                     self.c[(self.n_rounds_f / 2 + 1) * self.t + self.n_rounds_p + r * self.t + i];
             }
             // Mix with MDS matrix
-            state = self.mix(&state, &self.m);
+            self.mix_into(state, &self.m, scratch);
+            state.copy_from_slice(scratch);
         }
 
         // Final round (no round constants added after)
-        state = state.iter().map(|&x| Self::pow5(x)).collect();
-        state = self.mix(&state, &self.m);
-
-        state[0]
+        state.iter_mut().for_each(|x| *x = Self::pow5(*x));
+        self.mix_into(state, &self.m, scratch);
+        state.copy_from_slice(scratch);
     }
 
     /// Hash a single field element
@@ -222,6 +294,11 @@ impl PoseidonOptimized {
     pub fn hash4(&self, x: &Fr, y: &Fr, z: &Fr, w: &Fr) -> Fr {
         self.hash(&[*x, *y, *z, *w])
     }
+
+    /// Hash five field elements
+    pub fn hash5(&self, x: &Fr, y: &Fr, z: &Fr, w: &Fr, v: &Fr) -> Fr {
+        self.hash(&[*x, *y, *z, *w, *v])
+    }
 }
 
 // =============================================================================
@@ -300,6 +377,20 @@ impl PoseidonOptimizedVar {
         }
     }
 
+    /// Create constraint gadget for t=6 (5 inputs)
+    pub fn new_t6() -> Self {
+        let (c, s, m, p) = poseidon_constants_opt::constants_t6();
+        Self {
+            t: 6,
+            n_rounds_f: 8,
+            n_rounds_p: 60,
+            c,
+            s,
+            m,
+            p,
+        }
+    }
+
     /// S-box as constraint: x^5
     #[inline]
     fn pow5_var(x: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
@@ -315,6 +406,7 @@ impl PoseidonOptimizedVar {
         matrix: &[Vec<Fr>],
     ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
         let mut result = Vec::with_capacity(self.t);
+        #[allow(clippy::needless_range_loop)]
         for i in 0..self.t {
             let mut acc = FpVar::<Fr>::zero();
             for j in 0..self.t {
@@ -327,14 +419,25 @@ impl PoseidonOptimizedVar {
 
     /// Hash with constraint generation - matches optimized algorithm exactly
     pub fn hash(&self, inputs: &[FpVar<Fr>]) -> Result<FpVar<Fr>, SynthesisError> {
+        self.hash_with_domain(inputs, Fr::ZERO)
+    }
+
+    /// Hash with `domain` as the initial capacity element
+    ///
+    /// The domain is a circuit constant, so tagging adds no constraints.
+    pub fn hash_with_domain(
+        &self,
+        inputs: &[FpVar<Fr>],
+        domain: Fr,
+    ) -> Result<FpVar<Fr>, SynthesisError> {
         assert_eq!(
             inputs.len(),
             self.t - 1,
             "Wrong number of inputs for this hasher"
         );
 
-        // Initialize state: [0, input1, input2, ...]
-        let mut state = vec![FpVar::<Fr>::zero()];
+        // Initialize state: [domain, input1, input2, ...]
+        let mut state = vec![FpVar::<Fr>::constant(domain)];
         state.extend(inputs.iter().cloned());
 
         // Add initial round constants
@@ -449,6 +552,18 @@ impl PoseidonOptimizedVar {
     ) -> Result<FpVar<Fr>, SynthesisError> {
         self.hash(&[a.clone(), b.clone(), c.clone(), d.clone()])
     }
+
+    /// Hash five field elements
+    pub fn hash5(
+        &self,
+        a: &FpVar<Fr>,
+        b: &FpVar<Fr>,
+        c: &FpVar<Fr>,
+        d: &FpVar<Fr>,
+        e: &FpVar<Fr>,
+    ) -> Result<FpVar<Fr>, SynthesisError> {
+        self.hash(&[a.clone(), b.clone(), c.clone(), d.clone(), e.clone()])
+    }
 }
 
 /// Allow allocating PoseidonOptimizedVar as a constant in constraint systems
@@ -477,29 +592,78 @@ impl AllocVar<PoseidonOptimized, Fr> for PoseidonOptimizedVar {
 // CONVENIENCE FUNCTIONS
 // =============================================================================
 
-/// Helper to parse field element from decimal string
+/// Parses a field element constant, panicking if it is not one; see
+/// [`crate::field::parse_field`] for untrusted input.
 pub fn fr_from_str(s: &str) -> Fr {
-    Fr::from(BigUint::from_str_radix(s, 10).expect("Failed to parse field element"))
+    crate::field::parse_field(s).expect("Failed to parse field element")
+}
+
+static HASHER_T2: LazyLock<PoseidonOptimized> = LazyLock::new(PoseidonOptimized::new_t2);
+static HASHER_T3: LazyLock<PoseidonOptimized> = LazyLock::new(PoseidonOptimized::new_t3);
+static HASHER_T4: LazyLock<PoseidonOptimized> = LazyLock::new(PoseidonOptimized::new_t4);
+static HASHER_T5: LazyLock<PoseidonOptimized> = LazyLock::new(PoseidonOptimized::new_t5);
+static HASHER_T6: LazyLock<PoseidonOptimized> = LazyLock::new(PoseidonOptimized::new_t6);
+
+/// Shared t=2 hasher; its constants are parsed once per process
+pub fn hasher_t2() -> &'static PoseidonOptimized {
+    &HASHER_T2
+}
+
+/// Shared t=3 hasher; its constants are parsed once per process
+pub fn hasher_t3() -> &'static PoseidonOptimized {
+    &HASHER_T3
+}
+
+/// Shared t=4 hasher; its constants are parsed once per process
+pub fn hasher_t4() -> &'static PoseidonOptimized {
+    &HASHER_T4
+}
+
+/// Shared t=5 hasher; its constants are parsed once per process
+pub fn hasher_t5() -> &'static PoseidonOptimized {
+    &HASHER_T5
+}
+
+/// Shared t=6 hasher; its constants are parsed once per process
+pub fn hasher_t6() -> &'static PoseidonOptimized {
+    &HASHER_T6
+}
+
+/// Shared hasher taking `arity` inputs, if constants for that width exist
+pub fn hasher_for_arity(arity: usize) -> Option<&'static PoseidonOptimized> {
+    match arity {
+        1 => Some(hasher_t2()),
+        2 => Some(hasher_t3()),
+        3 => Some(hasher_t4()),
+        4 => Some(hasher_t5()),
+        5 => Some(hasher_t6()),
+        _ => None,
+    }
 }
 
 /// Hash a single field element (native)
 pub fn hash1(x: &Fr) -> Fr {
-    PoseidonOptimized::new_t2().hash1(x)
+    hasher_t2().hash1(x)
 }
 
 /// Hash two field elements (native)
 pub fn hash2(x: &Fr, y: &Fr) -> Fr {
-    PoseidonOptimized::new_t3().hash2(x, y)
+    hasher_t3().hash2(x, y)
 }
 
 /// Hash three field elements (native)
 pub fn hash3(x: &Fr, y: &Fr, z: &Fr) -> Fr {
-    PoseidonOptimized::new_t4().hash3(x, y, z)
+    hasher_t4().hash3(x, y, z)
 }
 
 /// Hash four field elements (native)
 pub fn hash4(x: &Fr, y: &Fr, z: &Fr, w: &Fr) -> Fr {
-    PoseidonOptimized::new_t5().hash4(x, y, z, w)
+    hasher_t5().hash4(x, y, z, w)
+}
+
+/// Hash five field elements (native)
+pub fn hash5(x: &Fr, y: &Fr, z: &Fr, w: &Fr, v: &Fr) -> Fr {
+    hasher_t6().hash5(x, y, z, w, v)
 }
 
 // =============================================================================
@@ -541,6 +705,77 @@ mod tests {
         assert_eq!(hash, expected);
     }
 
+    #[test]
+    fn test_hash_many_pairs_matches_hash2() {
+        let hasher = PoseidonOptimized::new_t3();
+        let pairs = (0..600u64)
+            .map(|i| (Fr::from(i), Fr::from(i * 31 + 7)))
+            .collect::<Vec<_>>();
+
+        let hashes = hasher.hash_many_pairs(&pairs);
+        assert_eq!(hashes.len(), pairs.len());
+        for ((left, right), hash) in pairs.iter().zip(&hashes) {
+            assert_eq!(*hash, hasher.hash2(left, right));
+        }
+        assert!(hasher.hash_many_pairs(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_grain_reproduces_embedded_constants() {
+        use poseidon_constants_opt::{
+            constants_t2, constants_t3, constants_t4, constants_t5, constants_t6,
+        };
+
+        let widths = [
+            PoseidonOptimized::new_t2(),
+            PoseidonOptimized::new_t3(),
+            PoseidonOptimized::new_t4(),
+            PoseidonOptimized::new_t5(),
+            PoseidonOptimized::new_t6(),
+        ];
+        let tables = [
+            constants_t2(),
+            constants_t3(),
+            constants_t4(),
+            constants_t5(),
+            constants_t6(),
+        ];
+        for (hasher, table) in widths.iter().zip(tables) {
+            let generated =
+                grain::generate_constants(hasher.t, hasher.n_rounds_f, hasher.n_rounds_p).unwrap();
+            assert!(
+                generated == table,
+                "t={} differs from the embedded table",
+                hasher.t
+            );
+        }
+
+        assert!(grain::generate_constants(1, 8, 56).is_err());
+        assert!(grain::generate_constants(3, 7, 57).is_err());
+    }
+
+    #[test]
+    fn test_shared_hashers_are_built_once() {
+        assert!(std::ptr::eq(hasher_t3(), hasher_t3()));
+
+        let (x, y, z, w) = (
+            Fr::from(1u64),
+            Fr::from(2u64),
+            Fr::from(3u64),
+            Fr::from(4u64),
+        );
+        assert_eq!(hash1(&x), PoseidonOptimized::new_t2().hash1(&x));
+        assert_eq!(hash2(&x, &y), PoseidonOptimized::new_t3().hash2(&x, &y));
+        assert_eq!(
+            hash3(&x, &y, &z),
+            PoseidonOptimized::new_t4().hash3(&x, &y, &z)
+        );
+        assert_eq!(
+            hash4(&x, &y, &z, &w),
+            PoseidonOptimized::new_t5().hash4(&x, &y, &z, &w)
+        );
+    }
+
     #[test]
     fn test_optimized_poseidon_t4() {
         let hasher = PoseidonOptimized::new_t4();
@@ -574,6 +809,67 @@ mod tests {
         assert_eq!(hash, expected);
     }
 
+    #[test]
+    fn test_optimized_poseidon_t6() {
+        let hasher = PoseidonOptimized::new_t6();
+
+        // Expected from TypeScript circomlibjs
+        let cases = [
+            (
+                [1u64, 2, 0, 0, 0],
+                "1018317224307729531995786483840663576608797660851238720571059489595066344487",
+            ),
+            (
+                [3u64, 4, 5, 10, 23],
+                "13034429309846638789535561449942021891039729847501137143363028890275222221409",
+            ),
+        ];
+        for (inputs, expected) in cases {
+            let [x, y, z, w, v] = inputs.map(Fr::from);
+            assert_eq!(hasher.hash(&[x, y, z, w, v]), fr_from_str(expected));
+            assert_eq!(hash5(&x, &y, &z, &w, &v), fr_from_str(expected));
+        }
+    }
+
+    #[test]
+    fn test_gadget_matches_native_for_every_width() {
+        let widths = [
+            (PoseidonOptimized::new_t2(), PoseidonOptimizedVar::new_t2()),
+            (PoseidonOptimized::new_t3(), PoseidonOptimizedVar::new_t3()),
+            (PoseidonOptimized::new_t4(), PoseidonOptimizedVar::new_t4()),
+            (PoseidonOptimized::new_t5(), PoseidonOptimizedVar::new_t5()),
+            (PoseidonOptimized::new_t6(), PoseidonOptimizedVar::new_t6()),
+        ];
+        let domain = Fr::from(7u64);
+
+        for (native, gadget) in widths {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let inputs = (1..native.t as u64).map(Fr::from).collect::<Vec<_>>();
+            let input_vars = inputs
+                .iter()
+                .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                gadget.hash(&input_vars).unwrap().value().unwrap(),
+                native.hash(&inputs),
+                "t={}",
+                native.t
+            );
+            assert_eq!(
+                gadget
+                    .hash_with_domain(&input_vars, domain)
+                    .unwrap()
+                    .value()
+                    .unwrap(),
+                native.hash_with_domain(&inputs, domain),
+                "t={}",
+                native.t
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
     #[test]
     fn test_constraint_gadget_matches_native() {
         let cs = ConstraintSystem::<Fr>::new_ref();
@@ -597,4 +893,38 @@ mod tests {
         // Check constraints are satisfied
         assert!(cs.is_satisfied().unwrap());
     }
+
+    #[test]
+    fn test_domain_tag_separates_hashes() {
+        let hasher = PoseidonOptimized::new_t3();
+        let inputs = [Fr::from(1u64), Fr::from(2u64)];
+
+        assert_eq!(
+            hasher.hash_with_domain(&inputs, Fr::ZERO),
+            hasher.hash(&inputs)
+        );
+        assert_ne!(
+            hasher.hash_with_domain(&inputs, Fr::from(1u64)),
+            hasher.hash_with_domain(&inputs, Fr::from(2u64))
+        );
+    }
+
+    #[test]
+    fn test_domain_tag_gadget_matches_native() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let domain = Fr::from(7u64);
+        let x = Fr::from(1u64);
+        let y = Fr::from(2u64);
+
+        let native_hash = PoseidonOptimized::new_t3().hash_with_domain(&[x, y], domain);
+
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+        let y_var = FpVar::new_witness(cs.clone(), || Ok(y)).unwrap();
+        let hash_var = PoseidonOptimizedVar::new_t3()
+            .hash_with_domain(&[x_var, y_var], domain)
+            .unwrap();
+
+        assert_eq!(hash_var.value().unwrap(), native_hash);
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
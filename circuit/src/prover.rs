@@ -0,0 +1,1829 @@
+pub use crate::verifier::verifying_key_from_bytes;
+use crate::{
+    association::AssociationSet,
+    backend::{Groth16Backend, ProvingBackend},
+    canonical::canonical_hash,
+    circuit::{
+        CircuitDescriptor, CircuitKind, CircuitVersion, ExtData, ExtDataHasher, TransactionCircuit,
+    },
+    constants::{
+        LARGE_TREE_LEVEL, MEDIUM_TREE_LEVEL, MERKLE_TREE_LEVEL, N_INS, N_INS_1, N_INS_16, N_OUTS,
+        SMALL_TREE_LEVEL, SUPPORTED_TREE_LEVELS,
+    },
+    field,
+    merkle_tree::Path,
+};
+use anyhow::{anyhow, Context};
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Proof output structure that matches the expected format for Sui Move contracts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofOutput {
+    /// Proof component A (compressed: 32 bytes)
+    pub proof_a: Vec<u8>,
+    /// Proof component B (compressed: 64 bytes)
+    pub proof_b: Vec<u8>,
+    /// Proof component C (compressed: 32 bytes)
+    pub proof_c: Vec<u8>,
+    /// All public inputs in order expected by Move contract
+    pub public_inputs: Vec<String>,
+    pub proof_serialized_hex: String,
+    pub public_inputs_serialized_hex: String,
+    /// Legacy opt-out: verification reduces public inputs at or above the
+    /// field modulus instead of rejecting them
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reduce_field_elements: bool,
+}
+
+/// Position of `root` in every circuit's public inputs, after `vortex`
+const ROOT_INPUT_POSITION: usize = 1;
+
+/// Most public inputs Sui's `groth16::public_proof_inputs_from_bytes` accepts
+const SUI_MAX_PUBLIC_INPUTS: usize = 8;
+
+const COMPRESSED_G1_SIZE: usize = 32;
+const COMPRESSED_PROOF_SIZE: usize = 128;
+const SCALAR_SIZE: usize = 32;
+
+/// Source of the Groth16 blinding factors of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProverRng {
+    /// Fresh entropy for every proof, so two proofs of one witness differ
+    #[default]
+    Entropy,
+    /// ChaCha20 seeded from the value, for byte-identical test vectors. The
+    /// seed reveals the blinding factors, so it only exists in tests and
+    /// `test-circuit` builds and is not reachable from the bindings
+    #[cfg(any(test, feature = "test-circuit"))]
+    Seeded(u64),
+}
+
+impl ProverRng {
+    fn rng(self) -> anyhow::Result<ChaCha20Rng> {
+        match self {
+            Self::Entropy => Ok(ChaCha20Rng::from_seed(entropy_seed()?)),
+            #[cfg(any(test, feature = "test-circuit"))]
+            Self::Seeded(seed) => Ok(ChaCha20Rng::seed_from_u64(seed)),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn entropy_seed() -> anyhow::Result<[u8; 32]> {
+    let mut seed = [0u8; 32];
+    rand_core::OsRng
+        .try_fill_bytes(&mut seed)
+        .map_err(|e| anyhow!("Failed to read OS entropy: {}", e))?;
+    Ok(seed)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn entropy_seed() -> anyhow::Result<[u8; 32]> {
+    crate::wasm::random_seed()
+}
+
+/// Input structure for proof generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofInput {
+    /// Hash layout version (defaults to v1 when omitted)
+    #[serde(default)]
+    pub circuit_version: CircuitVersion,
+
+    /// Circuit shape (defaults to the 2-input circuit when omitted). The
+    /// `*_1` input fields are ignored by the 1-input circuit, and the
+    /// 16-input circuit reads its other inputs from `extra_inputs`.
+    #[serde(default)]
+    pub circuit_kind: CircuitKind,
+
+    /// Merkle tree height of the pool (defaults to the deployed 26 when
+    /// omitted). Requires keys generated with `keygen --level`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_level: Option<usize>,
+
+    /// Other roots the contract currently accepts. When present, the proof is
+    /// made against whichever of `root` and these the Merkle paths lead to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidate_roots: Vec<String>,
+
+    /// Roots the circuit accepts besides `root`, allocated as public inputs.
+    /// Requires keys generated with the same history length.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub root_history: Vec<String>,
+
+    /// Legacy opt-out: values at or above the field modulus are reduced
+    /// instead of rejected
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reduce_field_elements: bool,
+
+    // Public inputs
+    pub vortex: String,
+    pub root: String,
+    pub public_amount: String,
+    pub input_nullifier_0: String,
+    pub input_nullifier_1: String,
+    pub output_commitment_0: String,
+    pub output_commitment_1: String,
+    pub hashed_account_secret: String,
+    /// Asset the notes are bound to, only for keys generated with the asset public input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+    /// Association set root, only for keys generated with the association check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association_root: Option<String>,
+    /// Relayer fee, only for keys generated with the fee public input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<String>,
+    /// External data hashed in-circuit, only for keys generated with the
+    /// `ext_data_hash` public input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ext_data: Option<ExtDataInput>,
+
+    // Private inputs - Input UTXOs
+    pub account_secret: String,
+    pub in_private_key_0: String,
+    pub in_private_key_1: String,
+    pub in_amount_0: String,
+    pub in_amount_1: String,
+    pub in_blinding_0: String,
+    pub in_blinding_1: String,
+    pub in_path_index_0: String,
+    pub in_path_index_1: String,
+
+    // Merkle paths (array of [left, right] pairs for each level)
+    pub merkle_path_0: Vec<[String; 2]>,
+    pub merkle_path_1: Vec<[String; 2]>,
+
+    // Association set paths, may be omitted for zero-amount inputs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub association_path_0: Vec<[String; 2]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub association_path_1: Vec<[String; 2]>,
+
+    /// Input UTXOs after the first two, only read by the 16-input circuit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_inputs: Vec<ExtraInputUtxo>,
+
+    // Private inputs - Output UTXOs
+    pub out_public_key_0: String,
+    pub out_public_key_1: String,
+    pub out_amount_0: String,
+    pub out_amount_1: String,
+    pub out_blinding_0: String,
+    pub out_blinding_1: String,
+}
+
+/// Input UTXO of [`ProofInput::extra_inputs`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraInputUtxo {
+    pub nullifier: String,
+    pub private_key: String,
+    pub amount: String,
+    pub blinding: String,
+    pub path_index: String,
+    pub merkle_path: Vec<[String; 2]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub association_path: Vec<[String; 2]>,
+}
+
+/// Private witnesses of the `ext_data_hash` public input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtDataInput {
+    pub recipient: String,
+    pub relayer: String,
+    pub fee: String,
+    pub encrypted_output_hash: String,
+    #[serde(default)]
+    pub hasher: ExtDataHasher,
+}
+
+impl ProofInput {
+    /// Returns a copy with every field element in reduced decimal form, so
+    /// hex and decimal encodings of the same value compare and hash equal.
+    pub fn normalized(&self) -> anyhow::Result<Self> {
+        if self.reduce_field_elements {
+            self.map_field_strings(reduced_field_string)
+        } else {
+            self.map_field_strings(canonical_field_string)
+        }
+    }
+
+    /// SHA-256 of the canonical JSON of the normalized input.
+    pub fn canonical_hash(&self) -> anyhow::Result<String> {
+        canonical_hash(&self.normalized()?)
+    }
+
+    /// Returns a copy with every field element reduced modulo the field, the
+    /// form [`reduce_field_elements`](Self::reduce_field_elements) proves.
+    pub fn reduced(&self) -> anyhow::Result<Self> {
+        Ok(Self {
+            reduce_field_elements: false,
+            ..self.map_field_strings(reduced_field_string)?
+        })
+    }
+
+    fn map_field_strings(&self, map: fn(&str) -> anyhow::Result<String>) -> anyhow::Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        if let Value::Object(fields) = &mut value {
+            for (key, field) in fields.iter_mut() {
+                match (key.as_str(), field) {
+                    ("circuitVersion" | "circuitKind", _) => {}
+                    ("extData", Value::Object(ext_data)) => {
+                        for (key, field) in ext_data.iter_mut() {
+                            if key != "hasher" {
+                                map_field_values(field, map)?;
+                            }
+                        }
+                    }
+                    (_, field) => map_field_values(field, map)?,
+                }
+            }
+        }
+        serde_json::from_value(value).context("Failed to rebuild normalized input")
+    }
+}
+
+impl ProofOutput {
+    /// Returns a copy with public inputs in reduced decimal form and lowercase hex.
+    pub fn normalized(&self) -> anyhow::Result<Self> {
+        let map = if self.reduce_field_elements {
+            reduced_field_string
+        } else {
+            canonical_field_string
+        };
+        Ok(Self {
+            public_inputs: self
+                .public_inputs
+                .iter()
+                .map(|input| map(input))
+                .collect::<anyhow::Result<_>>()?,
+            proof_serialized_hex: self.proof_serialized_hex.to_ascii_lowercase(),
+            public_inputs_serialized_hex: self.public_inputs_serialized_hex.to_ascii_lowercase(),
+            ..self.clone()
+        })
+    }
+
+    /// SHA-256 of the canonical JSON of the normalized output.
+    pub fn canonical_hash(&self) -> anyhow::Result<String> {
+        canonical_hash(&self.normalized()?)
+    }
+}
+
+/// Category of a [`ProveError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProveErrorKind {
+    /// A field element string is neither decimal nor 0x-hex
+    BadField,
+    /// The Merkle paths lead to no accepted root, or to different roots
+    RootMismatch,
+    /// A Merkle path has the wrong length for the tree level
+    TreeConstruction,
+    /// The proving key hex or bytes could not be decoded
+    KeyDeserialize,
+    /// The witness does not satisfy the circuit, e.g. unbalanced amounts
+    ConstraintUnsatisfied,
+    /// Any other malformed input, e.g. invalid JSON or a descriptor mismatch
+    InvalidInput,
+}
+
+impl ProveErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadField => "BadField",
+            Self::RootMismatch => "RootMismatch",
+            Self::TreeConstruction => "TreeConstruction",
+            Self::KeyDeserialize => "KeyDeserialize",
+            Self::ConstraintUnsatisfied => "ConstraintUnsatisfied",
+            Self::InvalidInput => "InvalidInput",
+        }
+    }
+}
+
+/// Failure of [`prove_json`] and [`prove_batch_json`].
+///
+/// Crosses the wasm boundary as an object with `kind` and `message`, so
+/// browsers can tell a bad input from a bad key without parsing the message.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProveError {
+    kind: ProveErrorKind,
+    message: String,
+}
+
+impl ProveError {
+    pub fn new(kind: ProveErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn kind(&self) -> ProveErrorKind {
+        self.kind
+    }
+}
+
+#[wasm_bindgen]
+impl ProveError {
+    /// [`ProveErrorKind`] name, e.g. `"BadField"`
+    #[wasm_bindgen(getter, js_name = kind)]
+    pub fn kind_name(&self) -> String {
+        self.kind.as_str().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl fmt::Display for ProveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ProveError {}
+
+/// Takes the kind of the innermost [`ProveError`] in the chain, or
+/// [`ProveErrorKind::InvalidInput`] when there is none, and the message of the
+/// whole chain.
+impl From<anyhow::Error> for ProveError {
+    fn from(error: anyhow::Error) -> Self {
+        let kind = error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<ProveError>())
+            .last()
+            .map_or(ProveErrorKind::InvalidInput, ProveError::kind);
+        Self::new(kind, format!("{:#}", error))
+    }
+}
+
+/// Category of a [`VerifyError`], checked before the pairing check runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerifyErrorKind {
+    /// The proof carries a different number of public inputs than the key
+    /// or descriptor expect
+    InputCount,
+    /// A root (`root`, `association_root` or `root_history_*`) is not a
+    /// decimal or 0x-hex value below the field modulus
+    NonCanonicalRoot,
+    /// An `input_nullifier_*` is not a canonical field element
+    NonCanonicalNullifier,
+    /// Any other public input is not a canonical field element
+    NonCanonicalInput,
+    /// Any other malformed input, e.g. invalid JSON, key or proof bytes
+    InvalidInput,
+}
+
+impl VerifyErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InputCount => "InputCount",
+            Self::NonCanonicalRoot => "NonCanonicalRoot",
+            Self::NonCanonicalNullifier => "NonCanonicalNullifier",
+            Self::NonCanonicalInput => "NonCanonicalInput",
+            Self::InvalidInput => "InvalidInput",
+        }
+    }
+}
+
+/// Failure of [`verify_json`], thrown across the wasm boundary like [`ProveError`].
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    kind: VerifyErrorKind,
+    message: String,
+}
+
+impl VerifyError {
+    pub fn new(kind: VerifyErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn kind(&self) -> VerifyErrorKind {
+        self.kind
+    }
+}
+
+#[wasm_bindgen]
+impl VerifyError {
+    /// [`VerifyErrorKind`] name, e.g. `"InputCount"`
+    #[wasm_bindgen(getter, js_name = kind)]
+    pub fn kind_name(&self) -> String {
+        self.kind.as_str().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Same as the [`ProveError`] conversion, defaulting to
+/// [`VerifyErrorKind::InvalidInput`].
+impl From<anyhow::Error> for VerifyError {
+    fn from(error: anyhow::Error) -> Self {
+        let kind = error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<VerifyError>())
+            .last()
+            .map_or(VerifyErrorKind::InvalidInput, VerifyError::kind);
+        Self::new(kind, format!("{:#}", error))
+    }
+}
+
+fn verify_error(kind: VerifyErrorKind, message: String) -> anyhow::Error {
+    VerifyError::new(kind, message).into()
+}
+
+fn prove_error(kind: ProveErrorKind, message: String) -> anyhow::Error {
+    ProveError::new(kind, message).into()
+}
+
+/// Byte vectors Sui's `groth16` module takes for a proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiProof {
+    /// `proof_points_from_bytes` argument: compressed `a || b || c`
+    pub proof_points_bytes: Vec<u8>,
+    /// `public_proof_inputs_from_bytes` argument: each input as 32 little-endian bytes
+    pub public_inputs_bytes: Vec<u8>,
+    /// `pvk_from_bytes` arguments, in order: `vk_gamma_abc_g1`,
+    /// `alpha_g1_beta_g2`, `gamma_g2_neg_pc` and `delta_g2_neg_pc`
+    pub prepared_vk_bytes: [Vec<u8>; 4],
+}
+
+impl ProofOutput {
+    /// Re-encodes the proof, its public inputs and `vk` in the byte layout of
+    /// Sui's `groth16` module.
+    pub fn to_sui_proof(&self, vk: &VerifyingKey<Bn254>) -> anyhow::Result<SuiProof> {
+        let proof_points_bytes =
+            hex::decode(&self.proof_serialized_hex).context("Failed to decode proof hex")?;
+
+        let mut public_inputs_bytes = Vec::new();
+        for (i, input) in self.public_inputs.iter().enumerate() {
+            parse_field_element(input)
+                .with_context(|| format!("Failed to parse public input {}", i))?
+                .serialize_compressed(&mut public_inputs_bytes)
+                .map_err(|e| anyhow!("Failed to serialize public input {}: {}", i, e))?;
+        }
+
+        Ok(SuiProof {
+            proof_points_bytes,
+            public_inputs_bytes,
+            prepared_vk_bytes: prepared_vk_bytes(vk)?,
+        })
+    }
+
+    /// Re-verifies the proof after encoding it as [`to_sui_proof`](Self::to_sui_proof)
+    /// does, so a layout mismatch shows up before a transaction fails on chain.
+    ///
+    /// # Errors
+    /// Returns error if `public_inputs_serialized_hex` disagrees with the
+    /// decimal public inputs or the bytes do not decode as Sui decodes them.
+    pub fn verify_onchain_format(&self, vk: &VerifyingKey<Bn254>) -> anyhow::Result<bool> {
+        let sui = self.to_sui_proof(vk)?;
+        let serialized = hex::decode(&self.public_inputs_serialized_hex)
+            .context("Failed to decode public inputs hex")?;
+        if serialized != sui.public_inputs_bytes {
+            return Err(anyhow!(
+                "public_inputs_serialized_hex does not encode public_inputs as 32-byte little-endian scalars"
+            ));
+        }
+        sui.verify()
+    }
+}
+
+impl SuiProof {
+    /// Decodes every byte vector the way Sui's `groth16` module does and
+    /// verifies the proof against the decoded prepared key.
+    pub fn verify(&self) -> anyhow::Result<bool> {
+        if self.proof_points_bytes.len() != COMPRESSED_PROOF_SIZE {
+            return Err(anyhow!(
+                "Proof points are {} bytes, expected {}",
+                self.proof_points_bytes.len(),
+                COMPRESSED_PROOF_SIZE
+            ));
+        }
+        let proof =
+            ark_groth16::Proof::<Bn254>::deserialize_compressed(&self.proof_points_bytes[..])
+                .map_err(|e| anyhow!("Failed to deserialize proof points: {}", e))?;
+
+        if !self.public_inputs_bytes.len().is_multiple_of(SCALAR_SIZE) {
+            return Err(anyhow!(
+                "Public inputs are {} bytes, not a multiple of {}",
+                self.public_inputs_bytes.len(),
+                SCALAR_SIZE
+            ));
+        }
+        let public_inputs = self
+            .public_inputs_bytes
+            .chunks(SCALAR_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                Fr::deserialize_compressed(chunk)
+                    .map_err(|e| anyhow!("Public input {} is not a canonical scalar: {}", i, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if public_inputs.len() > SUI_MAX_PUBLIC_INPUTS {
+            return Err(anyhow!(
+                "Proof has {} public inputs, Sui accepts at most {}",
+                public_inputs.len(),
+                SUI_MAX_PUBLIC_INPUTS
+            ));
+        }
+
+        let [gamma_abc_g1, alpha_g1_beta_g2, gamma_g2_neg_pc, delta_g2_neg_pc] =
+            &self.prepared_vk_bytes;
+        if !gamma_abc_g1.len().is_multiple_of(COMPRESSED_G1_SIZE) {
+            return Err(anyhow!(
+                "vk_gamma_abc_g1 is {} bytes, not a multiple of {}",
+                gamma_abc_g1.len(),
+                COMPRESSED_G1_SIZE
+            ));
+        }
+        let mut pvk = ark_groth16::PreparedVerifyingKey::<Bn254>::default();
+        pvk.vk.gamma_abc_g1 = gamma_abc_g1
+            .chunks(COMPRESSED_G1_SIZE)
+            .map(|chunk| {
+                ark_bn254::G1Affine::deserialize_compressed(chunk)
+                    .map_err(|e| anyhow!("Failed to deserialize vk_gamma_abc_g1: {}", e))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        pvk.alpha_g1_beta_g2 = CanonicalDeserialize::deserialize_compressed(&alpha_g1_beta_g2[..])
+            .map_err(|e| anyhow!("Failed to deserialize alpha_g1_beta_g2: {}", e))?;
+        pvk.gamma_g2_neg_pc = ark_bn254::G2Affine::deserialize_compressed(&gamma_g2_neg_pc[..])
+            .map_err(|e| anyhow!("Failed to deserialize gamma_g2_neg_pc: {}", e))?
+            .into();
+        pvk.delta_g2_neg_pc = ark_bn254::G2Affine::deserialize_compressed(&delta_g2_neg_pc[..])
+            .map_err(|e| anyhow!("Failed to deserialize delta_g2_neg_pc: {}", e))?
+            .into();
+
+        ark_groth16::Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs)
+            .map_err(|e| anyhow!("Failed to verify in Sui layout: {}", e))
+    }
+}
+
+/// Splits `vk` into the four byte vectors of Sui's `groth16::pvk_from_bytes`,
+/// matching what `groth16::prepare_verifying_key` computes on chain.
+pub fn prepared_vk_bytes(vk: &VerifyingKey<Bn254>) -> anyhow::Result<[Vec<u8>; 4]> {
+    let mut gamma_abc_g1 = Vec::new();
+    for point in &vk.gamma_abc_g1 {
+        gamma_abc_g1.extend(compressed_bytes(point, "vk_gamma_abc_g1")?);
+    }
+
+    Ok([
+        gamma_abc_g1,
+        compressed_bytes(
+            &ark_groth16::prepare_verifying_key(vk).alpha_g1_beta_g2,
+            "alpha_g1_beta_g2",
+        )?,
+        compressed_bytes(&-vk.gamma_g2, "gamma_g2_neg_pc")?,
+        compressed_bytes(&-vk.delta_g2, "delta_g2_neg_pc")?,
+    ])
+}
+
+fn compressed_bytes<T: CanonicalSerialize>(value: &T, name: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| anyhow!("Failed to serialize {}: {}", name, e))?;
+    Ok(bytes)
+}
+
+fn canonical_field_string(s: &str) -> anyhow::Result<String> {
+    Ok(parse_field_element(s)?.into_bigint().to_string())
+}
+
+fn reduced_field_string(s: &str) -> anyhow::Result<String> {
+    Ok(parse_field_element_reduced(s)?.into_bigint().to_string())
+}
+
+fn map_field_values(
+    value: &mut Value,
+    map: fn(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<()> {
+    match value {
+        Value::String(s) => *s = map(s)?,
+        Value::Array(items) => {
+            for item in items {
+                map_field_values(item, map)?;
+            }
+        }
+        Value::Object(fields) => {
+            for field in fields.values_mut() {
+                map_field_values(field, map)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Builds the 2-input transaction circuit described by `input`.
+pub fn circuit_from_input(input: &ProofInput) -> anyhow::Result<TransactionCircuit> {
+    shaped_circuit_from_input::<N_INS>(input)
+}
+
+/// Builds the `NI`-input circuit described by `input`, which must be of the
+/// matching [`CircuitKind`]. Only the first `NI` input UTXOs are read.
+pub fn shaped_circuit_from_input<const NI: usize>(
+    input: &ProofInput,
+) -> anyhow::Result<TransactionCircuit<NI, N_OUTS>> {
+    leveled_circuit_from_input::<NI, MERKLE_TREE_LEVEL>(input)
+}
+
+/// [`shaped_circuit_from_input`] over a tree of height `L`, which must be the
+/// input's `tree_level`.
+pub fn leveled_circuit_from_input<const NI: usize, const L: usize>(
+    input: &ProofInput,
+) -> anyhow::Result<TransactionCircuit<NI, N_OUTS, L>> {
+    if input.reduce_field_elements {
+        return leveled_circuit_from_input(&input.reduced()?);
+    }
+
+    let level = input.tree_level.unwrap_or(MERKLE_TREE_LEVEL);
+    if level != L {
+        return Err(anyhow!(
+            "A tree level {} input cannot build a level {} circuit",
+            level,
+            L
+        ));
+    }
+    if input.circuit_kind.n_ins() != NI {
+        return Err(anyhow!(
+            "A {:?} input cannot build a {}-input circuit",
+            input.circuit_kind,
+            NI
+        ));
+    }
+
+    // Convert input strings to field elements
+    let vortex = parse_field_element(&input.vortex)?;
+    let root = parse_field_element(&input.root)?;
+    let public_amount = parse_field_element(&input.public_amount)?;
+    let output_commitment_0 = parse_field_element(&input.output_commitment_0)?;
+    let output_commitment_1 = parse_field_element(&input.output_commitment_1)?;
+    let hashed_account_secret = parse_field_element(&input.hashed_account_secret)?;
+
+    let account_secret = parse_field_element(&input.account_secret)?;
+    let extra = &input.extra_inputs;
+
+    let input_nullifiers = parse_inputs(
+        [
+            input.input_nullifier_0.as_str(),
+            input.input_nullifier_1.as_str(),
+        ]
+        .into_iter()
+        .chain(extra.iter().map(|utxo| utxo.nullifier.as_str())),
+        parse_field_element,
+    )?;
+    let in_private_keys = parse_inputs(
+        [
+            input.in_private_key_0.as_str(),
+            input.in_private_key_1.as_str(),
+        ]
+        .into_iter()
+        .chain(extra.iter().map(|utxo| utxo.private_key.as_str())),
+        parse_field_element,
+    )?;
+    let in_amounts = parse_inputs(
+        [input.in_amount_0.as_str(), input.in_amount_1.as_str()]
+            .into_iter()
+            .chain(extra.iter().map(|utxo| utxo.amount.as_str())),
+        parse_field_element,
+    )?;
+    let in_blindings = parse_inputs(
+        [input.in_blinding_0.as_str(), input.in_blinding_1.as_str()]
+            .into_iter()
+            .chain(extra.iter().map(|utxo| utxo.blinding.as_str())),
+        parse_field_element,
+    )?;
+    let in_path_indices = parse_inputs(
+        [
+            input.in_path_index_0.as_str(),
+            input.in_path_index_1.as_str(),
+        ]
+        .into_iter()
+        .chain(extra.iter().map(|utxo| utxo.path_index.as_str())),
+        parse_field_element,
+    )?;
+
+    // Parse Merkle paths
+    let merkle_paths = parse_inputs(
+        [&input.merkle_path_0[..], &input.merkle_path_1[..]]
+            .into_iter()
+            .chain(extra.iter().map(|utxo| &utxo.merkle_path[..])),
+        parse_path::<L>,
+    )?;
+
+    let out_public_keys = [
+        parse_field_element(&input.out_public_key_0)?,
+        parse_field_element(&input.out_public_key_1)?,
+    ];
+
+    let out_amounts = [
+        parse_field_element(&input.out_amount_0)?,
+        parse_field_element(&input.out_amount_1)?,
+    ];
+
+    let out_blindings = [
+        parse_field_element(&input.out_blinding_0)?,
+        parse_field_element(&input.out_blinding_1)?,
+    ];
+
+    let mut circuit = TransactionCircuit::from_arrays(
+        vortex,
+        root,
+        public_amount,
+        input_nullifiers,
+        [output_commitment_0, output_commitment_1],
+        hashed_account_secret,
+        account_secret,
+        in_private_keys,
+        in_amounts,
+        in_blindings,
+        in_path_indices,
+        merkle_paths,
+        out_public_keys,
+        out_amounts,
+        out_blindings,
+    )
+    .context("Failed to create circuit")?
+    .with_version(input.circuit_version);
+
+    if let Some(asset_id) = &input.asset_id {
+        circuit = circuit.with_asset_id(parse_field_element(asset_id)?);
+    }
+
+    if let Some(association_root) = &input.association_root {
+        circuit = circuit.with_association(
+            parse_field_element(association_root)?,
+            parse_inputs(
+                [&input.association_path_0[..], &input.association_path_1[..]]
+                    .into_iter()
+                    .chain(extra.iter().map(|utxo| &utxo.association_path[..])),
+                parse_optional_path::<L>,
+            )?,
+        );
+    }
+
+    if let Some(fee) = &input.fee {
+        circuit = circuit.with_fee(parse_field_element(fee)?);
+    }
+
+    if let Some(ext_data) = &input.ext_data {
+        circuit = circuit.with_ext_data(
+            ExtData {
+                recipient: parse_field_element(&ext_data.recipient)?,
+                relayer: parse_field_element(&ext_data.relayer)?,
+                fee: parse_field_element(&ext_data.fee)?,
+                encrypted_output_hash: parse_field_element(&ext_data.encrypted_output_hash)?,
+            },
+            ext_data.hasher,
+        );
+    }
+
+    if !input.root_history.is_empty() {
+        circuit = circuit.with_root_history(
+            input
+                .root_history
+                .iter()
+                .map(|root| parse_field_element(root))
+                .collect::<anyhow::Result<_>>()?,
+        );
+    }
+
+    if input.candidate_roots.is_empty() {
+        return Ok(circuit);
+    }
+
+    let candidates = std::iter::once(&input.root)
+        .chain(&input.candidate_roots)
+        .map(|root| parse_field_element(root))
+        .collect::<anyhow::Result<Vec<Fr>>>()?;
+
+    circuit.select_root(&candidates).map_err(|e| {
+        prove_error(
+            ProveErrorKind::RootMismatch,
+            format!("Failed to select a valid Merkle root: {:#}", e),
+        )
+    })
+}
+
+/// Parses the first `NI` per-input values, the others are ignored.
+fn parse_inputs<'a, V: ?Sized + 'a, T, const NI: usize>(
+    values: impl IntoIterator<Item = &'a V>,
+    parse: impl Fn(&V) -> anyhow::Result<T>,
+) -> anyhow::Result<[T; NI]> {
+    let parsed = values
+        .into_iter()
+        .take(NI)
+        .map(parse)
+        .collect::<anyhow::Result<Vec<T>>>()?;
+    let count = parsed.len();
+    parsed
+        .try_into()
+        .map_err(|_| anyhow!("A {}-input circuit needs {} inputs, got {}", NI, NI, count))
+}
+
+/// Generates a Groth16 proof for `input` with the keys of its [`CircuitKind`].
+///
+/// The witness is checked against the constraint system first, so invalid
+/// inputs fail fast with an error instead of producing an unverifiable proof.
+pub fn prove(input: &ProofInput, pk: &ProvingKey<Bn254>) -> anyhow::Result<ProofOutput> {
+    prove_with_descriptor(input, pk, None)
+}
+
+/// [`prove`], additionally failing when `descriptor` (the one written next to
+/// the keys) does not describe the circuit `input` builds.
+pub fn prove_with_descriptor(
+    input: &ProofInput,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+) -> anyhow::Result<ProofOutput> {
+    prove_with_rng(input, pk, descriptor, ProverRng::default())
+}
+
+/// [`prove_with_descriptor`] drawing the proof's blinding factors from `rng`.
+pub fn prove_with_rng(
+    input: &ProofInput,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+    rng: ProverRng,
+) -> anyhow::Result<ProofOutput> {
+    match input.tree_level.unwrap_or(MERKLE_TREE_LEVEL) {
+        SMALL_TREE_LEVEL => prove_leveled::<SMALL_TREE_LEVEL>(input, pk, descriptor, rng),
+        MEDIUM_TREE_LEVEL => prove_leveled::<MEDIUM_TREE_LEVEL>(input, pk, descriptor, rng),
+        LARGE_TREE_LEVEL => prove_leveled::<LARGE_TREE_LEVEL>(input, pk, descriptor, rng),
+        MERKLE_TREE_LEVEL => prove_leveled::<MERKLE_TREE_LEVEL>(input, pk, descriptor, rng),
+        level => Err(anyhow!(
+            "Unsupported tree level {}, expected one of {:?}",
+            level,
+            SUPPORTED_TREE_LEVELS
+        )),
+    }
+}
+
+fn prove_leveled<const L: usize>(
+    input: &ProofInput,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+    rng: ProverRng,
+) -> anyhow::Result<ProofOutput> {
+    match input.circuit_kind {
+        CircuitKind::Transaction2 => prove_circuit(
+            leveled_circuit_from_input::<N_INS, L>(input)?,
+            pk,
+            descriptor,
+            rng,
+        ),
+        CircuitKind::Transaction1 => prove_circuit(
+            leveled_circuit_from_input::<N_INS_1, L>(input)?,
+            pk,
+            descriptor,
+            rng,
+        ),
+        CircuitKind::Transaction16 => prove_circuit(
+            leveled_circuit_from_input::<N_INS_16, L>(input)?,
+            pk,
+            descriptor,
+            rng,
+        ),
+    }
+}
+
+/// Proves several transactions with one deserialized proving key.
+///
+/// Native builds prove in parallel with rayon; wasm has no threads and proves
+/// one transaction at a time. Outputs follow the order of `inputs`, and the
+/// first failure is reported with the index of its transaction.
+pub fn prove_batch(
+    inputs: Vec<ProofInput>,
+    pk: &ProvingKey<Bn254>,
+) -> anyhow::Result<Vec<ProofOutput>> {
+    prove_batch_with_descriptor(inputs, pk, None)
+}
+
+/// [`prove_batch`] with the checks of [`prove_with_descriptor`].
+pub fn prove_batch_with_descriptor(
+    inputs: Vec<ProofInput>,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+) -> anyhow::Result<Vec<ProofOutput>> {
+    prove_batch_with_rng(inputs, pk, descriptor, ProverRng::default())
+}
+
+/// [`prove_batch_with_descriptor`] with [`prove_with_rng`]; a seeded `rng`
+/// restarts from its seed for every transaction.
+pub fn prove_batch_with_rng(
+    inputs: Vec<ProofInput>,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+    rng: ProverRng,
+) -> anyhow::Result<Vec<ProofOutput>> {
+    let prove_one = |(i, input): (usize, &ProofInput)| {
+        prove_with_rng(input, pk, descriptor, rng)
+            .with_context(|| format!("Failed to prove transaction {}", i))
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use rayon::prelude::*;
+        inputs.par_iter().enumerate().map(prove_one).collect()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        inputs.iter().enumerate().map(prove_one).collect()
+    }
+}
+
+/// Proves an already built circuit, checking it and `pk` against `descriptor`
+/// when one is given.
+pub fn prove_circuit<const NI: usize, const L: usize>(
+    circuit: TransactionCircuit<NI, N_OUTS, L>,
+    pk: &ProvingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+    rng: ProverRng,
+) -> anyhow::Result<ProofOutput> {
+    if let Some(descriptor) = descriptor {
+        descriptor.check_verifying_key(&pk.vk)?;
+        descriptor.check_circuit(&circuit.descriptor())?;
+    }
+
+    let mut rng = rng.rng()?;
+
+    // Extract public inputs BEFORE proving (circuit is consumed by prove())
+    // The order MUST match the order in which FpVar::new_input() is called in generate_constraints()
+    // This is: vortex, root, public_amount, input_nullifier_0, input_nullifier_1,
+    //          output_commitment_0, output_commitment_1, hashed_account_secret
+    let public_inputs_field = circuit.get_public_inputs();
+    if pk.vk.gamma_abc_g1.len() != public_inputs_field.len() + 1 {
+        return Err(anyhow!(
+            "Proving key expects {} public inputs, the {}-input circuit has {}. \
+             Use the keys of the input's circuit kind",
+            pk.vk.gamma_abc_g1.len() - 1,
+            NI,
+            public_inputs_field.len()
+        ));
+    }
+    let public_inputs_serialized = circuit
+        .get_public_inputs_serialized()
+        .context("Failed to serialize public inputs")?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit
+        .clone()
+        .generate_constraints(cs.clone())
+        .map_err(|e| anyhow!("Failed to generate constraints: {}", e))?;
+    if !cs
+        .is_satisfied()
+        .map_err(|e| anyhow!("Failed to check constraints: {}", e))?
+    {
+        return Err(prove_error(
+            ProveErrorKind::ConstraintUnsatisfied,
+            "Constraints are not satisfied".to_string(),
+        ));
+    }
+
+    // Generate proof - Groth16 will internally call generate_constraints() and extract public inputs
+    // It uses the same public inputs we extracted above (in the same order)
+    // Note: Groth16's prove() function extracts public inputs from the constraint system
+    // in the order they were allocated via FpVar::new_input(). Our get_public_inputs()
+    // should match this order exactly.
+    //
+    // IMPORTANT: Groth16 extracts public inputs from the constraint system during prove().
+    // The public inputs are stored in the constraint system in the order they were allocated.
+    // We extract them manually using get_public_inputs() which should match exactly.
+    let proof = Groth16Backend::prove(pk, circuit, &mut rng)?;
+
+    // Serialize proof components (compressed format)
+    let mut proof_a_bytes = Vec::new();
+    proof
+        .a
+        .serialize_compressed(&mut proof_a_bytes)
+        .map_err(|e| anyhow!("Failed to serialize proof.a: {}", e))?;
+
+    let mut proof_b_bytes = Vec::new();
+    proof
+        .b
+        .serialize_compressed(&mut proof_b_bytes)
+        .map_err(|e| anyhow!("Failed to serialize proof.b: {}", e))?;
+
+    let mut proof_c_bytes = Vec::new();
+    proof
+        .c
+        .serialize_compressed(&mut proof_c_bytes)
+        .map_err(|e| anyhow!("Failed to serialize proof.c: {}", e))?;
+
+    // Serialize proof
+    let mut proof_serialized = Vec::new();
+    proof
+        .serialize_compressed(&mut proof_serialized)
+        .map_err(|e| anyhow!("Failed to serialize proof: {}", e))?;
+
+    // Convert public inputs to strings for JSON output
+    // Use the field's underlying representation for reliable serialization/deserialization
+    // This ensures the string can be parsed back correctly by parse_field_element()
+    let public_inputs: Vec<String> = public_inputs_field
+        .iter()
+        .map(|input| {
+            // Convert Fr to BigInt representation, then to string
+            // This ensures reliable round-trip conversion
+            input.into_bigint().to_string()
+        })
+        .collect();
+
+    Ok(ProofOutput {
+        proof_a: proof_a_bytes,
+        proof_b: proof_b_bytes,
+        proof_c: proof_c_bytes,
+        public_inputs,
+        proof_serialized_hex: hex::encode(proof_serialized),
+        public_inputs_serialized_hex: hex::encode(public_inputs_serialized),
+        reduce_field_elements: false,
+    })
+}
+
+/// Verifies a proof produced by [`prove`].
+pub fn verify(proof_output: &ProofOutput, vk: &VerifyingKey<Bn254>) -> anyhow::Result<bool> {
+    verify_with_descriptor(proof_output, vk, None)
+}
+
+/// [`verify`], additionally failing when `vk` or the proof's public inputs do
+/// not follow `descriptor`'s layout.
+pub fn verify_with_descriptor(
+    proof_output: &ProofOutput,
+    vk: &VerifyingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+) -> anyhow::Result<bool> {
+    if let Some(descriptor) = descriptor {
+        descriptor.check_verifying_key(vk)?;
+    }
+    let public_inputs = checked_public_inputs(
+        &proof_output.public_inputs,
+        vk,
+        descriptor,
+        proof_output.reduce_field_elements,
+    )?;
+
+    let proof_bytes = hex::decode(&proof_output.proof_serialized_hex)
+        .context("Step 4 - Failed to decode proof hex")?;
+
+    let proof = ark_groth16::Proof::<Bn254>::deserialize_compressed(&proof_bytes[..])
+        .map_err(|e| anyhow!("Step 5 - Failed to deserialize proof: {}", e))?;
+
+    Groth16Backend::verify(vk, &public_inputs, &proof).map_err(|e| anyhow!("Step 7 - {}", e))
+}
+
+/// Parses public inputs for verification, rejecting a count other than the
+/// key's (and the descriptor's, when given) and, unless `reduce` opts out,
+/// values that are not canonical field elements.
+///
+/// Without a descriptor only the root at position 1 is named, since the
+/// nullifier positions depend on the circuit shape.
+fn checked_public_inputs(
+    inputs: &[String],
+    vk: &VerifyingKey<Bn254>,
+    descriptor: Option<&CircuitDescriptor>,
+    reduce: bool,
+) -> anyhow::Result<Vec<Fr>> {
+    let parse = if reduce {
+        field::parse_field_reduced
+    } else {
+        field::parse_field
+    };
+    let expected = match descriptor {
+        Some(descriptor) => descriptor.public_input_layout.len(),
+        None => vk.gamma_abc_g1.len().saturating_sub(1),
+    };
+    if inputs.len() != expected {
+        return Err(verify_error(
+            VerifyErrorKind::InputCount,
+            format!(
+                "Proof has {} public inputs but the {} expects {}",
+                inputs.len(),
+                if descriptor.is_some() {
+                    "descriptor"
+                } else {
+                    "verifying key"
+                },
+                expected
+            ),
+        ));
+    }
+
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let name = match descriptor {
+                Some(descriptor) => descriptor.public_input_layout[i].as_str(),
+                None if i == ROOT_INPUT_POSITION => "root",
+                None => "public input",
+            };
+            parse(input).map_err(|_| {
+                let kind = if name == "root"
+                    || name == "association_root"
+                    || name.starts_with("root_history_")
+                {
+                    VerifyErrorKind::NonCanonicalRoot
+                } else if name.starts_with("input_nullifier_") {
+                    VerifyErrorKind::NonCanonicalNullifier
+                } else {
+                    VerifyErrorKind::NonCanonicalInput
+                };
+                verify_error(
+                    kind,
+                    format!(
+                        "Public input {} ({}) is not a canonical field element: '{}'",
+                        i, name, input
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Deserializes a compressed proving key, as written by `keygen` to `proving_key.bin`.
+pub fn proving_key_from_bytes(bytes: &[u8]) -> anyhow::Result<ProvingKey<Bn254>> {
+    ProvingKey::<Bn254>::deserialize_compressed(bytes).map_err(|e| {
+        prove_error(
+            ProveErrorKind::KeyDeserialize,
+            format!("Failed to deserialize proving key: {}", e),
+        )
+    })
+}
+
+/// Association set root and the membership path of one commitment, in the
+/// string encoding used by [`ProofInput`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssociationProof {
+    pub root: String,
+    pub path: Vec<[String; 2]>,
+}
+
+/// Builds the association set from `commitments` and returns the path of `commitment`.
+pub fn association_proof(
+    commitments: &[String],
+    commitment: &str,
+) -> anyhow::Result<AssociationProof> {
+    let commitments = commitments
+        .iter()
+        .map(|c| parse_field_element(c))
+        .collect::<anyhow::Result<Vec<Fr>>>()?;
+    let set = AssociationSet::new(&commitments)?;
+    let path = set.proof(&parse_field_element(commitment)?)?;
+
+    Ok(AssociationProof {
+        root: set.root().into_bigint().to_string(),
+        path: path
+            .path
+            .iter()
+            .map(|(left, right)| {
+                [
+                    left.into_bigint().to_string(),
+                    right.into_bigint().to_string(),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// JSON entry point of [`association_proof`]: takes a JSON array of approved
+/// commitments and returns an [`AssociationProof`] document.
+pub fn association_proof_json(commitments_json: &str, commitment: &str) -> anyhow::Result<String> {
+    let commitments: Vec<String> =
+        serde_json::from_str(commitments_json).context("Failed to parse commitments JSON")?;
+
+    let proof = association_proof(&commitments, commitment)?;
+
+    serde_json::to_string(&proof).context("Failed to serialize association proof")
+}
+
+/// Generates a proof from JSON-encoded inputs and a hex-encoded proving key.
+///
+/// This is the string-based entry point behind the WASM and Node.js bindings;
+/// both accept and return exactly the same JSON documents.
+///
+/// `descriptor_json` is the optional `descriptor.json` published with the keys.
+/// Blinding factors always come from fresh entropy.
+pub fn prove_json(
+    input_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<String, ProveError> {
+    Ok(prove_json_inner(
+        input_json,
+        proving_key_hex,
+        descriptor_json,
+        ProverRng::Entropy,
+    )?)
+}
+
+fn prove_json_inner(
+    input_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+    rng: ProverRng,
+) -> anyhow::Result<String> {
+    let input: ProofInput =
+        serde_json::from_str(input_json).context("Failed to parse input JSON")?;
+    let output = prove_hex_inner(&input, proving_key_hex, descriptor_json, rng)?;
+
+    serde_json::to_string(&output).context("Failed to serialize output")
+}
+
+/// [`prove_json`] for an input that is already deserialized, returning the
+/// output as is. Bindings that convert to and from host objects directly use
+/// it to skip the JSON round trip.
+pub fn prove_hex(
+    input: &ProofInput,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<ProofOutput, ProveError> {
+    Ok(prove_hex_inner(
+        input,
+        proving_key_hex,
+        descriptor_json,
+        ProverRng::Entropy,
+    )?)
+}
+
+fn prove_hex_inner(
+    input: &ProofInput,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+    rng: ProverRng,
+) -> anyhow::Result<ProofOutput> {
+    let descriptor = descriptor_json.map(parse_descriptor).transpose()?;
+    let pk = proving_key_from_hex(proving_key_hex)?;
+
+    prove_with_rng(input, &pk, descriptor.as_ref(), rng)
+}
+
+fn proving_key_from_hex(proving_key_hex: &str) -> anyhow::Result<ProvingKey<Bn254>> {
+    let pk_bytes = hex::decode(proving_key_hex).map_err(|e| {
+        prove_error(
+            ProveErrorKind::KeyDeserialize,
+            format!("Failed to decode proving key hex: {}", e),
+        )
+    })?;
+    proving_key_from_bytes(&pk_bytes)
+}
+
+/// Batch counterpart of [`prove_json`]: takes a JSON array of inputs and
+/// returns a JSON array of outputs in the same order.
+pub fn prove_batch_json(
+    inputs_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<String, ProveError> {
+    Ok(prove_batch_json_inner(
+        inputs_json,
+        proving_key_hex,
+        descriptor_json,
+        ProverRng::Entropy,
+    )?)
+}
+
+fn prove_batch_json_inner(
+    inputs_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+    rng: ProverRng,
+) -> anyhow::Result<String> {
+    let inputs: Vec<ProofInput> =
+        serde_json::from_str(inputs_json).context("Failed to parse inputs JSON")?;
+    let outputs = prove_batch_hex_inner(inputs, proving_key_hex, descriptor_json, rng)?;
+
+    serde_json::to_string(&outputs).context("Failed to serialize outputs")
+}
+
+/// Batch counterpart of [`prove_hex`].
+pub fn prove_batch_hex(
+    inputs: Vec<ProofInput>,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<Vec<ProofOutput>, ProveError> {
+    Ok(prove_batch_hex_inner(
+        inputs,
+        proving_key_hex,
+        descriptor_json,
+        ProverRng::Entropy,
+    )?)
+}
+
+fn prove_batch_hex_inner(
+    inputs: Vec<ProofInput>,
+    proving_key_hex: &str,
+    descriptor_json: Option<&str>,
+    rng: ProverRng,
+) -> anyhow::Result<Vec<ProofOutput>> {
+    let descriptor = descriptor_json.map(parse_descriptor).transpose()?;
+    let pk = proving_key_from_hex(proving_key_hex)?;
+
+    prove_batch_with_rng(inputs, &pk, descriptor.as_ref(), rng)
+}
+
+/// Verifies a JSON-encoded proof produced by [`prove_json`] against a hex-encoded verifying key.
+pub fn verify_json(
+    proof_json: &str,
+    verifying_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<bool, VerifyError> {
+    Ok(verify_json_inner(
+        proof_json,
+        verifying_key_hex,
+        descriptor_json,
+    )?)
+}
+
+fn verify_json_inner(
+    proof_json: &str,
+    verifying_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> anyhow::Result<bool> {
+    let proof_output: ProofOutput =
+        serde_json::from_str(proof_json).context("Step 1 - Failed to parse proof JSON")?;
+    verify_hex_inner(&proof_output, verifying_key_hex, descriptor_json)
+}
+
+/// [`verify_json`] for a proof that is already deserialized.
+pub fn verify_hex(
+    proof_output: &ProofOutput,
+    verifying_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> Result<bool, VerifyError> {
+    Ok(verify_hex_inner(
+        proof_output,
+        verifying_key_hex,
+        descriptor_json,
+    )?)
+}
+
+fn verify_hex_inner(
+    proof_output: &ProofOutput,
+    verifying_key_hex: &str,
+    descriptor_json: Option<&str>,
+) -> anyhow::Result<bool> {
+    let descriptor = descriptor_json.map(parse_descriptor).transpose()?;
+
+    let vk_bytes = hex::decode(verifying_key_hex).context("Step 2 - Failed to decode VK hex")?;
+
+    let vk = verifying_key_from_bytes(&vk_bytes).map_err(|e| anyhow!("Step 3 - {}", e))?;
+
+    verify_with_descriptor(proof_output, &vk, descriptor.as_ref())
+}
+
+/// Converts a JSON proof produced by [`prove_json`] into a JSON [`SuiProof`]
+/// for a hex-encoded verifying key.
+pub fn to_sui_proof_json(proof_json: &str, verifying_key_hex: &str) -> anyhow::Result<String> {
+    let proof_output: ProofOutput =
+        serde_json::from_str(proof_json).context("Failed to parse proof JSON")?;
+    let vk_bytes = hex::decode(verifying_key_hex).context("Failed to decode VK hex")?;
+    let vk = verifying_key_from_bytes(&vk_bytes)?;
+
+    serde_json::to_string(&proof_output.to_sui_proof(&vk)?).context("Failed to serialize proof")
+}
+
+fn parse_descriptor(descriptor_json: &str) -> anyhow::Result<CircuitDescriptor> {
+    serde_json::from_str(descriptor_json).context("Failed to parse circuit descriptor JSON")
+}
+
+/// Parses a decimal or 0x-prefixed hex field element, see [`field::parse_field`].
+pub fn parse_field_element(s: &str) -> anyhow::Result<Fr> {
+    field::parse_field(s).map_err(|e| prove_error(ProveErrorKind::BadField, e.to_string()))
+}
+
+/// [`parse_field_element`] reducing values at or above the modulus, see
+/// [`field::parse_field_reduced`].
+pub fn parse_field_element_reduced(s: &str) -> anyhow::Result<Fr> {
+    field::parse_field_reduced(s).map_err(|e| prove_error(ProveErrorKind::BadField, e.to_string()))
+}
+
+/// Parses a Merkle path, treating an omitted (empty) path as [`Path::empty`].
+pub fn parse_optional_merkle_path(
+    path_data: &[[String; 2]],
+) -> anyhow::Result<Path<MERKLE_TREE_LEVEL>> {
+    parse_optional_path(path_data)
+}
+
+pub fn parse_merkle_path(path_data: &[[String; 2]]) -> anyhow::Result<Path<MERKLE_TREE_LEVEL>> {
+    parse_path(path_data)
+}
+
+fn parse_optional_path<const L: usize>(path_data: &[[String; 2]]) -> anyhow::Result<Path<L>> {
+    if path_data.is_empty() {
+        Ok(Path::empty())
+    } else {
+        parse_path(path_data)
+    }
+}
+
+fn parse_path<const L: usize>(path_data: &[[String; 2]]) -> anyhow::Result<Path<L>> {
+    if path_data.len() != L {
+        return Err(prove_error(
+            ProveErrorKind::TreeConstruction,
+            format!(
+                "Invalid Merkle path length: expected {}, got {}",
+                L,
+                path_data.len()
+            ),
+        ));
+    }
+
+    let mut path = [(Fr::from(0u64), Fr::from(0u64)); L];
+
+    for (i, pair) in path_data.iter().enumerate() {
+        let left = parse_field_element(&pair[0])?;
+        let right = parse_field_element(&pair[1])?;
+        path[i] = (left, right);
+    }
+
+    Ok(Path { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon_opt::{hash1, hash3, hash4};
+    use ark_ff::AdditiveGroup;
+    use num_bigint::BigUint;
+
+    fn field_string(value: Fr) -> String {
+        value.into_bigint().to_string()
+    }
+
+    fn zero_value_input() -> ProofInput {
+        let vortex = Fr::from(7u64);
+        let private_keys = [Fr::from(12345u64), Fr::from(67890u64)];
+        let blindings = [Fr::from(999u64), Fr::from(888u64)];
+        let path_indices = [Fr::from(0u64), Fr::from(1u64)];
+        let out_blindings = [Fr::from(777u64), Fr::from(666u64)];
+
+        let public_keys = private_keys.map(|key| hash1(&key));
+        let nullifiers: Vec<Fr> = (0..2)
+            .map(|i| {
+                let commitment = hash4(&Fr::ZERO, &public_keys[i], &blindings[i], &vortex);
+                let signature = hash3(&private_keys[i], &commitment, &path_indices[i]);
+                hash3(&commitment, &path_indices[i], &signature)
+            })
+            .collect();
+        let out_commitments: Vec<Fr> = (0..2)
+            .map(|i| hash4(&Fr::ZERO, &public_keys[i], &out_blindings[i], &vortex))
+            .collect();
+
+        let empty_path = vec![[field_string(Fr::ZERO), field_string(Fr::ZERO)]; MERKLE_TREE_LEVEL];
+        let zero = field_string(Fr::ZERO);
+
+        ProofInput {
+            circuit_version: CircuitVersion::V1,
+            circuit_kind: CircuitKind::Transaction2,
+            tree_level: None,
+            candidate_roots: Vec::new(),
+            root_history: Vec::new(),
+            reduce_field_elements: false,
+            vortex: field_string(vortex),
+            root: zero.clone(),
+            public_amount: zero.clone(),
+            input_nullifier_0: field_string(nullifiers[0]),
+            input_nullifier_1: field_string(nullifiers[1]),
+            output_commitment_0: field_string(out_commitments[0]),
+            output_commitment_1: field_string(out_commitments[1]),
+            hashed_account_secret: zero.clone(),
+            asset_id: None,
+            association_root: None,
+            fee: None,
+            ext_data: None,
+            account_secret: zero.clone(),
+            in_private_key_0: field_string(private_keys[0]),
+            in_private_key_1: field_string(private_keys[1]),
+            in_amount_0: zero.clone(),
+            in_amount_1: zero.clone(),
+            in_blinding_0: field_string(blindings[0]),
+            in_blinding_1: field_string(blindings[1]),
+            in_path_index_0: field_string(path_indices[0]),
+            in_path_index_1: field_string(path_indices[1]),
+            merkle_path_0: empty_path.clone(),
+            merkle_path_1: empty_path,
+            association_path_0: Vec::new(),
+            association_path_1: Vec::new(),
+            extra_inputs: Vec::new(),
+            out_public_key_0: field_string(public_keys[0]),
+            out_public_key_1: field_string(public_keys[1]),
+            out_amount_0: zero.clone(),
+            out_amount_1: zero,
+            out_blinding_0: field_string(out_blindings[0]),
+            out_blinding_1: field_string(out_blindings[1]),
+        }
+    }
+
+    #[test]
+    fn test_native_prove_verify_with_published_keys() {
+        // The checked deserialization of the repo's own key dominates debug test time
+        let pk = ProvingKey::<Bn254>::deserialize_compressed_unchecked(
+            &include_bytes!("../keys/proving_key.bin")[..],
+        )
+        .unwrap();
+        let vk = verifying_key_from_bytes(include_bytes!("../keys/verification_key.bin")).unwrap();
+
+        let mut input = zero_value_input();
+        let output = prove(&input, &pk).unwrap();
+        assert!(verify(&output, &vk).unwrap());
+
+        let proof_bytes = hex::decode(&output.proof_serialized_hex).unwrap();
+        let public_inputs = output
+            .public_inputs
+            .iter()
+            .map(|input| parse_field_element(input).unwrap())
+            .collect::<Vec<_>>();
+        let vk_bytes = include_bytes!("../keys/verification_key.bin");
+        assert!(crate::verifier::verify_proof(&proof_bytes, &public_inputs, vk_bytes).unwrap());
+
+        let mut tampered = output.clone();
+        tampered.public_inputs[2] = "1".to_string();
+        assert!(!verify(&tampered, &vk).unwrap());
+
+        let mut lite = input.clone();
+        lite.circuit_kind = CircuitKind::Transaction1;
+        let error = prove(&lite, &pk).unwrap_err().to_string();
+        assert!(
+            error.contains("Proving key expects 8 public inputs"),
+            "{error}"
+        );
+
+        let descriptor = parse_descriptor(include_str!("../keys/descriptor.json")).unwrap();
+        assert_eq!(descriptor, circuit_from_input(&input).unwrap().descriptor());
+        let described = prove_with_descriptor(&input, &pk, Some(&descriptor)).unwrap();
+        assert!(verify_with_descriptor(&described, &vk, Some(&descriptor)).unwrap());
+
+        let mut drifted = input.clone();
+        drifted.circuit_version = CircuitVersion::V2;
+        let error = prove_with_descriptor(&drifted, &pk, Some(&descriptor))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("version: keys V1, input V2") && error.contains("hashScheme"),
+            "{error}"
+        );
+
+        let sui = output.to_sui_proof(&vk).unwrap();
+        assert_eq!(sui.proof_points_bytes, proof_bytes);
+        assert_eq!(
+            hex::encode(&sui.public_inputs_bytes),
+            output.public_inputs_serialized_hex
+        );
+        assert_eq!(sui.prepared_vk_bytes[0].len(), 32 * vk.gamma_abc_g1.len());
+        assert_eq!(sui.prepared_vk_bytes[1].len(), 384);
+        let neg_gamma = ark_bn254::G2Affine::deserialize_compressed(&sui.prepared_vk_bytes[2][..]);
+        assert_eq!(-neg_gamma.unwrap(), vk.gamma_g2);
+        assert_ne!(sui.prepared_vk_bytes[2], sui.prepared_vk_bytes[3]);
+        assert!(output.verify_onchain_format(&vk).unwrap());
+        let mut swapped = sui.clone();
+        swapped.prepared_vk_bytes.swap(2, 3);
+        assert!(!swapped.verify().unwrap());
+        let mut big_endian = output.clone();
+        big_endian.public_inputs_serialized_hex = hex::encode(
+            sui.public_inputs_bytes
+                .chunks(32)
+                .rev()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+        assert!(big_endian.verify_onchain_format(&vk).is_err());
+        let mut uncompressed = sui.clone();
+        uncompressed.proof_points_bytes.extend([0u8; 32]);
+        assert!(uncompressed.verify().is_err());
+
+        let mut truncated = described.clone();
+        truncated.public_inputs.pop();
+        let error = VerifyError::from(
+            verify_with_descriptor(&truncated, &vk, Some(&descriptor)).unwrap_err(),
+        );
+        assert_eq!(error.kind(), VerifyErrorKind::InputCount);
+        let error = VerifyError::from(verify(&truncated, &vk).unwrap_err());
+        assert!(
+            error.message().contains("verifying key expects 8"),
+            "{error}"
+        );
+
+        let modulus = BigUint::from(Fr::MODULUS).to_string();
+        let mut unreduced = described.clone();
+        unreduced.public_inputs[1] = modulus.clone();
+        let error = VerifyError::from(verify(&unreduced, &vk).unwrap_err());
+        assert_eq!(error.kind(), VerifyErrorKind::NonCanonicalRoot);
+        unreduced.public_inputs[1] = described.public_inputs[1].clone();
+        unreduced.public_inputs[4] = modulus;
+        let error = VerifyError::from(
+            verify_with_descriptor(&unreduced, &vk, Some(&descriptor)).unwrap_err(),
+        );
+        assert_eq!(error.kind(), VerifyErrorKind::NonCanonicalNullifier);
+        assert!(error.message().contains("(input_nullifier_1)"), "{error}");
+        let error = VerifyError::from(verify(&unreduced, &vk).unwrap_err());
+        assert_eq!(error.kind(), VerifyErrorKind::NonCanonicalInput);
+
+        let mut legacy = described.clone();
+        let root = BigUint::parse_bytes(described.public_inputs[1].as_bytes(), 10).unwrap();
+        legacy.public_inputs[1] = (root + BigUint::from(Fr::MODULUS)).to_string();
+        let error = VerifyError::from(verify(&legacy, &vk).unwrap_err());
+        assert_eq!(error.kind(), VerifyErrorKind::NonCanonicalRoot);
+        legacy.reduce_field_elements = true;
+        assert!(verify(&legacy, &vk).unwrap());
+
+        assert_ne!(described.proof_serialized_hex, output.proof_serialized_hex);
+        let seeded = prove_with_rng(&input, &pk, None, ProverRng::Seeded(7)).unwrap();
+        assert!(verify(&seeded, &vk).unwrap());
+        let batch = prove_batch_with_rng(
+            vec![input.clone(), input.clone()],
+            &pk,
+            None,
+            ProverRng::Seeded(7),
+        )
+        .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert!(batch
+            .iter()
+            .all(|proof| proof.proof_serialized_hex == seeded.proof_serialized_hex));
+        let error = prove_batch(vec![input.clone(), lite.clone()], &pk).unwrap_err();
+        assert_eq!(error.to_string(), "Failed to prove transaction 1");
+
+        let mut hex_encoded = input.clone();
+        hex_encoded.vortex = "0x07".to_string();
+        assert_eq!(
+            hex_encoded.canonical_hash().unwrap(),
+            input.canonical_hash().unwrap()
+        );
+
+        input.public_amount = "1".to_string();
+        assert_ne!(
+            hex_encoded.canonical_hash().unwrap(),
+            input.canonical_hash().unwrap()
+        );
+        let error = ProveError::from(prove(&input, &pk).unwrap_err());
+        assert_eq!(error.kind(), ProveErrorKind::ConstraintUnsatisfied);
+
+        input.tree_level = Some(12);
+        let error = prove(&input, &pk).unwrap_err().to_string();
+        assert!(error.contains("Unsupported tree level 12"), "{error}");
+    }
+
+    #[test]
+    fn test_transfer_lite_input_builds_one_input_circuit() {
+        let mut input = zero_value_input();
+        input.circuit_kind = CircuitKind::Transaction1;
+        // Ignored by the 1-input circuit
+        input.in_private_key_1 = "not a field element".to_string();
+        input.merkle_path_1 = Vec::new();
+
+        let circuit = shaped_circuit_from_input::<N_INS_1>(&input).unwrap();
+        assert_eq!(circuit.get_public_inputs().len(), N_INS_1 + N_OUTS + 4);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        assert!(circuit_from_input(&input).is_err());
+        assert_eq!(
+            serde_json::from_value::<CircuitKind>(serde_json::json!("transaction1")).unwrap(),
+            CircuitKind::Transaction1
+        );
+        assert_eq!(
+            "transfer-lite".parse::<CircuitKind>().unwrap(),
+            CircuitKind::Transaction1
+        );
+    }
+
+    #[test]
+    fn test_transaction16_prove_verify() {
+        let mut input = zero_value_input();
+        let vortex = parse_field_element(&input.vortex).unwrap();
+        let empty_path = vec![[field_string(Fr::ZERO), field_string(Fr::ZERO)]; SMALL_TREE_LEVEL];
+        input.circuit_kind = CircuitKind::Transaction16;
+        input.tree_level = Some(SMALL_TREE_LEVEL);
+        input.merkle_path_0 = empty_path.clone();
+        input.merkle_path_1 = empty_path.clone();
+        input.extra_inputs = (N_INS as u64..N_INS_16 as u64)
+            .map(|i| {
+                let private_key = Fr::from(1000 + i);
+                let blinding = Fr::from(2000 + i);
+                let path_index = Fr::from(i);
+                let commitment = hash4(&Fr::ZERO, &hash1(&private_key), &blinding, &vortex);
+                let signature = hash3(&private_key, &commitment, &path_index);
+                ExtraInputUtxo {
+                    nullifier: field_string(hash3(&commitment, &path_index, &signature)),
+                    private_key: field_string(private_key),
+                    amount: field_string(Fr::ZERO),
+                    blinding: field_string(blinding),
+                    path_index: field_string(path_index),
+                    merkle_path: empty_path.clone(),
+                    association_path: Vec::new(),
+                }
+            })
+            .collect();
+
+        let (pk, vk) = Groth16Backend::setup(
+            TransactionCircuit::<N_INS_16, N_OUTS, SMALL_TREE_LEVEL>::empty(),
+            &mut ChaCha20Rng::from_seed([16u8; 32]),
+        )
+        .unwrap();
+        let output = prove_with_rng(&input, &pk, None, ProverRng::Seeded(16)).unwrap();
+        assert_eq!(output.public_inputs.len(), N_INS_16 + N_OUTS + 4);
+        assert!(verify(&output, &vk).unwrap());
+
+        input.extra_inputs.pop();
+        let error = prove(&input, &pk).unwrap_err().to_string();
+        assert!(
+            error.contains("A 16-input circuit needs 16 inputs, got 15"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_tree_level_selects_shallow_circuit() {
+        let mut input = zero_value_input();
+        let empty_path = vec![[field_string(Fr::ZERO), field_string(Fr::ZERO)]; SMALL_TREE_LEVEL];
+        input.merkle_path_0 = empty_path.clone();
+        input.merkle_path_1 = empty_path;
+
+        // Without a level the paths are read as the deployed height
+        assert!(circuit_from_input(&input).is_err());
+
+        input.tree_level = Some(SMALL_TREE_LEVEL);
+        let circuit = leveled_circuit_from_input::<N_INS, SMALL_TREE_LEVEL>(&input).unwrap();
+        assert_eq!(circuit.descriptor().level, SMALL_TREE_LEVEL);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert!(leveled_circuit_from_input::<N_INS, MEDIUM_TREE_LEVEL>(&input).is_err());
+    }
+
+    #[test]
+    fn test_prove_errors_are_typed() {
+        let mut input = zero_value_input();
+        input.vortex = "0xzz".to_string();
+        let error = ProveError::from(circuit_from_input(&input).unwrap_err());
+        assert_eq!(error.kind(), ProveErrorKind::BadField);
+        assert_eq!(error.kind_name(), "BadField");
+        assert!(
+            error.message().contains("Failed to parse hex '0xzz'"),
+            "{error}"
+        );
+
+        let mut input = zero_value_input();
+        let vortex = BigUint::parse_bytes(input.vortex.as_bytes(), 10).unwrap();
+        input.vortex = (vortex + BigUint::from(Fr::MODULUS)).to_string();
+        let error = ProveError::from(circuit_from_input(&input).unwrap_err());
+        assert_eq!(error.kind(), ProveErrorKind::BadField);
+        assert!(error.message().contains("not below"), "{error}");
+        input.reduce_field_elements = true;
+        assert_eq!(input.reduced().unwrap().vortex, zero_value_input().vortex);
+        assert!(circuit_from_input(&input).is_ok());
+
+        let mut input = zero_value_input();
+        input.merkle_path_0.pop();
+        let error = ProveError::from(circuit_from_input(&input).unwrap_err());
+        assert_eq!(error.kind(), ProveErrorKind::TreeConstruction);
+
+        let input_json = serde_json::to_string(&zero_value_input()).unwrap();
+        let error = prove_json(&input_json, "zz", None).unwrap_err();
+        assert_eq!(error.kind(), ProveErrorKind::KeyDeserialize);
+        let error = prove_json(&input_json, "00", None).unwrap_err();
+        assert_eq!(error.kind(), ProveErrorKind::KeyDeserialize);
+        let error = prove_hex(&zero_value_input(), "zz", None).unwrap_err();
+        assert_eq!(error.kind(), ProveErrorKind::KeyDeserialize);
+
+        let error = prove_batch_json("{", "00", None).unwrap_err();
+        assert_eq!(error.kind(), ProveErrorKind::InvalidInput);
+        assert!(error.message().starts_with("Failed to parse inputs JSON"));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{prover::parse_field_element, sync::exponential_backoff_ms};
+use anyhow::Context;
+use ark_ff::{BigInteger, PrimeField};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Separates relay idempotency keys from other hashes of the same nullifiers.
+const IDEMPOTENCY_KEY_DOMAIN: &[u8] = b"vortex-relay-idempotency-v1";
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+pub const DEFAULT_BASE_DELAY_MS: u64 = 1_000;
+pub const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+
+const HTTP_REQUEST_TIMEOUT: u16 = 408;
+/// The relayer is still executing a submission with the same key.
+const HTTP_CONFLICT: u16 = 409;
+const HTTP_TOO_EARLY: u16 = 425;
+const HTTP_TOO_MANY_REQUESTS: u16 = 429;
+const HTTP_SERVER_ERRORS: std::ops::Range<u16> = 500..600;
+
+/// `Idempotency-Key` header of a relayer submission spending `nullifier_0`
+/// and `nullifier_1`: the hex SHA-256 of both in canonical form, so every
+/// retry of the same spend carries the same key and the relayer returns the
+/// original digest instead of executing it twice.
+///
+/// # Errors
+/// Returns error if a nullifier is not a field element.
+pub fn idempotency_key(nullifier_0: &str, nullifier_1: &str) -> anyhow::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(IDEMPOTENCY_KEY_DOMAIN);
+    for nullifier in [nullifier_0, nullifier_1] {
+        let value = parse_field_element(nullifier).context("Invalid nullifier")?;
+        hasher.update(value.into_bigint().to_bytes_be());
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Retry policy of relayer submissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelayRetryPolicy {
+    /// Submissions made, including the first, before giving up.
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RelayRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay_ms: DEFAULT_BASE_DELAY_MS,
+            max_delay_ms: DEFAULT_MAX_DELAY_MS,
+        }
+    }
+}
+
+impl RelayRetryPolicy {
+    /// Delay before resubmitting after the `attempt`-th submission failed
+    /// with HTTP `status` (`None` for network errors), or `None` when the
+    /// failure is final or the attempts are exhausted.
+    ///
+    /// Resubmitting is only safe with the same [`idempotency_key`].
+    pub fn retry_delay_ms(&self, attempt: u32, status: Option<u16>) -> Option<u64> {
+        (attempt < self.max_attempts && is_retryable(status))
+            .then(|| exponential_backoff_ms(self.base_delay_ms, self.max_delay_ms, attempt))
+    }
+}
+
+/// Network errors, timeouts, rate limits, in-progress duplicates and server
+/// errors are transient; other statuses reject the submission itself.
+pub fn is_retryable(status: Option<u16>) -> bool {
+    match status {
+        None => true,
+        Some(status) => {
+            matches!(
+                status,
+                HTTP_REQUEST_TIMEOUT | HTTP_CONFLICT | HTTP_TOO_EARLY | HTTP_TOO_MANY_REQUESTS
+            ) || HTTP_SERVER_ERRORS.contains(&status)
+        }
+    }
+}
+
+/// Parses an optional JSON [`RelayRetryPolicy`], defaulting missing fields.
+pub fn parse_retry_policy(policy_json: Option<&str>) -> anyhow::Result<RelayRetryPolicy> {
+    policy_json
+        .map(|json| serde_json::from_str(json).context("Failed to parse relay retry policy JSON"))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotency_key_is_stable_per_nullifier_pair() {
+        let key = idempotency_key("1", "2").unwrap();
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+
+        assert_eq!(idempotency_key("0x01", "2").unwrap(), key);
+        assert_ne!(idempotency_key("2", "1").unwrap(), key);
+        assert_ne!(idempotency_key("1", "3").unwrap(), key);
+        assert!(idempotency_key("not a nullifier", "2").is_err());
+    }
+
+    #[test]
+    fn test_retry_policy_backs_off_transient_failures_only() {
+        let policy = RelayRetryPolicy {
+            max_attempts: 4,
+            base_delay_ms: 100,
+            max_delay_ms: 250,
+        };
+
+        let delays = (1..=4)
+            .map(|attempt| policy.retry_delay_ms(attempt, Some(503)))
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [Some(100), Some(200), Some(250), None]);
+
+        assert_eq!(policy.retry_delay_ms(1, None), Some(100));
+        assert_eq!(policy.retry_delay_ms(1, Some(HTTP_CONFLICT)), Some(100));
+        assert_eq!(
+            policy.retry_delay_ms(1, Some(HTTP_TOO_MANY_REQUESTS)),
+            Some(100)
+        );
+        assert_eq!(policy.retry_delay_ms(1, Some(400)), None);
+        assert_eq!(policy.retry_delay_ms(1, Some(401)), None);
+
+        assert_eq!(
+            parse_retry_policy(Some(r#"{"maxAttempts":2}"#)).unwrap(),
+            RelayRetryPolicy {
+                max_attempts: 2,
+                ..RelayRetryPolicy::default()
+            }
+        );
+        assert_eq!(
+            parse_retry_policy(None).unwrap(),
+            RelayRetryPolicy::default()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use crate::{
+    derivation::ViewingKey,
+    note::{decrypt_note, is_empty_output},
+    prover::parse_field_element,
+};
+use anyhow::{bail, Context};
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+
+/// Commitments requested per page; the API caps `limit` at 1000.
+pub const DEFAULT_CHUNK_SIZE: u64 = 500;
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+pub const DEFAULT_BASE_DELAY_MS: u64 = 500;
+pub const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+
+/// Page size and retry policy of a wallet sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncConfig {
+    pub chunk_size: u64,
+    /// Consecutive failures of one page tolerated before the sync gives up.
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay_ms: DEFAULT_BASE_DELAY_MS,
+            max_delay_ms: DEFAULT_MAX_DELAY_MS,
+        }
+    }
+}
+
+impl SyncConfig {
+    /// Delay before retrying after the `attempt`-th consecutive failure:
+    /// `base_delay_ms` doubled per attempt, capped at `max_delay_ms`.
+    pub fn backoff_ms(&self, attempt: u32) -> u64 {
+        exponential_backoff_ms(self.base_delay_ms, self.max_delay_ms, attempt)
+    }
+}
+
+/// `base_delay_ms` doubled per attempt after the first, capped at `max_delay_ms`.
+pub(crate) fn exponential_backoff_ms(base_delay_ms: u64, max_delay_ms: u64, attempt: u32) -> u64 {
+    let factor = 1u64
+        .checked_shl(attempt.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    base_delay_ms.saturating_mul(factor).min(max_delay_ms)
+}
+
+/// Owned note found while syncing, with its leaf index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedNote {
+    pub index: u64,
+    pub commitment: String,
+    pub amount: String,
+    pub blinding: String,
+}
+
+/// Progress of a pool scan. The wallet persists it after every page, so an
+/// interrupted sync resumes from `next_index` instead of from index 0.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCheckpoint {
+    pub coin_type: String,
+    pub next_index: u64,
+    pub notes: Vec<SyncedNote>,
+    /// Consecutive failures of the page at `next_index`.
+    #[serde(default)]
+    pub failed_attempts: u32,
+    /// Whether the last page applied was the pool's last one. The pool keeps
+    /// growing, so this only means caught up for now: the next request still
+    /// picks up commitments inserted since.
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Query of the next `GET /api/v1/commitments` page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRequest {
+    pub coin_type: String,
+    pub index: u64,
+    pub op: String,
+    pub limit: u64,
+}
+
+/// `data` of a `GET /api/v1/commitments` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentPage {
+    pub items: Vec<PageCommitment>,
+    pub has_next: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageCommitment {
+    pub index: u64,
+    pub commitment: String,
+    pub encrypted_output: Vec<u8>,
+}
+
+impl SyncCheckpoint {
+    pub fn new(coin_type: &str) -> Self {
+        Self {
+            coin_type: coin_type.to_string(),
+            next_index: 0,
+            notes: Vec::new(),
+            failed_attempts: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the page starting at `next_index` to fetch next.
+    pub fn next_request(&self, config: &SyncConfig) -> SyncRequest {
+        SyncRequest {
+            coin_type: self.coin_type.clone(),
+            index: self.next_index,
+            op: "gte".to_string(),
+            limit: config.chunk_size,
+        }
+    }
+
+    /// Scans a fetched page for notes owned by `viewing_key` and advances past it.
+    ///
+    /// Items before `next_index`, e.g. from a page fetched twice after a
+    /// retry, are skipped.
+    ///
+    /// # Errors
+    /// Returns error, leaving the checkpoint unchanged, if the page skips an
+    /// index or holds an invalid commitment, since notes could be missed.
+    pub fn apply_page(
+        &mut self,
+        page: &CommitmentPage,
+        viewing_key: &ViewingKey,
+    ) -> anyhow::Result<()> {
+        let mut next_index = self.next_index;
+        let mut notes = Vec::new();
+
+        for item in page
+            .items
+            .iter()
+            .filter(|item| item.index >= self.next_index)
+        {
+            if item.index != next_index {
+                bail!(
+                    "Commitment page skips from index {} to {}",
+                    next_index,
+                    item.index
+                );
+            }
+            next_index += 1;
+
+            if is_empty_output(&item.encrypted_output) {
+                continue;
+            }
+            if let Ok(note) = decrypt_note(&item.encrypted_output, viewing_key) {
+                notes.push(SyncedNote {
+                    index: item.index,
+                    commitment: parse_field_element(&item.commitment)
+                        .with_context(|| format!("Invalid commitment at index {}", item.index))?
+                        .into_bigint()
+                        .to_string(),
+                    amount: note.amount.into_bigint().to_string(),
+                    blinding: note.blinding.into_bigint().to_string(),
+                });
+            }
+        }
+
+        self.next_index = next_index;
+        self.notes.extend(notes);
+        self.failed_attempts = 0;
+        self.done = !page.has_next;
+        Ok(())
+    }
+
+    /// Records a failed fetch of the next page and returns how long to wait
+    /// before retrying it, or `None` once `max_retries` is exhausted. The
+    /// progress made so far is kept either way.
+    pub fn record_failure(&mut self, config: &SyncConfig) -> Option<u64> {
+        self.failed_attempts += 1;
+        (self.failed_attempts <= config.max_retries)
+            .then(|| config.backoff_ms(self.failed_attempts))
+    }
+}
+
+/// Result of [`sync_record_failure_json`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFailureJson {
+    pub checkpoint: SyncCheckpoint,
+    pub retry_in_ms: Option<u64>,
+}
+
+fn parse_checkpoint(checkpoint_json: &str) -> anyhow::Result<SyncCheckpoint> {
+    serde_json::from_str(checkpoint_json).context("Failed to parse sync checkpoint JSON")
+}
+
+fn parse_config(config_json: Option<&str>) -> anyhow::Result<SyncConfig> {
+    config_json
+        .map(|json| serde_json::from_str(json).context("Failed to parse sync config JSON"))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Returns a fresh [`SyncCheckpoint`] for `coin_type` as JSON.
+pub fn sync_checkpoint_json(coin_type: &str) -> anyhow::Result<String> {
+    serde_json::to_string(&SyncCheckpoint::new(coin_type)).context("Failed to serialize checkpoint")
+}
+
+/// Returns the next [`SyncRequest`] of a JSON checkpoint.
+pub fn sync_next_request_json(
+    checkpoint_json: &str,
+    config_json: Option<&str>,
+) -> anyhow::Result<String> {
+    let request = parse_checkpoint(checkpoint_json)?.next_request(&parse_config(config_json)?);
+    serde_json::to_string(&request).context("Failed to serialize request")
+}
+
+/// Applies a JSON [`CommitmentPage`] to a JSON checkpoint and returns the
+/// advanced checkpoint.
+pub fn sync_apply_page_json(
+    checkpoint_json: &str,
+    page_json: &str,
+    viewing_key: &str,
+) -> anyhow::Result<String> {
+    let mut checkpoint = parse_checkpoint(checkpoint_json)?;
+    let page: CommitmentPage =
+        serde_json::from_str(page_json).context("Failed to parse commitment page JSON")?;
+    checkpoint.apply_page(&page, &ViewingKey(parse_field_element(viewing_key)?))?;
+
+    serde_json::to_string(&checkpoint).context("Failed to serialize checkpoint")
+}
+
+/// Records a failed fetch on a JSON checkpoint and returns it with the retry
+/// delay as a [`SyncFailureJson`].
+pub fn sync_record_failure_json(
+    checkpoint_json: &str,
+    config_json: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut checkpoint = parse_checkpoint(checkpoint_json)?;
+    let retry_in_ms = checkpoint.record_failure(&parse_config(config_json)?);
+
+    serde_json::to_string(&SyncFailureJson {
+        checkpoint,
+        retry_in_ms,
+    })
+    .context("Failed to serialize sync failure")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::{encrypt_note, Note, EMPTY_OUTPUT_MARKER};
+    use ark_bn254::Fr;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn page(
+        viewing_key: &ViewingKey,
+        indices: std::ops::Range<u64>,
+        has_next: bool,
+    ) -> CommitmentPage {
+        let mut rng = ChaCha20Rng::from_seed([5u8; 32]);
+        let other = ViewingKey(Fr::from(99u64));
+        let items = indices
+            .map(|index| {
+                let encrypted_output = match index % 3 {
+                    0 => encrypt_note(
+                        &Note {
+                            amount: Fr::from(index),
+                            blinding: Fr::from(index + 100),
+                        },
+                        &viewing_key.encryption_key(),
+                        &mut rng,
+                    )
+                    .unwrap(),
+                    1 => EMPTY_OUTPUT_MARKER.to_vec(),
+                    _ => encrypt_note(
+                        &Note {
+                            amount: Fr::from(1u64),
+                            blinding: Fr::from(1u64),
+                        },
+                        &other.encryption_key(),
+                        &mut rng,
+                    )
+                    .unwrap(),
+                };
+                PageCommitment {
+                    index,
+                    commitment: index.to_string(),
+                    encrypted_output,
+                }
+            })
+            .collect();
+        CommitmentPage { items, has_next }
+    }
+
+    #[test]
+    fn test_sync_resumes_from_checkpoint() {
+        let viewing_key = ViewingKey(Fr::from(7u64));
+        let config = SyncConfig {
+            chunk_size: 4,
+            ..SyncConfig::default()
+        };
+        let mut checkpoint = SyncCheckpoint::new("0x2::sui::SUI");
+
+        let request = checkpoint.next_request(&config);
+        assert_eq!((request.index, request.limit), (0, 4));
+        checkpoint
+            .apply_page(&page(&viewing_key, 0..4, true), &viewing_key)
+            .unwrap();
+        assert_eq!(checkpoint.next_index, 4);
+
+        // Persisted and reloaded, then a retried page overlapping the first
+        let mut checkpoint: SyncCheckpoint =
+            serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+        assert_eq!(
+            checkpoint.record_failure(&config),
+            Some(DEFAULT_BASE_DELAY_MS)
+        );
+        checkpoint
+            .apply_page(&page(&viewing_key, 2..7, false), &viewing_key)
+            .unwrap();
+
+        assert!(checkpoint.done);
+        assert_eq!(checkpoint.failed_attempts, 0);
+        assert_eq!(checkpoint.next_request(&config).index, 7);
+        assert_eq!(
+            checkpoint
+                .notes
+                .iter()
+                .map(|note| (note.index, note.amount.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, "0"), (3, "3"), (6, "6")]
+        );
+
+        let mut gap = SyncCheckpoint::new("0x2::sui::SUI");
+        let err = gap
+            .apply_page(&page(&viewing_key, 1..3, false), &viewing_key)
+            .unwrap_err();
+        assert!(err.to_string().contains("skips from index 0 to 1"), "{err}");
+        assert_eq!(gap, SyncCheckpoint::new("0x2::sui::SUI"));
+    }
+
+    #[test]
+    fn test_sync_picks_up_commitments_after_catching_up() {
+        let viewing_key = ViewingKey(Fr::from(7u64));
+        let config = SyncConfig::default();
+        let mut checkpoint = SyncCheckpoint::new("0x2::sui::SUI");
+
+        checkpoint
+            .apply_page(&page(&viewing_key, 0..4, false), &viewing_key)
+            .unwrap();
+        assert!(checkpoint.done);
+
+        let request = checkpoint.next_request(&config);
+        assert_eq!(request.index, 4);
+        checkpoint
+            .apply_page(&page(&viewing_key, 4..7, false), &viewing_key)
+            .unwrap();
+
+        assert!(checkpoint.done);
+        assert_eq!(checkpoint.next_index, 7);
+        assert_eq!(
+            checkpoint
+                .notes
+                .iter()
+                .map(|note| note.index)
+                .collect::<Vec<_>>(),
+            [0, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_failures_back_off_then_give_up() {
+        let config = SyncConfig {
+            max_retries: 3,
+            base_delay_ms: 100,
+            max_delay_ms: 250,
+            ..SyncConfig::default()
+        };
+        let mut checkpoint = SyncCheckpoint::new("0x2::sui::SUI");
+
+        let delays = (0..4)
+            .map(|_| checkpoint.record_failure(&config))
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [Some(100), Some(200), Some(250), None]);
+        assert_eq!(config.backoff_ms(u32::MAX), 250);
+
+        let failure: SyncFailureJson = serde_json::from_str(
+            &sync_record_failure_json(&sync_checkpoint_json("0x2::sui::SUI").unwrap(), None)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(failure.retry_in_ms, Some(DEFAULT_BASE_DELAY_MS));
+        assert_eq!(failure.checkpoint.failed_attempts, 1);
+    }
+}
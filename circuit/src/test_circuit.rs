@@ -0,0 +1,135 @@
+use crate::backend::{Groth16Backend, ProvingBackend};
+use crate::builder::{InputUtxo, OutputUtxo, TransactionBuilder};
+use crate::circuit::{note_commitment, CircuitVersion, TransactionCircuit};
+use crate::constants::{N_INS, N_OUTS, ZERO_VALUE};
+use crate::merkle_tree::SparseMerkleTree;
+use crate::poseidon_opt::{fr_from_str, hash1, hasher_t3};
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Proof, ProvingKey, VerifyingKey};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::sync::OnceLock;
+
+/// Tree height of the test circuit (16 leaves).
+pub const TEST_TREE_LEVEL: usize = 4;
+
+/// Seed of the embedded keys and of [`prove`], so every run produces the same
+/// keys and proofs.
+pub const TEST_SEED: [u8; 32] = [42u8; 32];
+
+/// 2-input, 2-output circuit over a height-4 tree. Proves in milliseconds and
+/// exercises every constraint of the deployed shape except the tree depth.
+pub type TestCircuit = TransactionCircuit<N_INS, N_OUTS, TEST_TREE_LEVEL>;
+
+const PROVING_KEY_BYTES: &[u8] = include_bytes!("../keys/test/proving_key.bin");
+const VERIFYING_KEY_BYTES: &[u8] = include_bytes!("../keys/test/verification_key.bin");
+
+/// Regenerates the keys of [`TestCircuit::empty`] from [`TEST_SEED`]; what
+/// `keygen --test-circuit` writes to `keys/test`.
+pub fn setup() -> anyhow::Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>)> {
+    Groth16Backend::setup(TestCircuit::empty(), &mut ChaCha20Rng::from_seed(TEST_SEED))
+}
+
+/// Proving key embedded in the crate.
+pub fn proving_key() -> &'static ProvingKey<Bn254> {
+    static KEY: OnceLock<ProvingKey<Bn254>> = OnceLock::new();
+    KEY.get_or_init(|| {
+        Groth16Backend::deserialize(PROVING_KEY_BYTES).expect("embedded test proving key is valid")
+    })
+}
+
+/// Verifying key embedded in the crate.
+pub fn verifying_key() -> &'static VerifyingKey<Bn254> {
+    static KEY: OnceLock<VerifyingKey<Bn254>> = OnceLock::new();
+    KEY.get_or_init(|| {
+        Groth16Backend::deserialize(VERIFYING_KEY_BYTES)
+            .expect("embedded test verifying key is valid")
+    })
+}
+
+/// Spend of a 100-unit note, the second leaf of a one-pair tree, into notes
+/// of 60 and 40. Every value is fixed, so the public inputs never change.
+pub fn sample_transaction() -> anyhow::Result<TestCircuit> {
+    let hasher = hasher_t3();
+    let vortex = Fr::from(7u64);
+    let private_key = Fr::from(12345u64);
+    let blinding = Fr::from(1u64);
+    let amount = Fr::from(100u64);
+
+    let leaf = note_commitment(
+        CircuitVersion::V1,
+        vortex,
+        None,
+        amount,
+        hash1(&private_key),
+        blinding,
+    );
+    let mut tree = SparseMerkleTree::<TEST_TREE_LEVEL>::new_empty(hasher, &fr_from_str(ZERO_VALUE));
+    tree.insert_pair(Fr::from(5u64), leaf, hasher)?;
+
+    TransactionBuilder::<N_INS, N_OUTS, TEST_TREE_LEVEL>::new(vortex, tree.root())
+        .padding_seed(Fr::from(99u64))
+        .input(InputUtxo {
+            amount,
+            blinding,
+            private_key,
+            path_index: 1,
+            path: tree.generate_membership_proof(1)?,
+        })
+        .output(OutputUtxo {
+            amount: Fr::from(60u64),
+            public_key: Fr::from(42u64),
+            blinding: Fr::from(2u64),
+        })
+        .output(OutputUtxo {
+            amount: Fr::from(40u64),
+            public_key: hash1(&private_key),
+            blinding: Fr::from(3u64),
+        })
+        .build()
+}
+
+/// Proves `circuit` with the embedded proving key and a [`TEST_SEED`] rng.
+pub fn prove(circuit: TestCircuit) -> anyhow::Result<Proof<Bn254>> {
+    Groth16Backend::prove(
+        proving_key(),
+        circuit,
+        &mut ChaCha20Rng::from_seed(TEST_SEED),
+    )
+}
+
+/// Verifies `proof` with the embedded verifying key.
+pub fn verify(public_inputs: &[Fr], proof: &Proof<Bn254>) -> anyhow::Result<bool> {
+    Groth16Backend::verify(verifying_key(), public_inputs, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_keys_match_setup() {
+        let (pk, vk) = setup().unwrap();
+        assert_eq!(
+            Groth16Backend::serialize(&pk).unwrap(),
+            PROVING_KEY_BYTES,
+            "keys/test is stale, regenerate it with keygen --test-circuit"
+        );
+        assert_eq!(Groth16Backend::serialize(&vk).unwrap(), VERIFYING_KEY_BYTES);
+    }
+
+    #[test]
+    fn test_sample_transaction_proves_deterministically() {
+        let circuit = sample_transaction().unwrap();
+        let public_inputs = circuit.get_public_inputs();
+        assert_eq!(circuit.descriptor().level, TEST_TREE_LEVEL);
+
+        let proof = prove(circuit.clone()).unwrap();
+        assert!(verify(&public_inputs, &proof).unwrap());
+        assert_eq!(prove(circuit).unwrap(), proof);
+
+        let mut tampered = public_inputs.clone();
+        tampered[2] += Fr::from(1u64);
+        assert!(!verify(&tampered, &proof).unwrap());
+    }
+}
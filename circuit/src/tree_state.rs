@@ -0,0 +1,645 @@
+#[cfg(feature = "fetch")]
+use crate::audit::SuiRpc;
+use crate::constants::{MERKLE_TREE_LEVEL, ZERO_VALUE};
+use crate::field::FieldEncoding;
+use crate::merkle_tree::{verify_inclusion, Path, SparseMerkleTree};
+use crate::poseidon_opt::{fr_from_str, hasher_t3};
+use crate::prover::parse_field_element;
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fetch")]
+use serde_json::{json, Value};
+
+#[cfg(feature = "fetch")]
+const EVENTS_MODULE: &str = "events";
+#[cfg(feature = "fetch")]
+const NEW_COMMITMENT_EVENT: &str = "NewCommitment";
+
+/// A commitment and its leaf index in the pool tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedCommitment {
+    pub index: u64,
+    pub commitment: Fr,
+}
+
+/// [`IndexedCommitment`] with the commitment as a string, as the API, the
+/// JSON bindings and offline bundles carry it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitmentEntry {
+    pub index: u64,
+    pub commitment: String,
+}
+
+impl TryFrom<&CommitmentEntry> for IndexedCommitment {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: &CommitmentEntry) -> anyhow::Result<Self> {
+        Ok(Self {
+            index: entry.index,
+            commitment: parse_field_element(&entry.commitment)
+                .with_context(|| format!("Invalid commitment at index {}", entry.index))?,
+        })
+    }
+}
+
+impl From<IndexedCommitment> for CommitmentEntry {
+    fn from(commitment: IndexedCommitment) -> Self {
+        Self {
+            index: commitment.index,
+            commitment: commitment.commitment.into_bigint().to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+struct ApiResponse {
+    data: ApiCommitmentPage,
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiCommitmentPage {
+    items: Vec<CommitmentEntry>,
+    has_next: bool,
+}
+
+#[cfg(feature = "fetch")]
+/// Reads every commitment of `coin_type` from the Vortex API at `api_url`,
+/// following `GET /api/v1/commitments` page by page.
+pub fn fetch_commitments_from_api(
+    api_url: &str,
+    coin_type: &str,
+    page_size: usize,
+) -> anyhow::Result<Vec<IndexedCommitment>> {
+    let url = format!("{}/api/v1/commitments", api_url.trim_end_matches('/'));
+    let mut commitments = Vec::new();
+    let mut next_index = 0u64;
+
+    loop {
+        let body = ureq::get(&url)
+            .query("coin_type", coin_type)
+            .query("index", next_index.to_string())
+            .query("op", "gte")
+            .query("limit", page_size.to_string())
+            .call()
+            .with_context(|| format!("Failed to fetch commitments from {url}"))?
+            .into_body()
+            .read_to_string()
+            .context("Failed to read commitments response")?;
+        let page: ApiResponse =
+            serde_json::from_str(&body).context("Invalid commitments response")?;
+
+        for item in &page.data.items {
+            commitments.push(item.try_into()?);
+        }
+
+        match page.data.items.last() {
+            Some(last) if page.data.has_next => next_index = last.index + 1,
+            _ => break,
+        }
+    }
+
+    Ok(commitments)
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+struct ApiRootsResponse {
+    data: ApiRootsFeed,
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiRootsFeed {
+    items: Vec<ApiPoolRoot>,
+    latest_seq: u64,
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Deserialize)]
+struct ApiPoolRoot {
+    seq: u64,
+    root: String,
+}
+
+#[cfg(feature = "fetch")]
+/// Pool root read from `GET /api/v1/pools/{coin_type}/roots`.
+pub struct ApiRoot {
+    /// Leaves in the pool as of the API's latest root
+    pub latest_leaf_count: u64,
+    /// Root after the requested number of leaves, `None` if the API has not
+    /// recorded it
+    pub root: Option<Fr>,
+}
+
+#[cfg(feature = "fetch")]
+/// Reads the root the pool of `coin_type` had after `leaf_count` leaves, an
+/// even number since commitments are appended in pairs, from the Vortex API.
+pub fn fetch_root_from_api(
+    api_url: &str,
+    coin_type: &str,
+    leaf_count: u64,
+) -> anyhow::Result<ApiRoot> {
+    let url = format!(
+        "{}/api/v1/pools/{}/roots",
+        api_url.trim_end_matches('/'),
+        encode_path_segment(coin_type)
+    );
+    let seq = leaf_count / 2;
+    let body = ureq::get(&url)
+        .query("since_seq", seq.saturating_sub(1).to_string())
+        .query("limit", "1")
+        .call()
+        .with_context(|| format!("Failed to fetch roots from {url}"))?
+        .into_body()
+        .read_to_string()
+        .context("Failed to read roots response")?;
+    let feed = serde_json::from_str::<ApiRootsResponse>(&body)
+        .context("Invalid roots response")?
+        .data;
+
+    Ok(ApiRoot {
+        latest_leaf_count: 2 * feed.latest_seq,
+        root: feed
+            .items
+            .iter()
+            .find(|item| item.seq == seq && seq > 0)
+            .map(|item| parse_field_element(&item.root))
+            .transpose()?,
+    })
+}
+
+#[cfg(feature = "fetch")]
+fn encode_path_segment(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(feature = "fetch")]
+/// Reads every `NewCommitment<coin_type>` event emitted by `package` from a
+/// Sui fullnode, for when no indexer is available.
+pub fn fetch_commitments_from_rpc(
+    rpc: &SuiRpc,
+    package: &str,
+    coin_type: &str,
+    page_size: usize,
+) -> anyhow::Result<Vec<IndexedCommitment>> {
+    let event_type = format!("{package}::{EVENTS_MODULE}::{NEW_COMMITMENT_EVENT}<{coin_type}>");
+    let mut commitments = Vec::new();
+    let mut cursor = Value::Null;
+
+    loop {
+        let page = rpc.call(
+            "suix_queryEvents",
+            json!([{ "MoveEventType": event_type }, cursor, page_size, false]),
+        )?;
+
+        for event in page["data"].as_array().into_iter().flatten() {
+            commitments.push(parse_commitment_event(&event["parsedJson"])?);
+        }
+
+        if page["hasNextPage"].as_bool() != Some(true) {
+            break;
+        }
+        cursor = page["nextCursor"].clone();
+    }
+
+    Ok(commitments)
+}
+
+#[cfg(feature = "fetch")]
+fn parse_commitment_event(event: &Value) -> anyhow::Result<IndexedCommitment> {
+    let index = event["index"]
+        .as_str()
+        .ok_or_else(|| anyhow!("NewCommitment event has no index: {event}"))?
+        .parse()
+        .context("Invalid NewCommitment index")?;
+    let commitment = event["commitment"]
+        .as_str()
+        .ok_or_else(|| anyhow!("NewCommitment event has no commitment: {event}"))?;
+
+    Ok(IndexedCommitment {
+        index,
+        commitment: parse_field_element(commitment)?,
+    })
+}
+
+/// Pool tree rebuilt from its commitments, with the layout the contract uses.
+#[derive(Debug, Clone)]
+pub struct TreeState {
+    tree: SparseMerkleTree<MERKLE_TREE_LEVEL>,
+}
+
+/// Root of the rebuilt tree and the path of one leaf, in the string encoding
+/// used by [`ProofInput`](crate::prover::ProofInput).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeStatePath {
+    pub root: String,
+    pub size: usize,
+    pub index: usize,
+    pub commitment: String,
+    pub path: Vec<[String; 2]>,
+}
+
+impl Default for TreeState {
+    fn default() -> Self {
+        Self {
+            tree: SparseMerkleTree::new_empty(hasher_t3(), &fr_from_str(ZERO_VALUE)),
+        }
+    }
+}
+
+impl TreeState {
+    /// Rebuilds the tree from commitments given in any order.
+    ///
+    /// # Errors
+    /// Returns error if an index is missing or two commitments claim the same
+    /// index, since the resulting root would not match the contract's.
+    pub fn new(mut commitments: Vec<IndexedCommitment>) -> anyhow::Result<Self> {
+        commitments.sort_by_key(|c| c.index);
+        commitments.dedup();
+
+        for (expected, entry) in (0u64..).zip(&commitments) {
+            if entry.index < expected {
+                bail!(
+                    "Commitment index {} is claimed by two commitments",
+                    entry.index
+                );
+            }
+            if entry.index > expected {
+                bail!("Commitment index {} is missing", expected);
+            }
+        }
+
+        let hasher = hasher_t3();
+        let empty_leaf = fr_from_str(ZERO_VALUE);
+        let mut tree = SparseMerkleTree::new_empty(hasher, &empty_leaf);
+
+        let pairs = commitments
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).map_or(empty_leaf, |leaf| leaf.commitment);
+                (pair[0].commitment, right)
+            })
+            .collect::<Vec<_>>();
+        tree.insert_batch_fast(&pairs, hasher)?;
+
+        Ok(Self { tree })
+    }
+
+    /// Loads a tree shipped as a [`SparseMerkleTree::serialize`] snapshot
+    /// instead of every commitment.
+    ///
+    /// # Errors
+    /// Returns error if the snapshot is malformed or of another height.
+    pub fn from_snapshot(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            tree: SparseMerkleTree::deserialize(bytes, hasher_t3())?,
+        })
+    }
+
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.tree.serialize()
+    }
+
+    pub fn root(&self) -> Fr {
+        self.tree.root()
+    }
+
+    /// Returns the root the pool had when it held `leaf_count` commitments.
+    pub fn root_at(&self, leaf_count: usize) -> Option<Fr> {
+        self.tree.root_at(leaf_count, hasher_t3())
+    }
+
+    /// Returns true if the contract still accepts proofs against `root`, so
+    /// a proof built on a slightly stale tree can be checked before it is
+    /// submitted.
+    pub fn is_known_root(&self, root: &Fr) -> bool {
+        self.tree.is_known_root(root)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub(crate) fn leaves(&self) -> &[Fr] {
+        self.tree.leaves()
+    }
+
+    pub(crate) fn append_pairs(&mut self, pairs: &[(Fr, Fr)]) -> anyhow::Result<()> {
+        self.tree.insert_batch_fast(pairs, hasher_t3())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the index of `commitment`, if it is in the tree.
+    pub fn position(&self, commitment: &Fr) -> Option<usize> {
+        self.tree
+            .leaves()
+            .iter()
+            .position(|leaf| leaf == commitment)
+    }
+
+    /// Returns the membership path of the leaf at `index`, for spending it.
+    pub fn merkle_path(&self, index: usize) -> anyhow::Result<Path<MERKLE_TREE_LEVEL>> {
+        self.tree.generate_membership_proof(index)
+    }
+
+    /// Returns the root and the membership path of the leaf at `index`.
+    pub fn path(&self, index: usize) -> anyhow::Result<TreeStatePath> {
+        self.path_encoded(index, FieldEncoding::Decimal)
+    }
+
+    /// [`Self::path`] with its field elements in `encoding`.
+    pub fn path_encoded(
+        &self,
+        index: usize,
+        encoding: FieldEncoding,
+    ) -> anyhow::Result<TreeStatePath> {
+        let path = self.merkle_path(index)?;
+
+        Ok(TreeStatePath {
+            root: encoding.encode(&self.root()),
+            size: self.len(),
+            index,
+            commitment: encoding.encode(&self.tree.leaves()[index]),
+            path: path
+                .path
+                .iter()
+                .map(|(left, right)| [encoding.encode(left), encoding.encode(right)])
+                .collect(),
+        })
+    }
+}
+
+/// Rebuilds the tree from a JSON array of `{ index, commitment }` and returns
+/// the [`TreeStatePath`] of `commitment` as JSON.
+pub fn tree_state_path_json(commitments_json: &str, commitment: &str) -> anyhow::Result<String> {
+    let entries: Vec<CommitmentEntry> =
+        serde_json::from_str(commitments_json).context("Failed to parse commitments JSON")?;
+    let tree = TreeState::new(
+        entries
+            .iter()
+            .map(IndexedCommitment::try_from)
+            .collect::<anyhow::Result<_>>()?,
+    )?;
+
+    let index = tree
+        .position(&parse_field_element(commitment)?)
+        .ok_or_else(|| anyhow!("Commitment {commitment} is not in the tree"))?;
+    serde_json::to_string(&tree.path(index)?).context("Failed to serialize path")
+}
+
+/// Membership path of [`verify_inclusion_json`]: either the sibling of each
+/// level, or the `(left, right)` pairs of a [`TreeStatePath`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InclusionPath {
+    Siblings(Vec<String>),
+    Pairs(Vec<[String; 2]>),
+}
+
+/// Checks the claim "`commitment` is leaf `index` under `root`" for the pool
+/// tree. With pairs, only the side of each level that `index` selects as the
+/// sibling is used; the other side is recomputed from `commitment`.
+pub fn verify_inclusion_json(
+    root: &str,
+    index: u64,
+    commitment: &str,
+    path_json: &str,
+) -> anyhow::Result<bool> {
+    let path: InclusionPath = serde_json::from_str(path_json)
+        .context("Path must be a JSON array of sibling strings or of [left, right] pairs")?;
+    let siblings = match &path {
+        InclusionPath::Siblings(siblings) => siblings.iter().collect::<Vec<_>>(),
+        InclusionPath::Pairs(pairs) => (0..)
+            .zip(pairs)
+            .map(|(level, [left, right])| {
+                if level < u64::BITS && (index >> level) & 1 == 1 {
+                    left
+                } else {
+                    right
+                }
+            })
+            .collect(),
+    };
+    let siblings: [Fr; MERKLE_TREE_LEVEL] = siblings
+        .into_iter()
+        .map(|sibling| parse_field_element(sibling))
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .try_into()
+        .map_err(|siblings: Vec<Fr>| {
+            anyhow!(
+                "Path must have {} levels, got {}",
+                MERKLE_TREE_LEVEL,
+                siblings.len()
+            )
+        })?;
+
+    verify_inclusion(
+        &parse_field_element(root)?,
+        index,
+        &parse_field_element(commitment)?,
+        &siblings,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon_opt::{hash2, PoseidonOptimized};
+
+    fn commitments(values: &[u64]) -> Vec<IndexedCommitment> {
+        (0u64..)
+            .zip(values)
+            .map(|(index, value)| IndexedCommitment {
+                index,
+                commitment: Fr::from(*value),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rebuild_is_order_independent() {
+        let ordered = TreeState::new(commitments(&[11, 12, 13, 14, 15])).unwrap();
+        let mut shuffled = commitments(&[11, 12, 13, 14, 15]);
+        shuffled.reverse();
+        shuffled.push(shuffled[0]);
+        let shuffled = TreeState::new(shuffled).unwrap();
+
+        assert_eq!(ordered.root(), shuffled.root());
+        assert_eq!(ordered.len(), 6);
+        assert_eq!(ordered.position(&Fr::from(14u64)), Some(3));
+
+        let hasher = PoseidonOptimized::new_t3();
+        let mut expected =
+            SparseMerkleTree::<MERKLE_TREE_LEVEL>::new_empty(&hasher, &fr_from_str(ZERO_VALUE));
+        expected
+            .bulk_insert(&[11, 12, 13, 14].map(Fr::from), &hasher)
+            .unwrap();
+        expected.insert(Fr::from(15u64), &hasher).unwrap();
+        assert_eq!(ordered.root(), expected.root());
+
+        let path = ordered.path(3).unwrap();
+        assert_eq!(path.commitment, "14");
+        assert_eq!(path.path.len(), MERKLE_TREE_LEVEL);
+        assert_eq!(path.root, ordered.root().into_bigint().to_string());
+    }
+
+    /// `vortex_merkle_tree::append_pair`, transcribed from the Move contract.
+    struct MoveTree {
+        next_index: u64,
+        subtrees: Vec<Fr>,
+        zeros: Vec<Fr>,
+        root: Fr,
+    }
+
+    impl MoveTree {
+        fn new() -> Self {
+            let mut zeros = vec![fr_from_str(ZERO_VALUE)];
+            for i in 0..MERKLE_TREE_LEVEL {
+                zeros.push(hash2(&zeros[i], &zeros[i]));
+            }
+            Self {
+                next_index: 0,
+                subtrees: zeros[..MERKLE_TREE_LEVEL].to_vec(),
+                root: zeros[MERKLE_TREE_LEVEL],
+                zeros,
+            }
+        }
+
+        fn append_pair(&mut self, commitment0: Fr, commitment1: Fr) {
+            let mut current_index = self.next_index / 2;
+            let mut current_level_hash = hash2(&commitment0, &commitment1);
+            for i in 1..MERKLE_TREE_LEVEL {
+                let (left, right) = if current_index.is_multiple_of(2) {
+                    self.subtrees[i] = current_level_hash;
+                    (current_level_hash, self.zeros[i])
+                } else {
+                    (self.subtrees[i], current_level_hash)
+                };
+                current_level_hash = hash2(&left, &right);
+                current_index /= 2;
+            }
+            self.root = current_level_hash;
+            self.next_index += 2;
+        }
+    }
+
+    #[test]
+    fn test_rebuild_matches_move_append_pair() {
+        let values = (1..=10).collect::<Vec<u64>>();
+        let mut move_tree = MoveTree::new();
+        assert_eq!(TreeState::new(Vec::new()).unwrap().root(), move_tree.root);
+
+        for pair in values.chunks(2) {
+            move_tree.append_pair(Fr::from(pair[0]), Fr::from(pair[1]));
+        }
+        let tree = TreeState::new(commitments(&values)).unwrap();
+        assert_eq!(tree.root(), move_tree.root);
+
+        let path = tree.merkle_path(7).unwrap();
+        assert_eq!(
+            path.calculate_root(&Fr::from(8u64), &PoseidonOptimized::new_t3())
+                .unwrap(),
+            move_tree.root
+        );
+    }
+
+    #[test]
+    fn test_tree_state_path_json() {
+        let json = r#"[{"index":1,"commitment":"12"},{"index":0,"commitment":"0x0b"}]"#;
+        let path: TreeStatePath =
+            serde_json::from_str(&tree_state_path_json(json, "12").unwrap()).unwrap();
+        assert_eq!(path.index, 1);
+        assert_eq!(
+            path.root,
+            TreeState::new(commitments(&[11, 12]))
+                .unwrap()
+                .root()
+                .into_bigint()
+                .to_string()
+        );
+
+        let err = tree_state_path_json(json, "13").unwrap_err();
+        assert!(err.to_string().contains("not in the tree"), "{err}");
+        assert!(tree_state_path_json(r#"[{"index":1,"commitment":"12"}]"#, "12").is_err());
+    }
+
+    #[test]
+    fn test_verify_inclusion() {
+        let tree = TreeState::new(commitments(&[11, 12, 13, 14, 15])).unwrap();
+        let root = tree.root().into_bigint().to_string();
+        let path = tree.path(2).unwrap();
+        let pairs = serde_json::to_string(&path.path).unwrap();
+        let siblings = serde_json::to_string(
+            &path
+                .path
+                .iter()
+                .enumerate()
+                .map(|(level, [left, right])| {
+                    if (2u64 >> level) & 1 == 1 {
+                        left
+                    } else {
+                        right
+                    }
+                })
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        assert!(verify_inclusion_json(&root, 2, "13", &pairs).unwrap());
+        assert!(verify_inclusion_json(&root, 2, "13", &siblings).unwrap());
+        assert!(!verify_inclusion_json(&root, 3, "13", &siblings).unwrap());
+        assert!(!verify_inclusion_json(&root, 2, "14", &pairs).unwrap());
+        assert!(!verify_inclusion_json("1", 2, "13", &pairs).unwrap());
+
+        let err = verify_inclusion_json(&root, 2, "13", r#"["1","2"]"#).unwrap_err();
+        assert!(err.to_string().contains("26 levels, got 2"), "{err}");
+        assert!(verify_inclusion_json(&root, 1 << MERKLE_TREE_LEVEL, "13", &siblings).is_err());
+    }
+
+    #[test]
+    fn test_rebuild_rejects_gaps_and_conflicts() {
+        let mut gap = commitments(&[1, 2, 3]);
+        gap.remove(1);
+        let err = TreeState::new(gap).unwrap_err();
+        assert!(err.to_string().contains("index 1 is missing"), "{err}");
+
+        let mut conflict = commitments(&[1, 2]);
+        conflict.push(IndexedCommitment {
+            index: 1,
+            commitment: Fr::from(9u64),
+        });
+        let err = TreeState::new(conflict).unwrap_err();
+        assert!(err.to_string().contains("claimed by two"), "{err}");
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_parse_commitment_event() {
+        let event = json!({ "index": "7", "commitment": "12345", "encrypted_output": [] });
+        assert_eq!(
+            parse_commitment_event(&event).unwrap(),
+            IndexedCommitment {
+                index: 7,
+                commitment: Fr::from(12345u64)
+            }
+        );
+        assert!(parse_commitment_event(&json!({ "index": 7 })).is_err());
+    }
+}
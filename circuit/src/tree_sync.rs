@@ -0,0 +1,477 @@
+//! Follows the indexer's `NewCommitment` documents into a pool tree, so a
+//! prover can keep its tree current without rebuilding it per proof.
+
+use crate::field::{field_from_be_bytes, FIELD_BYTES};
+use crate::tree_state::{IndexedCommitment, TreeState};
+use anyhow::{anyhow, bail, Context};
+use ark_bn254::Fr;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// `NewCommitment` document as the indexer stores it in MongoDB. Other fields
+/// of the document are ignored.
+#[derive(Deserialize)]
+struct CommitmentDocument {
+    coin_type: String,
+    index: DocumentIndex,
+    commitment: String,
+}
+
+/// `mongoexport` writes 64-bit integers as plain numbers in relaxed mode and
+/// as `{ "$numberLong": "..." }` in canonical mode.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DocumentIndex {
+    Number(u64),
+    Long {
+        #[serde(rename = "$numberLong")]
+        value: String,
+    },
+}
+
+impl DocumentIndex {
+    fn value(&self) -> anyhow::Result<u64> {
+        match self {
+            Self::Number(index) => Ok(*index),
+            Self::Long { value } => value
+                .parse()
+                .with_context(|| format!("Invalid $numberLong index '{value}'")),
+        }
+    }
+}
+
+/// Decodes a commitment as the indexer stores it: `0x` followed by the hex of
+/// its little-endian bytes, the byte order of the on-chain `u256`.
+fn parse_document_commitment(s: &str) -> anyhow::Result<Fr> {
+    let bytes = s
+        .strip_prefix("0x")
+        .and_then(|hex| hex::decode(hex).ok())
+        .filter(|bytes| bytes.len() == FIELD_BYTES)
+        .ok_or_else(|| anyhow!("Expected 0x and {FIELD_BYTES} hex bytes, got '{s}'"))?;
+
+    let mut be_bytes = bytes;
+    be_bytes.reverse();
+    Ok(field_from_be_bytes(&be_bytes)?)
+}
+
+/// Reads the commitments of `coin_type` from an export of the indexer's
+/// `NewCommitment` collection, either a JSON array or one document per line as
+/// `mongoexport` writes by default.
+///
+/// `coin_type` is compared as stored by the indexer, in its normalized form.
+///
+/// # Errors
+/// Returns error if a document of the export is malformed.
+pub fn commitments_from_export(
+    export: &str,
+    coin_type: &str,
+) -> anyhow::Result<Vec<IndexedCommitment>> {
+    let documents: Vec<CommitmentDocument> = if export.trim_start().starts_with('[') {
+        serde_json::from_str(export).context("Invalid NewCommitment export")?
+    } else {
+        export
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Invalid NewCommitment document on line {}", number + 1)
+                })
+            })
+            .collect::<anyhow::Result<_>>()?
+    };
+
+    documents
+        .iter()
+        .filter(|document| document.coin_type == coin_type)
+        .map(|document| {
+            let index = document.index.value()?;
+            Ok(IndexedCommitment {
+                index,
+                commitment: parse_document_commitment(&document.commitment)
+                    .with_context(|| format!("Invalid commitment at index {index}"))?,
+            })
+        })
+        .collect()
+}
+
+/// Outcome of comparing a synced tree with the pool on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootCheck {
+    /// Same number of leaves and the same root
+    Matches,
+    /// The chain has leaves not synced yet
+    Behind { missing: u64 },
+    /// The synced tree has leaves the chain's root was read before
+    Ahead { extra: u64 },
+}
+
+/// Pool tree kept current from `NewCommitment` events arriving in batches.
+///
+/// The contract appends commitments in pairs, so a pair is applied once both
+/// of its commitments have been seen and every pair before it is applied.
+/// Commitments past a gap are held until the gap is filled.
+#[derive(Debug, Clone, Default)]
+pub struct TreeSyncer {
+    tree: TreeState,
+    pending: BTreeMap<u64, Fr>,
+}
+
+impl TreeSyncer {
+    /// Resumes syncing on a tree loaded from a snapshot or rebuilt earlier.
+    pub fn from_tree(tree: TreeState) -> Self {
+        Self {
+            tree,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub fn tree(&self) -> &TreeState {
+        &self.tree
+    }
+
+    /// Index of the first commitment not applied to the tree yet.
+    pub fn next_index(&self) -> u64 {
+        self.tree.len() as u64
+    }
+
+    /// Applies `commitments`, given in any order and possibly overlapping
+    /// earlier batches, and returns the number of leaves appended.
+    ///
+    /// # Errors
+    /// Returns error, leaving the syncer unchanged, if a commitment contradicts
+    /// one already seen at its index, since the tree could no longer match the
+    /// contract's.
+    pub fn apply(
+        &mut self,
+        commitments: impl IntoIterator<Item = IndexedCommitment>,
+    ) -> anyhow::Result<usize> {
+        let next_index = self.next_index();
+        let mut staged = BTreeMap::new();
+
+        for IndexedCommitment { index, commitment } in commitments {
+            let seen = if index < next_index {
+                Some(self.tree.leaves()[index as usize])
+            } else {
+                staged
+                    .get(&index)
+                    .or_else(|| self.pending.get(&index))
+                    .copied()
+            };
+
+            match seen {
+                Some(seen) if seen != commitment => {
+                    bail!("Commitment {commitment} at index {index} conflicts with {seen}")
+                }
+                Some(_) => {}
+                None => {
+                    staged.insert(index, commitment);
+                }
+            }
+        }
+
+        let mut pending = self.pending.clone();
+        pending.append(&mut staged);
+
+        let mut pairs = Vec::new();
+        let mut index = next_index;
+        while let (Some(&left), Some(&right)) = (pending.get(&index), pending.get(&(index + 1))) {
+            pairs.push((left, right));
+            index += 2;
+        }
+
+        self.tree.append_pairs(&pairs)?;
+        self.pending = pending.split_off(&index);
+        Ok(2 * pairs.len())
+    }
+
+    /// Index ranges holding back the commitments received past them.
+    pub fn missing(&self) -> Vec<Range<u64>> {
+        let mut gaps = Vec::new();
+        let mut expected = self.next_index();
+
+        for &index in self.pending.keys() {
+            if index > expected {
+                gaps.push(expected..index);
+            }
+            expected = index + 1;
+        }
+
+        // The partner of a held commitment completing the last pair
+        if expected % 2 == 1 {
+            gaps.push(expected..expected + 1);
+        }
+        gaps
+    }
+
+    /// Compares the tree with the on-chain pool, read from its `root` and
+    /// `next_index`. Roots are only comparable when both hold the same number
+    /// of leaves.
+    ///
+    /// # Errors
+    /// Returns error if the tree holds as many leaves as the pool but another
+    /// root.
+    pub fn check_root(&self, root: &Fr, next_index: u64) -> anyhow::Result<RootCheck> {
+        let synced = self.next_index();
+
+        if synced < next_index {
+            return Ok(RootCheck::Behind {
+                missing: next_index - synced,
+            });
+        }
+        if synced > next_index {
+            return Ok(RootCheck::Ahead {
+                extra: synced - next_index,
+            });
+        }
+        if self.tree.root() != *root {
+            bail!(
+                "Synced tree of {synced} leaves has root {}, the pool has {root}",
+                self.tree.root()
+            );
+        }
+        Ok(RootCheck::Matches)
+    }
+}
+
+/// Outcome of comparing a local tree with another source's roots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeDiff {
+    /// Both have the same root at `leaf_count`, the leaves they both hold
+    Matches { leaf_count: u64 },
+    /// The roots agree after `agree_until` leaves and differ after
+    /// `differ_at`, so the first divergent leaf lies in between. The range is
+    /// one pair wide unless the other source lacks the roots to narrow it.
+    Diverges {
+        agree_until: u64,
+        differ_at: u64,
+        local: Fr,
+        remote: Fr,
+    },
+}
+
+/// Compares `tree` with another source holding `remote_leaf_count` leaves,
+/// whose root after a given even number of leaves `remote_root_at` returns,
+/// or `None` if it does not know it.
+///
+/// The roots are first compared at the leaves both hold. On a mismatch the
+/// pair range is bisected, so the first divergent pair is found with a
+/// logarithmic number of `remote_root_at` calls.
+///
+/// # Errors
+/// Returns error if `remote_root_at` fails or does not know the root at the
+/// leaves both hold.
+pub fn diff_trees(
+    tree: &TreeState,
+    remote_leaf_count: u64,
+    mut remote_root_at: impl FnMut(u64) -> anyhow::Result<Option<Fr>>,
+) -> anyhow::Result<TreeDiff> {
+    let local_at = |leaf_count: u64| {
+        tree.root_at(leaf_count as usize)
+            .ok_or_else(|| anyhow!("Local tree has no root at {leaf_count} leaves"))
+    };
+    let common_pairs = (tree.len() as u64).min(remote_leaf_count) / 2;
+    if common_pairs == 0 {
+        return Ok(TreeDiff::Matches { leaf_count: 0 });
+    }
+
+    let common = 2 * common_pairs;
+    let mut local = local_at(common)?;
+    let mut remote =
+        remote_root_at(common)?.ok_or_else(|| anyhow!("No remote root at {common} leaves"))?;
+    if local == remote {
+        return Ok(TreeDiff::Matches { leaf_count: common });
+    }
+
+    let (mut agreed, mut low, mut high) = (0, 0, common_pairs);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        let Some(remote_mid) = remote_root_at(2 * mid)? else {
+            low = mid;
+            continue;
+        };
+        let local_mid = local_at(2 * mid)?;
+        if local_mid == remote_mid {
+            (agreed, low) = (mid, mid);
+        } else {
+            (high, local, remote) = (mid, local_mid, remote_mid);
+        }
+    }
+
+    Ok(TreeDiff::Diverges {
+        agree_until: 2 * agreed,
+        differ_at: 2 * high,
+        local,
+        remote,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::field_to_be_bytes;
+
+    fn commitment(index: u64) -> IndexedCommitment {
+        IndexedCommitment {
+            index,
+            commitment: Fr::from(1000 + index),
+        }
+    }
+
+    fn commitments(indices: impl IntoIterator<Item = u64>) -> Vec<IndexedCommitment> {
+        indices.into_iter().map(commitment).collect()
+    }
+
+    #[test]
+    fn test_syncer_applies_contiguous_pairs_and_reports_gaps() {
+        let mut syncer = TreeSyncer::default();
+        let full = TreeState::new(commitments(0..8)).unwrap();
+
+        assert_eq!(syncer.apply(commitments([0, 1, 5, 4, 6])).unwrap(), 2);
+        assert_eq!(syncer.next_index(), 2);
+        assert_eq!(syncer.missing(), [2..4, 7..8]);
+
+        assert_eq!(syncer.apply(commitments([3])).unwrap(), 0);
+        assert_eq!(syncer.missing(), [2..3, 7..8]);
+
+        assert_eq!(syncer.apply(commitments([2, 3, 1])).unwrap(), 4);
+        assert_eq!(syncer.next_index(), 6);
+        assert_eq!(syncer.missing(), [Range { start: 7, end: 8 }]);
+        assert_eq!(
+            syncer.check_root(&full.root(), 8).unwrap(),
+            RootCheck::Behind { missing: 2 }
+        );
+
+        assert_eq!(syncer.apply(commitments([7])).unwrap(), 2);
+        assert!(syncer.missing().is_empty());
+        assert_eq!(syncer.tree().root(), full.root());
+        assert_eq!(
+            syncer.check_root(&full.root(), 8).unwrap(),
+            RootCheck::Matches
+        );
+        assert_eq!(
+            syncer.check_root(&Fr::from(0u64), 6).unwrap(),
+            RootCheck::Ahead { extra: 2 }
+        );
+        assert!(syncer.check_root(&Fr::from(0u64), 8).is_err());
+
+        let resumed = TreeSyncer::from_tree(TreeState::from_snapshot(&full.snapshot()).unwrap());
+        assert_eq!(resumed.next_index(), 8);
+    }
+
+    #[test]
+    fn test_syncer_rejects_conflicting_commitments() {
+        let mut syncer = TreeSyncer::default();
+        syncer.apply(commitments([0, 1, 3])).unwrap();
+
+        let conflicting = |index| IndexedCommitment {
+            index,
+            commitment: Fr::from(7u64),
+        };
+        assert!(syncer.apply([conflicting(1)]).is_err());
+        assert!(syncer.apply([commitment(2), conflicting(3)]).is_err());
+        assert!(syncer.apply([commitment(4), conflicting(4)]).is_err());
+
+        assert_eq!(syncer.next_index(), 2);
+        assert_eq!(syncer.missing(), [Range { start: 2, end: 3 }]);
+    }
+
+    #[test]
+    fn test_diff_trees_bisects_to_first_divergent_pair() {
+        let local = TreeState::new(commitments(0..20)).unwrap();
+        let mut diverged = commitments(0..20);
+        diverged[9].commitment = Fr::from(7u64);
+        let remote = TreeState::new(diverged).unwrap();
+
+        let mut lookups = 0;
+        let diff = diff_trees(&local, 20, |leaf_count| {
+            lookups += 1;
+            Ok(remote.root_at(leaf_count as usize))
+        })
+        .unwrap();
+        assert_eq!(
+            diff,
+            TreeDiff::Diverges {
+                agree_until: 8,
+                differ_at: 10,
+                local: local.root_at(10).unwrap(),
+                remote: remote.root_at(10).unwrap(),
+            }
+        );
+        assert!(lookups <= 5, "{lookups} lookups");
+
+        // Roots the remote did not record cannot narrow the range
+        let diff = diff_trees(&local, 20, |leaf_count| {
+            Ok((leaf_count >= 12).then(|| remote.root_at(leaf_count as usize).unwrap()))
+        })
+        .unwrap();
+        assert!(matches!(
+            diff,
+            TreeDiff::Diverges {
+                agree_until: 0,
+                differ_at: 12,
+                ..
+            }
+        ));
+
+        let shorter = TreeState::new(commitments(0..16)).unwrap();
+        let full = TreeState::new(commitments(0..20)).unwrap();
+        assert_eq!(
+            diff_trees(&shorter, 20, |leaf_count| Ok(
+                full.root_at(leaf_count as usize)
+            ))
+            .unwrap(),
+            TreeDiff::Matches { leaf_count: 16 }
+        );
+        assert!(diff_trees(&shorter, 20, |_| Ok(None)).is_err());
+    }
+
+    #[test]
+    fn test_commitments_from_export() {
+        let le_hex = |value: u64| {
+            let mut bytes = field_to_be_bytes(&Fr::from(value));
+            bytes.reverse();
+            format!("0x{}", hex::encode(bytes))
+        };
+        let sui = "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+        let lines = [
+            format!(
+                r#"{{"_id":"a","coin_type":"{sui}","index":{{"$numberLong":"1"}},"commitment":"{}"}}"#,
+                le_hex(11)
+            ),
+            String::new(),
+            format!(
+                r#"{{"_id":"b","coin_type":"{sui}","index":0,"commitment":"{}"}}"#,
+                le_hex(10)
+            ),
+            format!(
+                r#"{{"_id":"c","coin_type":"0x1::other::OTHER","index":0,"commitment":"{}"}}"#,
+                le_hex(12)
+            ),
+        ];
+
+        let parsed = commitments_from_export(&lines.join("\n"), sui).unwrap();
+        assert_eq!(
+            parsed,
+            [
+                IndexedCommitment {
+                    index: 1,
+                    commitment: Fr::from(11u64)
+                },
+                IndexedCommitment {
+                    index: 0,
+                    commitment: Fr::from(10u64)
+                },
+            ]
+        );
+
+        let array = format!("[{}]", lines[0]);
+        assert_eq!(commitments_from_export(&array, sui).unwrap(), parsed[..1]);
+
+        let err = commitments_from_export(&format!("{}\n{{}}", lines[0]), sui).unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+        let short = format!(r#"{{"coin_type":"{sui}","index":0,"commitment":"0x0a"}}"#);
+        assert!(commitments_from_export(&short, sui).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::anyhow;
+use ark_bn254::Bn254;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+
+pub use ark_bn254::Fr;
+
+/// Deserializes a compressed verifying key, as written by `keygen` to `verification_key.bin`.
+pub fn verifying_key_from_bytes(bytes: &[u8]) -> anyhow::Result<VerifyingKey<Bn254>> {
+    VerifyingKey::<Bn254>::deserialize_compressed(bytes)
+        .map_err(|e| anyhow!("Failed to deserialize VK: {}", e))
+}
+
+/// Checks a compressed Groth16 proof against a compressed verifying key.
+///
+/// This is all a service that only checks proofs needs; build with
+/// `default-features = false, features = ["verifier"]` to leave out the
+/// circuit, the prover and the wasm bindings.
+pub fn verify_proof(
+    proof_bytes: &[u8],
+    public_inputs: &[Fr],
+    vk_bytes: &[u8],
+) -> anyhow::Result<bool> {
+    let vk = verifying_key_from_bytes(vk_bytes)?;
+    let expected = vk.gamma_abc_g1.len().saturating_sub(1);
+    if public_inputs.len() != expected {
+        return Err(anyhow!(
+            "Proof has {} public inputs but the verifying key expects {}",
+            public_inputs.len(),
+            expected
+        ));
+    }
+    let proof = Proof::<Bn254>::deserialize_compressed(proof_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize proof: {}", e))?;
+
+    let pvk = ark_groth16::prepare_verifying_key(&vk);
+    Groth16::<Bn254>::verify_proof(&pvk, &proof, public_inputs)
+        .map_err(|e| anyhow!("Verify failed (inputs={}): {}", public_inputs.len(), e))
+}
@@ -1,80 +1,39 @@
-use crate::{circuit::TransactionCircuit, constants::MERKLE_TREE_LEVEL, merkle_tree::Path};
-use ark_bn254::{Bn254, Fr};
-use ark_crypto_primitives::snark::SNARK;
-use ark_ff::PrimeField;
-use ark_groth16::Groth16;
-use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use num_bigint::BigUint;
-use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use crate::prover::{ProveError, ProveErrorKind, VerifyError, VerifyErrorKind};
+use crate::{deposit, derivation, hashing, memory, note, prover, relay, sync, tree_state};
+use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::prelude::*;
 
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = crypto, js_name = getRandomValues, catch)]
+    fn get_random_values(buf: &mut [u8]) -> Result<(), JsValue>;
+}
+
+/// Fresh entropy from the host's `crypto.getRandomValues`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn random_seed() -> anyhow::Result<[u8; 32]> {
+    let mut seed = [0u8; 32];
+    get_random_values(&mut seed)
+        .map_err(|e| anyhow::anyhow!("crypto.getRandomValues failed: {:?}", e))?;
+    Ok(seed)
+}
+
 // Set panic hook for better error messages in browser
 #[wasm_bindgen(start)]
 pub fn main() {
     console_error_panic_hook::set_once();
 }
 
-/// Proof output structure that matches the expected format for Sui Move contracts
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ProofOutput {
-    /// Proof component A (compressed: 32 bytes)
-    pub proof_a: Vec<u8>,
-    /// Proof component B (compressed: 64 bytes)
-    pub proof_b: Vec<u8>,
-    /// Proof component C (compressed: 32 bytes)
-    pub proof_c: Vec<u8>,
-    /// All public inputs in order expected by Move contract
-    pub public_inputs: Vec<String>,
-    pub proof_serialized_hex: String,
-    pub public_inputs_serialized_hex: String,
-}
-
-/// Input structure for proof generation
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ProofInput {
-    // Public inputs
-    pub vortex: String,
-    pub root: String,
-    pub public_amount: String,
-    pub input_nullifier_0: String,
-    pub input_nullifier_1: String,
-    pub output_commitment_0: String,
-    pub output_commitment_1: String,
-    pub hashed_account_secret: String,
-
-    // Private inputs - Input UTXOs
-    pub account_secret: String,
-    pub in_private_key_0: String,
-    pub in_private_key_1: String,
-    pub in_amount_0: String,
-    pub in_amount_1: String,
-    pub in_blinding_0: String,
-    pub in_blinding_1: String,
-    pub in_path_index_0: String,
-    pub in_path_index_1: String,
-
-    // Merkle paths (array of [left, right] pairs for each level)
-    pub merkle_path_0: Vec<[String; 2]>,
-    pub merkle_path_1: Vec<[String; 2]>,
-
-    // Private inputs - Output UTXOs
-    pub out_public_key_0: String,
-    pub out_public_key_1: String,
-    pub out_amount_0: String,
-    pub out_amount_1: String,
-    pub out_blinding_0: String,
-    pub out_blinding_1: String,
-}
-
 /// Generates a zero-knowledge proof for a privacy-preserving transaction
 ///
 /// # Arguments
 /// * `input_json` - JSON string containing all circuit inputs
 /// * `proving_key_hex` - Hex-encoded proving key (generated during setup)
+/// * `descriptor_json` - Optional `descriptor.json` published with the keys;
+///   proving fails with a clear error when it does not match the input
+///
+/// The blinding factors come from `crypto.getRandomValues`.
 ///
 /// # Returns
 /// JSON string containing the proof and public inputs
@@ -89,177 +48,84 @@ pub struct ProofInput {
 /// const proof = prove(JSON.stringify(input), provingKeyHex);
 /// const { proofA, proofB, proofC, publicInputs } = JSON.parse(proof);
 /// ```
+///
+/// Throws an `InsufficientMemory` instance before decoding the key when the
+/// runtime cannot reserve the memory proving needs, and a `ProveError` with a
+/// `kind` (`BadField`, `RootMismatch`, `TreeConstruction`, `KeyDeserialize`,
+/// `ConstraintUnsatisfied` or `InvalidInput`) and `message` otherwise.
 #[wasm_bindgen]
-pub fn prove(input_json: &str, proving_key_hex: &str) -> Result<String, JsValue> {
-    // Parse input
-    let input: ProofInput = serde_json::from_str(input_json)
-        .map_err(|e| JsValue::from(&format!("Failed to parse input JSON: {}", e)))?;
-
-    // Parse proving key
-    let pk_bytes = hex::decode(proving_key_hex)
-        .map_err(|e| JsValue::from(&format!("Failed to decode proving key hex: {}", e)))?;
-
-    let pk = ark_groth16::ProvingKey::<Bn254>::deserialize_compressed(&pk_bytes[..])
-        .map_err(|e| JsValue::from(&format!("Failed to deserialize proving key: {}", e)))?;
-
-    // Convert input strings to field elements
-    let vortex = parse_field_element(&input.vortex)?;
-    let root = parse_field_element(&input.root)?;
-    let public_amount = parse_field_element(&input.public_amount)?;
-    let input_nullifier_0 = parse_field_element(&input.input_nullifier_0)?;
-    let input_nullifier_1 = parse_field_element(&input.input_nullifier_1)?;
-    let output_commitment_0 = parse_field_element(&input.output_commitment_0)?;
-    let output_commitment_1 = parse_field_element(&input.output_commitment_1)?;
-    let hashed_account_secret = parse_field_element(&input.hashed_account_secret)?;
-
-    let account_secret = parse_field_element(&input.account_secret)?;
-
-    let in_private_keys = [
-        parse_field_element(&input.in_private_key_0)?,
-        parse_field_element(&input.in_private_key_1)?,
-    ];
-
-    let in_amounts = [
-        parse_field_element(&input.in_amount_0)?,
-        parse_field_element(&input.in_amount_1)?,
-    ];
-
-    let in_blindings = [
-        parse_field_element(&input.in_blinding_0)?,
-        parse_field_element(&input.in_blinding_1)?,
-    ];
-
-    let in_path_indices = [
-        parse_field_element(&input.in_path_index_0)?,
-        parse_field_element(&input.in_path_index_1)?,
-    ];
-
-    // Parse Merkle paths
-    let merkle_paths = [
-        parse_merkle_path(&input.merkle_path_0)?,
-        parse_merkle_path(&input.merkle_path_1)?,
-    ];
-
-    let out_public_keys = [
-        parse_field_element(&input.out_public_key_0)?,
-        parse_field_element(&input.out_public_key_1)?,
-    ];
-
-    let out_amounts = [
-        parse_field_element(&input.out_amount_0)?,
-        parse_field_element(&input.out_amount_1)?,
-    ];
-
-    let out_blindings = [
-        parse_field_element(&input.out_blinding_0)?,
-        parse_field_element(&input.out_blinding_1)?,
-    ];
-
-    // Create circuit
-    let circuit = TransactionCircuit::new(
-        vortex,
-        root,
-        public_amount,
-        input_nullifier_0,
-        input_nullifier_1,
-        output_commitment_0,
-        output_commitment_1,
-        hashed_account_secret,
-        account_secret,
-        in_private_keys,
-        in_amounts,
-        in_blindings,
-        in_path_indices,
-        merkle_paths,
-        out_public_keys,
-        out_amounts,
-        out_blindings,
-    )
-    .map_err(|e| JsValue::from(&format!("Failed to create circuit: {}", e)))?;
-
-    // Generate proof using deterministic RNG for testing
-    // In production, you should use a secure RNG
-    use rand_chacha::ChaCha20Rng;
-    use rand_core::SeedableRng;
-
-    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
-
-    // Extract public inputs BEFORE proving (circuit is consumed by prove())
-    // The order MUST match the order in which FpVar::new_input() is called in generate_constraints()
-    // This is: vortex, root, public_amount, input_nullifier_0, input_nullifier_1,
-    //          output_commitment_0, output_commitment_1, hashed_account_secret
-    let public_inputs_field = circuit.get_public_inputs();
-    let public_inputs_serialized = circuit
-        .get_public_inputs_serialized()
-        .map_err(|e| JsValue::from(&format!("Failed to serialize public inputs: {}", e)))?;
-
-    let cs = ConstraintSystem::<Fr>::new_ref();
-    circuit
-        .clone()
-        .generate_constraints(cs.clone())
-        .expect("Failed to generate constraints");
-    if !cs.is_satisfied().expect("Failed to check constraints") {
-        panic!("Constraints are not satisfied");
-    }
-
-    // Generate proof - Groth16 will internally call generate_constraints() and extract public inputs
-    // It uses the same public inputs we extracted above (in the same order)
-    // Note: Groth16's prove() function extracts public inputs from the constraint system
-    // in the order they were allocated via FpVar::new_input(). Our get_public_inputs()
-    // should match this order exactly.
-    //
-    // IMPORTANT: Groth16 extracts public inputs from the constraint system during prove().
-    // The public inputs are stored in the constraint system in the order they were allocated.
-    // We extract them manually using get_public_inputs() which should match exactly.
-    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng)
-        .map_err(|e| JsValue::from(&format!("Failed to generate proof: {}", e)))?;
-
-    // Serialize proof components (compressed format)
-    let mut proof_a_bytes = Vec::new();
-    proof
-        .a
-        .serialize_compressed(&mut proof_a_bytes)
-        .map_err(|e| JsValue::from(&format!("Failed to serialize proof.a: {}", e)))?;
-
-    let mut proof_b_bytes = Vec::new();
-    proof
-        .b
-        .serialize_compressed(&mut proof_b_bytes)
-        .map_err(|e| JsValue::from(&format!("Failed to serialize proof.b: {}", e)))?;
-
-    let mut proof_c_bytes = Vec::new();
-    proof
-        .c
-        .serialize_compressed(&mut proof_c_bytes)
-        .map_err(|e| JsValue::from(&format!("Failed to serialize proof.c: {}", e)))?;
-
-    // Serialize proof
-    let mut proof_serialized = Vec::new();
-    proof.serialize_compressed(&mut proof_serialized).unwrap();
-
-    // Convert public inputs to strings for JSON output
-    // Use the field's underlying representation for reliable serialization/deserialization
-    // This ensures the string can be parsed back correctly by parse_field_element()
-    let public_inputs: Vec<String> = public_inputs_field
-        .iter()
-        .map(|input| {
-            // Convert Fr to BigInt representation, then to string
-            // This ensures reliable round-trip conversion
-            input.into_bigint().to_string()
-        })
-        .collect();
-
-    let output = ProofOutput {
-        proof_a: proof_a_bytes,
-        proof_b: proof_b_bytes,
-        proof_c: proof_c_bytes,
-        public_inputs,
-        proof_serialized_hex: hex::encode(proof_serialized),
-        public_inputs_serialized_hex: hex::encode(public_inputs_serialized),
-    };
-
-    serde_json::to_string(&output)
-        .map_err(|e| JsValue::from(&format!("Failed to serialize output: {}", e)))
+pub fn prove(
+    input_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<String, JsValue> {
+    memory::ensure_proving_memory(proving_key_hex.len() / 2)?;
+    prover::prove_json(input_json, proving_key_hex, descriptor_json.as_deref())
+        .map_err(JsValue::from)
+}
+
+/// Generates proofs for several transactions, decoding the proving key once
+///
+/// # Arguments
+/// * `inputs_json` - JSON array of inputs, each in the format `prove` takes
+/// * `proving_key_hex` - Hex-encoded proving key
+/// * `descriptor_json` - Optional `descriptor.json` published with the keys
+///
+/// # Returns
+/// JSON array of proof outputs in the order of the inputs
+#[wasm_bindgen]
+pub fn prove_batch(
+    inputs_json: &str,
+    proving_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<String, JsValue> {
+    memory::ensure_proving_memory(proving_key_hex.len() / 2)?;
+    prover::prove_batch_json(inputs_json, proving_key_hex, descriptor_json.as_deref())
+        .map_err(JsValue::from)
+}
+
+/// `prove` taking the input as a JS object and returning the proof as one,
+/// shaped as `JSON.parse(prove(JSON.stringify(input), ...))` would be, without
+/// the JSON round trip that dominates for deep Merkle paths
+///
+/// Throws the same errors as `prove`; an object that does not describe an
+/// input throws a `ProveError` of kind `InvalidInput`.
+#[wasm_bindgen]
+pub fn prove_object(
+    input: JsValue,
+    proving_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<JsValue, JsValue> {
+    memory::ensure_proving_memory(proving_key_hex.len() / 2)?;
+    let input = from_js(input).map_err(|e| prove_conversion_error("input", e))?;
+    let output = prover::prove_hex(&input, proving_key_hex, descriptor_json.as_deref())?;
+    Ok(to_js(&output).map_err(|e| prove_conversion_error("proof", e))?)
+}
+
+/// `prove_batch` taking an array of input objects and returning an array of
+/// proof objects, as `prove_object` does for one
+#[wasm_bindgen]
+pub fn prove_batch_object(
+    inputs: JsValue,
+    proving_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<JsValue, JsValue> {
+    memory::ensure_proving_memory(proving_key_hex.len() / 2)?;
+    let inputs = from_js(inputs).map_err(|e| prove_conversion_error("inputs", e))?;
+    let outputs = prover::prove_batch_hex(inputs, proving_key_hex, descriptor_json.as_deref())?;
+    Ok(to_js(&outputs).map_err(|e| prove_conversion_error("proofs", e))?)
+}
+
+/// Checks whether a proving key of `proving_key_bytes` (compressed, not hex)
+/// can be loaded, so apps can pick the prover service or native app at startup
+/// instead of downloading a key they cannot use
+///
+/// # Returns
+/// `undefined`, or throws an `InsufficientMemory` instance with
+/// `requiredBytes`, `availableBytes` and a `message` with guidance
+#[wasm_bindgen]
+pub fn check_proving_memory(proving_key_bytes: usize) -> Result<(), JsValue> {
+    memory::ensure_proving_memory(proving_key_bytes).map_err(JsValue::from)
 }
 
 /// Verifies a proof (useful for testing before submitting to chain)
@@ -267,89 +133,367 @@ pub fn prove(input_json: &str, proving_key_hex: &str) -> Result<String, JsValue>
 /// # Arguments
 /// * `proof_json` - JSON string containing proof output from `prove()`
 /// * `verifying_key_hex` - Hex-encoded verifying key
+/// * `descriptor_json` - Optional `descriptor.json` published with the keys
 ///
 /// # Returns
 /// "true" if proof is valid, "false" otherwise
+///
+/// Throws a `VerifyError` with a `kind` (`InputCount`, `NonCanonicalRoot`,
+/// `NonCanonicalNullifier`, `NonCanonicalInput` or `InvalidInput`) and
+/// `message` when the proof is rejected before the pairing check.
 #[wasm_bindgen]
-pub fn verify(proof_json: &str, verifying_key_hex: &str) -> Result<bool, JsValue> {
-    let proof_output: ProofOutput = serde_json::from_str(proof_json)
-        .map_err(|e| JsValue::from(&format!("Step 1 - Failed to parse proof JSON: {}", e)))?;
-
-    let vk_bytes = hex::decode(verifying_key_hex)
-        .map_err(|e| JsValue::from(&format!("Step 2 - Failed to decode VK hex: {}", e)))?;
-
-    let vk = ark_groth16::VerifyingKey::<Bn254>::deserialize_compressed(&vk_bytes[..])
-        .map_err(|e| JsValue::from(&format!("Step 3 - Failed to deserialize VK: {}", e)))?;
-
-    let pvk = ark_groth16::prepare_verifying_key(&vk);
-
-    let proof_bytes = hex::decode(&proof_output.proof_serialized_hex)
-        .map_err(|e| JsValue::from(&format!("Step 4 - Failed to decode proof hex: {}", e)))?;
-
-    let proof = ark_groth16::Proof::<Bn254>::deserialize_compressed(&proof_bytes[..])
-        .map_err(|e| JsValue::from(&format!("Step 5 - Failed to deserialize proof: {}", e)))?;
-
-    let public_inputs: Result<Vec<Fr>, JsValue> = proof_output
-        .public_inputs
-        .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            parse_field_element(s).map_err(|e| {
-                JsValue::from(&format!(
-                    "Step 6 - Failed to parse public input {}: {:?}",
-                    i, e
-                ))
-            })
-        })
-        .collect();
-    let public_inputs = public_inputs?;
-
-    let is_valid = Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs).map_err(|e| {
-        JsValue::from(&format!(
-            "Step 7 - Verify failed (inputs={}): {}",
-            public_inputs.len(),
-            e
-        ))
+pub fn verify(
+    proof_json: &str,
+    verifying_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<bool, JsValue> {
+    prover::verify_json(proof_json, verifying_key_hex, descriptor_json.as_deref())
+        .map_err(JsValue::from)
+}
+
+/// `verify` taking the proof object `prove_object` returns
+///
+/// Throws the same errors as `verify`; an object that does not describe a
+/// proof throws a `VerifyError` of kind `InvalidInput`.
+#[wasm_bindgen]
+pub fn verify_object(
+    proof: JsValue,
+    verifying_key_hex: &str,
+    descriptor_json: Option<String>,
+) -> Result<bool, JsValue> {
+    let proof = from_js(proof).map_err(|e| {
+        VerifyError::new(
+            VerifyErrorKind::InvalidInput,
+            format!("Failed to read proof object: {e}"),
+        )
     })?;
+    Ok(prover::verify_hex(
+        &proof,
+        verifying_key_hex,
+        descriptor_json.as_deref(),
+    )?)
+}
+
+/// Converts a proof from `prove` into the byte vectors Sui's `groth16` module takes
+///
+/// # Arguments
+/// * `proof_json` - JSON string from `prove()` output
+/// * `verifying_key_hex` - Hex-encoded verifying key
+///
+/// # Returns
+/// JSON string `{ proofPointsBytes, publicInputsBytes, preparedVkBytes }`, the
+/// arguments of `proof_points_from_bytes`, `public_proof_inputs_from_bytes`
+/// and `pvk_from_bytes`
+#[wasm_bindgen]
+pub fn to_sui_proof(proof_json: &str, verifying_key_hex: &str) -> Result<String, JsValue> {
+    prover::to_sui_proof_json(proof_json, verifying_key_hex).map_err(to_js_error)
+}
+
+/// Builds the association set from approved commitments and returns the
+/// membership path of one of them
+///
+/// # Arguments
+/// * `commitments_json` - JSON array of approved commitments
+/// * `commitment` - Commitment of the note being spent
+///
+/// # Returns
+/// JSON string `{ root, path }` to use as `associationRoot` / `associationPath{0,1}`
+#[wasm_bindgen]
+pub fn association_proof(commitments_json: &str, commitment: &str) -> Result<String, JsValue> {
+    prover::association_proof_json(commitments_json, commitment).map_err(to_js_error)
+}
+
+/// Rebuilds the pool tree from its commitments and returns the path of one
+///
+/// # Arguments
+/// * `commitments_json` - JSON array of `{ index, commitment }`, in any order
+/// * `commitment` - Commitment of the note being spent
+///
+/// # Returns
+/// JSON string `{ root, size, index, commitment, path }`, with `root` and
+/// `path` ready for the proof input's `root` and `merklePath{0,1}`
+#[wasm_bindgen]
+pub fn tree_path(commitments_json: &str, commitment: &str) -> Result<String, JsValue> {
+    tree_state::tree_state_path_json(commitments_json, commitment).map_err(to_js_error)
+}
+
+/// Checks a claim that `commitment` is leaf `index` of the pool tree with `root`
+///
+/// # Arguments
+/// * `root` - Pool root the claim is made against
+/// * `index` - Claimed leaf index
+/// * `commitment` - Claimed commitment
+/// * `path_json` - JSON array of the sibling of each level, or the
+///   `[left, right]` pairs returned by `tree_path`
+///
+/// # Returns
+/// `true` if the path leads from the commitment at `index` to `root`
+#[wasm_bindgen]
+pub fn verify_inclusion(
+    root: &str,
+    index: u64,
+    commitment: &str,
+    path_json: &str,
+) -> Result<bool, JsValue> {
+    tree_state::verify_inclusion_json(root, index, commitment, path_json).map_err(to_js_error)
+}
+
+/// Derives the spend, public and viewing keys of a wallet account
+///
+/// # Arguments
+/// * `seed_hex` - Hex-encoded master seed (at least 16 bytes)
+/// * `account` - Account index
+///
+/// # Returns
+/// JSON string `{ spendKey, publicKey, viewingKey }`
+#[wasm_bindgen]
+pub fn derive_account(seed_hex: &str, account: u64) -> Result<String, JsValue> {
+    derivation::derive_account_json(seed_hex, account).map_err(to_js_error)
+}
+
+/// Derives the blinding of an account's `note_index`-th note from its viewing key
+#[wasm_bindgen]
+pub fn derive_blinding(viewing_key: &str, note_index: u64) -> Result<String, JsValue> {
+    derivation::derive_blinding(viewing_key, note_index).map_err(to_js_error)
+}
+
+/// Poseidon hash of one field element, identical to circomlibjs `poseidon([x])`
+///
+/// Inputs are decimal or 0x-prefixed hex strings; the hash is returned in decimal.
+#[wasm_bindgen]
+pub fn poseidon1(x: &str) -> Result<String, JsValue> {
+    hashing::poseidon1(x).map_err(to_js_error)
+}
+
+/// Poseidon hash of two field elements, identical to circomlibjs `poseidon([x, y])`
+#[wasm_bindgen]
+pub fn poseidon2(x: &str, y: &str) -> Result<String, JsValue> {
+    hashing::poseidon2(x, y).map_err(to_js_error)
+}
+
+/// Poseidon hash of three field elements, identical to circomlibjs `poseidon([x, y, z])`
+#[wasm_bindgen]
+pub fn poseidon3(x: &str, y: &str, z: &str) -> Result<String, JsValue> {
+    hashing::poseidon3(x, y, z).map_err(to_js_error)
+}
+
+/// Computes a note commitment exactly as the prover does
+///
+/// # Arguments
+/// * `amount` - Note amount
+/// * `public_key` - Owner's public key, `poseidon1(spendKey)`
+/// * `blinding` - Note blinding
+/// * `vortex` - Pool the note belongs to
+/// * `asset_id` - Asset of notes in asset-bound pools, `undefined` otherwise
+/// * `version` - Circuit version (`v1`, `v2` or `v3`), defaults to `v1`
+///
+/// # Returns
+/// The commitment in decimal
+#[wasm_bindgen]
+pub fn commitment(
+    amount: &str,
+    public_key: &str,
+    blinding: &str,
+    vortex: &str,
+    asset_id: Option<String>,
+    version: Option<String>,
+) -> Result<String, JsValue> {
+    hashing::commitment(
+        amount,
+        public_key,
+        blinding,
+        vortex,
+        asset_id.as_deref(),
+        version.as_deref(),
+    )
+    .map_err(to_js_error)
+}
+
+/// Computes the nullifier of the note `commitment` at leaf `path_index`
+/// exactly as the prover does
+///
+/// `version` is the circuit version (`v1`, `v2` or `v3`), defaulting to `v1`.
+#[wasm_bindgen]
+pub fn nullifier(
+    private_key: &str,
+    commitment: &str,
+    path_index: &str,
+    version: Option<String>,
+) -> Result<String, JsValue> {
+    hashing::nullifier(private_key, commitment, path_index, version.as_deref()).map_err(to_js_error)
+}
+
+/// Generates a deposit note with a random spend key and blinding
+///
+/// # Arguments
+/// * `vortex` - Pool the deposit goes to
+/// * `amount` - Deposited amount
+/// * `entropy_hex` - 32 fresh random bytes, e.g. from `crypto.getRandomValues`
+///
+/// # Returns
+/// JSON string `{ note, vortex, amount, spendKey, publicKey, blinding, commitment }`,
+/// where `note` is the `vortex-note-v1:...` string to back up
+#[wasm_bindgen]
+pub fn deposit_note(vortex: &str, amount: &str, entropy_hex: &str) -> Result<String, JsValue> {
+    deposit::generate_deposit_note_json(vortex, amount, entropy_hex).map_err(to_js_error)
+}
+
+/// Parses a `vortex-note-v1:...` string into the JSON `deposit_note` returns
+#[wasm_bindgen]
+pub fn parse_deposit_note(note: &str) -> Result<String, JsValue> {
+    deposit::parse_deposit_note_json(note).map_err(to_js_error)
+}
+
+/// Returns the hex-encoded key senders encrypt an account's outputs to
+#[wasm_bindgen]
+pub fn encryption_key(viewing_key: &str) -> Result<String, JsValue> {
+    note::encryption_key_hex(viewing_key).map_err(to_js_error)
+}
+
+/// Encrypts a note into the `encrypted_output` format
+///
+/// # Arguments
+/// * `note_json` - JSON string `{ amount, blinding }`
+/// * `encryption_key_hex` - Recipient's key from `encryption_key()`
+/// * `entropy_hex` - 32 fresh random bytes, e.g. from `crypto.getRandomValues`
+///
+/// # Returns
+/// Hex-encoded encrypted output
+#[wasm_bindgen]
+pub fn encrypt_note(
+    note_json: &str,
+    encryption_key_hex: &str,
+    entropy_hex: &str,
+) -> Result<String, JsValue> {
+    note::encrypt_note_json(note_json, encryption_key_hex, entropy_hex).map_err(to_js_error)
+}
+
+/// Decrypts a hex-encoded `encrypted_output` with the recipient's viewing key
+///
+/// # Returns
+/// JSON string `{ amount, blinding }`
+#[wasm_bindgen]
+pub fn decrypt_note(encrypted_hex: &str, viewing_key: &str) -> Result<String, JsValue> {
+    note::decrypt_note_json(encrypted_hex, viewing_key).map_err(to_js_error)
+}
+
+/// Finds the notes owned by a viewing key among hex-encoded `encrypted_output`s
+///
+/// Empty-output markers are skipped without attempting a decryption.
+///
+/// # Arguments
+/// * `outputs_json` - JSON array of hex-encoded encrypted outputs
+/// * `viewing_key` - Recipient's viewing key
+///
+/// # Returns
+/// JSON array `[{ position, amount, blinding }]`
+#[wasm_bindgen]
+pub fn scan_notes(outputs_json: &str, viewing_key: &str) -> Result<String, JsValue> {
+    note::scan_notes_json(outputs_json, viewing_key).map_err(to_js_error)
+}
+
+/// Starts a sync of a pool from index 0
+///
+/// # Returns
+/// JSON checkpoint `{ coinType, nextIndex, notes, failedAttempts, done }`. Persist
+/// it after every call below so an interrupted sync resumes where it stopped.
+#[wasm_bindgen]
+pub fn sync_checkpoint(coin_type: &str) -> Result<String, JsValue> {
+    sync::sync_checkpoint_json(coin_type).map_err(to_js_error)
+}
+
+/// Returns the next page to fetch for a checkpoint
+///
+/// # Arguments
+/// * `checkpoint_json` - Checkpoint from `sync_checkpoint` or a previous step
+/// * `config_json` - Optional `{ chunkSize, maxRetries, baseDelayMs, maxDelayMs }`
+///
+/// # Returns
+/// JSON `{ coinType, index, op, limit }` query for `GET /api/v1/commitments`
+#[wasm_bindgen]
+pub fn sync_next_request(
+    checkpoint_json: &str,
+    config_json: Option<String>,
+) -> Result<String, JsValue> {
+    sync::sync_next_request_json(checkpoint_json, config_json.as_deref()).map_err(to_js_error)
+}
+
+/// Scans a fetched page for owned notes and advances the checkpoint past it
+///
+/// # Arguments
+/// * `checkpoint_json` - Current checkpoint
+/// * `page_json` - `data` of the commitments response, `{ items, hasNext }`
+/// * `viewing_key` - Wallet viewing key
+///
+/// # Returns
+/// The advanced checkpoint, with any notes found appended to `notes`
+#[wasm_bindgen]
+pub fn sync_apply_page(
+    checkpoint_json: &str,
+    page_json: &str,
+    viewing_key: &str,
+) -> Result<String, JsValue> {
+    sync::sync_apply_page_json(checkpoint_json, page_json, viewing_key).map_err(to_js_error)
+}
+
+/// Records a failed page fetch
+///
+/// # Returns
+/// JSON `{ checkpoint, retryInMs }`; `retryInMs` is the backoff before
+/// refetching the same page, or `null` once the retries are exhausted
+#[wasm_bindgen]
+pub fn sync_record_failure(
+    checkpoint_json: &str,
+    config_json: Option<String>,
+) -> Result<String, JsValue> {
+    sync::sync_record_failure_json(checkpoint_json, config_json.as_deref()).map_err(to_js_error)
+}
+
+/// `Idempotency-Key` header of a relayer submission, the same for every
+/// retry of the spend of `nullifier_0` and `nullifier_1`
+#[wasm_bindgen]
+pub fn relay_idempotency_key(nullifier_0: &str, nullifier_1: &str) -> Result<String, JsValue> {
+    relay::idempotency_key(nullifier_0, nullifier_1).map_err(to_js_error)
+}
+
+/// Backoff before resubmitting a failed relayer submission
+///
+/// # Arguments
+/// * `policy_json` - Optional `{ maxAttempts, baseDelayMs, maxDelayMs }`
+/// * `attempt` - Submissions made so far, starting at 1
+/// * `status` - HTTP status of the failure, `undefined` for network errors
+///
+/// # Returns
+/// Milliseconds to wait before resubmitting with the same idempotency key,
+/// or `undefined` when the failure is final or the attempts are exhausted
+#[wasm_bindgen]
+pub fn relay_retry_delay(
+    policy_json: Option<String>,
+    attempt: u32,
+    status: Option<u16>,
+) -> Result<Option<u32>, JsValue> {
+    let policy = relay::parse_retry_policy(policy_json.as_deref()).map_err(to_js_error)?;
+    Ok(policy
+        .retry_delay_ms(attempt, status)
+        .map(|delay| u32::try_from(delay).unwrap_or(u32::MAX)))
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, serde_wasm_bindgen::Error> {
+    serde_wasm_bindgen::from_value(value)
+}
+
+/// Plain objects and arrays, as `JSON.parse` returns them.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+fn prove_conversion_error(what: &str, error: serde_wasm_bindgen::Error) -> ProveError {
+    ProveError::new(
+        ProveErrorKind::InvalidInput,
+        format!("Failed to convert {what} object: {error}"),
+    )
+}
 
-    Ok(is_valid)
-}
-
-// Helper functions
-fn parse_field_element(s: &str) -> Result<Fr, JsValue> {
-    // Handle both decimal and hex strings
-    let s = s.trim();
-
-    let big_uint = if s.starts_with("0x") || s.starts_with("0X") {
-        // Remove 0x prefix and parse as hex
-        let hex_str = &s[2..];
-        BigUint::parse_bytes(hex_str.as_bytes(), 16).ok_or_else(|| {
-            JsValue::from(&format!("Failed to parse hex '{}': invalid hex string", s))
-        })?
-    } else {
-        // Parse as decimal
-        BigUint::from_str(s)
-            .map_err(|e| JsValue::from(&format!("Failed to parse decimal '{}': {}", s, e)))?
-    };
-    Ok(Fr::from(big_uint))
-}
-
-fn parse_merkle_path(path_data: &[[String; 2]]) -> Result<Path<MERKLE_TREE_LEVEL>, JsValue> {
-    if path_data.len() != MERKLE_TREE_LEVEL {
-        return Err(JsValue::from(&format!(
-            "Invalid Merkle path length: expected {}, got {}",
-            MERKLE_TREE_LEVEL,
-            path_data.len()
-        )));
-    }
-
-    let mut path = [(Fr::from(0u64), Fr::from(0u64)); MERKLE_TREE_LEVEL];
-
-    for (i, pair) in path_data.iter().enumerate() {
-        let left = parse_field_element(&pair[0])?;
-        let right = parse_field_element(&pair[1])?;
-        path[i] = (left, right);
-    }
-
-    Ok(Path { path })
+fn to_js_error(error: anyhow::Error) -> JsValue {
+    JsValue::from(&format!("{:#}", error))
 }
@@ -0,0 +1,133 @@
+//! One test per security property listed on `TransactionCircuit`: a witness
+//! violating the property must leave the constraint system unsatisfied, and
+//! public inputs violating it must not verify a proof from the test circuit
+//! keys.
+#![cfg(feature = "test-circuit")]
+
+use ark_bn254::{Bn254, Fr};
+use ark_ff::Field;
+use ark_groth16::Proof;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use std::sync::OnceLock;
+use vortex::circuit::note_nullifier;
+use vortex::constants::MAX_AMOUNT_BITS;
+use vortex::test_circuit::{prove, sample_transaction, verify, TestCircuit};
+
+/// Synthesis itself fails on witnesses without an assignment, such as the
+/// inverse proving two equal nullifiers differ.
+fn is_satisfied(circuit: TestCircuit) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).is_ok() && cs.is_satisfied().unwrap()
+}
+
+/// The sample spend of a 100-unit note into 60 and 40, checked to be valid.
+fn honest() -> TestCircuit {
+    let circuit = sample_transaction().unwrap();
+    assert!(is_satisfied(circuit.clone()));
+    circuit
+}
+
+/// Asserts the proof of the honest spend does not verify against `forged`'s
+/// public inputs.
+fn assert_proof_rejected(forged: &TestCircuit) {
+    static PROOF: OnceLock<Proof<Bn254>> = OnceLock::new();
+    let proof = PROOF.get_or_init(|| prove(honest()).unwrap());
+    assert!(!verify(&forged.get_public_inputs(), proof).unwrap());
+}
+
+fn reseal_outputs(circuit: &mut TestCircuit) {
+    for i in 0..circuit.output_commitments.len() {
+        circuit.output_commitments[i] = circuit.output_commitment(i);
+    }
+}
+
+#[test]
+fn test_no_double_spending() {
+    let circuit = honest();
+
+    // Spending the note again reveals the nullifier the contract recorded
+    assert_eq!(
+        note_nullifier(
+            circuit.version,
+            circuit.in_private_keys[0],
+            circuit.input_commitment(0),
+            circuit.in_path_indices[0],
+        ),
+        circuit.input_nullifiers[0]
+    );
+
+    // and any other nullifier for it is rejected
+    let mut forged = circuit.clone();
+    forged.input_nullifiers[0] += Fr::ONE;
+    assert!(!is_satisfied(forged.clone()));
+    assert_proof_rejected(&forged);
+}
+
+#[test]
+fn test_amount_conservation() {
+    let mut minted = honest();
+    minted.public_amount += Fr::ONE;
+    assert!(!is_satisfied(minted.clone()));
+    assert_proof_rejected(&minted);
+
+    let mut inflated = honest();
+    inflated.out_amounts[0] += Fr::ONE;
+    reseal_outputs(&mut inflated);
+    assert!(!is_satisfied(inflated.clone()));
+    assert_proof_rejected(&inflated);
+}
+
+#[test]
+fn test_valid_merkle_proofs() {
+    let mut fake_root = honest();
+    fake_root.root += Fr::ONE;
+    assert!(!is_satisfied(fake_root.clone()));
+    assert_proof_rejected(&fake_root);
+
+    // A note that was never deposited, with its own valid nullifier
+    let mut unknown_note = honest();
+    unknown_note.in_blindings[0] += Fr::ONE;
+    unknown_note.input_nullifiers[0] = note_nullifier(
+        unknown_note.version,
+        unknown_note.in_private_keys[0],
+        unknown_note.input_commitment(0),
+        unknown_note.in_path_indices[0],
+    );
+    assert!(!is_satisfied(unknown_note.clone()));
+    assert_proof_rejected(&unknown_note);
+}
+
+#[test]
+fn test_no_overflow() {
+    // 100 = 101 + (p - 1) balances in the field
+    let mut wrapped = honest();
+    wrapped.out_amounts = [Fr::from(101u64), -Fr::ONE];
+    reseal_outputs(&mut wrapped);
+    assert!(!is_satisfied(wrapped.clone()));
+    assert_proof_rejected(&wrapped);
+
+    // A deposit balancing an output just past the amount range
+    let mut oversized = honest();
+    oversized.out_amounts[0] = Fr::from(2u64).pow([MAX_AMOUNT_BITS as u64]);
+    oversized.public_amount =
+        oversized.out_amounts[0] + oversized.out_amounts[1] - oversized.in_amounts[0];
+    reseal_outputs(&mut oversized);
+    assert!(!is_satisfied(oversized.clone()));
+    assert_proof_rejected(&oversized);
+}
+
+#[test]
+fn test_unique_nullifiers() {
+    // The note spent in both slots, with the outputs absorbing its amount twice
+    let mut duplicated = honest();
+    duplicated.in_private_keys[1] = duplicated.in_private_keys[0];
+    duplicated.in_amounts[1] = duplicated.in_amounts[0];
+    duplicated.in_blindings[1] = duplicated.in_blindings[0];
+    duplicated.in_path_indices[1] = duplicated.in_path_indices[0];
+    duplicated.merkle_paths[1] = duplicated.merkle_paths[0];
+    duplicated.input_nullifiers[1] = duplicated.input_nullifiers[0];
+    duplicated.out_amounts[0] += duplicated.in_amounts[0];
+    reseal_outputs(&mut duplicated);
+    assert!(!is_satisfied(duplicated.clone()));
+    assert_proof_rejected(&duplicated);
+}
@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::{IntGaugeVec, Opts, Registry};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use sui_indexer_alt_framework::{ingestion::IngestionConfig, pipeline::concurrent::BatchStatus};
+
+pub const DEFAULT_CHECKPOINT_BUFFER_SIZE: usize = 1000;
+pub const DEFAULT_INGEST_CONCURRENCY: usize = 50;
+pub const DEFAULT_MAX_BATCH_ROWS: usize = 5000;
+
+static MAX_BATCH_ROWS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_BATCH_ROWS);
+
+static PENDING_ROWS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "vortex_indexer_pending_rows",
+            "Rows buffered in a pipeline's batch waiting to be committed",
+        ),
+        &["pipeline"],
+    )
+    .expect("pending rows metric definition is valid")
+});
+
+/// High-water marks between checkpoint ingestion and the handler pipelines.
+///
+/// Ingestion stops fetching once `checkpoint_buffer_size` checkpoints are
+/// waiting for processors, and a handler batch is sent to the committer as
+/// soon as it holds `max_batch_rows` rows. Both bound memory during backfills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackpressureConfig {
+    pub checkpoint_buffer_size: usize,
+    pub ingest_concurrency: usize,
+    pub max_batch_rows: usize,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            checkpoint_buffer_size: DEFAULT_CHECKPOINT_BUFFER_SIZE,
+            ingest_concurrency: DEFAULT_INGEST_CONCURRENCY,
+            max_batch_rows: DEFAULT_MAX_BATCH_ROWS,
+        }
+    }
+}
+
+impl BackpressureConfig {
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.checkpoint_buffer_size > 0,
+            "checkpoint_buffer_size must be greater than 0"
+        );
+        anyhow::ensure!(
+            self.ingest_concurrency > 0,
+            "ingest_concurrency must be greater than 0"
+        );
+        anyhow::ensure!(
+            self.max_batch_rows > 0,
+            "max_batch_rows must be greater than 0"
+        );
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn ingestion_config(&self) -> IngestionConfig {
+        IngestionConfig {
+            checkpoint_buffer_size: self.checkpoint_buffer_size,
+            ingest_concurrency: self.ingest_concurrency,
+            ..Default::default()
+        }
+    }
+
+    /// Applies the batch high-water mark and registers the queue depth metric.
+    pub fn install(&self, registry: &Registry) -> Result<()> {
+        self.validate()?;
+        MAX_BATCH_ROWS.store(self.max_batch_rows, Ordering::Relaxed);
+        registry
+            .register(Box::new(PENDING_ROWS.clone()))
+            .context("Failed to register pending rows metric")
+    }
+}
+
+#[must_use]
+pub fn max_batch_rows() -> usize {
+    MAX_BATCH_ROWS.load(Ordering::Relaxed)
+}
+
+/// Moves values into `batch` until it reaches [`max_batch_rows`].
+///
+/// Values left in `values` are carried over to the next batch by the framework,
+/// so a full batch is committed before more rows are buffered.
+pub fn fill_batch<T>(
+    pipeline: &str,
+    batch: &mut Vec<T>,
+    values: &mut std::vec::IntoIter<T>,
+) -> BatchStatus {
+    let limit = max_batch_rows();
+    batch.extend(values.by_ref().take(limit.saturating_sub(batch.len())));
+
+    PENDING_ROWS
+        .with_label_values(&[pipeline])
+        .set(i64::try_from(batch.len()).unwrap_or(i64::MAX));
+
+    if batch.len() >= limit {
+        BatchStatus::Ready
+    } else {
+        BatchStatus::Pending
+    }
+}
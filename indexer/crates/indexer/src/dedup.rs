@@ -0,0 +1,232 @@
+//! Documents each run inserted and skipped as duplicates, per collection.
+//!
+//! Every document `_id` is derived from the event, so reprocessing a
+//! checkpoint inserts nothing new. A restart replays the checkpoints committed
+//! past the watermark before it stopped, which shows up as duplicates early in
+//! the run. Duplicates that keep arriving later point at a pipeline
+//! processing checkpoints twice.
+
+use anyhow::{Context, Result};
+use mongodb::bson::{self, doc, Document};
+use mongodb::Database;
+use once_cell::sync::{Lazy, OnceCell};
+use prometheus::{IntCounterVec, Opts, Registry};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use tracing::warn;
+use vortex_schema::{collections, IndexerRun, Watermark};
+
+/// Duplicates arriving this long after the start of a run are not replays.
+pub const REPLAY_WINDOW: Duration = Duration::from_secs(600);
+
+/// Runs `--status` prints, newest first.
+pub const STATUS_RUNS: i64 = 5;
+
+static RUN_ID: OnceCell<String> = OnceCell::new();
+
+static INSERTED_ROWS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "vortex_indexer_inserted_rows_total",
+            "Documents inserted by this run",
+        ),
+        &["collection"],
+    )
+    .expect("inserted rows metric definition is valid")
+});
+
+static DUPLICATE_ROWS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "vortex_indexer_duplicate_rows_total",
+            "Documents skipped by this run because their _id was already stored",
+        ),
+        &["collection"],
+    )
+    .expect("duplicate rows metric definition is valid")
+});
+
+/// Registers the counters and records the start of this run with the
+/// watermark each pipeline resumes from.
+pub async fn start_run(database: &Database, registry: &Registry) -> Result<()> {
+    registry
+        .register(Box::new(INSERTED_ROWS.clone()))
+        .context("Failed to register inserted rows metric")?;
+    registry
+        .register(Box::new(DUPLICATE_ROWS.clone()))
+        .context("Failed to register duplicate rows metric")?;
+
+    let mut resumed_from = BTreeMap::new();
+    let mut cursor = database
+        .collection::<Watermark>(collections::WATERMARKS)
+        .find(doc! {})
+        .await
+        .context("Failed to read watermarks")?;
+    while cursor
+        .advance()
+        .await
+        .context("Failed to read watermarks")?
+    {
+        let watermark = cursor
+            .deserialize_current()
+            .context("Failed to decode watermark")?;
+        resumed_from.insert(watermark.pipeline, watermark.checkpoint_hi_inclusive);
+    }
+
+    let run = IndexerRun {
+        id: bson::oid::ObjectId::new().to_hex(),
+        started_at: bson::DateTime::now(),
+        resumed_from,
+        collections: BTreeMap::new(),
+    };
+    database
+        .collection::<IndexerRun>(collections::INDEXER_RUNS)
+        .insert_one(&run)
+        .await
+        .context("Failed to record indexer run")?;
+
+    RUN_ID
+        .set(run.id)
+        .map_err(|_| anyhow::anyhow!("Indexer run is already started"))
+}
+
+/// Adds one bulk insert into `collection` to the counters of this run.
+/// `duplicates` holds the checkpoint of each skipped document, when known.
+///
+/// A failure to update the run report is logged rather than returned, so it
+/// does not fail, and replay, the batch.
+pub async fn record_writes(
+    database: &Database,
+    collection: &str,
+    inserted: usize,
+    duplicates: &[Option<u64>],
+) {
+    INSERTED_ROWS
+        .with_label_values(&[collection])
+        .inc_by(inserted as u64);
+    DUPLICATE_ROWS
+        .with_label_values(&[collection])
+        .inc_by(duplicates.len() as u64);
+
+    let Some(run_id) = RUN_ID.get() else {
+        return;
+    };
+    if inserted == 0 && duplicates.is_empty() {
+        return;
+    }
+
+    let field = |name: &str| format!("collections.{collection}.{name}");
+    let mut update = doc! {
+        "$inc": {
+            field("inserted"): inserted as i64,
+            field("duplicates"): duplicates.len() as i64,
+        },
+    };
+    let checkpoints = duplicates.iter().flatten().copied();
+    if let (Some(first), Some(last)) = (checkpoints.clone().min(), checkpoints.max()) {
+        update.insert(
+            "$min",
+            doc! { field("first_duplicate_checkpoint"): first as i64 },
+        );
+        update.insert(
+            "$max",
+            doc! {
+                field("last_duplicate_checkpoint"): last as i64,
+                field("last_duplicate_at"): bson::DateTime::now(),
+            },
+        );
+    }
+
+    if let Err(e) = database
+        .collection::<Document>(collections::INDEXER_RUNS)
+        .update_one(doc! { "_id": run_id }, update)
+        .await
+    {
+        warn!(collection, "Failed to update indexer run report: {e}");
+    }
+}
+
+/// The last [`STATUS_RUNS`] runs, newest first.
+pub async fn status(database: &Database) -> Result<StatusReport> {
+    let mut runs = Vec::new();
+    let mut cursor = database
+        .collection::<IndexerRun>(collections::INDEXER_RUNS)
+        .find(doc! {})
+        .sort(doc! { "started_at": -1 })
+        .limit(STATUS_RUNS)
+        .await
+        .context("Failed to read indexer runs")?;
+    while cursor
+        .advance()
+        .await
+        .context("Failed to read indexer runs")?
+    {
+        runs.push(
+            cursor
+                .deserialize_current()
+                .context("Failed to decode indexer run")?,
+        );
+    }
+    Ok(StatusReport { runs })
+}
+
+/// Inserts and duplicates of recent runs, printed by `--status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatusReport {
+    pub runs: Vec<IndexerRun>,
+}
+
+impl fmt::Display for StatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.runs.is_empty() {
+            return write!(f, "No indexer runs recorded");
+        }
+
+        for (i, run) in self.runs.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let started_at = run
+                .started_at
+                .try_to_rfc3339_string()
+                .unwrap_or_else(|_| run.started_at.to_string());
+            write!(f, "Run {} started {started_at}", run.id)?;
+            if run.collections.is_empty() {
+                write!(f, "\n  nothing written")?;
+            }
+
+            for (collection, writes) in &run.collections {
+                write!(
+                    f,
+                    "\n  {collection}: {} inserted, {} duplicates",
+                    writes.inserted, writes.duplicates
+                )?;
+                if let (Some(first), Some(last)) = (
+                    writes.first_duplicate_checkpoint,
+                    writes.last_duplicate_checkpoint,
+                ) {
+                    write!(f, " at checkpoints {first}..={last}")?;
+                }
+                if let Some(resumed) = run.resumed_from.get(collection) {
+                    write!(f, ", resumed after checkpoint {resumed}")?;
+                }
+                if let Some(last_at) = writes.last_duplicate_at {
+                    let after = last_at.timestamp_millis() - run.started_at.timestamp_millis();
+                    let after = Duration::from_millis(u64::try_from(after).unwrap_or(0));
+                    if after <= REPLAY_WINDOW {
+                        write!(f, " (restart replay)")?;
+                    } else {
+                        write!(
+                            f,
+                            " (last {}s into the run, check for double processing)",
+                            after.as_secs()
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
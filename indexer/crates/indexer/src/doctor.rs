@@ -0,0 +1,241 @@
+use crate::{parse_package_address, store::MongoStore, SuiNetwork, VortexEnv};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single startup check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", content = "detail", rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass(String),
+    /// Holds what went wrong and how to fix it
+    Fail(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+impl Check {
+    pub fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass(detail.into()),
+        }
+    }
+
+    pub fn fail(name: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail(hint.into()),
+        }
+    }
+
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        matches!(self.status, CheckStatus::Pass(_))
+    }
+}
+
+/// Pass/fail checklist printed before the indexer starts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(Check::passed)
+    }
+
+    pub fn push(&mut self, check: Check) {
+        self.checks.push(check);
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.status {
+                CheckStatus::Pass(detail) => writeln!(f, "[PASS] {}: {}", check.name, detail)?,
+                CheckStatus::Fail(hint) => writeln!(f, "[FAIL] {}: {}", check.name, hint)?,
+            }
+        }
+
+        let failed = self.checks.iter().filter(|check| !check.passed()).count();
+        if failed == 0 {
+            write!(f, "All {} checks passed", self.checks.len())
+        } else {
+            write!(f, "{failed} of {} checks failed", self.checks.len())
+        }
+    }
+}
+
+/// Configuration validated by [`run`].
+#[derive(Debug, Clone)]
+pub struct DoctorConfig {
+    pub network: SuiNetwork,
+    pub vortex_package: String,
+    pub mongodb_uri: String,
+    pub mongodb_database: String,
+}
+
+/// Validates the configuration against the outside world: the checkpoint
+/// store and fullnode are reachable, the package exists on the selected
+/// network, MongoDB accepts connections and has every index the pipelines
+/// query by.
+///
+/// Connecting creates missing indexes, so the store is returned for the
+/// indexer to reuse when the MongoDB checks pass.
+pub async fn run(config: &DoctorConfig) -> (DoctorReport, Option<MongoStore>) {
+    let mut report = DoctorReport::default();
+    let http = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let package_address = match parse_package_address(&config.vortex_package) {
+        Ok(address) => {
+            report.push(Check::pass("Package address", address.to_string()));
+            Some(address)
+        }
+        Err(e) => {
+            report.push(Check::fail(
+                "Package address",
+                format!("{e}. Set VORTEX_PACKAGE to the 0x-prefixed package ID"),
+            ));
+            None
+        }
+    };
+
+    report.push(check_remote_store(&http, config.network).await);
+    report.push(check_fullnode(&http, config.network).await);
+    if let Some(package_address) = package_address {
+        let env = VortexEnv::new(config.network, package_address);
+        report.push(check_package(&http, &env).await);
+    }
+
+    let store = match MongoStore::new(&config.mongodb_uri, &config.mongodb_database).await {
+        Ok(store) => {
+            report.push(Check::pass(
+                "MongoDB reachable",
+                format!("database '{}'", config.mongodb_database),
+            ));
+            Some(store)
+        }
+        Err(e) => {
+            report.push(Check::fail(
+                "MongoDB reachable",
+                format!("{e:#}. Check MONGODB_URI and that the server accepts connections"),
+            ));
+            None
+        }
+    };
+
+    if let Some(store) = &store {
+        report.push(match store.missing_indexes().await {
+            Ok(missing) if missing.is_empty() => Check::pass(
+                "MongoDB indexes",
+                format!("{} indexes present", MongoStore::INDEXES.len()),
+            ),
+            Ok(missing) => Check::fail(
+                "MongoDB indexes",
+                format!(
+                    "missing {}. The user needs the createIndex privilege on '{}'",
+                    missing.join(", "),
+                    config.mongodb_database
+                ),
+            ),
+            Err(e) => Check::fail("MongoDB indexes", format!("{e:#}")),
+        });
+    }
+
+    let store = store.filter(|_| report.passed());
+    (report, store)
+}
+
+async fn check_remote_store(http: &reqwest::Client, network: SuiNetwork) -> Check {
+    let name = "Checkpoint store reachable";
+    let url = network.remote_store_url();
+
+    // Any response proves the store is reachable; only server errors count
+    match http.get(url.clone()).send().await {
+        Ok(response) if !response.status().is_server_error() => Check::pass(name, url.as_str()),
+        Ok(response) => Check::fail(
+            name,
+            format!("{url} returned {}. Retry later", response.status()),
+        ),
+        Err(e) => Check::fail(
+            name,
+            format!("{url}: {e}. Check outbound HTTPS access and SUI_NETWORK"),
+        ),
+    }
+}
+
+async fn check_fullnode(http: &reqwest::Client, network: SuiNetwork) -> Check {
+    let name = "Fullnode reachable";
+    let url = network.streaming_url();
+
+    match rpc(http, &url, "sui_getChainIdentifier", json!([])).await {
+        Ok(chain) => Check::pass(name, format!("{url} (chain {chain})")),
+        Err(e) => Check::fail(
+            name,
+            format!("{url}: {e}. Check outbound HTTPS access and SUI_NETWORK"),
+        ),
+    }
+}
+
+async fn check_package(http: &reqwest::Client, env: &VortexEnv) -> Check {
+    let name = format!("Package exists on {}", env.network);
+    let params = json!([env.package_address.to_string(), { "showType": true }]);
+
+    match rpc(http, &env.streaming_url(), "sui_getObject", params).await {
+        Ok(object) if object["data"]["type"] == "package" => {
+            Check::pass(name, env.package_address.to_string())
+        }
+        Ok(object) if object["data"].is_object() => Check::fail(
+            name,
+            format!(
+                "{} is a {}, not a package. Set VORTEX_PACKAGE to the package ID",
+                env.package_address, object["data"]["type"]
+            ),
+        ),
+        Ok(_) => Check::fail(
+            name,
+            format!(
+                "{} not found. Check that VORTEX_PACKAGE was published on {}",
+                env.package_address, env.network
+            ),
+        ),
+        Err(e) => Check::fail(name, format!("{e}. Could not query the fullnode")),
+    }
+}
+
+/// Fullnodes serve JSON-RPC on the same endpoint as checkpoint streaming.
+pub(crate) async fn rpc(
+    http: &reqwest::Client,
+    url: &url::Url,
+    method: &str,
+    params: Value,
+) -> anyhow::Result<Value> {
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let response: Value = http
+        .post(url.clone())
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    match response.get("error") {
+        Some(error) => Err(anyhow::anyhow!("{method} failed: {error}")),
+        None => Ok(response["result"].clone()),
+    }
+}
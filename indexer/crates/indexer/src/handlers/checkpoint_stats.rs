@@ -0,0 +1,180 @@
+use crate::handlers::{extract_coin_type, is_vortex_tx};
+use crate::prices::PriceSource;
+use crate::VortexEnv;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::full_checkpoint_content::{Checkpoint, ExecutedTransaction};
+use sui_types::transaction::{Argument, CallArg, Command, TransactionDataAPI, TransactionKind};
+use vortex_schema::CheckpointStats;
+
+const PROOF_MODULE: &str = "vortex_proof";
+const PROOF_CONSTRUCTOR: &str = "new";
+const PROOF_PUBLIC_VALUE_ARGUMENT: usize = 3;
+
+/// Scalar field of BN254 as little-endian limbs; public values above half of
+/// it encode withdrawals.
+const FIELD_MODULUS: [u64; 4] = [
+    0x43e1_f593_f000_0001,
+    0x2833_e848_79b9_7091,
+    0xb850_45b6_8181_585d,
+    0x3064_4e72_e131_a029,
+];
+
+/// Counts Vortex events per coin type and checkpoint without keeping the
+/// events themselves; the analytics mode runs it instead of the event pipelines.
+pub struct CheckpointStatsHandler {
+    env: VortexEnv,
+    prices: Option<Arc<dyn PriceSource>>,
+}
+
+impl CheckpointStatsHandler {
+    #[must_use]
+    pub const fn new(env: VortexEnv) -> Self {
+        Self { env, prices: None }
+    }
+
+    /// Values amounts in USD with `prices` where they have a price.
+    #[must_use]
+    pub fn with_prices(self, prices: Arc<dyn PriceSource>) -> Self {
+        Self {
+            prices: Some(prices),
+            ..self
+        }
+    }
+}
+
+/// Decodes a little-endian `u256` public value into the signed amount it
+/// moves: positive for deposits, negative for withdrawals. Returns `None`
+/// for values that are not an amount of a `u64` coin.
+#[must_use]
+pub fn public_value_amount(value: &[u8; 32]) -> Option<i128> {
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(value.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().ok()?);
+    }
+    if limbs[1..].iter().all(|&limb| limb == 0) {
+        return Some(i128::from(limbs[0]));
+    }
+
+    let mut negated = [0u64; 4];
+    let mut borrow = false;
+    for ((out, modulus), limb) in negated.iter_mut().zip(FIELD_MODULUS).zip(limbs) {
+        let (diff, under) = modulus.overflowing_sub(limb);
+        let (diff, under_borrow) = diff.overflowing_sub(u64::from(borrow));
+        *out = diff;
+        borrow = under || under_borrow;
+    }
+    (!borrow && negated[1..].iter().all(|&limb| limb == 0)).then(|| -i128::from(negated[0]))
+}
+
+/// Public values of the `vortex_proof::new` calls in a transaction.
+fn proof_public_values(tx: &ExecutedTransaction) -> Vec<i128> {
+    let TransactionKind::ProgrammableTransaction(ptb) = tx.transaction.kind() else {
+        return Vec::new();
+    };
+
+    ptb.commands
+        .iter()
+        .filter_map(|command| {
+            let Command::MoveCall(call) = command else {
+                return None;
+            };
+            if call.module.as_str() != PROOF_MODULE || call.function.as_str() != PROOF_CONSTRUCTOR {
+                return None;
+            }
+            let Argument::Input(input) = call.arguments.get(PROOF_PUBLIC_VALUE_ARGUMENT)? else {
+                return None;
+            };
+            let CallArg::Pure(bytes) = ptb.inputs.get(usize::from(*input))? else {
+                return None;
+            };
+            public_value_amount(bytes.as_slice().try_into().ok()?)
+        })
+        .collect()
+}
+
+/// Adds one `event_name` event to `stats`. Returns false for events that are
+/// not counted.
+pub fn tally_event(stats: &mut CheckpointStats, event_name: &str) -> bool {
+    let counter = match event_name {
+        "NewPool" => &mut stats.new_pools,
+        "NewCommitment" => &mut stats.new_commitments,
+        "NullifierSpent" => &mut stats.nullifiers_spent,
+        _ => return false,
+    };
+    *counter += 1;
+    true
+}
+
+#[async_trait]
+impl Processor for CheckpointStatsHandler {
+    const NAME: &'static str = "checkpoint_stats";
+    type Value = CheckpointStats;
+
+    async fn process(&self, checkpoint: &Arc<Checkpoint>) -> Result<Vec<Self::Value>> {
+        crate::throttle::pace(checkpoint.summary.sequence_number).await;
+
+        let checkpoint_seq = checkpoint.summary.sequence_number;
+        let checkpoint_ts = checkpoint.summary.timestamp_ms;
+        let account_address = self.env.package_address.into();
+        let mut stats = BTreeMap::<String, CheckpointStats>::new();
+
+        for tx in &checkpoint.transactions {
+            if !is_vortex_tx(tx, self.env.package_address) {
+                continue;
+            }
+
+            let Some(events) = &tx.events else {
+                continue;
+            };
+
+            let mut tx_coin_type = None;
+            for ev in &events.data {
+                if ev.type_.address != account_address
+                    || ev.type_.module.as_str() != "vortex_events"
+                {
+                    continue;
+                }
+
+                let coin_type = extract_coin_type(&ev.type_.to_string()).unwrap_or_default();
+                let entry = stats.entry(coin_type.clone()).or_insert_with(|| {
+                    CheckpointStats::new(coin_type.clone(), checkpoint_seq, checkpoint_ts)
+                });
+                if tally_event(entry, ev.type_.name.as_str()) && tx_coin_type.is_none() {
+                    tx_coin_type = Some(coin_type);
+                }
+            }
+
+            if let Some(entry) = tx_coin_type.and_then(|coin_type| stats.get_mut(&coin_type)) {
+                entry.transactions += 1;
+                entry.gas_used += tx.effects.gas_cost_summary().net_gas_usage();
+                for amount in proof_public_values(tx) {
+                    entry.record_public_value(amount);
+                }
+            }
+        }
+
+        if let Some(prices) = &self.prices {
+            for entry in stats.values_mut() {
+                if let Some(price) = prices.price(&entry.coin_type, checkpoint_ts) {
+                    entry.set_price(price.usd, price.decimals);
+                }
+            }
+        }
+
+        Ok(stats
+            .into_values()
+            .filter(|entry| entry.new_pools + entry.new_commitments + entry.nullifiers_spent > 0)
+            .collect())
+    }
+}
+
+crate::impl_mongo_handler!(
+    CheckpointStatsHandler,
+    CheckpointStats,
+    vortex_schema::collections::CHECKPOINT_STATS
+);
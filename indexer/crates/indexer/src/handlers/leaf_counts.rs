@@ -0,0 +1,63 @@
+use crate::handlers::process_vortex_events;
+use crate::models::NewCommitmentEvent;
+use crate::VortexEnv;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_types::full_checkpoint_content::Checkpoint;
+use vortex_schema::LeafCount;
+
+/// Records the leaf count of every pool a checkpoint appends commitments to,
+/// the anonymity set size series the API serves per pool.
+pub struct LeafCountHandler {
+    env: VortexEnv,
+}
+
+impl LeafCountHandler {
+    #[must_use]
+    pub const fn new(env: VortexEnv) -> Self {
+        Self { env }
+    }
+}
+
+#[async_trait]
+impl Processor for LeafCountHandler {
+    const NAME: &'static str = "leaf_counts";
+    type Value = LeafCount;
+
+    async fn process(&self, checkpoint: &Arc<Checkpoint>) -> Result<Vec<Self::Value>> {
+        crate::throttle::pace(checkpoint.summary.sequence_number).await;
+
+        let checkpoint_seq = checkpoint.summary.sequence_number;
+        let checkpoint_ts = checkpoint.summary.timestamp_ms;
+        let mut counts = BTreeMap::<String, LeafCount>::new();
+
+        let leaves = process_vortex_events(
+            &checkpoint.transactions,
+            self.env.package_address,
+            "NewCommitment",
+            checkpoint_seq,
+            checkpoint_ts,
+            |event: NewCommitmentEvent, _digest, _sender, coin_type, _checkpoint_seq, _ts, _idx| {
+                (coin_type, event.index)
+            },
+        );
+
+        for (coin_type, index) in leaves {
+            counts
+                .entry(coin_type.clone())
+                .or_insert_with(|| LeafCount::new(coin_type, checkpoint_seq, checkpoint_ts))
+                .observe(index);
+        }
+
+        Ok(counts.into_values().collect())
+    }
+}
+
+crate::impl_mongo_handler!(
+    LeafCountHandler,
+    LeafCount,
+    vortex_schema::collections::LEAF_COUNTS
+);
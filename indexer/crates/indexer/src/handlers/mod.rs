@@ -1,20 +1,32 @@
+mod checkpoint_stats;
+mod leaf_counts;
 mod new_commitment;
 mod new_pool;
 mod nullifier_spent;
+mod watch_notifications;
 
+pub use checkpoint_stats::{public_value_amount, tally_event, CheckpointStatsHandler};
+pub use leaf_counts::LeafCountHandler;
 pub use new_commitment::NewCommitmentHandler;
 pub use new_pool::NewPoolHandler;
 pub use nullifier_spent::NullifierSpentHandler;
+pub use watch_notifications::WatchNotificationHandler;
 
-use anyhow::Result;
+use crate::dedup;
+use anyhow::{Context, Result};
+use mongodb::bson::{self, doc, Bson};
 use mongodb::options::InsertManyOptions;
+use mongodb::Database;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::de::DeserializeOwned;
 use sui_types::base_types::SuiAddress;
+use sui_types::effects::TransactionEffectsAPI;
 use sui_types::full_checkpoint_content::ExecutedTransaction;
+use sui_types::gas::GasCostSummary;
 use sui_types::transaction::TransactionDataAPI;
 use tracing::warn;
+use vortex_schema::{collections, DeadLetter, HexU256, TxMetadata};
 
 #[macro_export]
 macro_rules! impl_mongo_handler {
@@ -29,8 +41,11 @@ macro_rules! impl_mongo_handler {
                 batch: &mut Self::Batch,
                 values: &mut std::vec::IntoIter<$value>,
             ) -> sui_indexer_alt_framework::pipeline::concurrent::BatchStatus {
-                batch.extend(values);
-                sui_indexer_alt_framework::pipeline::concurrent::BatchStatus::Pending
+                $crate::backpressure::fill_batch(
+                    <$handler as sui_indexer_alt_framework::pipeline::Processor>::NAME,
+                    batch,
+                    values,
+                )
             }
 
             async fn commit<'a>(
@@ -38,8 +53,7 @@ macro_rules! impl_mongo_handler {
                 batch: &Self::Batch,
                 conn: &mut <Self::Store as sui_indexer_alt_framework_store_traits::Store>::Connection<'a>,
             ) -> anyhow::Result<usize> {
-                let collection = conn.database().collection::<$value>($collection);
-                $crate::handlers::bulk_insert_unordered(&collection, batch).await
+                $crate::handlers::bulk_insert_unordered(conn.database(), $collection, batch).await
             }
         }
     };
@@ -57,8 +71,8 @@ pub fn is_vortex_tx(tx: &ExecutedTransaction, package_address: SuiAddress) -> bo
         .unwrap_or(false)
 }
 
-pub fn u256_to_hex(value: &[u8; 32]) -> String {
-    format!("0x{}", hex::encode(value))
+pub const fn u256_to_hex(value: &[u8; 32]) -> HexU256 {
+    HexU256::from_le_bytes(*value)
 }
 
 pub fn bytes_to_address(bytes: &[u8; 32]) -> SuiAddress {
@@ -67,19 +81,64 @@ pub fn bytes_to_address(bytes: &[u8; 32]) -> SuiAddress {
 
 static COIN_TYPE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(.+)>").unwrap());
 
+/// An address at the start of a type tag or of one of its type arguments.
+static TYPE_ADDRESS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(^|[<,\s])(?:0x)?([0-9a-fA-F]{1,64})::").unwrap());
+
+/// Width of a Sui address in hex digits.
+const ADDRESS_HEX_LEN: usize = 64;
+
+/// Extracts the coin type argument of an event type, in the canonical form of
+/// [`normalize_coin_type`].
 pub fn extract_coin_type(type_str: &str) -> Option<String> {
     COIN_TYPE_RE
         .captures(type_str)
         .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().to_string())
+        .map(|m| normalize_coin_type(m.as_str()))
+}
+
+/// Rewrites every address in a type tag to its canonical long form, so
+/// `0x2::sui::SUI` and the zero-padded `0x00..02::sui::SUI` are stored alike.
+pub fn normalize_coin_type(coin_type: &str) -> String {
+    TYPE_ADDRESS_RE
+        .replace_all(coin_type, |caps: &regex::Captures| {
+            format!(
+                "{}0x{:0>width$}::",
+                &caps[1],
+                caps[2].to_lowercase(),
+                width = ADDRESS_HEX_LEN
+            )
+        })
+        .into_owned()
+}
+
+pub fn tx_metadata(tx: &ExecutedTransaction) -> TxMetadata {
+    gas_metadata(tx.transaction.gas_price(), tx.effects.gas_cost_summary())
+}
+
+pub fn gas_metadata(gas_price: u64, gas: &GasCostSummary) -> TxMetadata {
+    TxMetadata {
+        gas_price,
+        gas_used: gas.net_gas_usage(),
+        computation_cost: gas.computation_cost,
+        storage_cost: gas.storage_cost,
+        storage_rebate: gas.storage_rebate,
+        non_refundable_storage_fee: gas.non_refundable_storage_fee,
+    }
 }
 
 const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
 
 /// Performs an unordered bulk insert into MongoDB, continuing on duplicate key errors.
 /// Returns the number of successfully inserted documents.
+///
+/// Documents the server rejects for any other reason are moved to the dead
+/// letter collection instead of failing the batch, so one bad row does not
+/// stall the pipeline. Errors without per-document details (e.g. a lost
+/// connection) are returned and the framework retries the batch.
 pub async fn bulk_insert_unordered<T>(
-    collection: &mongodb::Collection<T>,
+    database: &Database,
+    collection: &str,
     batch: &[T],
 ) -> Result<usize>
 where
@@ -91,19 +150,40 @@ where
 
     let options = InsertManyOptions::builder().ordered(false).build();
 
-    match collection.insert_many(batch).with_options(options).await {
-        Ok(result) => Ok(result.inserted_ids.len()),
+    match database
+        .collection::<T>(collection)
+        .insert_many(batch)
+        .with_options(options)
+        .await
+    {
+        Ok(result) => {
+            let inserted = result.inserted_ids.len();
+            dedup::record_writes(database, collection, inserted, &[]).await;
+            Ok(inserted)
+        }
         Err(e) => {
             if let mongodb::error::ErrorKind::InsertMany(ref insert_err) = *e.kind {
                 if let Some(write_errors) = &insert_err.write_errors {
-                    let all_duplicates = write_errors
+                    let rejected: Vec<_> = write_errors
                         .iter()
-                        .all(|err| err.code == DUPLICATE_KEY_ERROR_CODE);
+                        .filter(|err| err.code != DUPLICATE_KEY_ERROR_CODE)
+                        .collect();
 
-                    if all_duplicates {
-                        let inserted = batch.len().saturating_sub(write_errors.len());
-                        return Ok(inserted);
+                    for err in &rejected {
+                        let Some(value) = batch.get(err.index) else {
+                            continue;
+                        };
+                        dead_letter(database, collection, value, &err.message).await?;
                     }
+
+                    let duplicates: Vec<_> = write_errors
+                        .iter()
+                        .filter(|err| err.code == DUPLICATE_KEY_ERROR_CODE)
+                        .map(|err| batch.get(err.index).and_then(document_checkpoint))
+                        .collect();
+                    let inserted = batch.len().saturating_sub(write_errors.len());
+                    dedup::record_writes(database, collection, inserted, &duplicates).await;
+                    return Ok(inserted);
                 }
             }
             Err(e.into())
@@ -111,6 +191,56 @@ where
     }
 }
 
+/// The `checkpoint` field of a stored document, if it has one.
+fn document_checkpoint<T: serde::Serialize>(value: &T) -> Option<u64> {
+    let document = bson::to_document(value).ok()?;
+    match document.get("checkpoint")? {
+        Bson::Int64(checkpoint) => u64::try_from(*checkpoint).ok(),
+        Bson::Int32(checkpoint) => u64::try_from(*checkpoint).ok(),
+        _ => None,
+    }
+}
+
+/// Records a document rejected by `collection` in the dead letter collection.
+async fn dead_letter<T: serde::Serialize>(
+    database: &Database,
+    collection: &str,
+    value: &T,
+    error: &str,
+) -> Result<()> {
+    let payload = bson::to_document(value).context("Failed to encode dead letter payload")?;
+    let document_id = match payload.get("_id") {
+        Some(Bson::String(id)) => id.clone(),
+        Some(id) => id.to_string(),
+        None => bson::oid::ObjectId::new().to_hex(),
+    };
+    let id = format!("{collection}:{document_id}");
+    let now = bson::DateTime::now();
+
+    warn!(collection, id = %id, error, "Moving rejected document to dead letters");
+
+    database
+        .collection::<DeadLetter>(collections::DEAD_LETTERS)
+        .update_one(
+            doc! { "_id": &id },
+            doc! {
+                "$setOnInsert": {
+                    "source": "indexer",
+                    "collection": collection,
+                    "payload": payload,
+                    "created_at": now,
+                },
+                "$set": { "error": error, "last_attempt_at": now },
+                "$inc": { "attempts": 1 },
+            },
+        )
+        .upsert(true)
+        .await
+        .context("Failed to write dead letter")?;
+
+    Ok(())
+}
+
 pub fn process_vortex_events<TEvent, TResult, F>(
     transactions: &[ExecutedTransaction],
     package_address: SuiAddress,
@@ -122,6 +252,40 @@ pub fn process_vortex_events<TEvent, TResult, F>(
 where
     TEvent: DeserializeOwned,
     F: FnMut(TEvent, String, String, String, u64, u64, usize) -> TResult,
+{
+    process_vortex_tx_events(
+        transactions,
+        package_address,
+        event_name,
+        checkpoint_seq,
+        checkpoint_ts,
+        |event, _tx, digest, sender, coin_type, checkpoint_seq, checkpoint_ts, idx| {
+            map_event(
+                event,
+                digest,
+                sender,
+                coin_type,
+                checkpoint_seq,
+                checkpoint_ts,
+                idx,
+            )
+        },
+    )
+}
+
+/// Like [`process_vortex_events`], but also hands the originating transaction to
+/// the mapper so handlers can record transaction-level data such as gas.
+pub fn process_vortex_tx_events<TEvent, TResult, F>(
+    transactions: &[ExecutedTransaction],
+    package_address: SuiAddress,
+    event_name: &str,
+    checkpoint_seq: u64,
+    checkpoint_ts: u64,
+    mut map_event: F,
+) -> Vec<TResult>
+where
+    TEvent: DeserializeOwned,
+    F: FnMut(TEvent, &ExecutedTransaction, String, String, String, u64, u64, usize) -> TResult,
 {
     let mut results = Vec::new();
     let account_address = package_address.into();
@@ -166,6 +330,7 @@ where
 
             results.push(map_event(
                 event,
+                tx,
                 digest.clone(),
                 sender.clone(),
                 coin_type,
@@ -25,6 +25,8 @@ impl Processor for NewPoolHandler {
     type Value = NewPool;
 
     async fn process(&self, checkpoint: &Arc<Checkpoint>) -> Result<Vec<Self::Value>> {
+        crate::throttle::pace(checkpoint.summary.sequence_number).await;
+
         let results = process_vortex_events(
             &checkpoint.transactions,
             self.env.package_address,
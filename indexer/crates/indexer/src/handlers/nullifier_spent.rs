@@ -1,4 +1,4 @@
-use crate::handlers::{process_vortex_events, u256_to_hex};
+use crate::handlers::{process_vortex_tx_events, tx_metadata, u256_to_hex};
 use crate::models::NullifierSpentEvent;
 use crate::VortexEnv;
 use anyhow::Result;
@@ -25,13 +25,16 @@ impl Processor for NullifierSpentHandler {
     type Value = NullifierSpent;
 
     async fn process(&self, checkpoint: &Arc<Checkpoint>) -> Result<Vec<Self::Value>> {
-        let results = process_vortex_events(
+        crate::throttle::pace(checkpoint.summary.sequence_number).await;
+
+        let results = process_vortex_tx_events(
             &checkpoint.transactions,
             self.env.package_address,
             "NullifierSpent",
             checkpoint.summary.sequence_number,
             checkpoint.summary.timestamp_ms,
             |event: NullifierSpentEvent,
+             tx,
              digest,
              sender,
              coin_type,
@@ -48,6 +51,7 @@ impl Processor for NullifierSpentHandler {
                     },
                     coin_type,
                     nullifier: u256_to_hex(&event.0),
+                    tx: Some(tx_metadata(tx)),
                 }
             },
         );
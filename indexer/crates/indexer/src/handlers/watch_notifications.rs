@@ -0,0 +1,113 @@
+use crate::backpressure::fill_batch;
+use crate::handlers::{bulk_insert_unordered, NullifierSpentHandler};
+use crate::store::MongoStore;
+use crate::VortexEnv;
+use anyhow::Result;
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::concurrent::{BatchStatus, Handler};
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_indexer_alt_framework_store_traits::Store;
+use sui_types::full_checkpoint_content::Checkpoint;
+use vortex_schema::{collections, HexU256, NullifierSpent, Watch, WatchKind, WatchNotification};
+
+/// Matches spent nullifiers against user watches and records a notification for
+/// each hit. Delivery (webhook or SSE) is left to the API, which owns the
+/// watches collection.
+pub struct WatchNotificationHandler {
+    nullifiers: NullifierSpentHandler,
+}
+
+impl WatchNotificationHandler {
+    #[must_use]
+    pub const fn new(env: VortexEnv) -> Self {
+        Self {
+            nullifiers: NullifierSpentHandler::new(env),
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for WatchNotificationHandler {
+    const NAME: &'static str = "watch_notifications";
+    type Value = NullifierSpent;
+
+    async fn process(&self, checkpoint: &Arc<Checkpoint>) -> Result<Vec<Self::Value>> {
+        self.nullifiers.process(checkpoint).await
+    }
+}
+
+#[async_trait]
+impl Handler for WatchNotificationHandler {
+    type Store = MongoStore;
+    type Batch = Vec<NullifierSpent>;
+
+    fn batch(
+        &self,
+        batch: &mut Self::Batch,
+        values: &mut std::vec::IntoIter<NullifierSpent>,
+    ) -> BatchStatus {
+        fill_batch(Self::NAME, batch, values)
+    }
+
+    async fn commit<'a>(
+        &self,
+        batch: &Self::Batch,
+        conn: &mut <Self::Store as Store>::Connection<'a>,
+    ) -> Result<usize> {
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let nullifiers: Vec<&HexU256> = batch.iter().map(|n| &n.nullifier).collect();
+
+        let watches = conn.database().collection::<Watch>(collections::WATCHES);
+        let mut cursor = watches
+            .find(doc! { "kind": "nullifier", "value": { "$in": nullifiers } })
+            .await?;
+
+        let mut by_value: HashMap<HexU256, Vec<Watch>> = HashMap::new();
+        while cursor.advance().await? {
+            let watch = cursor.deserialize_current()?;
+            by_value.entry(watch.value).or_default().push(watch);
+        }
+
+        if by_value.is_empty() {
+            return Ok(0);
+        }
+
+        let now = DateTime::now();
+        let notifications: Vec<WatchNotification> = batch
+            .iter()
+            .flat_map(|spent| {
+                by_value
+                    .get(&spent.nullifier)
+                    .into_iter()
+                    .flatten()
+                    .map(move |watch| WatchNotification {
+                        id: format!("{}:{}", watch.id, spent.base.event_digest),
+                        watch_id: watch.id.clone(),
+                        kind: WatchKind::Nullifier,
+                        value: spent.nullifier,
+                        event_digest: spent.base.event_digest.clone(),
+                        digest: spent.base.digest.clone(),
+                        coin_type: spent.coin_type.clone(),
+                        checkpoint: spent.base.checkpoint,
+                        checkpoint_timestamp_ms: spent.base.checkpoint_timestamp_ms,
+                        webhook_url: watch.webhook_url.clone(),
+                        delivered: false,
+                        created_at: now,
+                    })
+            })
+            .collect();
+
+        bulk_insert_unordered(
+            conn.database(),
+            collections::WATCH_NOTIFICATIONS,
+            &notifications,
+        )
+        .await
+    }
+}
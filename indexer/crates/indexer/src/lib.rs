@@ -2,9 +2,17 @@ use std::str::FromStr;
 use sui_types::base_types::SuiAddress;
 use url::Url;
 
+pub mod backpressure;
+pub mod dedup;
+pub mod doctor;
 pub mod handlers;
+pub mod migrate;
 pub mod models;
+pub mod output;
+pub mod prices;
 pub mod store;
+pub mod throttle;
+pub mod watchdog;
 
 pub const MAINNET_REMOTE_STORE_URL: &str = "https://checkpoints.mainnet.sui.io";
 pub const TESTNET_REMOTE_STORE_URL: &str = "https://checkpoints.testnet.sui.io";
@@ -69,6 +77,38 @@ impl std::fmt::Display for SuiNetwork {
     }
 }
 
+/// Which pipelines the indexer runs.
+///
+/// `Full` stores every pool, commitment and nullifier. `Analytics` only keeps
+/// per-checkpoint counters, for deployments that must not retain events. Both
+/// record the leaf count of each pool over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexerMode {
+    Full,
+    Analytics,
+}
+
+impl FromStr for IndexerMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "analytics" => Ok(Self::Analytics),
+            _ => Err(format!("Unknown mode '{}'. Use: full or analytics", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for IndexerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Analytics => write!(f, "analytics"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct VortexEnv {
     pub network: SuiNetwork,
@@ -1,25 +1,43 @@
 use anyhow::Context;
 use clap::Parser;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use sui_indexer_alt_framework::{
     ingestion::{
         ingestion_client::IngestionClientArgs, streaming_client::StreamingClientArgs, ClientArgs,
-        IngestionConfig,
     },
     pipeline::{concurrent::ConcurrentConfig, CommitterConfig},
     Indexer, IndexerArgs, TaskArgs,
 };
+use sui_indexer_alt_metrics::{MetricsArgs, MetricsService};
 use tracing::info;
+use vortex_runtime::Supervisor;
 
 use vortex_indexer::{
-    handlers::{NewCommitmentHandler, NewPoolHandler, NullifierSpentHandler},
+    backpressure::BackpressureConfig,
+    dedup,
+    doctor::{self, DoctorConfig},
+    handlers::{
+        CheckpointStatsHandler, LeafCountHandler, NewCommitmentHandler, NewPoolHandler,
+        NullifierSpentHandler, WatchNotificationHandler,
+    },
+    migrate,
+    output::{Envelope, OutputFormat},
     parse_package_address,
+    prices::StaticPriceSource,
     store::MongoStore,
-    SuiNetwork, VortexEnv,
+    throttle::ThrottleConfig,
+    watchdog::WatchdogConfig,
+    IndexerMode, SuiNetwork, VortexEnv,
 };
 
 const DEFAULT_VORTEX_PACKAGE: &str =
     "0xd9d3b65c318e7d7dd208050a28e113a45256765b4c45acd119626d8a228d7555";
 const DEFAULT_FIRST_CHECKPOINT: &str = "289686776";
+const DEFAULT_METRICS_ADDRESS: &str = "0.0.0.0:9184";
 
 #[derive(Parser)]
 #[clap(
@@ -47,6 +65,70 @@ struct Config {
 
     #[clap(long, env, default_value = "10")]
     write_concurrency: usize,
+
+    /// Checkpoints buffered between ingestion and the pipelines before fetching pauses
+    #[clap(long, env, default_value_t = vortex_indexer::backpressure::DEFAULT_CHECKPOINT_BUFFER_SIZE)]
+    checkpoint_buffer_size: usize,
+
+    #[clap(long, env, default_value_t = vortex_indexer::backpressure::DEFAULT_INGEST_CONCURRENCY)]
+    ingest_concurrency: usize,
+
+    /// Rows a pipeline buffers before its batch is handed to the committer
+    #[clap(long, env, default_value_t = vortex_indexer::backpressure::DEFAULT_MAX_BATCH_ROWS)]
+    max_batch_rows: usize,
+
+    /// Caps checkpoint processing, and with it remote store requests, per second
+    #[clap(long, env)]
+    max_checkpoints_per_second: Option<u32>,
+
+    /// Delay before a failed checkpoint fetch (e.g. 429 or 5xx) is retried
+    #[clap(long, env, default_value_t = vortex_indexer::throttle::DEFAULT_RETRY_INTERVAL_MS)]
+    ingest_retry_interval_ms: u64,
+
+    /// Seconds between samples of both checkpoint sources; 0 disables the watchdog
+    #[clap(long, env, default_value_t = vortex_indexer::watchdog::DEFAULT_WATCHDOG_INTERVAL_SECS)]
+    source_watchdog_interval_secs: u64,
+
+    /// A source that has not advanced for this long is reported stale
+    #[clap(long, env, default_value_t = vortex_indexer::watchdog::DEFAULT_STALE_AFTER_SECS)]
+    source_stale_after_secs: u64,
+
+    /// Checkpoints a source may trail the other before it is reported lagging
+    #[clap(long, env, default_value_t = vortex_indexer::watchdog::DEFAULT_MAX_SOURCE_LAG)]
+    max_source_lag: u64,
+
+    /// `full` stores every event; `analytics` only per-checkpoint counters.
+    /// Both record pool leaf counts
+    #[clap(long, env, default_value = "full")]
+    indexer_mode: IndexerMode,
+
+    /// JSON file of daily USD coin prices; analytics stats are valued with it
+    #[clap(long, env)]
+    price_file: Option<PathBuf>,
+
+    #[clap(long, env, default_value = DEFAULT_METRICS_ADDRESS)]
+    metrics_address: SocketAddr,
+
+    /// Seconds tasks get to stop on SIGINT/SIGTERM before they are aborted
+    #[clap(long, env, default_value_t = vortex_runtime::DEFAULT_SHUTDOWN_GRACE_SECS)]
+    shutdown_grace_secs: u64,
+
+    /// Run the startup checks, print the checklist and exit
+    #[clap(long)]
+    doctor: bool,
+
+    /// Rewrite stored coin types to the canonical long form and exit
+    #[clap(long)]
+    migrate_coin_types: bool,
+
+    /// Print inserted and duplicate documents of recent runs and exit
+    #[clap(long)]
+    status: bool,
+
+    /// Output of --doctor, --migrate-coin-types and --status: text, or json
+    /// envelopes of `{ version, status, data, warnings }` for scripts
+    #[clap(long, default_value = "text")]
+    output: OutputFormat,
 }
 
 #[tokio::main]
@@ -59,11 +141,92 @@ async fn main() -> anyhow::Result<()> {
 
     let config = Config::parse();
 
+    let (report, store) = doctor::run(&DoctorConfig {
+        network: config.sui_network,
+        vortex_package: config.vortex_package.clone(),
+        mongodb_uri: config.mongodb_uri.clone(),
+        mongodb_database: config.mongodb_database.clone(),
+    })
+    .await;
+    let json = config.output == OutputFormat::Json;
+
+    if config.doctor {
+        if json {
+            let failed = report.checks.iter().filter(|check| !check.passed()).count();
+            let envelope = if report.passed() {
+                Envelope::ok(&report)
+            } else {
+                Envelope::error(Some(&report), format!("{failed} startup checks failed"))
+            };
+            envelope.print()?;
+        } else {
+            println!("{report}");
+        }
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+    if json {
+        eprintln!("{report}");
+    } else {
+        println!("{report}");
+    }
+    let store = store.context("Startup checks failed, see the checklist above");
+
+    if config.migrate_coin_types {
+        let migration = match store {
+            Ok(store) => migrate::normalize_coin_types(store.database())
+                .await
+                .context("Coin type migration failed"),
+            Err(e) => Err(e),
+        };
+        return print_report(migration, config.output);
+    }
+    if config.status {
+        let status = match store {
+            Ok(store) => dedup::status(store.database())
+                .await
+                .context("Failed to read indexer runs"),
+            Err(e) => Err(e),
+        };
+        return print_report(status, config.output);
+    }
+    let store = store?;
+
     let package_address =
         parse_package_address(&config.vortex_package).context("Invalid VORTEX_PACKAGE address")?;
 
     let env = VortexEnv::new(config.sui_network, package_address);
 
+    let backpressure = BackpressureConfig {
+        checkpoint_buffer_size: config.checkpoint_buffer_size,
+        ingest_concurrency: config.ingest_concurrency,
+        max_batch_rows: config.max_batch_rows,
+    };
+
+    let registry = prometheus::Registry::new();
+    let mut supervisor = Supervisor::new();
+    backpressure
+        .install(&registry)
+        .context("Invalid backpressure configuration")?;
+
+    let throttle = ThrottleConfig {
+        max_checkpoints_per_second: config.max_checkpoints_per_second,
+        retry_interval_ms: config.ingest_retry_interval_ms,
+    };
+    throttle
+        .install(&registry)
+        .context("Invalid throttle configuration")?;
+
+    let watchdog = WatchdogConfig {
+        interval_secs: config.source_watchdog_interval_secs,
+        stale_after_secs: config.source_stale_after_secs,
+        max_lag_checkpoints: config.max_source_lag,
+    };
+    watchdog
+        .start(config.sui_network, &registry, &mut supervisor)
+        .context("Invalid source watchdog configuration")?;
+
+    dedup::start_run(store.database(), &registry).await?;
+
     let pipeline_config = ConcurrentConfig {
         committer: CommitterConfig {
             write_concurrency: config.write_concurrency,
@@ -74,19 +237,17 @@ async fn main() -> anyhow::Result<()> {
 
     info!(
         network = %config.sui_network,
+        mode = %config.indexer_mode,
         package = %config.vortex_package,
         mongodb = %config.mongodb_uri,
         database = %config.mongodb_database,
         write_concurrency = config.write_concurrency,
+        checkpoint_buffer_size = config.checkpoint_buffer_size,
+        max_batch_rows = config.max_batch_rows,
+        max_checkpoints_per_second = ?config.max_checkpoints_per_second,
         "Starting Vortex Indexer"
     );
 
-    let store = MongoStore::new(&config.mongodb_uri, &config.mongodb_database)
-        .await
-        .context("Failed to connect to MongoDB")?;
-
-    info!("Connected to MongoDB");
-
     let client_args = ClientArgs {
         ingestion: IngestionClientArgs {
             remote_store_url: Some(env.remote_store_url()),
@@ -111,13 +272,73 @@ async fn main() -> anyhow::Result<()> {
         store,
         indexer_args,
         client_args,
-        IngestionConfig::default(),
+        throttle.apply(backpressure.ingestion_config()),
         None,
-        &prometheus::Registry::new(),
+        &registry,
     )
     .await
     .context("Failed to create indexer")?;
 
+    match config.indexer_mode {
+        IndexerMode::Full => {
+            register_full_pipelines(&mut indexer, env, pipeline_config.clone()).await?
+        }
+        IndexerMode::Analytics => {
+            let mut handler = CheckpointStatsHandler::new(env);
+            if let Some(path) = &config.price_file {
+                handler = handler.with_prices(Arc::new(StaticPriceSource::from_file(path)?));
+            }
+            indexer
+                .concurrent_pipeline(handler, pipeline_config.clone())
+                .await
+                .context("Failed to register CheckpointStatsHandler pipeline")?
+        }
+    }
+
+    indexer
+        .concurrent_pipeline(LeafCountHandler::new(env), pipeline_config)
+        .await
+        .context("Failed to register LeafCountHandler pipeline")?;
+
+    let metrics = MetricsService::new(
+        MetricsArgs {
+            metrics_address: config.metrics_address,
+        },
+        registry,
+    );
+
+    info!("All pipelines registered, starting indexer...");
+
+    let metrics_service = metrics
+        .run()
+        .await
+        .context("Failed to start metrics service")?;
+
+    let service = indexer
+        .run()
+        .await
+        .context("Failed to start indexer")?
+        .merge(metrics_service);
+    supervisor.spawn_critical("indexer", async move {
+        service.join().await?;
+        anyhow::Ok(())
+    });
+
+    supervisor.shutdown_on_signal();
+    supervisor
+        .join(Duration::from_secs(config.shutdown_grace_secs))
+        .await?;
+
+    info!("Indexer stopped");
+
+    Ok(())
+}
+
+async fn register_full_pipelines(
+    indexer: &mut Indexer<MongoStore>,
+    env: VortexEnv,
+    pipeline_config: ConcurrentConfig,
+) -> anyhow::Result<()> {
     indexer
         .concurrent_pipeline(NewPoolHandler::new(env), pipeline_config.clone())
         .await
@@ -129,20 +350,34 @@ async fn main() -> anyhow::Result<()> {
         .context("Failed to register NewCommitmentHandler pipeline")?;
 
     indexer
-        .concurrent_pipeline(NullifierSpentHandler::new(env), pipeline_config)
+        .concurrent_pipeline(NullifierSpentHandler::new(env), pipeline_config.clone())
         .await
         .context("Failed to register NullifierSpentHandler pipeline")?;
 
-    info!("All pipelines registered, starting indexer...");
-
     indexer
-        .run()
+        .concurrent_pipeline(WatchNotificationHandler::new(env), pipeline_config)
         .await
-        .context("Failed to start indexer")?
-        .join()
-        .await?;
-
-    info!("Indexer stopped");
+        .context("Failed to register WatchNotificationHandler pipeline")?;
 
     Ok(())
 }
+
+/// Prints the outcome of an admin command as text or as an envelope; a
+/// failure in json mode still exits non-zero.
+fn print_report<T: Serialize + std::fmt::Display>(
+    report: anyhow::Result<T>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    match (report, output) {
+        (Ok(report), OutputFormat::Text) => {
+            println!("{report}");
+            Ok(())
+        }
+        (Ok(report), OutputFormat::Json) => Envelope::ok(report).print(),
+        (Err(e), OutputFormat::Text) => Err(e),
+        (Err(e), OutputFormat::Json) => {
+            Envelope::<T>::error(None, format!("{e:#}")).print()?;
+            std::process::exit(1);
+        }
+    }
+}
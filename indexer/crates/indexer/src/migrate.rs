@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use mongodb::bson::{self, doc, Document};
+use mongodb::Database;
+use serde::Serialize;
+use tracing::info;
+use vortex_schema::{collections, CheckpointStats};
+
+use crate::handlers::normalize_coin_type;
+
+/// Collections whose documents carry a `coin_type` outside their `_id`.
+const EVENT_COLLECTIONS: &[&str] = &[
+    collections::NEW_POOLS,
+    collections::NEW_COMMITMENTS,
+    collections::NULLIFIERS_SPENT,
+    collections::WATCH_NOTIFICATIONS,
+];
+
+/// Documents rewritten by [`normalize_coin_types`], per collection.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    pub rewritten: Vec<(&'static str, u64)>,
+}
+
+impl std::fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (collection, count) in &self.rewritten {
+            writeln!(f, "{collection}: {count} documents rewritten")?;
+        }
+        let total: u64 = self.rewritten.iter().map(|(_, count)| count).sum();
+        write!(f, "{total} documents rewritten in total")
+    }
+}
+
+/// Rewrites the coin types stored by older indexer versions to the canonical
+/// long form `extract_coin_type` now produces. Safe to rerun: coin types
+/// already canonical are left alone.
+///
+/// Checkpoint stats embed the coin type in their `_id`, so they are copied to
+/// the canonical id and the original removed. If the checkpoint was already
+/// reprocessed under the canonical id, that copy is kept as is.
+pub async fn normalize_coin_types(database: &Database) -> Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+
+    for &collection in EVENT_COLLECTIONS {
+        let rewritten = rewrite_event_collection(database, collection).await?;
+        report.rewritten.push((collection, rewritten));
+    }
+
+    let rewritten = rewrite_checkpoint_stats(database).await?;
+    report
+        .rewritten
+        .push((collections::CHECKPOINT_STATS, rewritten));
+
+    Ok(report)
+}
+
+/// Stored coin types whose canonical form differs, with that form.
+async fn stale_coin_types(database: &Database, collection: &str) -> Result<Vec<(String, String)>> {
+    let values = database
+        .collection::<Document>(collection)
+        .distinct("coin_type", doc! {})
+        .await
+        .with_context(|| format!("Failed to list coin types of {collection}"))?;
+
+    Ok(values
+        .iter()
+        .filter_map(|value| value.as_str())
+        .map(|coin_type| (coin_type.to_string(), normalize_coin_type(coin_type)))
+        .filter(|(stored, canonical)| stored != canonical)
+        .collect())
+}
+
+async fn rewrite_event_collection(database: &Database, collection: &str) -> Result<u64> {
+    let mut rewritten = 0;
+
+    for (stored, canonical) in stale_coin_types(database, collection).await? {
+        let result = database
+            .collection::<Document>(collection)
+            .update_many(
+                doc! { "coin_type": stored.as_str() },
+                doc! { "$set": { "coin_type": canonical.as_str() } },
+            )
+            .await
+            .with_context(|| format!("Failed to rewrite {stored} in {collection}"))?;
+
+        info!(
+            collection,
+            from = %stored,
+            to = %canonical,
+            count = result.modified_count,
+            "Rewrote coin type"
+        );
+        rewritten += result.modified_count;
+    }
+
+    Ok(rewritten)
+}
+
+async fn rewrite_checkpoint_stats(database: &Database) -> Result<u64> {
+    let stats = database.collection::<CheckpointStats>(collections::CHECKPOINT_STATS);
+    let mut rewritten = 0;
+
+    for (stored, canonical) in stale_coin_types(database, collections::CHECKPOINT_STATS).await? {
+        let mut cursor = stats
+            .find(doc! { "coin_type": stored.as_str() })
+            .await
+            .with_context(|| format!("Failed to read checkpoint stats of {stored}"))?;
+
+        while cursor
+            .advance()
+            .await
+            .context("Failed to read checkpoint stats")?
+        {
+            let old = cursor
+                .deserialize_current()
+                .context("Failed to decode checkpoint stats")?;
+            let new = CheckpointStats {
+                id: format!("{}:{canonical}", old.checkpoint),
+                coin_type: canonical.clone(),
+                ..old.clone()
+            };
+            let document = bson::to_document(&new).context("Failed to encode checkpoint stats")?;
+
+            stats
+                .update_one(
+                    doc! { "_id": new.id.as_str() },
+                    doc! { "$setOnInsert": document },
+                )
+                .upsert(true)
+                .await
+                .with_context(|| format!("Failed to write checkpoint stats {}", new.id))?;
+            stats
+                .delete_one(doc! { "_id": old.id.as_str() })
+                .await
+                .with_context(|| format!("Failed to remove checkpoint stats {}", old.id))?;
+
+            rewritten += 1;
+        }
+
+        info!(from = %stored, to = %canonical, "Rewrote checkpoint stats coin type");
+    }
+
+    Ok(rewritten)
+}
@@ -0,0 +1,81 @@
+//! Versioned JSON envelope the admin commands print with `--output json`,
+//! the same shape as the CLIs of the circuit crate print.
+
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Bumped when the envelope or the `data` of a command changes incompatibly.
+pub const OUTPUT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown output '{}'. Use: text or json", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub status: Status,
+    pub data: Option<T>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T: Serialize> Envelope<T> {
+    pub fn ok(data: T) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            status: Status::Ok,
+            data: Some(data),
+            warnings: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// A failed command, with whatever `data` it produced before failing.
+    pub fn error(data: Option<T>, error: impl std::fmt::Display) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            status: Status::Error,
+            data,
+            warnings: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn print(&self) -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string_pretty(self)?);
+        Ok(())
+    }
+}
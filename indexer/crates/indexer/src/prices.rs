@@ -0,0 +1,84 @@
+use crate::handlers::normalize_coin_type;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Price of one whole coin on one day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoinPrice {
+    pub usd: f64,
+    /// Decimals of the coin, to convert base units to whole coins
+    pub decimals: u8,
+}
+
+/// Daily coin prices the analytics pipeline values amounts with.
+///
+/// Lookups happen while processing checkpoints, so sources answer from memory;
+/// ones backed by a remote service should load their prices up front.
+pub trait PriceSource: Send + Sync {
+    /// Price of `coin_type` on the UTC day containing `timestamp_ms`, if known.
+    fn price(&self, coin_type: &str, timestamp_ms: u64) -> Option<CoinPrice>;
+}
+
+#[derive(Deserialize)]
+struct StaticCoin {
+    decimals: u8,
+    /// USD price by `YYYY-MM-DD` day
+    prices: BTreeMap<NaiveDate, f64>,
+}
+
+/// [`PriceSource`] read from a JSON file keyed by coin type:
+///
+/// ```json
+/// { "0x2::sui::SUI": { "decimals": 9, "prices": { "2025-01-01": 4.12 } } }
+/// ```
+///
+/// Days without a price are left unvalued rather than filled in.
+#[derive(Default)]
+pub struct StaticPriceSource {
+    coins: HashMap<String, StaticCoin>,
+}
+
+impl StaticPriceSource {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let coins: HashMap<String, StaticCoin> =
+            serde_json::from_str(json).context("Invalid price file")?;
+
+        for (coin_type, coin) in &coins {
+            if let Some((day, price)) = coin
+                .prices
+                .iter()
+                .find(|(_, price)| !price.is_finite() || **price < 0.0)
+            {
+                anyhow::bail!("Invalid price {price} for {coin_type} on {day}");
+            }
+        }
+
+        Ok(Self {
+            coins: coins
+                .into_iter()
+                .map(|(coin_type, coin)| (normalize_coin_type(&coin_type), coin))
+                .collect(),
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read price file {}", path.display()))?;
+        Self::from_json(&json).with_context(|| format!("Failed to load {}", path.display()))
+    }
+}
+
+impl PriceSource for StaticPriceSource {
+    fn price(&self, coin_type: &str, timestamp_ms: u64) -> Option<CoinPrice> {
+        let coin = self.coins.get(coin_type)?;
+        let day = DateTime::from_timestamp_millis(i64::try_from(timestamp_ms).ok()?)?.date_naive();
+
+        coin.prices.get(&day).map(|&usd| CoinPrice {
+            usd,
+            decimals: coin.decimals,
+        })
+    }
+}
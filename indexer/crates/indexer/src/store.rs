@@ -24,6 +24,20 @@ pub struct MongoConnection {
 }
 
 impl MongoStore {
+    /// `(collection, index name)` of every index created on startup
+    pub const INDEXES: &'static [(&'static str, &'static str)] = &[
+        (collections::NEW_COMMITMENTS, "coin_type_index_idx"),
+        (collections::NEW_COMMITMENTS, "checkpoint_idx"),
+        (collections::NULLIFIERS_SPENT, "coin_type_nullifier_idx"),
+        (collections::NULLIFIERS_SPENT, "sender_checkpoint_idx"),
+        (collections::WATCHES, "kind_value_idx"),
+        (collections::WATCH_NOTIFICATIONS, "watch_id_checkpoint_idx"),
+        (collections::NEW_POOLS, "coin_type_idx"),
+        (collections::NEW_POOLS, "pool_address_idx"),
+        (collections::CHECKPOINT_STATS, "coin_type_checkpoint_idx"),
+        (collections::LEAF_COUNTS, "coin_type_checkpoint_idx"),
+    ];
+
     pub async fn new(uri: &str, db_name: &str) -> Result<Self> {
         let mut client_options = ClientOptions::parse(uri)
             .await
@@ -54,6 +68,26 @@ impl MongoStore {
         &self.database
     }
 
+    /// Returns `collection.index` for each of [`Self::INDEXES`] the database lacks.
+    pub async fn missing_indexes(&self) -> Result<Vec<String>> {
+        let mut missing = Vec::new();
+
+        for (collection, index) in Self::INDEXES {
+            let names = self
+                .database
+                .collection::<bson::Document>(collection)
+                .list_index_names()
+                .await
+                .with_context(|| format!("Failed to list indexes of {collection}"))?;
+
+            if !names.iter().any(|name| name == index) {
+                missing.push(format!("{collection}.{index}"));
+            }
+        }
+
+        Ok(missing)
+    }
+
     async fn create_indexes(&self) -> Result<()> {
         self.create_index::<vortex_schema::NewCommitment>(
             collections::NEW_COMMITMENTS,
@@ -79,6 +113,30 @@ impl MongoStore {
         )
         .await?;
 
+        self.create_index::<vortex_schema::NullifierSpent>(
+            collections::NULLIFIERS_SPENT,
+            doc! { "sender": 1, "checkpoint": 1 },
+            Some("sender_checkpoint_idx"),
+            false,
+        )
+        .await?;
+
+        self.create_index::<vortex_schema::Watch>(
+            collections::WATCHES,
+            doc! { "kind": 1, "value": 1 },
+            Some("kind_value_idx"),
+            false,
+        )
+        .await?;
+
+        self.create_index::<vortex_schema::WatchNotification>(
+            collections::WATCH_NOTIFICATIONS,
+            doc! { "watch_id": 1, "checkpoint": 1 },
+            Some("watch_id_checkpoint_idx"),
+            false,
+        )
+        .await?;
+
         self.create_index::<vortex_schema::NewPool>(
             collections::NEW_POOLS,
             doc! { "coin_type": 1 },
@@ -95,6 +153,22 @@ impl MongoStore {
         )
         .await?;
 
+        self.create_index::<vortex_schema::CheckpointStats>(
+            collections::CHECKPOINT_STATS,
+            doc! { "coin_type": 1, "checkpoint": 1 },
+            Some("coin_type_checkpoint_idx"),
+            false,
+        )
+        .await?;
+
+        self.create_index::<vortex_schema::LeafCount>(
+            collections::LEAF_COUNTS,
+            doc! { "coin_type": 1, "checkpoint": 1 },
+            Some("coin_type_checkpoint_idx"),
+            false,
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use prometheus::{Counter, IntCounter, IntGauge, Registry};
+use std::time::{Duration, Instant};
+use sui_indexer_alt_framework::ingestion::IngestionConfig;
+
+pub const DEFAULT_RETRY_INTERVAL_MS: u64 = 200;
+
+static PACER: OnceCell<CheckpointPacer> = OnceCell::new();
+
+/// Cost controls for the remote checkpoint store.
+///
+/// `max_checkpoints_per_second` paces the pipelines; with the bounded
+/// checkpoint buffer in front of them this caps the fetch rate once the buffer
+/// fills, so public stores are not hit harder than configured. Transient
+/// fetch errors (429, 5xx) are retried by the framework's client every
+/// `retry_interval_ms`, and show up in its ingestion retry metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleConfig {
+    /// `None` disables pacing
+    pub max_checkpoints_per_second: Option<u32>,
+    pub retry_interval_ms: u64,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_checkpoints_per_second: None,
+            retry_interval_ms: DEFAULT_RETRY_INTERVAL_MS,
+        }
+    }
+}
+
+impl ThrottleConfig {
+    #[must_use]
+    pub fn apply(&self, config: IngestionConfig) -> IngestionConfig {
+        IngestionConfig {
+            retry_interval_ms: self.retry_interval_ms,
+            ..config
+        }
+    }
+
+    /// Installs the pacer shared by every pipeline and registers its metrics.
+    pub fn install(&self, registry: &Registry) -> Result<()> {
+        let Some(rate) = self.max_checkpoints_per_second else {
+            return Ok(());
+        };
+        anyhow::ensure!(
+            rate > 0,
+            "max_checkpoints_per_second must be greater than 0"
+        );
+
+        let pacer = CheckpointPacer::new(rate)?;
+        pacer.configured_rate.set(i64::from(rate));
+        pacer.register(registry)?;
+        PACER
+            .set(pacer)
+            .map_err(|_| anyhow::anyhow!("Checkpoint pacer is already installed"))
+    }
+}
+
+/// Releases checkpoints on a fixed schedule: checkpoint `n` is processed no
+/// earlier than `interval * (n - anchor)` after the first checkpoint seen.
+#[derive(Debug)]
+pub struct CheckpointPacer {
+    interval: Duration,
+    anchor: OnceCell<(Instant, u64)>,
+    configured_rate: IntGauge,
+    throttled_checkpoints: IntCounter,
+    throttled_seconds: Counter,
+}
+
+impl CheckpointPacer {
+    pub fn new(max_checkpoints_per_second: u32) -> Result<Self> {
+        Ok(Self {
+            interval: Duration::from_secs(1) / max_checkpoints_per_second,
+            anchor: OnceCell::new(),
+            configured_rate: IntGauge::new(
+                "vortex_indexer_max_checkpoints_per_second",
+                "Configured checkpoint processing rate limit",
+            )?,
+            throttled_checkpoints: IntCounter::new(
+                "vortex_indexer_throttled_checkpoints_total",
+                "Checkpoints delayed by the rate limiter",
+            )?,
+            throttled_seconds: Counter::new(
+                "vortex_indexer_throttled_seconds_total",
+                "Time pipelines spent waiting on the rate limiter",
+            )?,
+        })
+    }
+
+    fn register(&self, registry: &Registry) -> Result<()> {
+        registry
+            .register(Box::new(self.configured_rate.clone()))
+            .context("Failed to register rate limit metric")?;
+        registry
+            .register(Box::new(self.throttled_checkpoints.clone()))
+            .context("Failed to register throttled checkpoints metric")?;
+        registry
+            .register(Box::new(self.throttled_seconds.clone()))
+            .context("Failed to register throttled seconds metric")
+    }
+
+    /// Time left until `checkpoint` may be processed, given the anchor.
+    #[must_use]
+    pub fn delay(&self, checkpoint: u64, anchor: (Instant, u64), now: Instant) -> Duration {
+        let (start, first) = anchor;
+        let offset = u32::try_from(checkpoint.saturating_sub(first)).unwrap_or(u32::MAX);
+        (start + self.interval * offset).saturating_duration_since(now)
+    }
+
+    pub async fn wait(&self, checkpoint: u64) {
+        let now = Instant::now();
+        let anchor = *self.anchor.get_or_init(|| (now, checkpoint));
+        let delay = self.delay(checkpoint, anchor, now);
+
+        if !delay.is_zero() {
+            self.throttled_checkpoints.inc();
+            self.throttled_seconds.inc_by(delay.as_secs_f64());
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Waits until `checkpoint` may be processed under the installed rate limit.
+pub async fn pace(checkpoint: u64) {
+    if let Some(pacer) = PACER.get() {
+        pacer.wait(checkpoint).await;
+    }
+}
@@ -0,0 +1,418 @@
+use crate::{doctor::rpc, SuiNetwork};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::{IntGaugeVec, Opts, Registry};
+use serde_json::json;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use vortex_runtime::{CancellationToken, RestartPolicy, Supervisor};
+
+pub const DEFAULT_WATCHDOG_INTERVAL_SECS: u64 = 30;
+pub const DEFAULT_STALE_AFTER_SECS: u64 = 120;
+pub const DEFAULT_MAX_SOURCE_LAG: u64 = 100;
+
+/// Remote store requests one search for its newest checkpoint may make.
+const MAX_PROBES: u32 = 32;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The watchdog only reports, so it is restarted rather than taking the
+/// indexer down with it.
+const RESTART_POLICY: RestartPolicy = RestartPolicy::OnFailure {
+    max_restarts: 10,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+};
+
+static LATEST_CHECKPOINT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "vortex_indexer_source_latest_checkpoint",
+            "Newest checkpoint the ingestion source was seen serving",
+        ),
+        &["source"],
+    )
+    .expect("source latest checkpoint metric definition is valid")
+});
+
+static SOURCE_LAG: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "vortex_indexer_source_lag_checkpoints",
+            "Checkpoints the ingestion source is behind the other one",
+        ),
+        &["source"],
+    )
+    .expect("source lag metric definition is valid")
+});
+
+static SOURCE_STALE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "vortex_indexer_source_stale",
+            "1 while the ingestion source has not advanced within the staleness window",
+        ),
+        &["source"],
+    )
+    .expect("source stale metric definition is valid")
+});
+
+/// The two places checkpoints are ingested from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointSource {
+    /// The fullnode streaming new checkpoints
+    Streaming,
+    /// The checkpoint bucket backfills read from
+    RemoteStore,
+}
+
+impl std::fmt::Display for CheckpointSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Streaming => write!(f, "streaming"),
+            Self::RemoteStore => write!(f, "remote_store"),
+        }
+    }
+}
+
+/// Newest checkpoint a source was seen serving, and when that last changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceTracker {
+    latest: Option<u64>,
+    advanced_at: Option<Instant>,
+}
+
+impl SourceTracker {
+    /// Records a sample; checkpoints at or below the latest one are ignored.
+    pub fn observe(&mut self, checkpoint: u64, now: Instant) {
+        if self.latest.is_none_or(|latest| checkpoint > latest) {
+            self.latest = Some(checkpoint);
+            self.advanced_at = Some(now);
+        }
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Option<u64> {
+        self.latest
+    }
+
+    /// A source never seen serving a checkpoint counts as stale.
+    #[must_use]
+    pub fn is_stale(&self, now: Instant, stale_after: Duration) -> bool {
+        self.advanced_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= stale_after)
+    }
+}
+
+/// One source's state after a watchdog sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceStatus {
+    pub source: CheckpointSource,
+    pub latest: Option<u64>,
+    /// Checkpoints behind the other source, when both have been seen
+    pub lag: Option<u64>,
+    pub stale: bool,
+}
+
+impl SourceStatus {
+    #[must_use]
+    pub fn is_healthy(&self, max_lag: u64) -> bool {
+        !self.stale && self.lag.is_none_or(|lag| lag <= max_lag)
+    }
+}
+
+/// Compares the two sources, `[streaming, remote store]`.
+#[must_use]
+pub fn assess(
+    streaming: &SourceTracker,
+    remote_store: &SourceTracker,
+    now: Instant,
+    stale_after: Duration,
+) -> [SourceStatus; 2] {
+    let status = |source, tracker: &SourceTracker, other: &SourceTracker| SourceStatus {
+        source,
+        latest: tracker.latest(),
+        lag: tracker
+            .latest()
+            .zip(other.latest())
+            .map(|(latest, other)| other.saturating_sub(latest)),
+        stale: tracker.is_stale(now, stale_after),
+    };
+
+    [
+        status(CheckpointSource::Streaming, streaming, remote_store),
+        status(CheckpointSource::RemoteStore, remote_store, streaming),
+    ]
+}
+
+/// Tells whether a checkpoint can be fetched from a source.
+pub trait CheckpointProbe {
+    fn available(&self, checkpoint: u64) -> impl Future<Output = Result<bool>> + Send;
+}
+
+/// Counts the requests a search makes against [`MAX_PROBES`].
+struct ProbeBudget<'a, P> {
+    probe: &'a P,
+    used: u32,
+}
+
+impl<P: CheckpointProbe> ProbeBudget<'_, P> {
+    async fn available(&mut self, checkpoint: u64) -> Result<bool> {
+        self.used += 1;
+        self.probe.available(checkpoint).await
+    }
+
+    fn exhausted(&self) -> bool {
+        self.used >= MAX_PROBES
+    }
+}
+
+/// Newest checkpoint `probe` serves, assuming it serves a contiguous range.
+///
+/// Searches down from `head`, the streaming source's latest checkpoint, or up
+/// from `floor`, the last one found, when the head is unknown. Gallops to
+/// bracket the answer, then bisects. If the request budget runs out the best
+/// lower bound found is returned.
+pub async fn newest_available<P: CheckpointProbe>(
+    probe: &P,
+    floor: Option<u64>,
+    head: Option<u64>,
+) -> Result<Option<u64>> {
+    let mut probes = ProbeBudget { probe, used: 0 };
+
+    let (mut lo, mut hi) = match (head, floor) {
+        (Some(head), _) => {
+            if probes.available(head).await? {
+                return Ok(Some(head));
+            }
+            let mut hi = head;
+            let mut step = 1u64;
+            loop {
+                let candidate = head.saturating_sub(step);
+                if let Some(floor) = floor.filter(|&floor| candidate <= floor) {
+                    break (floor, hi);
+                }
+                if probes.exhausted() {
+                    return Ok(floor);
+                }
+                if probes.available(candidate).await? {
+                    break (candidate, hi);
+                }
+                if candidate == 0 {
+                    return Ok(None);
+                }
+                hi = candidate;
+                step = step.saturating_mul(2);
+            }
+        }
+        (None, Some(floor)) => {
+            let mut lo = floor;
+            let mut step = 1u64;
+            loop {
+                if probes.exhausted() {
+                    return Ok(Some(lo));
+                }
+                let candidate = floor.saturating_add(step);
+                if !probes.available(candidate).await? {
+                    break (lo, candidate);
+                }
+                lo = candidate;
+                step = step.saturating_mul(2);
+            }
+        }
+        (None, None) => return Ok(None),
+    };
+
+    while hi.saturating_sub(lo) > 1 && !probes.exhausted() {
+        let mid = lo + (hi - lo) / 2;
+        if probes.available(mid).await? {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(Some(lo))
+}
+
+struct RemoteStoreProbe {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl CheckpointProbe for RemoteStoreProbe {
+    async fn available(&self, checkpoint: u64) -> Result<bool> {
+        let url = format!("{}/{checkpoint}.chk", self.base_url);
+        let response = self
+            .http
+            .head(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to probe {url}"))?;
+
+        if response.status().is_server_error() {
+            anyhow::bail!("{url} returned {}", response.status());
+        }
+        Ok(response.status().is_success())
+    }
+}
+
+/// Periodically samples the newest checkpoint of both ingestion sources.
+///
+/// Lag and staleness are exported per source as metrics, and a warning is
+/// logged when a source becomes unhealthy: more than `max_lag_checkpoints`
+/// behind the other, or not advancing for `stale_after_secs`. The framework's
+/// ingestion client decides which source to read from; these signals show
+/// operators which one it is falling back from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchdogConfig {
+    /// 0 disables the watchdog
+    pub interval_secs: u64,
+    pub stale_after_secs: u64,
+    pub max_lag_checkpoints: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: DEFAULT_WATCHDOG_INTERVAL_SECS,
+            stale_after_secs: DEFAULT_STALE_AFTER_SECS,
+            max_lag_checkpoints: DEFAULT_MAX_SOURCE_LAG,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Registers the source metrics and spawns the sampling task on `supervisor`.
+    pub fn start(
+        &self,
+        network: SuiNetwork,
+        registry: &Registry,
+        supervisor: &mut Supervisor,
+    ) -> Result<()> {
+        if self.interval_secs == 0 {
+            return Ok(());
+        }
+        anyhow::ensure!(
+            self.stale_after_secs > 0,
+            "stale_after_secs must be greater than 0"
+        );
+
+        for metric in [&*LATEST_CHECKPOINT, &*SOURCE_LAG, &*SOURCE_STALE] {
+            registry
+                .register(Box::new(metric.clone()))
+                .context("Failed to register source watchdog metric")?;
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build source watchdog HTTP client")?;
+        let config = *self;
+        supervisor.spawn("source-watchdog", RESTART_POLICY, move |token| {
+            run(config, network, http.clone(), token)
+        });
+        Ok(())
+    }
+}
+
+async fn run(
+    config: WatchdogConfig,
+    network: SuiNetwork,
+    http: reqwest::Client,
+    token: CancellationToken,
+) -> Result<()> {
+    let streaming_url = network.streaming_url();
+    let probe = RemoteStoreProbe {
+        http: http.clone(),
+        base_url: network
+            .remote_store_url()
+            .as_str()
+            .trim_end_matches('/')
+            .to_string(),
+    };
+    let stale_after = Duration::from_secs(config.stale_after_secs);
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+    let mut streaming = SourceTracker::default();
+    let mut remote_store = SourceTracker::default();
+    let mut healthy = [true; 2];
+
+    loop {
+        tokio::select! {
+            () = token.cancelled() => return Ok(()),
+            _ = interval.tick() => {}
+        }
+
+        let head = match latest_streamed_checkpoint(&http, &streaming_url).await {
+            Ok(head) => Some(head),
+            Err(e) => {
+                warn!(source = %CheckpointSource::Streaming, "Watchdog sample failed: {e:#}");
+                None
+            }
+        };
+        let now = Instant::now();
+        if let Some(head) = head {
+            streaming.observe(head, now);
+        }
+
+        match newest_available(&probe, remote_store.latest(), head).await {
+            Ok(Some(latest)) => remote_store.observe(latest, now),
+            Ok(None) => {}
+            Err(e) => {
+                warn!(source = %CheckpointSource::RemoteStore, "Watchdog sample failed: {e:#}");
+            }
+        }
+
+        let statuses = assess(&streaming, &remote_store, now, stale_after);
+        for (status, was_healthy) in statuses.iter().zip(&mut healthy) {
+            report(status, config.max_lag_checkpoints, was_healthy);
+        }
+    }
+}
+
+fn report(status: &SourceStatus, max_lag: u64, was_healthy: &mut bool) {
+    let source = status.source.to_string();
+    if let Some(latest) = status.latest {
+        LATEST_CHECKPOINT
+            .with_label_values(&[source.as_str()])
+            .set(i64::try_from(latest).unwrap_or(i64::MAX));
+    }
+    if let Some(lag) = status.lag {
+        SOURCE_LAG
+            .with_label_values(&[source.as_str()])
+            .set(i64::try_from(lag).unwrap_or(i64::MAX));
+    }
+    SOURCE_STALE
+        .with_label_values(&[source.as_str()])
+        .set(i64::from(status.stale));
+
+    let healthy = status.is_healthy(max_lag);
+    if healthy != *was_healthy {
+        if healthy {
+            info!(source = %source, latest = ?status.latest, "Ingestion source recovered");
+        } else {
+            warn!(
+                source = %source,
+                latest = ?status.latest,
+                lag = ?status.lag,
+                stale = status.stale,
+                "Ingestion source is lagging"
+            );
+        }
+        *was_healthy = healthy;
+    }
+}
+
+async fn latest_streamed_checkpoint(http: &reqwest::Client, url: &url::Url) -> Result<u64> {
+    let latest = rpc(
+        http,
+        url,
+        "sui_getLatestCheckpointSequenceNumber",
+        json!([]),
+    )
+    .await?;
+    latest
+        .as_str()
+        .context("Latest checkpoint is not a string")?
+        .parse()
+        .context("Invalid latest checkpoint")
+}
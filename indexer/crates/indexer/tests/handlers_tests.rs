@@ -1,28 +1,83 @@
+use mongodb::bson::DateTime;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use sui_indexer_alt_framework::pipeline::concurrent::BatchStatus;
 use sui_types::base_types::SuiAddress;
-use vortex_indexer::handlers::{bytes_to_address, extract_coin_type, u256_to_hex};
+use sui_types::gas::GasCostSummary;
+use vortex_indexer::backpressure::{fill_batch, max_batch_rows};
+use vortex_indexer::dedup::{StatusReport, REPLAY_WINDOW};
+use vortex_indexer::handlers::{
+    bytes_to_address, extract_coin_type, gas_metadata, normalize_coin_type, public_value_amount,
+    tally_event, u256_to_hex,
+};
+use vortex_indexer::throttle::CheckpointPacer;
+use vortex_schema::{
+    collection_schemas, collections, CheckpointStats, CollectionWrites, HexU256, IndexerRun,
+    LeafCount, TxMetadata,
+};
+
+const SUI: &str = "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
 
 #[test]
 fn test_u256_to_hex() {
     let value = [0u8; 32];
     assert_eq!(
-        u256_to_hex(&value),
+        u256_to_hex(&value).to_string(),
         "0x0000000000000000000000000000000000000000000000000000000000000000"
     );
 
     let value = [0xff; 32];
     assert_eq!(
-        u256_to_hex(&value),
+        u256_to_hex(&value).to_string(),
         "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
     );
 
     let mut value = [0u8; 32];
     value[31] = 0x42;
     assert_eq!(
-        u256_to_hex(&value),
+        u256_to_hex(&value).to_string(),
         "0x0000000000000000000000000000000000000000000000000000000000000042"
     );
 }
 
+#[test]
+fn test_hex_u256_normalization() {
+    let mut bytes = [0u8; 32];
+    bytes[0] = 0x2a;
+    bytes[1] = 0x01;
+    let expected = HexU256::from_le_bytes(bytes);
+    let canonical = "0x2a01000000000000000000000000000000000000000000000000000000000000";
+
+    for input in [
+        canonical,
+        "2a01000000000000000000000000000000000000000000000000000000000000",
+        "0X2A01000000000000000000000000000000000000000000000000000000000000",
+        "298",
+    ] {
+        let parsed: HexU256 = input.parse().unwrap();
+        assert_eq!(parsed, expected, "{input}");
+        assert_eq!(parsed.to_string(), canonical);
+    }
+
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    assert_eq!(
+        max.parse::<HexU256>().unwrap(),
+        HexU256::from_le_bytes([0xff; 32])
+    );
+    let overflow = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+    assert!(overflow.parse::<HexU256>().is_err());
+    assert!("0x2a".parse::<HexU256>().is_err());
+    assert!("".parse::<HexU256>().is_err());
+
+    let json = serde_json::to_string(&expected).unwrap();
+    assert_eq!(json, format!("\"{canonical}\""));
+    let upper: HexU256 = serde_json::from_str(
+        "\"0X2A01000000000000000000000000000000000000000000000000000000000000\"",
+    )
+    .unwrap();
+    assert_eq!(upper, expected);
+}
+
 #[test]
 fn test_bytes_to_address() {
     let bytes = [0u8; 32];
@@ -40,12 +95,15 @@ fn test_extract_coin_type_valid() {
 
     assert_eq!(
         extract_coin_type("SomeEvent<0x2::sui::SUI>"),
-        Some("0x2::sui::SUI".to_string())
+        Some(SUI.to_string())
     );
 
     assert_eq!(
         extract_coin_type("Module::Event<0xabc::token::TOKEN>"),
-        Some("0xabc::token::TOKEN".to_string())
+        Some(
+            "0x0000000000000000000000000000000000000000000000000000000000000abc::token::TOKEN"
+                .to_string()
+        )
     );
 
     assert_eq!(
@@ -54,6 +112,19 @@ fn test_extract_coin_type_valid() {
     );
 }
 
+#[test]
+fn test_normalize_coin_type() {
+    assert_eq!(normalize_coin_type("0x2::sui::SUI"), SUI);
+    assert_eq!(normalize_coin_type(SUI), SUI);
+    assert_eq!(
+        normalize_coin_type("0xAB::lp::LP<0x2::sui::SUI, 0xab::usdc::USDC>"),
+        format!(
+            "{ab}::lp::LP<{SUI}, {ab}::usdc::USDC>",
+            ab = "0x00000000000000000000000000000000000000000000000000000000000000ab"
+        )
+    );
+}
+
 #[test]
 fn test_extract_coin_type_empty() {
     assert_eq!(extract_coin_type(""), None);
@@ -66,3 +137,213 @@ fn test_extract_coin_type_nested() {
     assert!(result.is_some());
     assert!(result.unwrap().contains("Type1"));
 }
+
+#[test]
+fn test_fill_batch_stops_at_high_water_mark() {
+    let limit = max_batch_rows();
+    let mut values = vec![0u64; limit + 10].into_iter();
+    let mut batch = Vec::new();
+
+    assert!(matches!(
+        fill_batch("test", &mut batch, &mut values),
+        BatchStatus::Ready
+    ));
+    assert_eq!(batch.len(), limit);
+    assert_eq!(values.len(), 10);
+
+    let mut batch = Vec::new();
+    assert!(matches!(
+        fill_batch("test", &mut batch, &mut values),
+        BatchStatus::Pending
+    ));
+    assert_eq!(batch.len(), 10);
+    assert_eq!(values.len(), 0);
+}
+
+#[test]
+fn test_checkpoint_pacer_schedule() {
+    let pacer = CheckpointPacer::new(10).unwrap();
+    let start = Instant::now();
+    let anchor = (start, 100);
+
+    assert_eq!(pacer.delay(100, anchor, start), Duration::ZERO);
+    assert_eq!(pacer.delay(105, anchor, start), Duration::from_millis(500));
+    assert_eq!(
+        pacer.delay(105, anchor, start + Duration::from_millis(200)),
+        Duration::from_millis(300)
+    );
+
+    // Checkpoints before the anchor and ones already due are not delayed
+    assert_eq!(pacer.delay(90, anchor, start), Duration::ZERO);
+    assert_eq!(
+        pacer.delay(105, anchor, start + Duration::from_secs(1)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn test_collection_schemas() {
+    let schemas = collection_schemas();
+    assert_eq!(schemas.len(), 10);
+
+    let (_, commitment) = schemas
+        .iter()
+        .find(|(collection, _)| *collection == collections::NEW_COMMITMENTS)
+        .unwrap();
+    let schema = commitment.as_value();
+    assert_eq!(
+        schema["properties"]["commitment"]["$ref"],
+        "#/$defs/HexU256"
+    );
+    assert_eq!(schema["$defs"]["HexU256"]["pattern"], "^0x[0-9a-f]{64}$");
+    assert!(schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|field| field == "_id"));
+}
+
+#[test]
+fn test_tally_event_counts_per_kind() {
+    let mut stats = CheckpointStats::new("0x2::sui::SUI".to_string(), 42, 1_000);
+    assert_eq!(stats.id, "42:0x2::sui::SUI");
+
+    for name in [
+        "NewCommitment",
+        "NewCommitment",
+        "NullifierSpent",
+        "NewPool",
+    ] {
+        assert!(tally_event(&mut stats, name));
+    }
+    assert!(!tally_event(&mut stats, "NewEncryptionKey"));
+
+    assert_eq!(stats.new_commitments, 2);
+    assert_eq!(stats.nullifiers_spent, 1);
+    assert_eq!(stats.new_pools, 1);
+    assert_eq!(stats.transactions, 0);
+}
+
+#[test]
+fn test_public_values_are_signed_amounts() {
+    // BN254 scalar field modulus minus 1_000
+    let mut withdrawal =
+        "21888242871839275222246405745257275088548364400416034343698204186575808494617"
+            .parse::<HexU256>()
+            .unwrap()
+            .to_le_bytes();
+    assert_eq!(public_value_amount(&withdrawal), Some(-1_000));
+
+    let mut deposit = [0u8; 32];
+    deposit[..8].copy_from_slice(&5_000u64.to_le_bytes());
+    assert_eq!(public_value_amount(&deposit), Some(5_000));
+
+    withdrawal[31] = 0xff;
+    deposit[16] = 1;
+    assert_eq!(public_value_amount(&withdrawal), None);
+    assert_eq!(public_value_amount(&deposit), None);
+
+    let mut stats = CheckpointStats::new(SUI.to_string(), 42, 1_000);
+    stats.record_public_value(5_000_000_000);
+    stats.record_public_value(-1_000_000_000);
+    stats.record_public_value(-500_000_000);
+    assert_eq!(
+        (stats.deposited, stats.withdrawn),
+        (5_000_000_000, 1_500_000_000)
+    );
+
+    stats.set_price(2.0, 9);
+    assert_eq!(stats.price_usd, Some(2.0));
+    assert_eq!(stats.deposited_usd, Some(10.0));
+    assert_eq!(stats.withdrawn_usd, Some(3.0));
+}
+
+#[test]
+fn test_leaf_count_is_one_past_highest_index() {
+    let mut count = LeafCount::new(SUI.to_string(), 42, 1_000);
+    assert_eq!(count.id, format!("42:{SUI}"));
+    assert_eq!(count.leaf_count, 0);
+
+    for index in [7, 6, 9, 8] {
+        count.observe(index);
+    }
+    assert_eq!(count.leaf_count, 10);
+}
+
+#[test]
+fn test_gas_metadata_nets_storage_rebate() {
+    let gas = GasCostSummary {
+        computation_cost: 1_000,
+        storage_cost: 4_000,
+        storage_rebate: 7_000,
+        non_refundable_storage_fee: 70,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        gas_metadata(750, &gas),
+        TxMetadata {
+            gas_price: 750,
+            gas_used: -2_000,
+            computation_cost: 1_000,
+            storage_cost: 4_000,
+            storage_rebate: 7_000,
+            non_refundable_storage_fee: 70,
+        }
+    );
+}
+
+#[test]
+fn test_status_report_tells_replays_from_double_processing() {
+    let started_at = DateTime::from_millis(1_700_000_000_000);
+    let late = started_at.timestamp_millis() + 2 * REPLAY_WINDOW.as_millis() as i64;
+    let writes =
+        |inserted, duplicates, range: Option<(u64, u64)>, at: Option<i64>| CollectionWrites {
+            inserted,
+            duplicates,
+            first_duplicate_checkpoint: range.map(|(first, _)| first),
+            last_duplicate_checkpoint: range.map(|(_, last)| last),
+            last_duplicate_at: at.map(DateTime::from_millis),
+        };
+
+    let run = IndexerRun {
+        id: "run".to_string(),
+        started_at,
+        resumed_from: BTreeMap::from([(collections::NEW_COMMITMENTS.to_string(), 99)]),
+        collections: BTreeMap::from([
+            (
+                collections::NEW_COMMITMENTS.to_string(),
+                writes(
+                    10,
+                    2,
+                    Some((100, 101)),
+                    Some(started_at.timestamp_millis() + 1_000),
+                ),
+            ),
+            (
+                collections::NULLIFIERS_SPENT.to_string(),
+                writes(5, 3, Some((200, 900)), Some(late)),
+            ),
+            (collections::NEW_POOLS.to_string(), writes(1, 0, None, None)),
+        ]),
+    };
+
+    let report = StatusReport { runs: vec![run] }.to_string();
+    let lines: Vec<_> = report.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("Run run started"));
+    assert!(report.contains(&format!(
+        "{}: 10 inserted, 2 duplicates at checkpoints 100..=101, resumed after checkpoint 99 (restart replay)",
+        collections::NEW_COMMITMENTS
+    )));
+    assert!(report.contains("check for double processing"));
+    assert!(report.contains(&format!(
+        "{}: 1 inserted, 0 duplicates",
+        collections::NEW_POOLS
+    )));
+
+    assert_eq!(
+        StatusReport::default().to_string(),
+        "No indexer runs recorded"
+    );
+}
@@ -1,5 +1,8 @@
+use serde_json::json;
 use std::str::FromStr;
 use sui_types::base_types::SuiAddress;
+use vortex_indexer::doctor::{Check, DoctorReport};
+use vortex_indexer::output::{Envelope, OutputFormat, OUTPUT_VERSION};
 use vortex_indexer::{parse_package_address, SuiNetwork, VortexEnv};
 
 #[test]
@@ -116,3 +119,163 @@ fn parse_package_address_error_message() {
     assert!(err_msg.contains("Invalid package address"));
     assert!(err_msg.contains("bad_address"));
 }
+
+#[test]
+fn doctor_report_checklist() {
+    use vortex_indexer::doctor::{Check, DoctorReport};
+
+    let mut report = DoctorReport::default();
+    report.push(Check::pass("Package address", "0x2"));
+    assert!(report.passed());
+    assert_eq!(
+        report.to_string(),
+        "[PASS] Package address: 0x2\nAll 1 checks passed"
+    );
+
+    report.push(Check::fail("MongoDB reachable", "Check MONGODB_URI"));
+    assert!(!report.passed());
+    assert_eq!(
+        report.to_string(),
+        "[PASS] Package address: 0x2\n[FAIL] MongoDB reachable: Check MONGODB_URI\n1 of 2 checks failed"
+    );
+}
+
+#[test]
+fn watchdog_reports_lagging_and_stale_sources() {
+    use std::time::{Duration, Instant};
+    use vortex_indexer::watchdog::{assess, CheckpointSource, SourceTracker};
+
+    let start = Instant::now();
+    let stale_after = Duration::from_secs(60);
+    let mut streaming = SourceTracker::default();
+    let mut remote_store = SourceTracker::default();
+
+    let [streamed, stored] = assess(&streaming, &remote_store, start, stale_after);
+    assert!(streamed.stale && stored.stale);
+    assert_eq!(streamed.lag, None);
+
+    streaming.observe(1_000, start);
+    remote_store.observe(800, start);
+    remote_store.observe(700, start + Duration::from_secs(30));
+
+    let now = start + Duration::from_secs(45);
+    let [streamed, stored] = assess(&streaming, &remote_store, now, stale_after);
+    assert_eq!(stored.source, CheckpointSource::RemoteStore);
+    assert_eq!(stored.latest, Some(800));
+    assert_eq!(stored.lag, Some(200));
+    assert_eq!(streamed.lag, Some(0));
+    assert!(streamed.is_healthy(100));
+    assert!(!stored.is_healthy(100));
+    assert!(stored.is_healthy(200));
+
+    let now = start + Duration::from_secs(60);
+    let [streamed, _] = assess(&streaming, &remote_store, now, stale_after);
+    assert!(streamed.stale);
+    assert!(!streamed.is_healthy(100));
+}
+
+#[tokio::test]
+async fn watchdog_finds_newest_remote_checkpoint() {
+    use std::future::Future;
+    use vortex_indexer::watchdog::{newest_available, CheckpointProbe};
+
+    struct UpTo(u64);
+
+    impl CheckpointProbe for UpTo {
+        fn available(&self, checkpoint: u64) -> impl Future<Output = anyhow::Result<bool>> + Send {
+            std::future::ready(Ok(checkpoint <= self.0))
+        }
+    }
+
+    let store = UpTo(12_345);
+    assert_eq!(
+        newest_available(&store, None, Some(20_000)).await.unwrap(),
+        Some(12_345)
+    );
+    assert_eq!(
+        newest_available(&store, None, Some(12_000)).await.unwrap(),
+        Some(12_000)
+    );
+    assert_eq!(
+        newest_available(&store, Some(12_300), Some(12_400))
+            .await
+            .unwrap(),
+        Some(12_345)
+    );
+    assert_eq!(
+        newest_available(&store, Some(10_000), None).await.unwrap(),
+        Some(12_345)
+    );
+    assert_eq!(newest_available(&store, None, None).await.unwrap(), None);
+    assert_eq!(
+        newest_available(&UpTo(0), None, Some(5)).await.unwrap(),
+        Some(0)
+    );
+}
+
+#[test]
+fn static_prices_are_looked_up_by_utc_day() {
+    use vortex_indexer::prices::{CoinPrice, PriceSource, StaticPriceSource};
+
+    let prices = StaticPriceSource::from_json(
+        r#"{ "0x2::sui::SUI": { "decimals": 9, "prices": { "2025-01-01": 4.5, "2025-01-02": 4.0 } } }"#,
+    )
+    .unwrap();
+    let sui = "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+    let jan_1_end_ms = 1_735_775_999_999;
+
+    assert_eq!(
+        prices.price(sui, jan_1_end_ms),
+        Some(CoinPrice {
+            usd: 4.5,
+            decimals: 9
+        })
+    );
+    assert_eq!(
+        prices.price(sui, jan_1_end_ms + 1).map(|p| p.usd),
+        Some(4.0)
+    );
+    assert_eq!(prices.price(sui, 0), None);
+    assert_eq!(prices.price("0x2::sui::SUI", jan_1_end_ms), None);
+
+    assert!(StaticPriceSource::from_json(
+        r#"{ "0x2::sui::SUI": { "decimals": 9, "prices": { "2025-01-01": -1 } } }"#
+    )
+    .is_err());
+    assert!(StaticPriceSource::from_json(r#"{ "0x2::sui::SUI": { "prices": {} } }"#).is_err());
+}
+
+#[test]
+fn output_envelope_is_versioned_json() {
+    assert_eq!("JSON".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    assert_eq!("text".parse::<OutputFormat>(), Ok(OutputFormat::Text));
+    assert!("yaml".parse::<OutputFormat>().is_err());
+
+    let report = DoctorReport {
+        checks: vec![
+            Check::pass("MongoDB", "connected"),
+            Check::fail("Package", "not found"),
+        ],
+    };
+    let envelope = Envelope::error(Some(&report), "1 startup checks failed");
+    assert_eq!(
+        serde_json::to_value(&envelope).unwrap(),
+        json!({
+            "version": OUTPUT_VERSION,
+            "status": "error",
+            "data": {
+                "checks": [
+                    { "name": "MongoDB", "status": { "result": "pass", "detail": "connected" } },
+                    { "name": "Package", "status": { "result": "fail", "detail": "not found" } },
+                ],
+            },
+            "warnings": [],
+            "error": "1 startup checks failed",
+        })
+    );
+
+    assert_eq!(
+        serde_json::to_value(Envelope::ok(42)).unwrap(),
+        json!({ "version": OUTPUT_VERSION, "status": "ok", "data": 42, "warnings": [] })
+    );
+}
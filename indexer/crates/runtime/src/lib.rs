@@ -0,0 +1,272 @@
+//! Coordinated shutdown and task supervision for the Vortex services.
+//!
+//! A [`Supervisor`] owns the root [`CancellationToken`] of a process and hands
+//! every task it runs a child of it, so cancelling the root, on SIGINT/SIGTERM
+//! or when a critical task exits, reaches every task.
+
+use anyhow::{anyhow, Context, Result};
+use std::any::Any;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::task::{JoinError, JoinHandle, JoinSet};
+pub use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Time tasks get to stop after shutdown starts before they are aborted.
+pub const DEFAULT_SHUTDOWN_GRACE_SECS: u64 = 10;
+
+/// What happens when a supervised task fails or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The failure shuts the process down
+    Never,
+    /// The task is restarted after `base_delay`, doubled per restart and capped
+    /// at `max_delay`. The process shuts down once `max_restarts` is exceeded.
+    /// An attempt that ran for at least `max_delay` before failing was healthy
+    /// and starts the count over
+    OnFailure {
+        max_restarts: u32,
+        base_delay: Duration,
+        max_delay: Duration,
+    },
+}
+
+impl RestartPolicy {
+    /// Delay before the `restart`-th restart, counted from 1, or `None` once
+    /// the task may not be restarted again.
+    #[must_use]
+    pub fn delay(&self, restart: u32) -> Option<Duration> {
+        match *self {
+            Self::Never => None,
+            Self::OnFailure {
+                max_restarts,
+                base_delay,
+                max_delay,
+            } => (restart <= max_restarts).then(|| {
+                let factor = 1u32
+                    .checked_shl(restart.saturating_sub(1))
+                    .unwrap_or(u32::MAX);
+                base_delay.saturating_mul(factor).min(max_delay)
+            }),
+        }
+    }
+
+    /// Whether an attempt that failed after running for `uptime` was healthy,
+    /// so its failure is counted as the first restart again.
+    #[must_use]
+    pub fn is_healthy_run(&self, uptime: Duration) -> bool {
+        match *self {
+            Self::Never => false,
+            Self::OnFailure { max_delay, .. } => uptime >= max_delay,
+        }
+    }
+}
+
+/// Aborts the task when dropped, so aborting a supervisor also stops the
+/// attempt it is waiting on.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Runs `future` on its own task, turning a panic into an error.
+async fn run_attempt(future: impl Future<Output = Result<()>> + Send + 'static) -> Result<()> {
+    let mut handle = AbortOnDrop(tokio::spawn(future));
+    (&mut handle.0).await.unwrap_or_else(|e| Err(join_error(e)))
+}
+
+fn join_error(e: JoinError) -> anyhow::Error {
+    if e.is_panic() {
+        anyhow!("panicked: {}", panic_message(&*e.into_panic()))
+    } else {
+        anyhow!("was aborted")
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string payload")
+}
+
+/// Waits for SIGINT or SIGTERM and returns its name.
+///
+/// # Errors
+/// Returns error if the signal handlers cannot be installed.
+pub async fn shutdown_signal() -> Result<&'static str> {
+    let mut terminate = signal(SignalKind::terminate()).context("Failed to listen for SIGTERM")?;
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            result.context("Failed to listen for SIGINT")?;
+            Ok("SIGINT")
+        }
+        _ = terminate.recv() => Ok("SIGTERM"),
+    }
+}
+
+/// Runs the long-lived tasks of a process and shuts them down together.
+pub struct Supervisor {
+    token: CancellationToken,
+    tasks: JoinSet<(&'static str, Result<()>)>,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tasks: JoinSet::new(),
+        }
+    }
+
+    /// Root token; cancelling it shuts every task down.
+    #[must_use]
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Runs `task` under `policy`, calling it again for every restart.
+    ///
+    /// The task receives a child token and should return once it is
+    /// cancelled. Returning `Ok` ends the task without a restart.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, policy: RestartPolicy, mut task: F)
+    where
+        F: FnMut(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let token = self.token.clone();
+        self.tasks.spawn(async move {
+            let mut restarts = 0;
+            loop {
+                let started = Instant::now();
+                let Err(e) = run_attempt(task(token.child_token())).await else {
+                    return (name, Ok(()));
+                };
+                if token.is_cancelled() {
+                    return (name, Err(e));
+                }
+
+                if policy.is_healthy_run(started.elapsed()) {
+                    restarts = 0;
+                }
+                restarts += 1;
+                let Some(delay) = policy.delay(restarts) else {
+                    error!(task = name, "Task failed, shutting down: {e:#}");
+                    token.cancel();
+                    return (name, Err(e));
+                };
+                warn!(
+                    task = name,
+                    restarts, "Task failed, restarting in {delay:?}: {e:#}"
+                );
+
+                tokio::select! {
+                    () = token.cancelled() => return (name, Ok(())),
+                    () = tokio::time::sleep(delay) => {}
+                }
+            }
+        });
+    }
+
+    /// Runs `future` once; the process shuts down when it exits, whether it
+    /// finished or failed. For a service's main loop, which is not cancelled
+    /// cooperatively but aborted once the shutdown grace period ends.
+    pub fn spawn_critical(
+        &mut self,
+        name: &'static str,
+        future: impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        let token = self.token.clone();
+        self.tasks.spawn(async move {
+            let result = run_attempt(future).await;
+            match &result {
+                Ok(()) => info!(task = name, "Task finished, shutting down"),
+                Err(e) => error!(task = name, "Task failed, shutting down: {e:#}"),
+            }
+            token.cancel();
+            (name, result)
+        });
+    }
+
+    /// Cancels the root token on SIGINT or SIGTERM.
+    ///
+    /// Installing the handlers replaces the default of exiting immediately,
+    /// so call this once the process is ready to shut down cooperatively.
+    pub fn shutdown_on_signal(&self) {
+        let token = self.token.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                () = token.cancelled() => {}
+                signal = shutdown_signal() => {
+                    match signal {
+                        Ok(signal) => info!(signal, "Received shutdown signal"),
+                        Err(e) => error!("Shutting down: {e:#}"),
+                    }
+                    token.cancel();
+                }
+            }
+        });
+    }
+
+    /// Waits until every task has exited, or until the root token is
+    /// cancelled and the tasks have stopped. Tasks still running `grace`
+    /// after cancellation are aborted.
+    ///
+    /// # Errors
+    /// Returns the first task failure, if any.
+    pub async fn join(mut self, grace: Duration) -> Result<()> {
+        let mut failure = None;
+
+        loop {
+            tokio::select! {
+                () = self.token.cancelled() => break,
+                exit = self.tasks.join_next() => match exit {
+                    Some(exit) => record_exit(exit, &mut failure),
+                    None => return failure.map_or(Ok(()), Err),
+                },
+            }
+        }
+
+        let drain = async {
+            while let Some(exit) = self.tasks.join_next().await {
+                record_exit(exit, &mut failure);
+            }
+        };
+        if tokio::time::timeout(grace, drain).await.is_err() {
+            warn!(
+                remaining = self.tasks.len(),
+                "Tasks did not stop within {grace:?}, aborting them"
+            );
+            self.tasks.abort_all();
+            while self.tasks.join_next().await.is_some() {}
+        }
+
+        failure.map_or(Ok(()), Err)
+    }
+}
+
+fn record_exit(
+    exit: Result<(&'static str, Result<()>), JoinError>,
+    failure: &mut Option<anyhow::Error>,
+) {
+    let error = match exit {
+        Ok((_, Ok(()))) => return,
+        Ok((name, Err(e))) => e.context(format!("Task {name} failed")),
+        Err(e) if e.is_cancelled() => return,
+        Err(e) => join_error(e).context("Supervised task"),
+    };
+    failure.get_or_insert(error);
+}
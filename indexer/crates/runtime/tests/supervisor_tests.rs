@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use vortex_runtime::{CancellationToken, RestartPolicy, Supervisor};
+
+const GRACE: Duration = Duration::from_secs(1);
+
+fn on_failure(max_restarts: u32) -> RestartPolicy {
+    RestartPolicy::OnFailure {
+        max_restarts,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(4),
+    }
+}
+
+async fn broken(_token: CancellationToken) -> anyhow::Result<()> {
+    anyhow::bail!("broken")
+}
+
+#[test]
+fn restart_delay_backs_off_until_exhausted() {
+    let policy = on_failure(4);
+    let delays = (1..=5)
+        .map(|restart| policy.delay(restart))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        delays,
+        [1, 2, 4, 4]
+            .map(|ms| Some(Duration::from_millis(ms)))
+            .into_iter()
+            .chain([None])
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(RestartPolicy::Never.delay(1), None);
+    assert_eq!(
+        on_failure(u32::MAX).delay(u32::MAX),
+        Some(Duration::from_millis(4))
+    );
+}
+
+#[tokio::test]
+async fn panicking_task_is_restarted() {
+    let attempts = Arc::new(AtomicU32::new(0));
+    let mut supervisor = Supervisor::new();
+
+    let counter = attempts.clone();
+    supervisor.spawn("flaky", on_failure(3), move |_token| {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if attempt < 2 {
+                panic!("attempt {attempt}");
+            }
+            anyhow::Ok(())
+        }
+    });
+
+    supervisor.join(GRACE).await.unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn healthy_runs_reset_the_restart_count() {
+    let attempts = Arc::new(AtomicU32::new(0));
+    let mut supervisor = Supervisor::new();
+
+    let counter = attempts.clone();
+    supervisor.spawn("transient", on_failure(1), move |_token| {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if attempt == 3 {
+                return anyhow::Ok(());
+            }
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            anyhow::bail!("transient {attempt}")
+        }
+    });
+
+    supervisor.join(GRACE).await.unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    assert!(on_failure(1).is_healthy_run(Duration::from_millis(4)));
+    assert!(!on_failure(1).is_healthy_run(Duration::from_millis(3)));
+    assert!(!RestartPolicy::Never.is_healthy_run(Duration::MAX));
+}
+
+#[tokio::test]
+async fn exhausted_restarts_shut_every_task_down() {
+    let mut supervisor = Supervisor::new();
+    let token = supervisor.token();
+
+    supervisor.spawn("failing", on_failure(2), broken);
+    supervisor.spawn("worker", RestartPolicy::Never, |token| async move {
+        token.cancelled().await;
+        anyhow::Ok(())
+    });
+
+    let err = supervisor.join(GRACE).await.unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Task failing failed: broken"),
+        "{err:#}"
+    );
+    assert!(token.is_cancelled());
+}
+
+#[tokio::test]
+async fn critical_exit_aborts_tasks_ignoring_cancellation() {
+    let mut supervisor = Supervisor::new();
+
+    supervisor.spawn("stuck", RestartPolicy::Never, |_token| {
+        std::future::pending::<anyhow::Result<()>>()
+    });
+    supervisor.spawn_critical("service", async { anyhow::Ok(()) });
+
+    tokio::time::timeout(GRACE * 2, supervisor.join(Duration::from_millis(10)))
+        .await
+        .expect("stuck task is aborted after the grace period")
+        .unwrap();
+}
@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::PathBuf;
+use vortex_schema::collection_schemas;
+
+const USAGE: &str = "Usage: schema-dump [--out <dir>]
+
+Prints the JSON Schema of every collection document written by the indexer,
+keyed by collection name. With --out, writes one <collection>.schema.json per
+collection into <dir> instead.";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let out = match (args.next().as_deref(), args.next()) {
+        (None, _) => None,
+        (Some("--out"), Some(dir)) => Some(PathBuf::from(dir)),
+        _ => return Err(USAGE.into()),
+    };
+
+    let schemas = collection_schemas();
+
+    let Some(dir) = out else {
+        let combined: serde_json::Map<_, _> = schemas
+            .into_iter()
+            .map(|(collection, schema)| (collection.to_string(), schema.to_value()))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&combined)?);
+        return Ok(());
+    };
+
+    fs::create_dir_all(&dir)?;
+    for (collection, schema) in schemas {
+        let path = dir.join(format!("{collection}.schema.json"));
+        fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use mongodb::bson::Bson;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// A Move `u256` (commitment, nullifier) in the form the index stores it:
+/// `0x` followed by the 64 lowercase hex digits of its little-endian bytes.
+///
+/// Parsing accepts what clients send:
+/// - hex of the little-endian bytes, with or without `0x`, in any case
+/// - the decimal value, without prefix
+///
+/// Unprefixed strings made only of digits are read as decimal, so hex with no
+/// letters must carry the `0x` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HexU256([u8; 32]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHexU256Error(String);
+
+impl fmt::Display for ParseHexU256Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid u256 {:?}: expected decimal or 64 hex digits",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseHexU256Error {}
+
+impl HexU256 {
+    pub const fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn to_le_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    fn parse_decimal(s: &str) -> Option<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        for digit in s.bytes() {
+            let mut carry = u16::from(digit - b'0');
+            for byte in &mut bytes {
+                let value = u16::from(*byte) * 10 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(bytes)
+    }
+
+    fn parse_hex(s: &str) -> Option<[u8; 32]> {
+        if s.len() != 64 {
+            return None;
+        }
+        let mut bytes = [0u8; 32];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            *byte = u8::from_str_radix(pair, 16).ok()?;
+        }
+        Some(bytes)
+    }
+}
+
+impl FromStr for HexU256 {
+    type Err = ParseHexU256Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let bytes = match trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+        {
+            Some(hex) => Self::parse_hex(hex),
+            None if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) => {
+                Self::parse_decimal(trimmed)
+            }
+            None => Self::parse_hex(trimmed),
+        };
+
+        bytes
+            .map(Self)
+            .ok_or_else(|| ParseHexU256Error(s.to_string()))
+    }
+}
+
+impl fmt::Display for HexU256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl From<[u8; 32]> for HexU256 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<HexU256> for Bson {
+    fn from(value: HexU256) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl JsonSchema for HexU256 {
+    fn schema_name() -> Cow<'static, str> {
+        "HexU256".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": "^0x[0-9a-f]{64}$",
+            "description": "u256 as 0x and the 64 lowercase hex digits of its little-endian bytes",
+        })
+    }
+}
+
+impl Serialize for HexU256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HexU256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
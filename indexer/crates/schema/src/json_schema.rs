@@ -0,0 +1,46 @@
+use crate::{
+    collections, CheckpointStats, DeadLetter, IndexerRun, LeafCount, NewCommitment, NewPool,
+    NullifierSpent, Watch, WatchNotification, Watermark,
+};
+use schemars::{json_schema, schema_for, Schema, SchemaGenerator};
+
+/// JSON Schema of the document stored in each collection, keyed by
+/// collection name.
+pub fn collection_schemas() -> Vec<(&'static str, Schema)> {
+    vec![
+        (collections::NEW_POOLS, schema_for!(NewPool)),
+        (collections::NEW_COMMITMENTS, schema_for!(NewCommitment)),
+        (collections::NULLIFIERS_SPENT, schema_for!(NullifierSpent)),
+        (collections::WATERMARKS, schema_for!(Watermark)),
+        (collections::WATCHES, schema_for!(Watch)),
+        (
+            collections::WATCH_NOTIFICATIONS,
+            schema_for!(WatchNotification),
+        ),
+        (collections::DEAD_LETTERS, schema_for!(DeadLetter)),
+        (collections::CHECKPOINT_STATS, schema_for!(CheckpointStats)),
+        (collections::LEAF_COUNTS, schema_for!(LeafCount)),
+        (collections::INDEXER_RUNS, schema_for!(IndexerRun)),
+    ]
+}
+
+pub(crate) fn date_time(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "string",
+        "format": "date-time",
+        "description": "BSON date; `{\"$date\": ...}` in extended JSON exports",
+    })
+}
+
+pub(crate) fn optional_date_time(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [date_time(generator), { "type": "null" }],
+    })
+}
+
+pub(crate) fn document(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "object",
+        "description": "Arbitrary BSON document",
+    })
+}
@@ -1,3 +1,7 @@
+mod hex;
+mod json_schema;
 mod models;
 
+pub use hex::{HexU256, ParseHexU256Error};
+pub use json_schema::collection_schemas;
 pub use models::*;
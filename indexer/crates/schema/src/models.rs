@@ -1,14 +1,23 @@
+use crate::HexU256;
 use mongodb::bson;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub mod collections {
     pub const NEW_POOLS: &str = "new_pools";
     pub const NEW_COMMITMENTS: &str = "new_commitments";
     pub const NULLIFIERS_SPENT: &str = "nullifiers_spent";
     pub const WATERMARKS: &str = "watermarks";
+    pub const WATCHES: &str = "watches";
+    pub const WATCH_NOTIFICATIONS: &str = "watch_notifications";
+    pub const DEAD_LETTERS: &str = "dead_letters";
+    pub const CHECKPOINT_STATS: &str = "checkpoint_stats";
+    pub const LEAF_COUNTS: &str = "leaf_counts";
+    pub const INDEXER_RUNS: &str = "indexer_runs";
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EventBase {
     #[serde(rename = "_id")]
     pub event_digest: String,
@@ -18,7 +27,7 @@ pub struct EventBase {
     pub checkpoint_timestamp_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NewPool {
     #[serde(flatten)]
     pub base: EventBase,
@@ -26,25 +35,228 @@ pub struct NewPool {
     pub coin_type: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NewCommitment {
     #[serde(flatten)]
     pub base: EventBase,
     pub coin_type: String,
     pub index: u64,
-    pub commitment: String,
+    pub commitment: HexU256,
     pub encrypted_output: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NullifierSpent {
     #[serde(flatten)]
     pub base: EventBase,
     pub coin_type: String,
-    pub nullifier: String,
+    pub nullifier: HexU256,
+    #[serde(default)]
+    pub tx: Option<TxMetadata>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Gas taken from the transaction effects.
+///
+/// `gas_used` is the net cost charged to the sender in MIST
+/// (computation + storage - rebate) and may be negative. There is no status:
+/// `NullifierSpent` is only emitted by transactions that succeeded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TxMetadata {
+    pub gas_price: u64,
+    pub gas_used: i64,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    pub non_refundable_storage_fee: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchKind {
+    Nullifier,
+}
+
+/// A user registered interest in an on-chain value. Watches are written by the
+/// API; the indexer only reads them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Watch {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub kind: WatchKind,
+    pub value: HexU256,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
+    pub created_at: bson::DateTime,
+}
+
+/// One match between a watch and an indexed event. The `_id` is
+/// `{watch_id}:{event_digest}` so reprocessing a checkpoint is idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WatchNotification {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub watch_id: String,
+    pub kind: WatchKind,
+    pub value: HexU256,
+    pub event_digest: String,
+    pub digest: String,
+    pub coin_type: String,
+    pub checkpoint: u64,
+    pub checkpoint_timestamp_ms: u64,
+    pub webhook_url: Option<String>,
+    pub delivered: bool,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
+    pub created_at: bson::DateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterSource {
+    Indexer,
+    Relayer,
+}
+
+/// Work that failed permanently, kept with enough context to replay it.
+///
+/// Indexer entries hold a document the store rejected and the collection it
+/// belongs to; their `_id` is `{collection}:{document _id}`, so a checkpoint
+/// that fails again does not add duplicates. Relayer entries are written by
+/// the API. Replays go through the API's admin routes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeadLetter {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub source: DeadLetterSource,
+    #[serde(default)]
+    pub collection: Option<String>,
+    #[schemars(schema_with = "crate::json_schema::document")]
+    pub payload: bson::Document,
+    pub error: String,
+    pub attempts: u32,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
+    pub created_at: bson::DateTime,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
+    pub last_attempt_at: bson::DateTime,
+    #[serde(default)]
+    #[schemars(schema_with = "crate::json_schema::optional_date_time")]
+    pub replayed_at: Option<bson::DateTime>,
+}
+
+/// Vortex activity of one coin type in one checkpoint, written instead of the
+/// individual events when the indexer runs in analytics mode. The `_id` is
+/// `{checkpoint}:{coin_type}` so reprocessing a checkpoint is idempotent.
+///
+/// Notes stay private, but each transaction's public value is visible: the
+/// amount moved into the pool, or out of it including the relayer fee. A
+/// transaction's amounts and gas count towards the coin type of its first
+/// Vortex event.
+///
+/// The `_usd` fields are set when a price for the coin type on the checkpoint's
+/// UTC day is configured. `price_usd` is kept so TVL on that day is the running
+/// sum of `deposited - withdrawn` times the price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointStats {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub coin_type: String,
+    pub checkpoint: u64,
+    pub checkpoint_timestamp_ms: u64,
+    pub transactions: u64,
+    pub new_pools: u64,
+    pub new_commitments: u64,
+    pub nullifiers_spent: u64,
+    pub gas_used: i64,
+    /// Base units of the coin
+    #[serde(default)]
+    pub deposited: u64,
+    /// Base units of the coin
+    #[serde(default)]
+    pub withdrawn: u64,
+    /// Price of one whole coin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposited_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawn_usd: Option<f64>,
+}
+
+impl CheckpointStats {
+    pub fn new(coin_type: String, checkpoint: u64, checkpoint_timestamp_ms: u64) -> Self {
+        Self {
+            id: format!("{checkpoint}:{coin_type}"),
+            coin_type,
+            checkpoint,
+            checkpoint_timestamp_ms,
+            transactions: 0,
+            new_pools: 0,
+            new_commitments: 0,
+            nullifiers_spent: 0,
+            gas_used: 0,
+            deposited: 0,
+            withdrawn: 0,
+            price_usd: None,
+            deposited_usd: None,
+            withdrawn_usd: None,
+        }
+    }
+
+    /// Adds a transaction's public value: positive for deposits, negative for
+    /// withdrawals.
+    pub fn record_public_value(&mut self, amount: i128) {
+        let magnitude = u64::try_from(amount.unsigned_abs()).unwrap_or(u64::MAX);
+        let total = if amount >= 0 {
+            &mut self.deposited
+        } else {
+            &mut self.withdrawn
+        };
+        *total = total.saturating_add(magnitude);
+    }
+
+    /// Sets the USD values from the price of one whole coin with `decimals`.
+    pub fn set_price(&mut self, price_usd: f64, decimals: u8) {
+        let unit = 10f64.powi(i32::from(decimals));
+        self.price_usd = Some(price_usd);
+        self.deposited_usd = Some(self.deposited as f64 / unit * price_usd);
+        self.withdrawn_usd = Some(self.withdrawn as f64 / unit * price_usd);
+    }
+}
+
+/// Leaves in a pool's Merkle tree after one checkpoint, written for every
+/// checkpoint that appends commitments to the pool. The `_id` is
+/// `{checkpoint}:{coin_type}` so reprocessing a checkpoint is idempotent.
+///
+/// The count is one past the highest commitment index seen, so the series is
+/// right regardless of the order checkpoints are committed in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LeafCount {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub coin_type: String,
+    pub checkpoint: u64,
+    pub checkpoint_timestamp_ms: u64,
+    pub leaf_count: u64,
+}
+
+impl LeafCount {
+    pub fn new(coin_type: String, checkpoint: u64, checkpoint_timestamp_ms: u64) -> Self {
+        Self {
+            id: format!("{checkpoint}:{coin_type}"),
+            coin_type,
+            checkpoint,
+            checkpoint_timestamp_ms,
+            leaf_count: 0,
+        }
+    }
+
+    /// Accounts for the commitment appended at `index`.
+    pub fn observe(&mut self, index: u64) {
+        self.leaf_count = self.leaf_count.max(index + 1);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Watermark {
     #[serde(rename = "_id")]
     pub pipeline: String,
@@ -54,6 +266,7 @@ pub struct Watermark {
     pub timestamp_ms_hi_inclusive: u64,
     pub reader_lo: u64,
     pub pruner_hi: u64,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
     pub pruner_timestamp: bson::DateTime,
 }
 
@@ -71,3 +284,35 @@ impl Watermark {
         }
     }
 }
+
+/// Writes of one indexer process, kept so operators can tell the duplicate
+/// inserts of a restart replaying its last checkpoints from a pipeline
+/// processing checkpoints twice. `_id` is an ObjectId, so runs sort by start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IndexerRun {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[schemars(schema_with = "crate::json_schema::date_time")]
+    pub started_at: bson::DateTime,
+    /// Committer watermark of each pipeline when the run started
+    pub resumed_from: BTreeMap<String, u64>,
+    /// Keyed by collection
+    #[serde(default)]
+    pub collections: BTreeMap<String, CollectionWrites>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CollectionWrites {
+    #[serde(default)]
+    pub inserted: u64,
+    /// Documents skipped because their `_id` was already stored
+    #[serde(default)]
+    pub duplicates: u64,
+    #[serde(default)]
+    pub first_duplicate_checkpoint: Option<u64>,
+    #[serde(default)]
+    pub last_duplicate_checkpoint: Option<u64>,
+    #[serde(default)]
+    #[schemars(schema_with = "crate::json_schema::optional_date_time")]
+    pub last_duplicate_at: Option<bson::DateTime>,
+}